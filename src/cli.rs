@@ -1,24 +1,75 @@
-use std::fmt;
+use std::{fmt, num::NonZeroUsize, path::PathBuf};
 
 use clap::{Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
 
+use crate::domain::{Date, Time, Weekday};
+
 #[derive(Debug, Parser)]
 #[command(name = "mm")]
 #[command(about = "Manage my studies", version = "0.2.0")]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    /// Print filesystem changes, the config file and link updates to stderr and record store
+    /// timings of this command for `mm debug timings`
+    #[arg(long, short, global = true)]
+    pub verbose: bool,
+    /// Only print errors and the output of the command, no success and info messages
+    #[arg(long, short, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+    /// Refuse commands which change the store, e.g. on a read-only mount
+    #[arg(long, global = true)]
+    pub read_only: bool,
+    /// Print the changes to the filesystem a command would make without making them
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+    /// Read the config from this file instead of `mm/config.toml` in the config directory
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    /// Run as if mm was started in this directory, e.g. for `mm switch` without reference
+    #[arg(short = 'C', global = true, value_name = "DIR")]
+    pub directory: Option<PathBuf>,
+    /// Append the command, the active semester and course and all filesystem changes to this
+    /// file. Defaults to a log for each day in `mm/logs` in the config directory, of which the
+    /// last 7 are kept
+    #[arg(long, global = true, value_name = "FILE", num_args = 0..=1, require_equals = true)]
+    pub log_file: Option<Option<PathBuf>>,
 }
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
     #[command(about = "Show the current active semester or course")]
     #[command(alias = "s")]
-    Status {},
+    Status {
+        /// Show averages converted to another grade scale
+        #[arg(long, value_enum)]
+        scale: Option<ScaleDO>,
+        /// Print `key=value` lines for scripts. Exits with 0 if a course is active, 2 if only a
+        /// semester is active and 3 without active semester
+        #[arg(long, conflicts_with = "scale")]
+        porcelain: bool,
+        /// Only count courses of this degree, e.g. `Informatik` for `B.Sc. Informatik`
+        #[arg(long, conflicts_with = "porcelain")]
+        degree: Option<String>,
+        /// Only count semesters of this study cycle
+        #[arg(long, value_enum, conflicts_with = "porcelain")]
+        cycle: Option<StudyCycleDO>,
+        /// Only count these semesters: one like `b03` or a range like `b01..b04`, `b03..` or
+        /// `..b04`
+        #[arg(long, conflicts_with = "porcelain")]
+        semester: Option<String>,
+    },
+    #[command(about = "Browse semesters and courses in a full-screen interface")]
+    Ui,
     #[command(about = "Switch to a semester or course")]
     #[command(alias = "sw")]
-    Switch { reference: Option<String> },
+    Switch {
+        reference: Option<String>,
+        /// Activate the semester which lasts over today, see `start` and `end` in its `.mm` file
+        #[arg(long, conflicts_with = "reference")]
+        auto: bool,
+    },
     #[command(about = "Manage semesters")]
     #[command(alias = "se")]
     Semester {
@@ -31,12 +82,272 @@ pub enum Commands {
         #[command(subcommand)]
         command: Option<CourseCommands>,
     },
-    #[command(about = "exercises")]
+    #[command(
+        about = "Find courses by name, code, module id, instructor or tag, or notes by their content"
+    )]
+    Search {
+        query: String,
+        /// Search the lines of the notes and the main document instead of the course settings
+        #[arg(long)]
+        content: bool,
+        /// Also search the text of PDFs in the course folders, requires `pdftotext`
+        #[arg(long, requires = "content")]
+        pdf: bool,
+        /// Search the active course, the active semester or all semesters
+        #[arg(long, value_enum, default_value = "all")]
+        scope: SearchScopeDO,
+    },
+    #[command(about = "Show the ECTS progress of configured degrees")]
+    Degree {
+        #[command(subcommand)]
+        command: Option<DegreeCommands>,
+    },
+    #[command(about = "Manage the module catalog of the degree program")]
+    Catalog {
+        #[command(subcommand)]
+        command: CatalogCommands,
+    },
+    #[command(about = "Show open required modules and suggest modules for the next semester")]
+    Plan {
+        /// ECTS to plan for the next semester
+        #[arg(long, default_value_t = 30)]
+        ects: u32,
+        #[command(subcommand)]
+        command: Option<PlanCommands>,
+    },
+    #[command(about = "Show grade statistics")]
+    Stats {
+        /// Show courses, earned ECTS and averages for each semester
+        #[arg(long)]
+        by_semester: bool,
+        /// Print every course and the figures of each semester and degree as JSON
+        #[arg(long, conflicts_with = "by_semester")]
+        json: bool,
+    },
+    #[command(about = "Simulate grades or compute the average needed to reach a target")]
+    Whatif {
+        /// Hypothetical grades like `Algorithms=1.3`, the course is a reference like for switch
+        #[arg(value_name = "COURSE=GRADE")]
+        grades: Vec<String>,
+        /// Target weighted average to compute the needed average over the remaining ECTS
+        #[arg(long, short)]
+        target: Option<f32>,
+    },
+    #[command(about = "Manage exercise sheets and their points")]
     #[command(alias = "ex")]
     Exercise {
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short, global = true)]
+        course: Option<String>,
+        #[command(subcommand)]
+        command: Option<ExerciseCommands>,
+    },
+    #[command(about = "Move the newest downloads from the inbox into a course")]
+    Attach {
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short)]
+        course: Option<String>,
+        /// Number of files to move, newest first
+        #[arg(long, short = 'n', default_value_t = 1)]
+        count: usize,
+        /// Move into the `lectures` folder of the course
+        #[arg(long, short, conflicts_with = "exercise")]
+        lectures: bool,
+        /// Move into the latest exercise sheet of the course
+        #[arg(long, short)]
+        exercise: bool,
+        /// Rename to lowercase names with dashes, e.g. `uebung-3-loesung.pdf`
+        #[arg(long, short)]
+        rename: bool,
+    },
+    #[command(
+        about = "Watch the inbox and file new downloads into the active course by inbox_rules"
+    )]
+    Watch {
+        /// Seconds between two checks of the inbox
+        #[arg(long, short, default_value_t = 2)]
+        interval: u64,
+    },
+    #[command(about = "Manage tasks of a course")]
+    Todo {
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short, global = true)]
+        course: Option<String>,
+        #[command(subcommand)]
+        command: Option<TodoCommands>,
+    },
+    #[command(about = "Manage deadlines of a course")]
+    #[command(alias = "dl")]
+    Deadline {
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short, global = true)]
+        course: Option<String>,
+        #[command(subcommand)]
+        command: Option<DeadlineCommands>,
+    },
+    #[command(about = "Manage exams")]
+    Exam {
+        #[command(subcommand)]
+        command: Option<ExamCommands>,
+    },
+    #[command(about = "Show the weekly timetable of the active semester")]
+    #[command(alias = "sc")]
+    Schedule {
+        #[command(subcommand)]
+        command: Option<ScheduleCommands>,
+    },
+    #[command(about = "Export and import deadlines, exams and lecture slots as iCalendar")]
+    #[command(alias = "cal")]
+    Calendar {
+        #[command(subcommand)]
+        command: CalendarCommands,
+    },
+    #[command(about = "Print the active semester and course as a shell prompt segment")]
+    Prompt {
+        /// Overrides `prompt_format` of the config, e.g. "{{course_name}}"
+        #[arg(long, short)]
+        format: Option<String>,
+    },
+    #[command(about = "Print the active context as shell variables, use with eval \"$(mm env)\"")]
+    Env,
+    #[command(about = "Run a command inside the active course directory")]
+    Exec {
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short)]
+        course: Option<String>,
+        #[arg(
+            value_name = "COMMAND",
+            required = true,
+            trailing_var_arg = true,
+            allow_hyphen_values = true
+        )]
+        command: Vec<String>,
+    },
+    #[command(about = "Print a single raw value for use in scripts")]
+    Get {
+        #[command(subcommand)]
+        command: GetCommands,
+    },
+    #[command(about = "Run mm commands from a file (or stdin), one per line")]
+    Batch {
+        #[arg(value_name = "FILE")]
+        file: Option<PathBuf>,
+    },
+    #[command(about = "Create a sample store to explore mm")]
+    Demo {
+        #[command(subcommand)]
+        command: DemoCommands,
+    },
+    #[command(about = "Track study time for the active course")]
+    Track {
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short, global = true)]
+        course: Option<String>,
+        #[command(subcommand)]
+        command: Option<TrackCommands>,
+    },
+    #[command(about = "Manage lecture notes of a course")]
+    Note {
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short, global = true)]
+        course: Option<String>,
+        #[command(subcommand)]
+        command: Option<NoteCommands>,
+    },
+    #[command(about = "Verify course materials against a checksum manifest")]
+    Materials {
+        /// Course reference (`course` or `semester/course`). Defaults to the active semester.
+        #[arg(long, short, global = true)]
+        course: Option<String>,
+        #[command(subcommand)]
+        command: MaterialsCommands,
+    },
+    #[command(about = "Export study cards written in the notes of a course")]
+    Flashcards {
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short, global = true)]
+        course: Option<String>,
+        #[command(subcommand)]
+        command: FlashcardsCommands,
+    },
+    #[command(about = "Manage the literature of a course")]
+    Lit {
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short, global = true)]
+        course: Option<String>,
+        #[command(subcommand)]
+        command: Option<LitCommands>,
+    },
+    #[command(about = "Organize the PDFs of a course")]
+    Pdf {
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short, global = true)]
+        course: Option<String>,
+        #[command(subcommand)]
+        command: PdfCommands,
+    },
+    #[command(about = "Export the study structure")]
+    Export {
+        #[command(subcommand)]
+        command: ExportCommands,
+    },
+    #[command(about = "Back up the metadata files or the whole entry point")]
+    Backup {
+        /// Archive the whole entry point instead of only `.mm` and `course.toml` files.
+        #[arg(long, short)]
+        full: bool,
+        #[command(subcommand)]
+        command: Option<BackupCommands>,
+    },
+    #[command(about = "Synchronize the store with the remote configured in [sync]")]
+    Sync {
+        #[command(subcommand)]
+        command: SyncCommands,
+    },
+    #[command(about = "Import grades from the campus system")]
+    Grades {
+        #[command(subcommand)]
+        command: GradesCommands,
+    },
+    #[command(about = "Link courses to Moodle and import assignment deadlines")]
+    Moodle {
+        #[command(subcommand)]
+        command: MoodleCommands,
+    },
+    #[command(about = "Serve the store as a JSON API and a web dashboard over HTTP")]
+    Serve {
+        /// Address to listen on. Use 0.0.0.0 to allow other devices in the network, which then
+        /// need the token printed at start
+        #[arg(long, default_value = "127.0.0.1")]
+        bind: String,
+        #[arg(long, short, default_value_t = 7878)]
+        port: u16,
+    },
+    #[command(about = "Show the log of changes to the store")]
+    History {
+        /// Number of entries to show
+        #[arg(long, short = 'n', default_value_t = 20)]
+        limit: usize,
+        /// Only show changes on or after this date, formatted as YYYY-MM-DD
+        #[arg(long)]
+        since: Option<Date>,
+        /// Only show changes on or before this date, formatted as YYYY-MM-DD
+        #[arg(long)]
+        until: Option<Date>,
+        /// Only show changes whose description or command contains this text
+        #[arg(long, short)]
+        search: Option<String>,
+        /// Show the commits recorded with the 'git' config option instead
+        #[arg(long, conflicts_with_all = ["since", "until", "search"])]
+        git: bool,
+    },
+    #[command(about = "Collect diagnostics for bug reports")]
+    Debug {
         #[command(subcommand)]
-        command: ExerciseCommands,
+        command: DebugCommands,
     },
+    #[command(about = "Upgrade the data files of a store created by an older version of mm")]
+    Migrate,
     #[command(about = "Change configuration (to be implemented)")]
     Config {
         #[command(subcommand)]
@@ -46,35 +357,594 @@ pub enum Commands {
 
 #[derive(Debug, Subcommand)]
 pub enum SemesterCommands {
-    List,
+    List {
+        /// Also show the position of each semester in its study cycle, e.g. `3rd Bachelor semester`
+        #[arg(long, short)]
+        ordinal: bool,
+    },
     Add {
         number: u16,
         study_cycle: Option<StudyCycleDO>,
+        /// First day of the semester, formatted as YYYY-MM-DD
+        #[arg(long, requires = "end")]
+        start: Option<Date>,
+        /// Last day of the semester, formatted as YYYY-MM-DD
+        #[arg(long, requires = "start")]
+        end: Option<Date>,
+        /// ECTS to enroll in during the semester. Defaults to `ects_budget` of the config
+        #[arg(long, value_name = "ECTS")]
+        ects_budget: Option<u32>,
     },
     Remove {
         name: String,
+        /// Remove the semester without confirmation
+        #[arg(long, short)]
+        force: bool,
     },
+    #[command(about = "Guided end-of-term checklist for a semester")]
+    Close { name: String },
 }
 
 #[derive(Debug, Subcommand)]
 pub enum CourseCommands {
+    List {
+        /// Only list courses with this status
+        #[arg(long, short, value_enum)]
+        status: Option<CourseStatusDO>,
+        /// List the courses of all semesters
+        #[arg(long, short, conflicts_with = "semester")]
+        all: bool,
+        /// List the courses of this semester instead of the active one
+        #[arg(long)]
+        semester: Option<String>,
+        /// Order of the courses within the list
+        #[arg(long, value_enum, default_value = "name")]
+        sort: CourseSortDO,
+        /// Only list courses with a grade
+        #[arg(long, conflicts_with = "ungraded")]
+        graded: bool,
+        /// Only list courses without a grade
+        #[arg(long)]
+        ungraded: bool,
+        /// Only list courses counting towards this degree
+        #[arg(long)]
+        degree: Option<String>,
+        /// Only list courses marked with üBK
+        #[arg(long)]
+        uebk: bool,
+        /// Show the grade and ECTS of each course
+        #[arg(long, short)]
+        details: bool,
+    },
+    Add {
+        #[arg(value_name = "COURSE_NAME", required = true)]
+        names: Vec<String>,
+        /// Folder template from the template directory. Defaults to `course` if given without name
+        #[arg(long, short, num_args = 0..=1, default_missing_value = "course")]
+        template: Option<String>,
+        /// Clone a git repository with course material into the course
+        #[arg(long, value_name = "URL")]
+        git: Option<String>,
+        /// Folder for the repository relative to the course, `.` for the course folder itself.
+        /// Defaults to `material`
+        #[arg(long, requires = "git", value_name = "DIR")]
+        git_dir: Option<String>,
+    },
+    #[command(about = "Pull the material repositories of courses")]
+    Update {
+        #[arg(value_name = "COURSE_REF")]
+        course: Option<String>,
+        /// Update the courses of all semesters instead of the active one
+        #[arg(long, short)]
+        all: bool,
+    },
+    Remove {
+        #[arg(value_name = "COURSE_NAME", required = true)]
+        names: Vec<String>,
+        /// Remove the courses without confirmation, even if they are locked
+        #[arg(long, short)]
+        force: bool,
+    },
+    #[command(
+        about = "Copy the folders and settings of a course without its results into another semester"
+    )]
+    Copy {
+        /// Course reference (`semester/course` or `course`)
+        course: String,
+        /// Semester the course is copied to
+        semester: String,
+        /// Folder name of the copy, e.g. `Analysis2`. Defaults to the name of the course folder
+        #[arg(long)]
+        name: Option<String>,
+    },
+    #[command(about = "Move a course to another semester, e.g. when it is postponed")]
+    Move {
+        /// Course reference (`semester/course` or `course`)
+        course: String,
+        /// Semester the course is moved to
+        #[arg(long)]
+        to: String,
+        /// Move the course even if it is locked
+        #[arg(long, short)]
+        force: bool,
+    },
+    #[command(
+        about = "Set the final grade of a course or the grade of one of its components. This locks the course"
+    )]
+    Grade {
+        grade: f32,
+        /// Grade a component from `course.toml` instead. The final grade is computed once all
+        /// weighted components are graded
+        #[arg(long, value_name = "NAME")]
+        component: Option<String>,
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short)]
+        course: Option<String>,
+        /// Change the grade even if the course is locked
+        #[arg(long, short)]
+        force: bool,
+    },
+    #[command(about = "Set the status of a course, e.g. when dropping it")]
+    Status {
+        #[arg(value_enum)]
+        status: CourseStatusDO,
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short)]
+        course: Option<String>,
+        /// Change the status even if the course is locked
+        #[arg(long, short)]
+        force: bool,
+    },
+    #[command(about = "Protect a course against changes")]
+    Lock {
+        #[arg(value_name = "COURSE_REF")]
+        course: Option<String>,
+    },
+    #[command(about = "Allow changes to a locked course again")]
+    Unlock {
+        #[arg(value_name = "COURSE_REF")]
+        course: Option<String>,
+    },
+    #[command(about = "Show the settings and results of a course")]
+    Show {
+        #[arg(value_name = "COURSE_REF")]
+        course: Option<String>,
+    },
+    #[command(about = "Show the prerequisites of a course as a tree")]
+    Deps {
+        #[arg(value_name = "COURSE_REF")]
+        course: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum FlashcardsCommands {
+    #[command(about = "Write the Q:/A: cards of the notes and flashcards.toml as Anki TSV")]
+    Export {
+        /// Defaults to `flashcards.tsv` in the course folder
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LitCommands {
     List,
+    #[command(about = "Add a reference by DOI or URL, fetching title, authors and year")]
     Add {
-        #[arg(value_name = "COURSE_NAME")]
+        #[arg(value_name = "DOI_OR_URL")]
+        source: String,
+        /// Download the PDF into the `literature` folder of the course
+        #[arg(long)]
+        pdf: bool,
+    },
+    #[command(about = "Add the references of a BibTeX file, e.g. a Zotero export")]
+    Import {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+    Remove {
+        key: String,
+    },
+    #[command(about = "Write the literature as a .bib file")]
+    Export {
+        #[arg(long, required = true)]
+        bibtex: bool,
+        /// Defaults to `literature.bib` in the course folder
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PdfCommands {
+    #[command(about = "Rename slides after the slide_pattern of the config so they sort by week")]
+    Normalize {
+        /// Folder inside the course holding the slides
+        #[arg(long, short, default_value = "lectures")]
+        dir: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExerciseCommands {
+    List,
+    #[command(about = "Create the folder of a new sheet, named after the last one by default")]
+    Add {
+        name: Option<String>,
+        /// Due date formatted as YYYY-MM-DD, added as exercise deadline
+        #[arg(long)]
+        due: Option<Date>,
+    },
+    #[command(
+        about = "Download a sheet from the exercise_url of the course, the latest by default"
+    )]
+    Fetch {
+        name: Option<String>,
+    },
+    Remove {
         name: String,
     },
+    #[command(about = "Rename a sheet. With a single name the latest sheet is renamed")]
+    Move {
+        from: String,
+        to: Option<String>,
+    },
+    #[command(about = "Record the points of a sheet, e.g. 'mm exercise points ex03 18/20'")]
+    Points {
+        sheet: String,
+        #[arg(value_name = "POINTS/MAX")]
+        points: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TodoCommands {
+    List {
+        /// Include tasks that are already done
+        #[arg(long, short)]
+        all: bool,
+    },
+    Add {
+        title: String,
+        /// Due date formatted as YYYY-MM-DD
+        #[arg(long)]
+        due: Option<Date>,
+    },
+    Done {
+        /// Number of the task as listed, starting at 1
+        id: NonZeroUsize,
+    },
     Remove {
+        /// Number of the task as listed, starting at 1
+        id: NonZeroUsize,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DeadlineCommands {
+    List {
+        /// Include deadlines that have already passed
+        #[arg(long, short)]
+        all: bool,
+    },
+    Add {
+        title: String,
+        /// Date formatted as YYYY-MM-DD
+        #[arg(long)]
+        date: Date,
+        #[arg(long, default_value = "exercise")]
+        kind: DeadlineKindDO,
+    },
+    #[command(about = "Send upcoming deadlines to the channel configured in [notifications]")]
+    Remind {
+        /// Days ahead to announce, overrides `days` of the config
+        #[arg(long, short)]
+        days: Option<i64>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExamCommands {
+    List {
+        /// Include past exams
+        #[arg(long, short)]
+        all: bool,
+    },
+    Add {
+        #[arg(value_name = "COURSE_REF")]
+        course: String,
+        /// Date formatted as YYYY-MM-DD
+        #[arg(long)]
+        date: Date,
+        #[arg(long)]
+        location: Option<String>,
+        /// Defaults to the attempt after the last registered one
+        #[arg(long)]
+        attempt: Option<u8>,
+    },
+    #[command(
+        about = "Record an exam result. The passed attempt chosen by 'attempt_policy' becomes the final grade"
+    )]
+    Grade {
+        #[arg(value_name = "COURSE_REF")]
+        course: String,
+        grade: f32,
+        /// Defaults to the latest attempt
+        #[arg(long)]
+        attempt: Option<u8>,
+        /// Record the result even if the course is locked
+        #[arg(long, short)]
+        force: bool,
+    },
+    #[command(about = "Show all exam attempts of a course and which one counts")]
+    Attempts {
+        #[arg(value_name = "COURSE_REF")]
+        course: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ScheduleCommands {
+    Show,
+    #[command(about = "Add a weekly lecture or tutorial slot to a course")]
+    Add {
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short)]
+        course: Option<String>,
+        /// e.g. "mon" or "monday"
+        weekday: Weekday,
+        /// Formatted as HH:MM
+        start: Time,
+        /// Formatted as HH:MM
+        end: Time,
+        #[arg(long)]
+        room: Option<String>,
+        #[arg(long, default_value = "lecture")]
+        kind: SlotKindDO,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CalendarCommands {
+    #[command(about = "Write an .ics file. Prints the calendar if no file is given")]
+    Export {
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+    },
+    #[command(about = "Import lecture slots and exam dates from an .ics file or URL")]
+    Import {
+        #[arg(value_name = "URL_OR_FILE")]
+        source: String,
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short)]
+        course: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GetCommands {
+    #[command(about = "Folder name of the active course")]
+    CurrentCourse,
+    #[command(about = "Folder name of the active semester")]
+    CurrentSemester,
+    #[command(about = "Path to the entry point")]
+    EntryPoint,
+    #[command(about = "Weighted average over all courses or a single degree")]
+    Average {
+        #[arg(long)]
+        degree: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DemoCommands {
+    Create {
+        #[arg(value_name = "PATH")]
+        path: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TrackCommands {
+    #[command(about = "Start a study session")]
+    Start,
+    #[command(about = "Stop the running study session")]
+    Stop,
+    #[command(about = "Discard the running study session")]
+    Cancel,
+    #[command(about = "Add a session manually, e.g. `1h30m`, `45m` or `1:30`")]
+    Add {
+        duration: String,
+        /// Day of the session. Defaults to today.
+        #[arg(long, short)]
+        date: Option<Date>,
+    },
+    #[command(about = "Show hours spent per course and week")]
+    Report {
+        /// Number of weeks to show
+        #[arg(long, short, default_value_t = 4)]
+        weeks: usize,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum NoteCommands {
+    #[command(about = "Create a dated note from a template")]
+    New {
+        #[arg(default_value = "lecture")]
+        title: String,
+        #[arg(long, short, value_enum)]
+        format: Option<NoteFormatDO>,
+    },
+    List,
+    #[command(about = "Open a note in $EDITOR")]
+    Open {
+        /// `latest` or a part of the file name
+        #[arg(default_value = "latest")]
+        note: String,
+    },
+    #[command(about = "Compile a LaTeX note or the main document with latexmk or tectonic")]
+    Compile {
+        /// `latest`, `main` or a part of the file name
+        #[arg(default_value = "latest")]
+        note: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MaterialsCommands {
+    #[command(about = "Report changed, missing and corrupted files and record new ones")]
+    Verify {
+        /// Check the courses of all semesters
+        #[arg(long, short)]
+        all: bool,
+    },
+    #[command(about = "Accept the current files and rewrite the manifest")]
+    Update {
+        /// Update the courses of all semesters
+        #[arg(long, short)]
+        all: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExportCommands {
+    #[command(about = "Graph of degrees, semesters and courses for Graphviz or Mermaid")]
+    Graph {
+        #[arg(long, short, value_enum, default_value = "dot")]
+        format: GraphFormatDO,
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CatalogCommands {
+    #[command(
+        about = "Import a module catalog from a TOML or JSON file with code, name, ects, category, semester and required per module"
+    )]
+    Import {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PlanCommands {
+    #[command(about = "Plan a course for a future semester without creating folders")]
+    Add {
+        /// Name of the future semester, e.g. `b04`
+        semester: String,
         #[arg(value_name = "COURSE_NAME")]
         name: String,
+        /// Defaults to the ECTS of the module with this name in the catalog
+        #[arg(long)]
+        ects: Option<u8>,
+        /// Degree the course counts towards, can be given several times
+        #[arg(long)]
+        degree: Vec<String>,
+        /// Module number in the catalog, defaults to the code of the module with this name
+        #[arg(long)]
+        module_id: Option<String>,
+    },
+    #[command(about = "Remove a planned course or, without course, the whole planned semester")]
+    Remove {
+        semester: String,
+        #[arg(value_name = "COURSE_NAME")]
+        name: Option<String>,
     },
+    #[command(about = "List the planned semesters with their courses and ECTS")]
+    Show,
+    #[command(about = "Create the folders of a planned semester and its courses")]
+    Apply { semester: String },
 }
 
 #[derive(Debug, Subcommand)]
-pub enum ExerciseCommands {
+pub enum DegreeCommands {
+    #[command(about = "List degrees with their earned and required ECTS")]
+    List,
+    #[command(about = "Show earned and required ECTS per category of a degree")]
+    Show {
+        /// Name or part of the name of the degree. Defaults to the only configured degree
+        #[arg(value_name = "DEGREE")]
+        name: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum GradesCommands {
+    #[command(
+        about = "Fill in grades and ECTS from a CSV or spreadsheet export (HISinOne, CampusOnline)"
+    )]
+    Import {
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+        /// Write the changes without review
+        #[arg(long, short)]
+        yes: bool,
+        /// Also change the grades of locked courses
+        #[arg(long, short)]
+        force: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum MoodleCommands {
+    #[command(about = "List enrolled Moodle courses and their linked local courses")]
+    Courses,
+    #[command(about = "Link a course to a Moodle course. Without id courses are matched by name")]
+    Link {
+        #[arg(value_name = "MOODLE_ID")]
+        id: Option<u64>,
+        /// Course reference (`course` or `semester/course`). Defaults to the active course.
+        #[arg(long, short)]
+        course: Option<String>,
+    },
+    #[command(about = "Import assignment due dates of linked courses as deadlines")]
+    Import {
+        /// Import for the courses of all semesters instead of the active one
+        #[arg(long, short)]
+        all: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BackupCommands {
+    #[command(about = "List existing backups")]
     List,
-    Add { name: Option<String> },
-    Remove { name: String },
-    Move { from: Option<String>, to: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SyncCommands {
+    #[command(about = "Copy local changes to the remote")]
+    Push {
+        /// Overwrite files changed on both sides with the local version.
+        #[arg(long, short)]
+        force: bool,
+    },
+    #[command(about = "Copy remote changes to the local store")]
+    Pull {
+        /// Overwrite files changed on both sides with the remote version.
+        #[arg(long, short)]
+        force: bool,
+    },
+    #[command(about = "Show files which differ between the store and the remote")]
+    Status,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DebugCommands {
+    #[command(about = "Write a diagnostics bundle (command line, config, store summary)")]
+    Bundle {
+        /// Output file. Defaults to a new file in the temp directory.
+        #[arg(long, short)]
+        out: Option<PathBuf>,
+    },
+    #[command(about = "Show store timings of the last command run with -v")]
+    Timings,
+    #[command(about = "Find empty or corrupt data files and offer to regenerate them")]
+    Repair,
 }
 
 #[derive(Debug, Subcommand)]
@@ -101,3 +971,63 @@ impl fmt::Display for StudyCycleDO {
         write!(f, "{}", cycle_str)
     }
 }
+
+#[derive(Debug, Serialize, Deserialize, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum CourseStatusDO {
+    Planned,
+    Enrolled,
+    Passed,
+    Failed,
+    Dropped,
+}
+
+#[derive(Debug, Serialize, Deserialize, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum CourseSortDO {
+    Name,
+    /// Best grade first
+    Grade,
+    /// Most ECTS first
+    Ects,
+    Status,
+}
+
+#[derive(Debug, Serialize, Deserialize, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum DeadlineKindDO {
+    Exercise,
+    Exam,
+    Project,
+}
+
+#[derive(Debug, Serialize, Deserialize, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum SlotKindDO {
+    Lecture,
+    Tutorial,
+}
+
+#[derive(Debug, Serialize, Deserialize, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum SearchScopeDO {
+    Course,
+    Semester,
+    All,
+}
+
+#[derive(Debug, Serialize, Deserialize, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum NoteFormatDO {
+    Md,
+    Tex,
+}
+
+/// Grade scales of `mm status --scale`, see `[grade_scale]` in the config file.
+#[derive(Debug, Serialize, Deserialize, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum ScaleDO {
+    /// US grade point average by the modified Bavarian formula
+    Gpa,
+    /// ECTS letter grades from A to F
+    Ects,
+}
+
+#[derive(Debug, Serialize, Deserialize, ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum GraphFormatDO {
+    Dot,
+    Mermaid,
+}
@@ -0,0 +1,118 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::MmError;
+
+use super::{
+    date::{now_utc, Date, Time},
+    paths::is_read_only,
+};
+
+/// Name of the audit log in the entry point.
+pub const AUDIT_LOG: &str = ".mm-history";
+
+/// Append-only log of the changes to the store, one line per command with the time in UTC, the
+/// change and the command line, separated by tabs. Unlike [History](super::History) it does not
+/// require git and is always written.
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    date: Date,
+    time: Time,
+    change: String,
+    command: String,
+}
+
+impl AuditLog {
+    pub fn new(entry_point: &Path) -> AuditLog {
+        AuditLog {
+            path: entry_point.join(AUDIT_LOG),
+        }
+    }
+
+    /// Appends the change made by the running command.
+    pub fn record(&self, change: &str) -> Result<()> {
+        if is_read_only() {
+            bail!(MmError::ReadOnly);
+        }
+        let (date, time) = now_utc();
+        let command = std::iter::once("mm".to_string())
+            .chain(std::env::args().skip(1).map(|it| quote(&it)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let line = format!(
+            "{} {}\t{}\t{}\n",
+            date,
+            time,
+            clean(change),
+            clean(&command)
+        );
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .with_context(|| anyhow!("Failed to write audit log: {}", self.path.display()))
+    }
+
+    /// All entries, oldest first. Lines which can not be parsed are skipped.
+    pub fn entries(&self) -> Result<Vec<AuditEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = std::fs::read_to_string(&self.path)
+            .with_context(|| anyhow!("Failed to read audit log: {}", self.path.display()))?;
+        let entries = content
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, '\t');
+                let (date, time) = parts.next()?.split_once(' ')?;
+                Some(AuditEntry {
+                    date: date.parse().ok()?,
+                    time: time.parse().ok()?,
+                    change: parts.next()?.to_string(),
+                    command: parts.next().unwrap_or_default().to_string(),
+                })
+            })
+            .collect();
+        Ok(entries)
+    }
+}
+
+impl AuditEntry {
+    pub fn date(&self) -> &Date {
+        &self.date
+    }
+
+    pub fn time(&self) -> &Time {
+        &self.time
+    }
+
+    pub fn change(&self) -> &str {
+        &self.change
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+}
+
+/// Keeps every entry on a single line.
+fn clean(text: &str) -> String {
+    text.replace(['\t', '\n', '\r'], " ")
+}
+
+pub(super) fn quote(arg: &str) -> String {
+    match arg.contains(char::is_whitespace) || arg.is_empty() {
+        true => format!("'{}'", arg.replace('\'', "'\\''")),
+        false => arg.to_string(),
+    }
+}
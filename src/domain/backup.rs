@@ -0,0 +1,326 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Mutex,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use walkdir::WalkDir;
+
+use super::{audit::quote, date::now, effects, paths::EntryPoint, progress::Progress};
+
+/// Metadata files written by mm.
+const METADATA_FILES: [&str; 2] = [".mm", "course.toml"];
+const PREFIX: &str = "mm-backup-";
+const FULL_SUFFIX: &str = "-full";
+const SNAPSHOT_DIR: &str = "snapshots";
+const SNAPSHOT_PREFIX: &str = "mm-snapshot-";
+
+/// Snapshots taken by the running command, never removed by the rotation so that a command
+/// removing many folders keeps all of them restorable.
+static CREATED_SNAPSHOTS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Where backups are stored and how many of each kind are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupSettings {
+    dir: PathBuf,
+    keep: usize,
+    snapshot_keep: usize,
+}
+
+/// Archive of a semester or course folder taken before it is removed or moved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    path: PathBuf,
+    entry_point: PathBuf,
+}
+
+/// A backup tarball named `mm-backup-<date>-<time>[-<counter>][-full].tar.gz`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Backup {
+    /// `<date>-<time>` and counter of backups created in the same minute, used for ordering.
+    created: (String, u32),
+    path: PathBuf,
+    full: bool,
+    size: u64,
+}
+
+impl BackupSettings {
+    pub fn new(dir: PathBuf, keep: usize, snapshot_keep: usize) -> BackupSettings {
+        BackupSettings {
+            dir,
+            keep,
+            snapshot_keep,
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Archives the metadata files or, with `full`, the whole entry point and removes the oldest
+    /// backups of the same kind exceeding the retention limit. Returns the new backup and the
+    /// removed ones.
+    pub fn create(&self, entry_point: &EntryPoint, full: bool) -> Result<(Backup, Vec<Backup>)> {
+        effects::create_dir_all(&self.dir).with_context(|| {
+            anyhow!(
+                "Failed to create backup directory at: {}",
+                self.dir.display()
+            )
+        })?;
+
+        let (date, time) = now();
+        let suffix = if full { FULL_SUFFIX } else { "" };
+        let stem = format!("{}{}-{:02}{:02}", PREFIX, date, time.hour(), time.minute());
+        // metadata and full backups share the counter to keep the listing chronological
+        let mut name = stem.clone();
+        let mut counter = 2;
+        while [name.clone(), format!("{}{}", name, FULL_SUFFIX)]
+            .iter()
+            .any(|it| self.dir.join(format!("{}.tar.gz", it)).exists())
+        {
+            name = format!("{}-{}", stem, counter);
+            counter += 1;
+        }
+        let path = self.dir.join(format!("{}{}.tar.gz", name, suffix));
+
+        let mut command = Command::new("tar");
+        command.arg("-czf").arg(&path);
+        if full {
+            let parent = entry_point
+                .parent()
+                .ok_or_else(|| anyhow!("Entry point has no parent: {}", entry_point.display()))?;
+            let name = entry_point
+                .file_name()
+                .ok_or_else(|| anyhow!("Invalid entry point: {}", entry_point.display()))?;
+            if let Ok(inside) = self.dir.strip_prefix(parent) {
+                command.arg(format!("--exclude={}", inside.display()));
+            }
+            command.arg("-C").arg(parent).arg(name);
+        } else {
+            let files = self.metadata_files(entry_point);
+            if files.is_empty() {
+                bail!("No metadata files found in: {}", entry_point.display());
+            }
+            command.arg("-C").arg(entry_point.as_path()).args(files);
+        }
+        let progress = Progress::spinner(match full {
+            true => "Creating full backup",
+            false => "Creating backup",
+        });
+        let status =
+            effects::status(&mut command).context("Failed to run 'tar'. Is it installed?")?;
+        drop(progress);
+        if !status.success() {
+            let _ = effects::remove_file(&path);
+            bail!("'tar' failed to create backup at: {}", path.display());
+        }
+
+        let backup =
+            Backup::from_path(path).ok_or_else(|| anyhow!("Failed to read created backup"))?;
+        let removed = self.rotate(full)?;
+        Ok((backup, removed))
+    }
+
+    /// Archives `folder` below the entry point into `snapshots/` of the backup directory, named
+    /// `mm-snapshot-<date>-<time>-<folder>.tar.gz`. The oldest snapshots exceeding the snapshot
+    /// retention limit are removed, snapshots of the running command are always kept.
+    pub fn snapshot(&self, entry_point: &EntryPoint, folder: &Path) -> Result<Snapshot> {
+        let relative = folder
+            .strip_prefix(entry_point.as_path())
+            .with_context(|| {
+                anyhow!(
+                    "Folder is not part of the entry point: {}",
+                    folder.display()
+                )
+            })?;
+        let dir = self.dir.join(SNAPSHOT_DIR);
+        effects::create_dir_all(&dir).with_context(|| {
+            anyhow!("Failed to create snapshot directory at: {}", dir.display())
+        })?;
+
+        let (date, time) = now();
+        let stem = format!(
+            "{}{}-{:02}{:02}-{}",
+            SNAPSHOT_PREFIX,
+            date,
+            time.hour(),
+            time.minute(),
+            relative.display().to_string().replace('/', "_")
+        );
+        let mut path = dir.join(format!("{}.tar.gz", stem));
+        let mut counter = 2;
+        while path.exists() {
+            path = dir.join(format!("{}-{}.tar.gz", stem, counter));
+            counter += 1;
+        }
+
+        let progress = Progress::spinner(format!("Saving a snapshot of {}", relative.display()));
+        let status = effects::status(
+            Command::new("tar")
+                .arg("-czf")
+                .arg(&path)
+                .arg("-C")
+                .arg(entry_point.as_path())
+                .arg(relative),
+        )
+        .context("Failed to run 'tar'. Is it installed?")?;
+        drop(progress);
+        if !status.success() {
+            let _ = effects::remove_file(&path);
+            bail!("'tar' failed to create snapshot at: {}", path.display());
+        }
+
+        CREATED_SNAPSHOTS
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(path.clone());
+        self.rotate_snapshots(&dir)?;
+        Ok(Snapshot {
+            path,
+            entry_point: entry_point.to_path_buf(),
+        })
+    }
+
+    fn rotate_snapshots(&self, dir: &Path) -> Result<()> {
+        if !dir.is_dir() {
+            return Ok(());
+        }
+        let mut snapshots = std::fs::read_dir(dir)
+            .with_context(|| anyhow!("Failed to read snapshots at: {}", dir.display()))?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| name.starts_with(SNAPSHOT_PREFIX))
+            })
+            .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+            .collect::<Vec<_>>();
+        snapshots.sort();
+        let excess = snapshots.len().saturating_sub(self.snapshot_keep);
+        let created = CREATED_SNAPSHOTS
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone();
+        let removable = snapshots
+            .into_iter()
+            .take(excess)
+            .filter(|(_, path)| !created.contains(path));
+        for (_, path) in removable {
+            effects::remove_file(&path)
+                .with_context(|| anyhow!("Failed to remove old snapshot: {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    /// All backups, oldest first.
+    pub fn list(&self) -> Result<Vec<Backup>> {
+        if !self.dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut backups = std::fs::read_dir(&self.dir)
+            .with_context(|| anyhow!("Failed to read backups at: {}", self.dir.display()))?
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| Backup::from_path(entry.path()))
+            .collect::<Vec<_>>();
+        backups.sort();
+        Ok(backups)
+    }
+
+    fn rotate(&self, full: bool) -> Result<Vec<Backup>> {
+        let backups = self
+            .list()?
+            .into_iter()
+            .filter(|it| it.full == full)
+            .collect::<Vec<_>>();
+        let excess = backups.len().saturating_sub(self.keep);
+        let removed = backups.into_iter().take(excess).collect::<Vec<_>>();
+        for backup in &removed {
+            effects::remove_file(&backup.path).with_context(|| {
+                anyhow!("Failed to remove old backup: {}", backup.path.display())
+            })?;
+        }
+        Ok(removed)
+    }
+
+    /// Metadata files relative to the entry point, excluding the backup directory.
+    fn metadata_files(&self, entry_point: &EntryPoint) -> Vec<PathBuf> {
+        WalkDir::new(entry_point.as_path())
+            .max_depth(3)
+            .into_iter()
+            .filter_entry(|entry| entry.path() != self.dir)
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter(|entry| METADATA_FILES.iter().any(|name| entry.file_name() == *name))
+            .filter_map(|entry| {
+                entry
+                    .path()
+                    .strip_prefix(entry_point.as_path())
+                    .ok()
+                    .map(Path::to_path_buf)
+            })
+            .collect()
+    }
+}
+
+impl Backup {
+    fn from_path(path: PathBuf) -> Option<Backup> {
+        let name = path.file_name()?.to_str()?;
+        let stem = name.strip_prefix(PREFIX)?.strip_suffix(".tar.gz")?;
+        let (stem, full) = match stem.strip_suffix(FULL_SUFFIX) {
+            Some(stem) => (stem, true),
+            None => (stem, false),
+        };
+        // `<date>-<time>` has a fixed length of 15 characters
+        let created = match stem.split_at_checked(15)? {
+            (created, "") => (created.to_string(), 1),
+            (created, counter) => (
+                created.to_string(),
+                counter.strip_prefix('-')?.parse().ok()?,
+            ),
+        };
+        // a backup planned by `mm --dry-run` does not exist yet
+        let size = path.metadata().map_or(0, |it| it.len());
+        Some(Backup {
+            created,
+            path,
+            full,
+            size,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn file_name(&self) -> &str {
+        self.path
+            .file_name()
+            .and_then(|it| it.to_str())
+            .unwrap_or_default()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.full
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl Snapshot {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Shell command which extracts the snapshot back into the entry point.
+    pub fn restore_command(&self) -> String {
+        format!(
+            "tar -xzf {} -C {}",
+            quote(&self.path.display().to_string()),
+            quote(&self.entry_point.display().to_string())
+        )
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    course::{normalize_name, Course},
+    effects,
+    paths::{EntryPoint, ReadWriteDO},
+};
+
+/// Module catalog of the degree program, imported with `mm catalog import` and stored in
+/// `catalog.toml` at the entry point.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Catalog {
+    #[serde(default)]
+    modules: Vec<Module>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Module {
+    code: Option<String>,
+    name: String,
+    ects: u8,
+    category: Option<String>,
+    /// Semester the module is recommended for in the study plan.
+    semester: Option<u16>,
+    /// Whether the module has to be taken, e.g. compulsory modules.
+    #[serde(default)]
+    required: bool,
+    /// Modules which have to be passed first, by name or code.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    prerequisites: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CatalogFile(PathBuf);
+
+/// JSON exports list the modules at the top level or in `modules`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CatalogJson {
+    Modules(Vec<Module>),
+    Catalog(Catalog),
+}
+
+impl Catalog {
+    pub fn file(entry_point: &EntryPoint) -> CatalogFile {
+        CatalogFile(entry_point.join("catalog.toml"))
+    }
+
+    /// The imported catalog or an empty one.
+    pub fn load(entry_point: &EntryPoint) -> Result<Catalog> {
+        let file = Catalog::file(entry_point);
+        match file.is_file() {
+            true => file.read(),
+            false => Ok(Catalog::default()),
+        }
+    }
+
+    /// Reads a catalog from a TOML or JSON file, chosen by its extension.
+    pub fn read(path: &Path) -> Result<Catalog> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| anyhow!("Failed to read: {}", path.display()))?;
+        let catalog = match path.extension().and_then(|it| it.to_str()) {
+            Some("json") => match serde_json::from_str::<CatalogJson>(&content)
+                .with_context(|| anyhow!("Failed to parse catalog from: {}", path.display()))?
+            {
+                CatalogJson::Modules(modules) => Catalog { modules },
+                CatalogJson::Catalog(catalog) => catalog,
+            },
+            Some("toml") => toml_edit::de::from_str::<Catalog>(&content)
+                .with_context(|| anyhow!("Failed to parse catalog from: {}", path.display()))?,
+            _ => bail!("Catalogs must be .toml or .json files"),
+        };
+        if catalog.modules.is_empty() {
+            bail!("The catalog does not contain any modules");
+        }
+        Ok(catalog)
+    }
+
+    pub fn save(&self, entry_point: &EntryPoint) -> Result<()> {
+        Catalog::file(entry_point).write(self)
+    }
+
+    pub fn modules(&self) -> &Vec<Module> {
+        &self.modules
+    }
+}
+
+impl Module {
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn ects(&self) -> u8 {
+        self.ects
+    }
+
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    pub fn semester(&self) -> Option<u16> {
+        self.semester
+    }
+
+    pub fn is_required(&self) -> bool {
+        self.required
+    }
+
+    pub fn prerequisites(&self) -> &Vec<String> {
+        &self.prerequisites
+    }
+
+    /// Whether a prerequisite, which names a module by its name or code, means this module.
+    pub fn is_named(&self, prerequisite: &str) -> bool {
+        self.code
+            .as_ref()
+            .is_some_and(|it| it.eq_ignore_ascii_case(prerequisite))
+            || normalize_name(&self.name) == normalize_name(prerequisite)
+    }
+
+    /// A course is this module if its `module_id` is the code of the module or, without code,
+    /// if the names match.
+    pub fn matches(&self, course: &Course) -> bool {
+        if let (Some(code), Some(id)) = (&self.code, course.module_id()) {
+            return code.eq_ignore_ascii_case(id);
+        }
+        normalize_name(&self.name) == normalize_name(&course.name())
+    }
+}
+
+impl Deref for CatalogFile {
+    type Target = PathBuf;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl ReadWriteDO for CatalogFile {
+    type Object = Catalog;
+
+    fn write(&self, object: &Self::Object) -> Result<()> {
+        let data = toml_edit::ser::to_string_pretty(&object).with_context(|| {
+            anyhow!(
+                "Failed to serialize data to toml for: {}",
+                self.deref().display()
+            )
+        })?;
+        effects::write(self.deref(), data)
+            .with_context(|| anyhow!("Failed to write data to file: {}", self.deref().display()))
+    }
+}
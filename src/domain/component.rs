@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+
+use super::{course::serialize_grade, exam::PASSING_GRADE};
+
+/// A part of the final grade of a course, e.g. the exam or a project, stored in the
+/// `course.toml` of a course.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Serialize, Deserialize)]
+pub struct Component {
+    name: String,
+    /// Relative weight, e.g. 70 for an exam making up 70% of the grade.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    weight: f32,
+    /// Bonus components improve the final grade by their grade, e.g. 0.3 for exercise points.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    bonus: bool,
+    #[serde(
+        serialize_with = "serialize_grade",
+        skip_serializing_if = "Option::is_none"
+    )]
+    grade: Option<f32>,
+}
+
+impl Component {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The component of a copied course, which has not been graded yet.
+    pub(super) fn without_grade(&self) -> Component {
+        Component {
+            grade: None,
+            ..self.clone()
+        }
+    }
+
+    pub fn is_bonus(&self) -> bool {
+        self.bonus
+    }
+
+    pub fn grade(&self) -> Option<f32> {
+        self.grade
+    }
+
+    pub(super) fn set_grade(&mut self, grade: f32) {
+        self.grade = Some(grade);
+    }
+}
+
+fn is_zero(weight: &f32) -> bool {
+    *weight == 0.0
+}
+
+/// The final grade once every weighted component is graded: the weighted mean, improved by the
+/// bonus components if it is a passing grade, rounded to one decimal and never better than 1.0.
+pub fn final_grade(components: &[Component]) -> Option<f32> {
+    let weighted = components.iter().filter(|it| !it.bonus).collect::<Vec<_>>();
+    let total = weighted.iter().map(|it| it.weight).sum::<f32>();
+    if weighted.is_empty() || total <= 0.0 {
+        return None;
+    }
+    let sum = weighted
+        .iter()
+        .map(|it| Some(it.grade? * it.weight))
+        .sum::<Option<f32>>()?;
+    let mut grade = sum / total;
+    if grade <= PASSING_GRADE {
+        let bonus = components
+            .iter()
+            .filter(|it| it.bonus)
+            .filter_map(|it| it.grade)
+            .sum::<f32>();
+        grade = (grade - bonus).max(1.0);
+    }
+    Some((grade * 10.0).round() / 10.0)
+}
@@ -1,21 +1,105 @@
 use anyhow::{anyhow, bail, Context, Result};
 use regex::Regex;
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
 
-use crate::ConfigProvider;
+use crate::{ConfigProvider, MmError};
 
 use super::{
-    paths::{EntryPoint, MaybeSymLinkable},
-    semester::StudyCycle,
+    backup::BackupSettings,
+    degree::Degree,
+    exam::AttemptPolicy,
+    history::GitMode,
+    inbox::{Inbox, InboxRule},
+    language::Language,
+    moodle::MoodleSettings,
+    notify::{NotificationBackend, NotificationSettings},
+    paths::{fill_number, EntryPoint, Ignore, Link, LinkStrategy, LinkTarget, MaybeSymLinkable},
+    scale::GradeScale,
+    semester::{StudyCycle, Term},
+    sync::{SyncSettings, SyncTool},
+    theme::Theme,
 };
 
+/// Config file given by `mm --config`, replacing the default location.
+static CONFIG_FILE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
 #[derive(Debug, serde::Deserialize)]
 struct ConfigDO {
     entry_point: String,
     semster_names: Option<String>,
+    semester_name_format: Option<String>,
     study_cycle_mapping: Option<StudyCycleMappingDO>,
+    calendar_semesters: Option<StudyCycleMappingDO>,
     semester_link: Option<PathBuf>,
     course_link: Option<PathBuf>,
+    link_strategy: Option<LinkStrategy>,
+    ignore: Option<Vec<String>>,
+    #[serde(default)]
+    links: Vec<LinkDO>,
+    template_dir: Option<PathBuf>,
+    backup_dir: Option<PathBuf>,
+    backup_keep: Option<usize>,
+    snapshot_keep: Option<usize>,
+    sync: Option<SyncDO>,
+    git: Option<GitMode>,
+    moodle: Option<MoodleDO>,
+    prompt_format: Option<String>,
+    #[serde(default)]
+    degrees: Vec<Degree>,
+    grade_scale: Option<GradeScale>,
+    theme: Option<Theme>,
+    language: Option<Language>,
+    attempt_policy: Option<AttemptPolicy>,
+    inbox: Option<PathBuf>,
+    #[serde(default)]
+    inbox_rules: Vec<InboxRuleDO>,
+    slide_pattern: Option<String>,
+    notifications: Option<NotificationsDO>,
+    #[serde(default)]
+    auto_switch: bool,
+    #[serde(default)]
+    log: bool,
+    ects_budget: Option<u32>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct LinkDO {
+    name: String,
+    path: PathBuf,
+    target: LinkTarget,
+    strategy: Option<LinkStrategy>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct NotificationsDO {
+    backend: Option<NotificationBackend>,
+    url: String,
+    token: Option<String>,
+    days: Option<i64>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct InboxRuleDO {
+    pattern: String,
+    folder: String,
+    #[serde(default)]
+    rename: bool,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct MoodleDO {
+    url: String,
+    token: String,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct SyncDO {
+    tool: Option<SyncTool>,
+    remote: String,
 }
 
 #[derive(Debug, serde::Deserialize, Clone)]
@@ -25,15 +109,50 @@ struct StudyCycleMappingDO {
     doctorate: Option<String>,
 }
 
-pub(crate) struct Config {
+/// Settings read from `mm/config.toml` in the config directory, see [Config::new].
+pub struct Config {
     /// The path to the directory where the university data is stored.
     entry_point: EntryPoint,
-    /// A regex pattern to match the names of the semesters.
+    /// How the names of the semester folders are parsed and formatted.
     semester_names: SemesterNames,
-    /// Path to optional symlink to the current semester folder.
-    semester_link: MaybeSymLinkable,
-    /// Path to optional symlink to the current course folder.
-    course_link: MaybeSymLinkable,
+    /// Links to the current semester and course folders, including `semester_link` and
+    /// `course_link`.
+    links: Vec<Link>,
+    /// Directory holding note and course templates. Defaults to `templates` next to the config file.
+    template_dir: PathBuf,
+    /// Where `mm backup` stores its tarballs and the snapshots of removed folders, and how many of
+    /// each kind are kept.
+    backup: BackupSettings,
+    /// Remote for `mm sync`, if configured.
+    sync: Option<SyncSettings>,
+    /// Files committed to a git repository at the entry point after every mutating command.
+    git: Option<GitMode>,
+    /// Moodle instance for `mm moodle`, if configured.
+    moodle: Option<MoodleSettings>,
+    /// Format of `mm prompt` with the placeholders `{{semester}}`, `{{course}}` and `{{course_name}}`.
+    prompt_format: String,
+    /// Degrees with their ECTS requirements.
+    degrees: Vec<Degree>,
+    /// Conversion of grades for `mm status --scale`.
+    grade_scale: GradeScale,
+    /// Colors of messages and grades.
+    theme: Theme,
+    /// Language of the output, taken from the environment if not set.
+    language: Option<Language>,
+    /// Which passed exam attempt becomes the grade of a course.
+    attempt_policy: AttemptPolicy,
+    /// Folder `mm attach` takes new downloads from. Defaults to the download directory.
+    inbox: Inbox,
+    /// File names of `mm pdf normalize` with `{course}`, `{week}` and `{title}`.
+    slide_pattern: String,
+    /// Channel for `mm deadline remind`, if configured.
+    notifications: Option<NotificationSettings>,
+    /// Activate the semester lasting over today before every command, like `mm switch --auto`.
+    auto_switch: bool,
+    /// Log every command to `mm/logs` in the config directory, like `mm --log-file`.
+    log: bool,
+    /// ECTS to enroll in per semester, unless a semester sets its own.
+    ects_budget: Option<u32>,
 }
 
 /// [SemesterNames] defines the relationship between the folder names and the study cycle as well es semester number.
@@ -43,21 +162,87 @@ pub(crate) struct Config {
 /// - "b" -> [StudyCycle::Bachelor]
 /// - "m" -> [StudyCycle::Master]
 /// - "d" -> [StudyCycle::Doctorate]
-/// A custom mapping can be provided using the StudyCycleMapping Table [StudyCycleMappingDO]
+///
+/// A custom mapping can be provided using the `study_cycle_mapping` table of the config file.
 ///
 /// If no regex is provided it defaults to: `r"^(?P<study_cycle>[bmd])(?P<semester_number>\d{2})$"`
+///
+/// Names of new semesters are built from the format, which must produce names matched by the
+/// regex. `{study_cycle}` is replaced by the mapped study cycle and `{semester_number}` by the
+/// number, padded with zeros for `{semester_number:02}`. It defaults to
+/// `{study_cycle}{semester_number:02}`.
+///
+/// With `calendar_semesters` the folders are named by their term instead, like `WS2024` or
+/// `SS2025`, and the config maps each study cycle to its first term. A term belongs to the study
+/// cycle started last before it and its number counts the terms since that start.
 #[derive(Debug, Clone)]
-pub(crate) struct SemesterNames {
+pub struct SemesterNames {
     regex: Regex,
+    format: String,
     study_cycle_mapping: Vec<(String, StudyCycle)>,
+    /// First term of each study cycle, empty unless semesters are named by their term.
+    calendar: Vec<(Term, StudyCycle)>,
 }
 
 impl SemesterNames {
     pub fn is_name(&self, name: &str) -> bool {
-        self.regex.is_match(name)
+        match self.calendar.is_empty() {
+            true => self.regex.is_match(name),
+            false => self.deserialize(name).is_ok(),
+        }
+    }
+
+    /// The folder name of a semester, the inverse of [Self::deserialize].
+    pub fn serialize(&self, semester_number: u16, study_cycle: StudyCycle) -> Result<String> {
+        if !self.calendar.is_empty() {
+            let (start, _) = self
+                .calendar
+                .iter()
+                .find(|(_, it)| *it == study_cycle)
+                .ok_or_else(|| {
+                    anyhow!(
+                        "No first term of the {} configured in 'calendar_semesters'",
+                        study_cycle
+                    )
+                })?;
+            if semester_number == 0 {
+                bail!("Semester numbers start at 1");
+            }
+            return Ok(start.after(semester_number - 1).to_string());
+        }
+
+        let (cycle, _) = self
+            .study_cycle_mapping
+            .iter()
+            .find(|(_, it)| *it == study_cycle)
+            .ok_or_else(|| anyhow!("No mapping found for study cycle: {}", study_cycle))?;
+        let name = fill_number(
+            &self.format.replace("{study_cycle}", cycle),
+            "semester_number",
+            u32::from(semester_number),
+        );
+        match self.deserialize(&name) {
+            Result::Ok(parsed) if parsed == (semester_number, study_cycle) => Ok(name),
+            _ => bail!(
+                "The semester name '{}' does not match the semester name regex '{}'. Set 'semester_name_format' in the config file to a format matching 'semster_names'",
+                name,
+                self.regex
+            ),
+        }
     }
 
     pub fn deserialize(&self, name: &str) -> Result<(u16, StudyCycle)> {
+        if !self.calendar.is_empty() {
+            let term = name.parse::<Term>()?;
+            let (number, study_cycle) = self
+                .calendar
+                .iter()
+                .filter_map(|(start, cycle)| Some((term.since(start)? + 1, *cycle)))
+                .min_by_key(|(number, _)| *number)
+                .ok_or_else(|| anyhow!("The term {} is before the first study cycle", term))?;
+            return Ok((number, study_cycle));
+        }
+
         let captures = self
             .regex
             .captures(name)
@@ -73,9 +258,9 @@ impl SemesterNames {
             .ok_or_else(|| anyhow!("Failed to extract study cycle"))?
             .as_str()
             .to_string();
-        let (_, study_cycle) = self.study_cycle_mapping.iter().filter(|(it, _)| it == &study_cycle).next().
+        let (_, study_cycle) = self.study_cycle_mapping.iter().find(|(it, _)| it == &study_cycle).
             with_context(|| anyhow!("semester name capute (<study_cylce>: {}) could not be matched to study cycle: \nSemester name: {}", study_cycle, name))?;
-        Ok((semester_number, study_cycle.clone()))
+        Ok((semester_number, *study_cycle))
     }
 }
 
@@ -86,8 +271,17 @@ impl Config {
     /// Linux: $XDG_CONFIG_HOME or $HOME/.config/mm/config.toml
     /// macOS: $HOME/.config/mm/config.toml
     /// Windows: {FOLDERID_RoamingAppData}\mm\config.toml
+    ///
+    /// A config file given by [Config::set_file] is never created.
     pub fn new() -> Result<Config> {
-        let config_path = Self::config_path()?.join("mm").join("config.toml");
+        let config_path = Self::file_path()?;
+        match Self::custom_file() {
+            Some(_) => tracing::trace!("config from --config: {}", config_path.display()),
+            None => tracing::trace!("config: {}", config_path.display()),
+        }
+        if !config_path.is_file() && Self::custom_file().is_some() {
+            bail!(MmError::ConfigNotFound(config_path));
+        }
         if !config_path.is_file() {
             Self::create_default_config_file()?;
             bail!(
@@ -102,22 +296,104 @@ impl Config {
     where
         P: AsRef<Path>,
     {
-        let file =
-            std::fs::read_to_string(path).with_context(|| anyhow!("Failed to open config file"))?;
-        let config_do = toml_edit::de::from_str::<ConfigDO>(&file)
-            .with_context(|| anyhow!("Could not read Config from toml"))?;
+        let path = path.as_ref();
+        let file = std::fs::read_to_string(path)
+            .with_context(|| MmError::ConfigNotFound(path.to_path_buf()))?;
+        let config_do =
+            toml_edit::de::from_str::<ConfigDO>(&file).with_context(|| MmError::ConfigInvalid {
+                path: path.to_path_buf(),
+            })?;
 
-        let entry_point = EntryPoint::new(&config_do.entry_point)?;
-        let semester_names =
-            SemesterNames::new(config_do.semster_names, config_do.study_cycle_mapping)?;
-        let course_link = MaybeSymLinkable::new(config_do.course_link)?;
-        let semester_link = MaybeSymLinkable::new(config_do.semester_link)?;
+        let entry_point = EntryPoint::new(&config_do.entry_point, Ignore::new(config_do.ignore))?;
+        let semester_names = SemesterNames::new(
+            config_do.semster_names,
+            config_do.semester_name_format,
+            config_do.study_cycle_mapping,
+            config_do.calendar_semesters,
+        )?;
+        let links = Self::links(
+            config_do.links,
+            config_do.semester_link,
+            config_do.course_link,
+            config_do.link_strategy.unwrap_or_default(),
+        )?;
+        let template_dir = match config_do.template_dir {
+            Some(dir) => dir,
+            None => Self::config_path()?.join("mm").join("templates"),
+        };
+
+        let backup_dir = match config_do.backup_dir {
+            Some(dir) => dir,
+            None => dirs::data_local_dir()
+                .context("Failed to find data directory on your system.")?
+                .join("mm")
+                .join("backups"),
+        };
+        let backup_keep = config_do.backup_keep.unwrap_or(10);
+        if backup_keep < 1 {
+            // the backup just taken would be rotated away right away
+            bail!("'backup_keep' has to keep at least 1 backup");
+        }
+        let backup = BackupSettings::new(
+            backup_dir,
+            backup_keep,
+            config_do.snapshot_keep.unwrap_or(20),
+        );
+
+        let inbox = match config_do.inbox {
+            Some(dir) => dir,
+            None => match dirs::download_dir() {
+                Some(dir) => dir,
+                None => dirs::home_dir()
+                    .context("Failed to find home directory on your system")?
+                    .join("Downloads"),
+            },
+        };
+
+        let inbox_rules = config_do
+            .inbox_rules
+            .into_iter()
+            .map(|it| InboxRule::new(&it.pattern, it.folder, it.rename))
+            .collect::<Result<Vec<_>>>()?;
+
+        let sync = config_do
+            .sync
+            .map(|it| SyncSettings::new(it.tool.unwrap_or_default(), it.remote));
 
         let config = Config {
             entry_point,
             semester_names,
-            course_link,
-            semester_link,
+            links,
+            template_dir,
+            backup,
+            sync,
+            git: config_do.git,
+            moodle: config_do
+                .moodle
+                .map(|it| MoodleSettings::new(it.url, it.token)),
+            prompt_format: config_do
+                .prompt_format
+                .unwrap_or_else(|| "{{semester}}/{{course}}".into()),
+            degrees: config_do.degrees,
+            grade_scale: config_do.grade_scale.unwrap_or_default(),
+            theme: config_do.theme.unwrap_or_default(),
+            language: config_do.language,
+            attempt_policy: config_do.attempt_policy.unwrap_or_default(),
+            inbox: Inbox::new(inbox, inbox_rules),
+            slide_pattern: config_do
+                .slide_pattern
+                .unwrap_or_else(|| "{course}_{week:02}_{title}.pdf".into()),
+            notifications: config_do.notifications.map(|it| {
+                NotificationSettings::new(
+                    it.backend.unwrap_or_default(),
+                    it.url,
+                    it.token,
+                    it.days.unwrap_or(3),
+                )
+            }),
+            auto_switch: config_do.auto_switch,
+            log: config_do.log,
+            ects_budget: config_do.ects_budget,
         };
         Ok(config)
     }
@@ -133,6 +409,77 @@ impl Config {
         Ok(())
     }
 
+    /// Reads the config from `path` instead of the default location, see `mm --config`.
+    pub fn set_file(path: Option<PathBuf>) {
+        *CONFIG_FILE.lock().unwrap_or_else(|err| err.into_inner()) = path;
+    }
+
+    /// The config file given by `mm --config` or `mm/config.toml` in the config directory.
+    pub fn file_path() -> Result<PathBuf> {
+        match Self::custom_file() {
+            Some(path) => Ok(path),
+            None => Ok(Self::config_path()?.join("mm").join("config.toml")),
+        }
+    }
+
+    /// The `links` of the config followed by `semester_link` and `course_link`. Paths starting with
+    /// `~` are relative to the home directory.
+    fn links(
+        links: Vec<LinkDO>,
+        semester_link: Option<PathBuf>,
+        course_link: Option<PathBuf>,
+        strategy: LinkStrategy,
+    ) -> Result<Vec<Link>> {
+        let legacy = [
+            ("semester_link", semester_link, LinkTarget::Semester),
+            ("course_link", course_link, LinkTarget::Course),
+        ]
+        .into_iter()
+        .filter_map(|(name, path, target)| {
+            path.map(|path| LinkDO {
+                name: name.to_string(),
+                path,
+                target,
+                strategy: None,
+            })
+        });
+
+        let mut names = HashSet::new();
+        let mut paths = HashSet::new();
+        links
+            .into_iter()
+            .chain(legacy)
+            .map(|it| {
+                let path = match it.path.strip_prefix("~") {
+                    Ok(rest) => dirs::home_dir()
+                        .context("Failed to find home directory on your system")?
+                        .join(rest),
+                    Err(_) => it.path,
+                };
+                if !names.insert(it.name.clone()) {
+                    bail!("The link name '{}' is used more than once", it.name)
+                }
+                if !paths.insert(path.clone()) {
+                    bail!("The link path '{}' is used more than once", path.display())
+                }
+                let link = MaybeSymLinkable::new(Some(path), it.strategy.unwrap_or(strategy))
+                    .with_context(|| anyhow!("Invalid link '{}'", it.name))?;
+                Ok(Link {
+                    name: it.name,
+                    target: it.target,
+                    link,
+                })
+            })
+            .collect()
+    }
+
+    fn custom_file() -> Option<PathBuf> {
+        CONFIG_FILE
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
     pub fn config_path() -> Result<PathBuf> {
         if cfg!(target_os = "macos") {
             let home_dir =
@@ -149,26 +496,97 @@ impl ConfigProvider for Config {
         self.entry_point.clone()
     }
 
-    fn current_course_link(&self) -> MaybeSymLinkable {
-        self.course_link.clone()
-    }
-
-    fn current_semester_link(&self) -> MaybeSymLinkable {
-        self.semester_link.clone()
+    fn links(&self) -> Vec<Link> {
+        self.links.clone()
     }
 
     fn semester_names(&self) -> SemesterNames {
         self.semester_names.clone()
     }
+
+    fn template_dir(&self) -> PathBuf {
+        self.template_dir.clone()
+    }
+
+    fn backup_settings(&self) -> BackupSettings {
+        self.backup.clone()
+    }
+
+    fn sync_settings(&self) -> Option<SyncSettings> {
+        self.sync.clone()
+    }
+
+    fn git_mode(&self) -> Option<GitMode> {
+        self.git
+    }
+
+    fn moodle_settings(&self) -> Option<MoodleSettings> {
+        self.moodle.clone()
+    }
+
+    fn prompt_format(&self) -> String {
+        self.prompt_format.clone()
+    }
+
+    fn degrees(&self) -> Vec<Degree> {
+        self.degrees.clone()
+    }
+
+    fn grade_scale(&self) -> GradeScale {
+        self.grade_scale.clone()
+    }
+
+    fn theme(&self) -> Theme {
+        self.theme.clone()
+    }
+
+    fn language(&self) -> Option<Language> {
+        self.language
+    }
+
+    fn attempt_policy(&self) -> AttemptPolicy {
+        self.attempt_policy
+    }
+
+    fn inbox(&self) -> Inbox {
+        self.inbox.clone()
+    }
+
+    fn slide_pattern(&self) -> String {
+        self.slide_pattern.clone()
+    }
+
+    fn notification_settings(&self) -> Option<NotificationSettings> {
+        self.notifications.clone()
+    }
+
+    fn auto_switch(&self) -> bool {
+        self.auto_switch
+    }
+
+    fn log(&self) -> bool {
+        self.log
+    }
+
+    fn ects_budget(&self) -> Option<u32> {
+        self.ects_budget
+    }
 }
 
 impl SemesterNames {
     pub(self) fn new(
         regex: Option<String>,
+        format: Option<String>,
         study_cylce_mapping: Option<StudyCycleMappingDO>,
+        calendar: Option<StudyCycleMappingDO>,
     ) -> Result<SemesterNames> {
+        let calendar = match calendar {
+            Some(starts) => validate::calendar_semesters(starts)?,
+            None => Vec::new(),
+        };
         let capture_groups = vec!["study_cycle", "semester_number"];
         let default_regex = r"^(?P<study_cycle>[bmd])(?P<semester_number>\d{2})";
+        let format = format.unwrap_or_else(|| "{study_cycle}{semester_number:02}".into());
         let default_map = StudyCycleMappingDO {
             bachelor: Some("b".into()),
             master: Some("m".into()),
@@ -182,7 +600,9 @@ impl SemesterNames {
                 let study_cycle_mapping = validate::study_cycle_mapping(None, default_map)?;
                 let semester_names = SemesterNames {
                     regex,
+                    format,
                     study_cycle_mapping,
+                    calendar,
                 };
                 return Ok(semester_names);
             }
@@ -191,7 +611,9 @@ impl SemesterNames {
         let study_cycle_mapping = validate::study_cycle_mapping(study_cylce_mapping, default_map)?;
         let semester_names = SemesterNames {
             regex,
+            format,
             study_cycle_mapping,
+            calendar,
         };
         Ok(semester_names)
     }
@@ -242,4 +664,27 @@ mod validate {
         ];
         Ok(mapping)
     }
+
+    pub(super) fn calendar_semesters(
+        starts: StudyCycleMappingDO,
+    ) -> Result<Vec<(Term, StudyCycle)>> {
+        let starts = [
+            (starts.bachelor, StudyCycle::Bachelor),
+            (starts.master, StudyCycle::Master),
+            (starts.doctorate, StudyCycle::Doctorate),
+        ]
+        .into_iter()
+        .filter_map(|(start, cycle)| Some((start?, cycle)))
+        .map(|(start, cycle)| {
+            let term = start
+                .parse::<Term>()
+                .with_context(|| anyhow!("Invalid first term of the {}", cycle))?;
+            Ok((term, cycle))
+        })
+        .collect::<Result<Vec<_>>>()?;
+        if starts.is_empty() {
+            bail!("'calendar_semesters' needs the first term of at least one study cycle");
+        }
+        Ok(starts)
+    }
 }
@@ -1,9 +1,28 @@
-use std::ops::Deref;
+use std::{
+    fmt,
+    ops::Deref,
+    path::{Path, PathBuf},
+};
 
-use anyhow::{anyhow, Context, Result};
-use serde::{Deserialize, Serialize};
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize, Serializer};
+use toml_edit::DocumentMut;
 
-use super::paths::{CourseDataFile, CoursePath, ReadWriteDO};
+use crate::cli::CourseStatusDO;
+
+use super::{
+    component::{final_grade, Component},
+    date::{Date, Time},
+    deadline::Deadline,
+    exam::{AttemptPolicy, Exam},
+    exercise::SheetPoints,
+    paths::{CourseDataFile, CoursePath, ReadWriteDO, DATA_VERSION},
+    plan::PlannedCourse,
+    repository::Repository,
+    session::Session,
+    slot::Slot,
+    task::Task,
+};
 
 #[derive(Debug, PartialEq, PartialOrd, Clone)]
 pub struct Course {
@@ -12,31 +31,239 @@ pub struct Course {
     ects: Option<u8>,
     name: Option<String>,
     degrees: Option<Vec<String>>,
+    category: Option<String>,
     uebk: Option<bool>,
+    locked: Option<bool>,
+    status: Option<CourseStatus>,
+    kind: Option<CourseKind>,
+    module_id: Option<String>,
+    code: Option<String>,
+    instructor: Option<String>,
+    url: Option<String>,
+    tags: Vec<String>,
+    moodle_id: Option<u64>,
+    tasks: Vec<Task>,
+    deadlines: Vec<Deadline>,
+    exams: Vec<Exam>,
+    components: Vec<Component>,
+    admission: Option<f32>,
+    points: Vec<SheetPoints>,
+    exercise_template: Option<PathBuf>,
+    exercise_url: Option<String>,
+    slots: Vec<Slot>,
+    sessions: Vec<Session>,
+    related: Vec<String>,
+    prerequisites: Vec<String>,
+    repository: Option<Repository>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CourseDO {
+    #[serde(default, skip_serializing)]
+    version: u32,
     name: Option<String>,
+    #[serde(
+        serialize_with = "serialize_grade",
+        skip_serializing_if = "Option::is_none"
+    )]
     grade: Option<f32>,
     ects: Option<u8>,
     degrees: Option<Vec<String>>,
+    /// Category of the configured degrees the course counts towards, e.g. "Wahlpflicht".
+    category: Option<String>,
     #[serde(rename = "übK")]
     uebk: Option<bool>,
+    locked: Option<bool>,
+    status: Option<CourseStatus>,
+    kind: Option<CourseKind>,
+    /// Module number in the course catalog, e.g. "INF-324".
+    module_id: Option<String>,
+    /// Course number the university lists the course under, e.g. "INF3120".
+    code: Option<String>,
+    instructor: Option<String>,
+    /// Website of the course.
+    url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    /// Id of the linked Moodle course, set by `mm moodle link`.
+    moodle_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tasks: Vec<Task>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    deadlines: Vec<Deadline>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    exams: Vec<Exam>,
+    /// Weighted parts of the final grade, see [final_grade].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    components: Vec<Component>,
+    /// Percentage of exercise points required for the exam admission.
+    #[serde(
+        serialize_with = "serialize_grade",
+        skip_serializing_if = "Option::is_none"
+    )]
+    admission: Option<f32>,
+    /// Points of graded exercise sheets, recorded with `mm exercise points`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    points: Vec<SheetPoints>,
+    /// Folder in the template directory copied into new exercise sheets.
+    exercise_template: Option<PathBuf>,
+    /// Download URL of the sheets with `{nr}` or a padded `{nr:02}` for the sheet number.
+    exercise_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    slots: Vec<Slot>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    sessions: Vec<Session>,
+    /// References of related courses, e.g. the preceding part of a lecture series.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    related: Vec<String>,
+    /// Courses which have to be passed first, see [Course::fulfills].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    prerequisites: Vec<String>,
+    /// Git repository with course material, updated with `mm course update`.
+    repository: Option<Repository>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CourseStatus {
+    Planned,
+    Enrolled,
+    Passed,
+    Failed,
+    Dropped,
+}
+
+/// Determines the format of new notes. LaTeX courses additionally get a `main.tex`.
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum CourseKind {
+    #[default]
+    Markdown,
+    Latex,
 }
 
 impl Course {
     pub fn from_path(path: CoursePath) -> Result<Course> {
         let data = path.data_file()?;
         let course_do = data.read()?;
-        let course = Course {
+        Ok(Course::from_do(path, course_do))
+    }
+
+    fn from_do(path: CoursePath, course_do: CourseDO) -> Course {
+        Course {
             path,
             grade: course_do.grade,
             ects: course_do.ects,
             name: course_do.name,
             uebk: course_do.uebk,
             degrees: course_do.degrees,
+            category: course_do.category,
+            locked: course_do.locked,
+            status: course_do.status,
+            kind: course_do.kind,
+            module_id: course_do.module_id,
+            code: course_do.code,
+            instructor: course_do.instructor,
+            url: course_do.url,
+            tags: course_do.tags,
+            moodle_id: course_do.moodle_id,
+            tasks: course_do.tasks,
+            deadlines: course_do.deadlines,
+            exams: course_do.exams,
+            components: course_do.components,
+            admission: course_do.admission,
+            points: course_do.points,
+            exercise_template: course_do.exercise_template,
+            exercise_url: course_do.exercise_url,
+            slots: course_do.slots,
+            sessions: course_do.sessions,
+            related: course_do.related,
+            prerequisites: course_do.prerequisites,
+            repository: course_do.repository,
+        }
+    }
+
+    /// Course of the content of a `course.toml` without a folder on disk.
+    #[cfg(test)]
+    pub(super) fn from_toml(name: &str, content: &str) -> Course {
+        let course_do = toml_edit::de::from_str(content).expect("valid course data");
+        Course::from_do(CoursePath::detached(name), course_do)
+    }
+
+    fn to_do(&self) -> CourseDO {
+        CourseDO {
+            version: DATA_VERSION,
+            name: self.name.clone(),
+            grade: self.grade,
+            ects: self.ects,
+            degrees: self.degrees.clone(),
+            category: self.category.clone(),
+            uebk: self.uebk,
+            locked: self.locked,
+            status: self.status,
+            kind: self.kind,
+            module_id: self.module_id.clone(),
+            code: self.code.clone(),
+            instructor: self.instructor.clone(),
+            url: self.url.clone(),
+            tags: self.tags.clone(),
+            moodle_id: self.moodle_id,
+            tasks: self.tasks.clone(),
+            deadlines: self.deadlines.clone(),
+            exams: self.exams.clone(),
+            components: self.components.clone(),
+            admission: self.admission,
+            points: self.points.clone(),
+            exercise_template: self.exercise_template.clone(),
+            exercise_url: self.exercise_url.clone(),
+            slots: self.slots.clone(),
+            sessions: self.sessions.clone(),
+            related: self.related.clone(),
+            prerequisites: self.prerequisites.clone(),
+            repository: self.repository.clone(),
+        }
+    }
+
+    fn write(&self) -> Result<()> {
+        self.path.data_file()?.write(&self.to_do())
+    }
+
+    /// Copies the folder structure and the settings of the course into `path`, which lists this
+    /// course as related. Results like the grade, exams, points and tracked sessions are left out.
+    /// The name is kept if the folder name is.
+    pub fn copy_to(&self, path: CoursePath, reference: String) -> Result<Course> {
+        self.path.copy_skeleton(&path)?;
+        let name = match path.name() == self.path.name() {
+            true => self.name.clone(),
+            false => None,
+        };
+        let mut related = self.related.clone();
+        if !related.contains(&reference) {
+            related.push(reference);
+        }
+        let course = Course {
+            path,
+            name,
+            grade: None,
+            locked: None,
+            status: None,
+            moodle_id: None,
+            tasks: Vec::new(),
+            deadlines: Vec::new(),
+            exams: Vec::new(),
+            components: self
+                .components
+                .iter()
+                .map(Component::without_grade)
+                .collect(),
+            points: Vec::new(),
+            sessions: Vec::new(),
+            related,
+            ..self.clone()
         };
+        course.write()?;
         Ok(course)
     }
 
@@ -64,23 +291,538 @@ impl Course {
         self.degrees.as_ref().unwrap_or(&EMPTY)
     }
 
+    pub fn category(&self) -> Option<&str> {
+        self.category.as_deref()
+    }
+
+    /// A course is passed if its status says so or if it has a passing grade and has not been
+    /// marked as failed or dropped.
+    pub fn is_passed(&self) -> bool {
+        match self.status {
+            Some(CourseStatus::Passed) => true,
+            Some(CourseStatus::Failed | CourseStatus::Dropped) => false,
+            _ => self.grade.is_some_and(|it| it <= 4.0),
+        }
+    }
+
+    /// Whether the course is still running: neither graded, passed, failed nor dropped.
+    pub fn is_enrolled(&self) -> bool {
+        self.grade.is_none()
+            && !self.is_passed()
+            && !matches!(
+                self.status,
+                Some(CourseStatus::Failed | CourseStatus::Dropped)
+            )
+    }
+
+    /// ECTS of the course once it is passed.
+    pub fn earned_ects(&self) -> Option<u8> {
+        self.ects.filter(|_| self.is_passed())
+    }
+
+    pub fn kind(&self) -> CourseKind {
+        self.kind.unwrap_or_default()
+    }
+
+    pub fn related(&self) -> &Vec<String> {
+        &self.related
+    }
+
+    pub fn prerequisites(&self) -> &Vec<String> {
+        &self.prerequisites
+    }
+
+    /// Whether the course is meant by a prerequisite, which names a course by its name, folder
+    /// name or module id, e.g. "Analysis I", "Analysis1" or "MA-101".
+    pub fn fulfills(&self, prerequisite: &str) -> bool {
+        let prerequisite_name = normalize_name(prerequisite);
+        normalize_name(&self.name()) == prerequisite_name
+            || normalize_name(self.path.name()) == prerequisite_name
+            || self
+                .module_id
+                .as_ref()
+                .is_some_and(|it| it.eq_ignore_ascii_case(prerequisite))
+    }
+
+    /// The first field containing the query, case insensitive, with its value. Searched are the
+    /// name, the folder name, the code, the module id, the instructor and the tags.
+    pub fn search(&self, query: &str) -> Option<(&'static str, String)> {
+        let query = query.to_lowercase();
+        let fields = [
+            ("name", self.name.clone()),
+            ("folder", Some(self.path.name().to_string())),
+            ("code", self.code.clone()),
+            ("module", self.module_id.clone()),
+            ("instructor", self.instructor.clone()),
+        ];
+        let tags = self.tags.iter().map(|tag| ("tag", Some(tag.clone())));
+        fields
+            .into_iter()
+            .chain(tags)
+            .filter_map(|(field, value)| Some((field, value?)))
+            .find(|(_, value)| value.to_lowercase().contains(&query))
+    }
+
+    pub fn repository(&self) -> Option<&Repository> {
+        self.repository.as_ref()
+    }
+
+    pub fn set_repository(&mut self, repository: Repository) -> Result<()> {
+        self.repository = Some(repository);
+        self.write()
+    }
+
+    pub fn module_id(&self) -> Option<&str> {
+        self.module_id.as_deref()
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    pub fn instructor(&self) -> Option<&str> {
+        self.instructor.as_deref()
+    }
+
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
+
+    pub fn tags(&self) -> &Vec<String> {
+        &self.tags
+    }
+
+    pub fn moodle_id(&self) -> Option<u64> {
+        self.moodle_id
+    }
+
+    pub fn set_moodle_id(&mut self, id: u64) -> Result<()> {
+        self.moodle_id = Some(id);
+        self.write()
+    }
+
     pub fn uebk(&self) -> Option<bool> {
         self.uebk
     }
+
+    /// Locked courses are protected against accidental changes. Mutating commands require
+    /// `--force` for them.
+    pub fn is_locked(&self) -> bool {
+        self.locked.unwrap_or(false)
+    }
+
+    pub fn set_locked(&mut self, locked: bool) -> Result<()> {
+        self.locked = Some(locked);
+        self.write()
+    }
+
+    pub fn status(&self) -> Option<CourseStatus> {
+        self.status
+    }
+
+    /// The stored status or, for courses without one, the status derived from the grade.
+    /// Ungraded courses count as enrolled.
+    pub fn lifecycle(&self) -> CourseStatus {
+        match (self.status, self.grade) {
+            (Some(status), _) => status,
+            (None, Some(grade)) if grade <= 4.0 => CourseStatus::Passed,
+            (None, Some(_)) => CourseStatus::Failed,
+            (None, None) => CourseStatus::Enrolled,
+        }
+    }
+
+    /// Takes over the ECTS, degrees and module id of a course planned with `mm plan add`.
+    pub fn set_planned(&mut self, planned: &PlannedCourse) -> Result<()> {
+        self.ects = planned.ects();
+        self.degrees = (!planned.degrees().is_empty()).then(|| planned.degrees().clone());
+        self.module_id = planned.module_id().map(str::to_string);
+        self.write()
+    }
+
+    pub fn set_status(&mut self, status: CourseStatus) -> Result<()> {
+        self.status = Some(status);
+        self.write()
+    }
+
+    /// Sets the final grade. Entering a final grade locks the course and marks it as passed or
+    /// failed.
+    pub fn set_grade(&mut self, grade: f32) -> Result<()> {
+        check_grade(grade)?;
+        self.apply_grade(grade);
+        self.write()
+    }
+
+    /// Records an imported result. A grade locks the course like [Course::set_grade].
+    pub fn record_result(&mut self, grade: Option<f32>, ects: Option<u8>) -> Result<()> {
+        if let Some(grade) = grade {
+            check_grade(grade)?;
+            self.apply_grade(grade);
+        }
+        if let Some(ects) = ects {
+            self.ects = Some(ects);
+        }
+        self.write()
+    }
+
+    fn apply_grade(&mut self, grade: f32) {
+        self.grade = Some(grade);
+        self.locked = Some(true);
+        self.status = Some(match grade <= 4.0 {
+            true => CourseStatus::Passed,
+            false => CourseStatus::Failed,
+        });
+    }
+
+    pub fn tasks(&self) -> &Vec<Task> {
+        &self.tasks
+    }
+
+    pub fn open_tasks(&self) -> impl Iterator<Item = &Task> {
+        self.tasks.iter().filter(|task| !task.done())
+    }
+
+    pub fn add_task(&mut self, task: Task) -> Result<()> {
+        self.tasks.push(task);
+        self.write()
+    }
+
+    /// Marks the task at `index` (0-based) as done.
+    pub fn complete_task(&mut self, index: usize) -> Result<&Task> {
+        let task = self
+            .tasks
+            .get_mut(index)
+            .ok_or_else(|| anyhow!("No task with id {}", index + 1))?;
+        task.set_done(true);
+        self.write()?;
+        Ok(&self.tasks[index])
+    }
+
+    /// Removes the task at `index` (0-based).
+    pub fn remove_task(&mut self, index: usize) -> Result<Task> {
+        if index >= self.tasks.len() {
+            bail!("No task with id {}", index + 1);
+        }
+        let task = self.tasks.remove(index);
+        self.write()?;
+        Ok(task)
+    }
+
+    pub fn deadlines(&self) -> &Vec<Deadline> {
+        &self.deadlines
+    }
+
+    pub fn add_deadline(&mut self, deadline: Deadline) -> Result<()> {
+        self.deadlines.push(deadline);
+        self.write()
+    }
+
+    /// Adds imported deadlines. A deadline with the same title and kind as an existing one
+    /// replaces its date. Returns the number of added and moved deadlines.
+    pub fn import_deadlines(&mut self, deadlines: Vec<Deadline>) -> Result<(usize, usize)> {
+        let mut changed = (0, 0);
+        for deadline in deadlines {
+            let existing = self
+                .deadlines
+                .iter_mut()
+                .find(|it| it.title() == deadline.title() && it.kind() == deadline.kind());
+            match existing {
+                Some(existing) if existing.date() != deadline.date() => {
+                    existing.set_date(deadline.date());
+                    changed.1 += 1;
+                }
+                Some(_) => {}
+                None => {
+                    self.deadlines.push(deadline);
+                    changed.0 += 1;
+                }
+            }
+        }
+        if changed != (0, 0) {
+            self.write()?;
+        }
+        Ok(changed)
+    }
+
+    pub fn exams(&self) -> &Vec<Exam> {
+        &self.exams
+    }
+
+    /// Number of the next exam attempt.
+    pub fn next_attempt(&self) -> u8 {
+        self.exams.iter().map(|it| it.attempt()).max().unwrap_or(0) + 1
+    }
+
+    /// Registers an exam attempt. Registering a retake of a failed course enrolls it again.
+    pub fn add_exam(&mut self, exam: Exam) -> Result<()> {
+        if self.exams.iter().any(|it| it.attempt() == exam.attempt()) {
+            bail!("Attempt {} is already registered", exam.attempt());
+        }
+        if self.status == Some(CourseStatus::Failed) && self.grade.is_none() {
+            self.status = Some(CourseStatus::Enrolled);
+        }
+        self.exams.push(exam);
+        self.exams.sort_by_key(|it| it.attempt());
+        self.write()
+    }
+
+    /// Records the result of an exam attempt. Without an attempt the latest one is used. The
+    /// passed attempt chosen by `policy` becomes the final grade of the course, which locks it.
+    /// Failing without any passed attempt marks the course as failed until a retake is added.
+    pub fn set_exam_grade(
+        &mut self,
+        attempt: Option<u8>,
+        grade: f32,
+        policy: AttemptPolicy,
+    ) -> Result<&Exam> {
+        check_grade(grade)?;
+        let index = match attempt {
+            Some(attempt) => self
+                .exams
+                .iter()
+                .position(|it| it.attempt() == attempt)
+                .ok_or_else(|| anyhow!("No exam found for attempt {}", attempt))?,
+            None => self
+                .exams
+                .len()
+                .checked_sub(1)
+                .ok_or_else(|| anyhow!("No exams registered for this course"))?,
+        };
+        self.exams[index].set_grade(grade);
+        match policy.counting(&self.exams).and_then(|it| it.grade()) {
+            Some(grade) => self.apply_grade(grade),
+            None if self.grade.is_none() => self.status = Some(CourseStatus::Failed),
+            None => {}
+        }
+        self.write()?;
+        Ok(&self.exams[index])
+    }
+
+    pub fn components(&self) -> &Vec<Component> {
+        &self.components
+    }
+
+    /// Grades a component defined in `course.toml`. Once all weighted components are graded the
+    /// computed final grade is set like [Course::set_grade] and returned.
+    pub fn set_component_grade(&mut self, name: &str, grade: f32) -> Result<Option<f32>> {
+        check_grade(grade)?;
+        let names = self
+            .components
+            .iter()
+            .map(|it| it.name().to_string())
+            .collect::<Vec<_>>();
+        let component = self
+            .components
+            .iter_mut()
+            .find(|it| it.name().eq_ignore_ascii_case(name))
+            .ok_or_else(|| match names.is_empty() {
+                true => anyhow!("No grade components defined for this course"),
+                false => anyhow!(
+                    "No component '{}' found. Components are: {}",
+                    name,
+                    names.join(", ")
+                ),
+            })?;
+        component.set_grade(grade);
+        let computed = final_grade(&self.components);
+        if let Some(grade) = computed {
+            self.apply_grade(grade);
+        }
+        self.write()?;
+        Ok(computed)
+    }
+
+    /// Percentage of exercise points required for the exam admission, if any.
+    pub fn admission(&self) -> Option<f32> {
+        self.admission
+    }
+
+    pub fn exercise_template(&self) -> Option<&Path> {
+        self.exercise_template.as_deref()
+    }
+
+    pub fn exercise_url(&self) -> Option<&str> {
+        self.exercise_url.as_deref()
+    }
+
+    pub fn points(&self) -> &Vec<SheetPoints> {
+        &self.points
+    }
+
+    /// Records the points of a sheet, replacing points recorded before for the same sheet.
+    pub fn record_points(&mut self, points: SheetPoints) -> Result<()> {
+        match self
+            .points
+            .iter_mut()
+            .find(|it| it.sheet() == points.sheet())
+        {
+            Some(existing) => *existing = points,
+            None => self.points.push(points),
+        }
+        self.points.sort_by(|a, b| a.sheet().cmp(b.sheet()));
+        self.write()
+    }
+
+    pub fn slots(&self) -> &Vec<Slot> {
+        &self.slots
+    }
+
+    pub fn add_slot(&mut self, slot: Slot) -> Result<()> {
+        self.slots.push(slot);
+        self.write()
+    }
+
+    pub fn sessions(&self) -> &Vec<Session> {
+        &self.sessions
+    }
+
+    pub fn running_session(&self) -> Option<&Session> {
+        self.sessions.iter().find(|it| it.is_running())
+    }
+
+    /// Adds a session. Only one session per course may be running at a time.
+    pub fn add_session(&mut self, session: Session) -> Result<()> {
+        if session.is_running() && self.running_session().is_some() {
+            bail!("A session is already running for '{}'", self.name());
+        }
+        self.sessions.push(session);
+        self.write()
+    }
+
+    /// Stops the running session at the given point in time and returns it.
+    pub fn stop_session(&mut self, date: &Date, time: &Time) -> Result<&Session> {
+        let index = self
+            .sessions
+            .iter()
+            .position(|it| it.is_running())
+            .ok_or_else(|| anyhow!("No session running for '{}'", self.name()))?;
+        let minutes = self.sessions[index].elapsed(date, time);
+        self.sessions[index].stop(minutes);
+        self.write()?;
+        Ok(&self.sessions[index])
+    }
+
+    /// Discards the running session.
+    pub fn cancel_session(&mut self) -> Result<Session> {
+        let index = self
+            .sessions
+            .iter()
+            .position(|it| it.is_running())
+            .ok_or_else(|| anyhow!("No session running for '{}'", self.name()))?;
+        let session = self.sessions.remove(index);
+        self.write()?;
+        Ok(session)
+    }
+
+    /// Adds imported slots and exams, skipping entries which are already present.
+    /// Returns the number of added slots and exams.
+    pub fn import_calendar(
+        &mut self,
+        slots: Vec<Slot>,
+        exams: Vec<Exam>,
+    ) -> Result<(usize, usize)> {
+        let mut added = (0, 0);
+        for slot in slots {
+            if !self.slots.contains(&slot) {
+                self.slots.push(slot);
+                added.0 += 1;
+            }
+        }
+        for exam in exams {
+            if !self.exams.iter().any(|it| it.date() == exam.date()) {
+                self.exams.push(exam);
+                added.1 += 1;
+            }
+        }
+        self.exams.sort_by_key(|it| it.attempt());
+        self.write()?;
+        Ok(added)
+    }
+}
+
+/// German grades range from 1.0 (best) to 5.0 (failed).
+fn check_grade(grade: f32) -> Result<()> {
+    if !(1.0..=5.0).contains(&grade) {
+        bail!("Invalid grade {}. Grades range from 1.0 to 5.0", grade);
+    }
+    Ok(())
+}
+
+/// Prerequisites which no passed course of `courses` fulfills.
+pub fn missing_prerequisites<'a>(prerequisites: &'a [String], courses: &[Course]) -> Vec<&'a str> {
+    prerequisites
+        .iter()
+        .filter(|prerequisite| {
+            !courses
+                .iter()
+                .any(|it| it.is_passed() && it.fulfills(prerequisite))
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+/// Lowercase alphanumeric characters of a course name, used to match names from other systems.
+pub fn normalize_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+impl CourseStatus {
+    pub fn from_do(status: CourseStatusDO) -> CourseStatus {
+        match status {
+            CourseStatusDO::Planned => CourseStatus::Planned,
+            CourseStatusDO::Enrolled => CourseStatus::Enrolled,
+            CourseStatusDO::Passed => CourseStatus::Passed,
+            CourseStatusDO::Failed => CourseStatus::Failed,
+            CourseStatusDO::Dropped => CourseStatus::Dropped,
+        }
+    }
+}
+
+impl fmt::Display for CourseStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status_str = match self {
+            CourseStatus::Planned => "planned",
+            CourseStatus::Enrolled => "enrolled",
+            CourseStatus::Passed => "passed",
+            CourseStatus::Failed => "failed",
+            CourseStatus::Dropped => "dropped",
+        };
+        write!(f, "{}", status_str)
+    }
+}
+
+/// The toml serializer widens `f32` to `f64`, which would write `1.3` as `1.2999999523162842`.
+/// Going through the shortest decimal representation keeps grades readable.
+pub(super) fn serialize_grade<S>(grade: &Option<f32>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match grade {
+        Some(grade) => {
+            let grade = grade.to_string().parse::<f64>().unwrap_or(*grade as f64);
+            serializer.serialize_some(&grade)
+        }
+        None => serializer.serialize_none(),
+    }
 }
 
 impl ReadWriteDO for CourseDataFile {
     type Object = CourseDO;
 
-    fn write(&self, object: &Self::Object) -> Result<()> {
-        let data = toml_edit::ser::to_string(&object).with_context(|| {
+    fn version(object: &Self::Object) -> Option<u32> {
+        Some(object.version)
+    }
+
+    /// Course files are edited by hand, so new values are written in the pretty format.
+    fn to_document(&self, object: &Self::Object) -> Result<DocumentMut> {
+        let data = toml_edit::ser::to_string_pretty(&object).with_context(|| {
             anyhow!(
                 "Failed to serialize data to toml for: {}",
                 self.deref().display()
             )
         })?;
-        std::fs::write(self.deref(), data)
-            .with_context(|| anyhow!("Failed to write data to file: {}", self.deref().display()))?;
-        Ok(())
+        Ok(data.parse()?)
     }
 }
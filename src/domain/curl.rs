@@ -0,0 +1,132 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::{bail, Context, Result};
+
+use super::effects::{self, Effect};
+
+/// A request run by curl. The URL, headers and body are passed as a curl config on stdin instead
+/// of arguments, so tokens in them do not show up in the process list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Curl {
+    url: String,
+    headers: Vec<String>,
+    body: Option<String>,
+    output: Option<PathBuf>,
+    follow_redirects: bool,
+}
+
+impl Curl {
+    pub fn new(url: &str) -> Curl {
+        Curl {
+            url: url.to_string(),
+            headers: Vec::new(),
+            body: None,
+            output: None,
+            follow_redirects: false,
+        }
+    }
+
+    pub fn header(mut self, header: impl Into<String>) -> Curl {
+        self.headers.push(header.into());
+        self
+    }
+
+    /// Adds an `Authorization: Bearer` header if there is a token.
+    pub fn bearer(self, token: Option<&str>) -> Curl {
+        match token {
+            Some(token) => self.header(format!("Authorization: Bearer {}", token)),
+            None => self,
+        }
+    }
+
+    /// Posts the body as it is.
+    pub fn body(mut self, body: impl Into<String>) -> Curl {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Writes the response to the file instead of returning it.
+    pub fn output(mut self, path: &Path) -> Curl {
+        self.output = Some(path.to_path_buf());
+        self
+    }
+
+    pub fn follow_redirects(mut self) -> Curl {
+        self.follow_redirects = true;
+        self
+    }
+
+    /// Runs curl and returns the response. A failed request is reported as `failure` followed by
+    /// the message of curl.
+    pub fn send(&self, failure: impl std::fmt::Display) -> Result<Vec<u8>> {
+        // a download into a file is a change to the filesystem like any other
+        if let Some(output) = &self.output {
+            if effects::plan(Effect::Write(output.clone())) {
+                return Ok(Vec::new());
+            }
+        }
+        let mut child = Command::new("curl")
+            .args(["-fsS", "--config", "-"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to run 'curl'. Is it installed?")?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(self.config().as_bytes())?;
+        }
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            bail!(
+                "{}: {}",
+                failure,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(output.stdout)
+    }
+
+    fn config(&self) -> String {
+        let mut config = format!("url = {}\n", quote(&self.url));
+        for header in &self.headers {
+            config.push_str(&format!("header = {}\n", quote(header)));
+        }
+        if let Some(body) = &self.body {
+            // unlike `data-binary`, `data-raw` does not read a file for a body starting with `@`
+            config.push_str(&format!("data-raw = {}\n", quote(body)));
+        }
+        if let Some(output) = &self.output {
+            config.push_str(&format!(
+                "output = {}\n",
+                quote(&output.display().to_string())
+            ));
+        }
+        if self.follow_redirects {
+            config.push_str("location\n");
+        }
+        config
+    }
+}
+
+/// Double quoted parameter of a curl config with the escapes curl understands.
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for char in value.chars() {
+        match char {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            '\u{b}' => quoted.push_str("\\v"),
+            _ => quoted.push(char),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,399 @@
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// A calendar date without time zone information.
+///
+/// Dates are written as `YYYY-MM-DD` strings. When reading, native TOML dates (`due = 2024-11-05`)
+/// are accepted as well, so hand-written data files do not need quotes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u8, day: u8) -> Result<Date> {
+        if !(1..=12).contains(&month) {
+            bail!("Invalid month: {}", month);
+        }
+        if day == 0 || day > days_in_month(year, month) {
+            bail!("Invalid day {} for {:04}-{:02}", day, year, month);
+        }
+        Ok(Date { year, month, day })
+    }
+
+    /// The current local date.
+    pub fn today() -> Date {
+        now().0
+    }
+
+    /// Number of days from `self` until `other`. Negative if `other` lies in the past.
+    pub fn days_until(&self, other: &Date) -> i64 {
+        other.days() - self.days()
+    }
+
+    /// The local date of a unix timestamp.
+    pub fn from_unix(seconds: i64) -> Date {
+        Date::from_days((seconds + utc_offset(seconds)).div_euclid(86_400))
+    }
+
+    pub fn add_days(&self, days: i64) -> Date {
+        Date::from_days(self.days() + days)
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        // 1970-01-01 was a Thursday
+        Weekday::ALL[(self.days() + 3).rem_euclid(7) as usize]
+    }
+
+    /// The monday of the week containing this date.
+    pub fn start_of_week(&self) -> Date {
+        self.add_days(-(self.weekday() as i64))
+    }
+
+    /// Days since 1970-01-01.
+    fn days(&self) -> i64 {
+        // see http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+        let year = if self.month <= 2 {
+            self.year as i64 - 1
+        } else {
+            self.year as i64
+        };
+        let era = year.div_euclid(400);
+        let yoe = year - era * 400;
+        let month = self.month as i64;
+        let doy =
+            (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    fn from_days(days: i64) -> Date {
+        // see http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let doe = days - era * 146_097;
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u8;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+        let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+        Date {
+            year: year as i32,
+            month,
+            day,
+        }
+    }
+}
+
+fn days_in_month(year: i32, month: u8) -> u8 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl FromStr for Date {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(3, '-');
+        let mut next = |name: &str| {
+            parts
+                .next()
+                .ok_or_else(|| anyhow!("Missing {} in date '{}'. Expected YYYY-MM-DD", name, s))
+        };
+        let year = next("year")?;
+        let month = next("month")?;
+        let day = next("day")?;
+        let year = year
+            .parse::<i32>()
+            .with_context(|| anyhow!("Invalid year in date '{}'", s))?;
+        let month = month
+            .parse::<u8>()
+            .with_context(|| anyhow!("Invalid month in date '{}'", s))?;
+        let day = day
+            .parse::<u8>()
+            .with_context(|| anyhow!("Invalid day in date '{}'", s))?;
+        Date::new(year, month, day)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The current local date and time, see [utc_offset].
+pub fn now() -> (Date, Time) {
+    let seconds = timestamp();
+    date_time(seconds + utc_offset(seconds))
+}
+
+/// The current date and time in UTC, e.g. for logs which are compared across time zones.
+pub fn now_utc() -> (Date, Time) {
+    date_time(timestamp())
+}
+
+/// A date and time in UTC, e.g. of [now_utc], in the local time zone.
+pub fn to_local(date: Date, time: Time) -> (Date, Time) {
+    let seconds = date.days() * 86_400 + time.hour as i64 * 3_600 + time.minute as i64 * 60;
+    date_time(seconds + utc_offset(seconds))
+}
+
+/// A date and time in the time zone `zone` of the tz database, e.g. "Europe/Berlin", in the local
+/// time zone. The zone is read from the tz database of the system. `None` if the zone is unknown.
+pub fn zone_to_local(date: Date, time: Time, zone: &str) -> Option<(Date, Time)> {
+    let zone = jiff::tz::TimeZone::get(zone).ok()?;
+    let civil = jiff::civil::date(date.year as i16, date.month as i8, date.day as i8).at(
+        time.hour as i8,
+        time.minute as i8,
+        0,
+        0,
+    );
+    // times skipped or repeated by a daylight saving change resolve like most calendars do
+    let seconds = zone
+        .to_ambiguous_timestamp(civil)
+        .compatible()
+        .ok()?
+        .as_second();
+    Some(date_time(seconds + utc_offset(seconds)))
+}
+
+fn timestamp() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|it| it.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// Offset of the local time zone to UTC in seconds at the unix timestamp, including daylight
+/// saving time. The zone is taken from `TZ` or the system. Without time zone support, on
+/// Windows, local time is UTC.
+pub fn utc_offset(timestamp: i64) -> i64 {
+    #[cfg(unix)]
+    {
+        let time = timestamp as libc::time_t;
+        // SAFETY: `tm` is plain data which `localtime_r` fills in, both pointers are valid
+        let mut tm = unsafe { std::mem::zeroed::<libc::tm>() };
+        if !unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+            return tm.tm_gmtoff as i64;
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = timestamp;
+    0
+}
+
+fn date_time(seconds: i64) -> (Date, Time) {
+    let date = Date::from_days(seconds.div_euclid(86_400));
+    let minutes = seconds.rem_euclid(86_400) / 60;
+    let time = Time {
+        hour: (minutes / 60) as u8,
+        minute: (minutes % 60) as u8,
+    };
+    (date, time)
+}
+
+/// A time of day with minute precision, written as `HH:MM`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    hour: u8,
+    minute: u8,
+}
+
+impl Time {
+    pub fn new(hour: u8, minute: u8) -> Result<Time> {
+        if hour > 23 || minute > 59 {
+            bail!("Invalid time: {:02}:{:02}", hour, minute);
+        }
+        Ok(Time { hour, minute })
+    }
+
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+}
+
+impl FromStr for Time {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(3, ':');
+        let hour = parts
+            .next()
+            .and_then(|it| it.parse::<u8>().ok())
+            .ok_or_else(|| anyhow!("Invalid hour in time '{}'. Expected HH:MM", s))?;
+        let minute = parts
+            .next()
+            .and_then(|it| it.parse::<u8>().ok())
+            .ok_or_else(|| anyhow!("Invalid minute in time '{}'. Expected HH:MM", s))?;
+        Time::new(hour, minute)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl Weekday {
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    pub fn abbreviation(&self) -> &'static str {
+        match self {
+            Weekday::Monday => "Mon",
+            Weekday::Tuesday => "Tue",
+            Weekday::Wednesday => "Wed",
+            Weekday::Thursday => "Thu",
+            Weekday::Friday => "Fri",
+            Weekday::Saturday => "Sat",
+            Weekday::Sunday => "Sun",
+        }
+    }
+}
+
+/// Accepts full names and three letter abbreviations, ignoring case.
+impl FromStr for Weekday {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        Weekday::ALL
+            .into_iter()
+            .find(|day| {
+                let abbreviation = day.abbreviation().to_lowercase();
+                lower == abbreviation || format!("{:?}", day).to_lowercase() == lower
+            })
+            .ok_or_else(|| anyhow!("Invalid weekday: {}", s))
+    }
+}
+
+impl Serialize for Date {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct DateVisitor;
+
+        impl<'de> de::Visitor<'de> for DateVisitor {
+            type Value = Date;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a date formatted as YYYY-MM-DD")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                v.parse::<Date>().map_err(E::custom)
+            }
+
+            // native TOML dates are handed to serde as a map with a single private key
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let (_, value) = map
+                    .next_entry::<String, String>()?
+                    .ok_or_else(|| de::Error::custom("empty date"))?;
+                let value = value.get(..10).unwrap_or(&value);
+                value.parse::<Date>().map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(DateVisitor)
+    }
+}
+
+impl Serialize for Time {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Time {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TimeVisitor;
+
+        impl<'de> de::Visitor<'de> for TimeVisitor {
+            type Value = Time;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a time formatted as HH:MM")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                v.parse::<Time>().map_err(E::custom)
+            }
+
+            // native TOML times are handed to serde as a map with a single private key
+            fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+            where
+                A: de::MapAccess<'de>,
+            {
+                let (_, value) = map
+                    .next_entry::<String, String>()?
+                    .ok_or_else(|| de::Error::custom("empty time"))?;
+                value.parse::<Time>().map_err(de::Error::custom)
+            }
+        }
+
+        deserializer.deserialize_any(TimeVisitor)
+    }
+}
@@ -0,0 +1,71 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli::DeadlineKindDO;
+
+use super::date::Date;
+
+/// A dated submission or exam stored in the `course.toml` of a course.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Serialize, Deserialize)]
+pub struct Deadline {
+    title: String,
+    date: Date,
+    kind: DeadlineKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DeadlineKind {
+    Exercise,
+    Exam,
+    Project,
+}
+
+impl Deadline {
+    pub fn new(title: String, date: Date, kind: DeadlineKind) -> Deadline {
+        Deadline { title, date, kind }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    pub(super) fn set_date(&mut self, date: Date) {
+        self.date = date;
+    }
+
+    pub fn kind(&self) -> DeadlineKind {
+        self.kind
+    }
+
+    /// Days left until the deadline. Negative if the deadline has passed.
+    pub fn days_left(&self, today: &Date) -> i64 {
+        today.days_until(&self.date)
+    }
+}
+
+impl DeadlineKind {
+    pub fn from_do(kind: DeadlineKindDO) -> DeadlineKind {
+        match kind {
+            DeadlineKindDO::Exercise => DeadlineKind::Exercise,
+            DeadlineKindDO::Exam => DeadlineKind::Exam,
+            DeadlineKindDO::Project => DeadlineKind::Project,
+        }
+    }
+}
+
+impl fmt::Display for DeadlineKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind_str = match self {
+            DeadlineKind::Exercise => "Exercise",
+            DeadlineKind::Exam => "Exam",
+            DeadlineKind::Project => "Project",
+        };
+        write!(f, "{}", kind_str)
+    }
+}
@@ -0,0 +1,326 @@
+use serde::Deserialize;
+
+use super::course::Course;
+
+/// A degree configured with `[[degrees]]` in the config file. Courses belong to a degree by
+/// listing its name in `degrees` and to a category by their `category`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Degree {
+    name: String,
+    /// ECTS required to finish the degree.
+    ects: u32,
+    #[serde(default)]
+    categories: Vec<Category>,
+    /// Maximum übK ECTS which count towards the degree.
+    uebk_cap: Option<u32>,
+    /// Rules which drop courses from the average of the degree.
+    #[serde(default)]
+    rules: Vec<Rule>,
+    /// Final grade aimed for, see [Degree::target].
+    target_grade: Option<f32>,
+}
+
+/// Examination regulations often leave out some grades from the final average.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum Rule {
+    /// The worst `count` grades, optionally only of one category, do not count.
+    DropWorst {
+        #[serde(default = "one")]
+        count: usize,
+        category: Option<String>,
+    },
+    /// Only the best `ects` ECTS count. A course at the limit counts with the ECTS left.
+    BestEcts { ects: u32 },
+}
+
+/// A group of modules of a degree with the ECTS required from it, e.g. compulsory or elective
+/// modules.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct Category {
+    name: String,
+    ects: u32,
+}
+
+/// Where the average of a degree stands towards its target grade, see [Degree::target].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Target {
+    pub grade: f32,
+    /// Average of the degree after applying its rules, `None` without graded courses.
+    pub average: Option<f32>,
+    /// ECTS still missing to the requirement of the degree.
+    pub remaining: u32,
+    /// Average needed over the remaining ECTS, `None` if none are missing.
+    pub needed: Option<f32>,
+}
+
+/// ECTS earned in a degree, see [Degree::progress].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DegreeProgress {
+    /// Earned and required ECTS of each configured category.
+    pub categories: Vec<(String, u32, u32)>,
+    /// ECTS of courses without or with an unknown category.
+    pub uncategorized: u32,
+    /// ECTS of übK courses before applying the cap.
+    pub uebk: u32,
+}
+
+impl Degree {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn ects(&self) -> u32 {
+        self.ects
+    }
+
+    pub fn uebk_cap(&self) -> Option<u32> {
+        self.uebk_cap
+    }
+
+    /// Matches the full name or, case insensitive, a part of it like "Informatik" for
+    /// "B.Sc. Informatik".
+    pub fn matches(&self, reference: &str) -> bool {
+        self.name == reference || self.name.to_lowercase().contains(&reference.to_lowercase())
+    }
+
+    /// Whether the course counts towards this degree.
+    pub fn contains(&self, course: &Course) -> bool {
+        course.degrees().iter().any(|it| it == &self.name)
+    }
+
+    /// Sums up the ECTS of passed courses of this degree by category.
+    pub fn progress(&self, courses: &[Course]) -> DegreeProgress {
+        let mut progress = DegreeProgress {
+            categories: self
+                .categories
+                .iter()
+                .map(|it| (it.name.clone(), 0, it.ects))
+                .collect(),
+            uncategorized: 0,
+            uebk: 0,
+        };
+        for course in courses.iter().filter(|it| self.contains(it)) {
+            let Some(ects) = course.earned_ects() else {
+                continue;
+            };
+            let ects = ects as u32;
+            if course.uebk().unwrap_or(false) {
+                progress.uebk += ects;
+                continue;
+            }
+            let category = course.category().and_then(|name| {
+                progress
+                    .categories
+                    .iter_mut()
+                    .find(|(it, _, _)| it.eq_ignore_ascii_case(name))
+            });
+            match category {
+                Some((_, earned, _)) => *earned += ects,
+                None => progress.uncategorized += ects,
+            }
+        }
+        progress
+    }
+
+    /// The average needed over the ECTS still missing to finish with `target_grade`, assuming
+    /// they are all graded. Like [Degree::average] only passed courses after applying the rules
+    /// count. `None` without target grade.
+    pub fn target(&self, courses: &[Course]) -> Option<Target> {
+        let grade = self.target_grade?;
+        let (sum, ects) = self.weighted_grades(courses);
+        let remaining = self
+            .ects
+            .saturating_sub(self.progress(courses).counted(self));
+        let needed =
+            (remaining > 0).then(|| (grade * (ects + remaining) as f32 - sum) / remaining as f32);
+        Some(Target {
+            grade,
+            average: (ects > 0).then(|| sum / ects as f32),
+            remaining,
+            needed,
+        })
+    }
+
+    /// Weighted average of the passed courses of this degree without übK after applying the
+    /// rules in order. `None` if no course counts.
+    pub fn average(&self, courses: &[Course]) -> Option<f32> {
+        let (sum, ects) = self.weighted_grades(courses);
+        (ects > 0).then(|| sum / ects as f32)
+    }
+
+    /// Sum of the grades weighted by ECTS and the sum of the ECTS counting towards the average.
+    fn weighted_grades(&self, courses: &[Course]) -> (f32, u32) {
+        let mut graded = courses
+            .iter()
+            .filter(|it| self.contains(it) && it.is_passed() && !it.uebk().unwrap_or(false))
+            .filter_map(|it| Some((it, it.grade()?, it.ects()? as u32)))
+            .collect::<Vec<_>>();
+        // Best grades first, so rules can cut from the end.
+        graded.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        for rule in &self.rules {
+            match rule {
+                Rule::DropWorst { count, category } => {
+                    let in_category = |course: &Course| match category {
+                        Some(category) => course
+                            .category()
+                            .is_some_and(|it| it.eq_ignore_ascii_case(category)),
+                        None => true,
+                    };
+                    let mut dropped = 0;
+                    for idx in (0..graded.len()).rev() {
+                        if dropped == *count {
+                            break;
+                        }
+                        if in_category(graded[idx].0) {
+                            graded.remove(idx);
+                            dropped += 1;
+                        }
+                    }
+                }
+                Rule::BestEcts { ects } => {
+                    let mut left = *ects;
+                    graded.retain_mut(|(_, _, credits)| {
+                        *credits = (*credits).min(left);
+                        left -= *credits;
+                        *credits > 0
+                    });
+                }
+            }
+        }
+
+        graded
+            .iter()
+            .fold((0f32, 0u32), |(sum, count), (_, grade, ects)| {
+                (sum + grade * *ects as f32, count + ects)
+            })
+    }
+}
+
+fn one() -> usize {
+    1
+}
+
+impl DegreeProgress {
+    /// ECTS which count towards the degree. ECTS beyond the requirement of a category and übK
+    /// ECTS beyond the cap do not count.
+    pub fn counted(&self, degree: &Degree) -> u32 {
+        let categories = self
+            .categories
+            .iter()
+            .map(|(_, earned, required)| *earned.min(required))
+            .sum::<u32>();
+        let uebk = match degree.uebk_cap {
+            Some(cap) => self.uebk.min(cap),
+            None => self.uebk,
+        };
+        categories + self.uncategorized + uebk
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Course, Degree};
+
+    fn degree(content: &str) -> Degree {
+        let content = format!("name = \"B.Sc. Informatik\"\nects = 30\n{}", content);
+        toml_edit::de::from_str(&content).unwrap()
+    }
+
+    fn course(name: &str, content: &str) -> Course {
+        let content = format!("degrees = [\"B.Sc. Informatik\"]\n{}", content);
+        Course::from_toml(name, &content)
+    }
+
+    fn assert_close(actual: Option<f32>, expected: f32) {
+        let actual = actual.expect("an average");
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "{} is not {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn average_weights_passed_courses_by_ects() {
+        let courses = [
+            course("Info1", "grade = 1.0\nects = 6"),
+            course("Datenbanken", "grade = 2.0\nects = 12"),
+            course("Numerik", "grade = 5.0\nects = 6"),
+            course("Krypto", "grade = 1.0\nects = 6\nstatus = \"failed\""),
+            course("Japanisch", "grade = 1.0\nects = 3\n\"übK\" = true"),
+            course("Betriebssysteme", "ects = 6"),
+            Course::from_toml(
+                "Physik",
+                "grade = 1.0\nects = 6\ndegrees = [\"B.Sc. Physik\"]",
+            ),
+        ];
+        assert_close(degree("").average(&courses), 30.0 / 18.0);
+        assert_eq!(degree("").average(&courses[5..]), None);
+    }
+
+    #[test]
+    fn drop_worst_leaves_out_the_worst_grades() {
+        let courses = [
+            course("Info1", "grade = 1.0\nects = 6\ncategory = \"Pflicht\""),
+            course(
+                "Datenbanken",
+                "grade = 2.0\nects = 12\ncategory = \"Pflicht\"",
+            ),
+            course("Seminar", "grade = 3.0\nects = 6\ncategory = \"Seminar\""),
+            course(
+                "Proseminar",
+                "grade = 2.7\nects = 6\ncategory = \"Seminar\"",
+            ),
+        ];
+        let rules = "rules = [{ rule = \"drop_worst\" }]";
+        assert_close(degree(rules).average(&courses), 46.2 / 24.0);
+        let rules = "rules = [{ rule = \"drop_worst\", count = 2 }]";
+        assert_close(degree(rules).average(&courses), 30.0 / 18.0);
+        let rules = "rules = [{ rule = \"drop_worst\", category = \"pflicht\" }]";
+        assert_close(degree(rules).average(&courses), 40.2 / 18.0);
+    }
+
+    #[test]
+    fn best_ects_counts_the_course_at_the_limit_partially() {
+        let courses = [
+            course("Info1", "grade = 1.0\nects = 6"),
+            course("Datenbanken", "grade = 2.0\nects = 12"),
+            course("Seminar", "grade = 3.0\nects = 6"),
+        ];
+        let rules = "rules = [{ rule = \"best_ects\", ects = 15 }]";
+        assert_close(degree(rules).average(&courses), 24.0 / 15.0);
+    }
+
+    #[test]
+    fn rules_apply_in_order() {
+        let courses = [
+            course("Info1", "grade = 1.0\nects = 6"),
+            course("Datenbanken", "grade = 2.0\nects = 6"),
+            course("Seminar", "grade = 3.0\nects = 6"),
+        ];
+        // dropping first leaves both remaining courses within the limit
+        let rules = "rules = [{ rule = \"drop_worst\" }, { rule = \"best_ects\", ects = 12 }]";
+        assert_close(degree(rules).average(&courses), 1.5);
+        // limiting first leaves two courses of which the worse one is dropped
+        let rules = "rules = [{ rule = \"best_ects\", ects = 12 }, { rule = \"drop_worst\" }]";
+        assert_close(degree(rules).average(&courses), 1.0);
+    }
+
+    #[test]
+    fn target_uses_the_courses_of_the_average() {
+        let courses = [
+            course("Info1", "grade = 1.0\nects = 6"),
+            course("Datenbanken", "grade = 2.0\nects = 12"),
+            course("Numerik", "grade = 5.0\nects = 6"),
+        ];
+        let degree = degree("target_grade = 1.5\nrules = [{ rule = \"drop_worst\" }]");
+        let target = degree.target(&courses).unwrap();
+        assert_close(target.average, 1.0);
+        assert_eq!(target.remaining, 12);
+        // 1.5 over the 6 counted and 12 missing ECTS
+        assert_close(target.needed, 1.75);
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus, Output},
+    sync::Mutex,
+};
+
+/// Effects recorded while running dry, `None` otherwise.
+static PLAN: Mutex<Option<Vec<Effect>>> = Mutex::new(None);
+
+/// A change to the filesystem. Commands make them through the functions of this module, which
+/// mirror their counterparts in [std::fs]. With `mm --dry-run` the effects are recorded instead
+/// of applied and the functions succeed without touching the filesystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Effect {
+    CreateDir(PathBuf),
+    Write(PathBuf),
+    Rename {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    Copy {
+        from: PathBuf,
+        to: PathBuf,
+    },
+    Remove(PathBuf),
+    Symlink {
+        link: PathBuf,
+        original: PathBuf,
+    },
+    /// An external program changing files, e.g. `tar`.
+    Run(String),
+    /// A request changing something outside of the store, e.g. sending a notification.
+    Send(String),
+}
+
+impl Display for Effect {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Effect::CreateDir(path) => write!(f, "create directory {}", path.display()),
+            Effect::Write(path) => match path.exists() {
+                true => write!(f, "overwrite {}", path.display()),
+                false => write!(f, "create {}", path.display()),
+            },
+            Effect::Rename { from, to } => {
+                write!(f, "rename {} to {}", from.display(), to.display())
+            }
+            Effect::Copy { from, to } => write!(f, "copy {} to {}", from.display(), to.display()),
+            Effect::Remove(path) => write!(f, "remove {}", path.display()),
+            Effect::Symlink { link, original } => {
+                write!(f, "link {} to {}", link.display(), original.display())
+            }
+            Effect::Run(command) => write!(f, "run `{}`", command),
+            Effect::Send(url) => write!(f, "send a request to {}", url),
+        }
+    }
+}
+
+/// Records instead of applies the following effects, see `mm --dry-run`.
+pub fn set_dry_run(dry_run: bool) {
+    *PLAN.lock().unwrap_or_else(|err| err.into_inner()) = dry_run.then(Vec::new);
+}
+
+pub fn is_dry_run() -> bool {
+    PLAN.lock().unwrap_or_else(|err| err.into_inner()).is_some()
+}
+
+/// The effects recorded since the last call, in order.
+pub fn take_planned() -> Vec<Effect> {
+    PLAN.lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .as_mut()
+        .map(std::mem::take)
+        .unwrap_or_default()
+}
+
+/// Records the effect while running dry. Returns whether it has been recorded, in which case it
+/// must not be applied. Otherwise the effect is traced with `-v`.
+pub(crate) fn plan(effect: Effect) -> bool {
+    match PLAN.lock().unwrap_or_else(|err| err.into_inner()).as_mut() {
+        Some(plan) => {
+            plan.push(effect);
+            true
+        }
+        None => {
+            tracing::trace!("{}", effect);
+            false
+        }
+    }
+}
+
+pub fn create_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    if plan(Effect::CreateDir(path.to_path_buf())) {
+        return Ok(());
+    }
+    std::fs::create_dir(path)
+}
+
+/// Unlike [std::fs::create_dir_all] only records an effect if the directory is missing.
+pub fn create_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    if is_dry_run() {
+        if !path.is_dir() {
+            plan(Effect::CreateDir(path.to_path_buf()));
+        }
+        return Ok(());
+    }
+    std::fs::create_dir_all(path)
+}
+
+pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    let path = path.as_ref();
+    if plan(Effect::Write(path.to_path_buf())) {
+        return Ok(());
+    }
+    std::fs::write(path, contents)
+}
+
+/// Like [write()], but a new file is only readable by the user, e.g. for diagnostics which may
+/// contain personal data.
+pub fn write_private<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    let path = path.as_ref();
+    if plan(Effect::Write(path.to_path_buf())) {
+        return Ok(());
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    io::Write::write_all(&mut options.open(path)?, contents.as_ref())
+}
+
+pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let effect = Effect::Rename {
+        from: from.to_path_buf(),
+        to: to.to_path_buf(),
+    };
+    if plan(effect) {
+        return Ok(());
+    }
+    std::fs::rename(from, to)
+}
+
+pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<u64> {
+    let (from, to) = (from.as_ref(), to.as_ref());
+    let effect = Effect::Copy {
+        from: from.to_path_buf(),
+        to: to.to_path_buf(),
+    };
+    if plan(effect) {
+        return Ok(0);
+    }
+    std::fs::copy(from, to)
+}
+
+pub fn remove_file<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    if plan(Effect::Remove(path.to_path_buf())) {
+        return Ok(());
+    }
+    std::fs::remove_file(path)
+}
+
+pub fn remove_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    if plan(Effect::Remove(path.to_path_buf())) {
+        return Ok(());
+    }
+    std::fs::remove_dir_all(path)
+}
+
+pub fn remove_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    if plan(Effect::Remove(path.to_path_buf())) {
+        return Ok(());
+    }
+    std::fs::remove_dir(path)
+}
+
+/// Creates `link` pointing to the directory `original`.
+pub fn symlink<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> io::Result<()> {
+    let (original, link) = (original.as_ref(), link.as_ref());
+    let effect = Effect::Symlink {
+        link: link.to_path_buf(),
+        original: original.to_path_buf(),
+    };
+    if plan(effect) {
+        return Ok(());
+    }
+
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(original, link)
+    }
+
+    #[cfg(windows)]
+    {
+        std::os::windows::fs::symlink_dir(original, link)
+    }
+}
+
+/// Runs a program which changes files and waits for it. While running dry it counts as
+/// successful.
+pub fn status(command: &mut Command) -> io::Result<ExitStatus> {
+    if plan(Effect::Run(command_line(command))) {
+        return Ok(ExitStatus::default());
+    }
+    command.status()
+}
+
+/// Like [status], but collects the output of the program. While running dry the output is empty.
+pub fn output(command: &mut Command) -> io::Result<Output> {
+    if plan(Effect::Run(command_line(command))) {
+        return Ok(Output {
+            status: ExitStatus::default(),
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+        });
+    }
+    command.output()
+}
+
+fn command_line(command: &Command) -> String {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|it| it.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+
+use super::{course::serialize_grade, date::Date};
+
+/// Grades up to this value are passing grades.
+pub const PASSING_GRADE: f32 = 4.0;
+
+/// An exam attempt stored in the `course.toml` of a course.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Serialize, Deserialize)]
+pub struct Exam {
+    date: Date,
+    location: Option<String>,
+    #[serde(default = "first_attempt")]
+    attempt: u8,
+    #[serde(
+        serialize_with = "serialize_grade",
+        skip_serializing_if = "Option::is_none"
+    )]
+    grade: Option<f32>,
+}
+
+/// Which passed exam attempt becomes the final grade of a course, configured with
+/// `attempt_policy` in the config file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttemptPolicy {
+    /// The most recent passed attempt, e.g. after an attempt to improve the grade.
+    #[default]
+    Latest,
+    /// The passed attempt with the best grade.
+    Best,
+    /// The first passed attempt. Later attempts do not change the grade.
+    First,
+}
+
+impl AttemptPolicy {
+    /// The attempt which counts among `exams`, if any attempt has been passed.
+    pub fn counting<'e>(&self, exams: &'e [Exam]) -> Option<&'e Exam> {
+        let passed = exams.iter().filter(|it| it.passed());
+        match self {
+            AttemptPolicy::Latest => passed.max_by_key(|it| (it.attempt, it.date)),
+            AttemptPolicy::Best => passed.min_by(|a, b| {
+                a.grade
+                    .unwrap_or(f32::MAX)
+                    .total_cmp(&b.grade.unwrap_or(f32::MAX))
+            }),
+            AttemptPolicy::First => passed.min_by_key(|it| (it.attempt, it.date)),
+        }
+    }
+}
+
+fn first_attempt() -> u8 {
+    1
+}
+
+impl Exam {
+    pub fn new(date: Date, location: Option<String>, attempt: u8) -> Exam {
+        Exam {
+            date,
+            location,
+            attempt,
+            grade: None,
+        }
+    }
+
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    pub fn attempt(&self) -> u8 {
+        self.attempt
+    }
+
+    pub fn grade(&self) -> Option<f32> {
+        self.grade
+    }
+
+    pub fn passed(&self) -> bool {
+        self.grade.is_some_and(|grade| grade <= PASSING_GRADE)
+    }
+
+    pub(super) fn set_grade(&mut self, grade: f32) {
+        self.grade = Some(grade);
+    }
+}
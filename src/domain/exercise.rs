@@ -0,0 +1,211 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    course::serialize_grade,
+    curl::Curl,
+    effects,
+    paths::{apply_template, fill_number, CoursePath},
+};
+
+/// Name of the folder inside a course holding one folder per exercise sheet.
+pub const EXERCISES_DIR: &str = "exercises";
+
+/// An exercise sheet folder like `exercises/ex03` inside a course folder.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Exercise {
+    name: String,
+    path: PathBuf,
+}
+
+/// Points of a graded exercise sheet stored in the `course.toml` of a course.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Serialize, Deserialize)]
+pub struct SheetPoints {
+    sheet: String,
+    #[serde(serialize_with = "serialize_points")]
+    points: f32,
+    #[serde(serialize_with = "serialize_points")]
+    max: f32,
+}
+
+impl Exercise {
+    /// All exercise sheets of a course ordered by name.
+    pub fn list(course: &CoursePath) -> Result<Vec<Exercise>> {
+        let dir = course.join(EXERCISES_DIR);
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut exercises = std::fs::read_dir(&dir)
+            .with_context(|| anyhow!("Failed to read exercises at: {}", dir.display()))?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_str()?.to_string();
+                Some(Exercise {
+                    name,
+                    path: entry.path(),
+                })
+            })
+            .collect::<Vec<_>>();
+        exercises.sort();
+        Ok(exercises)
+    }
+
+    /// Name of the sheet after the highest numbered one, e.g. `ex04` after `ex03`.
+    pub fn next_name(course: &CoursePath) -> Result<String> {
+        let next = Exercise::list(course)?
+            .iter()
+            .filter_map(|it| it.number())
+            .max()
+            .unwrap_or(0)
+            + 1;
+        Ok(format!("ex{:02}", next))
+    }
+
+    pub fn find(course: &CoursePath, name: &str) -> Result<Exercise> {
+        Exercise::list(course)?
+            .into_iter()
+            .find(|it| it.name == name || it.number().is_some_and(|nr| name.parse() == Ok(nr)))
+            .ok_or_else(|| anyhow!("No exercise found by name: {}", name))
+    }
+
+    pub fn create(course: &CoursePath, name: &str) -> Result<Exercise> {
+        let path = course.join(EXERCISES_DIR).join(name);
+        if path.exists() {
+            bail!("Exercise '{}' already exists", name);
+        }
+        effects::create_dir_all(&path)
+            .with_context(|| anyhow!("Failed to create exercise at: {}", path.display()))?;
+        Ok(Exercise {
+            name: name.to_string(),
+            path,
+        })
+    }
+
+    pub fn rename(self, name: &str) -> Result<Exercise> {
+        let Some(dir) = self.path.parent() else {
+            bail!("Exercise has no parent folder: {}", self.path.display());
+        };
+        let path = dir.join(name);
+        if path.exists() {
+            bail!("Exercise '{}' already exists", name);
+        }
+        effects::rename(&self.path, &path)
+            .with_context(|| anyhow!("Failed to rename exercise: {}", self.path.display()))?;
+        Ok(Exercise {
+            name: name.to_string(),
+            path,
+        })
+    }
+
+    pub fn remove(self) -> Result<()> {
+        effects::remove_dir_all(&self.path)
+            .with_context(|| anyhow!("Failed to remove exercise: {}", self.path.display()))
+    }
+
+    /// Copies the directory tree of `template` into the sheet folder and returns the number of
+    /// created files.
+    pub fn apply_template(
+        &self,
+        template: &Path,
+        render: impl Fn(&str) -> String,
+    ) -> Result<usize> {
+        apply_template(template, &self.path, render)
+    }
+
+    /// Downloads the sheet from the `exercise_url` pattern of the course into the sheet folder,
+    /// named after the last segment of the URL.
+    pub fn fetch(&self, pattern: &str) -> Result<PathBuf> {
+        let Some(nr) = self.number() else {
+            bail!("Exercise '{}' has no sheet number", self.name);
+        };
+        let url = fill_number(pattern, "nr", nr);
+        let file = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|it| it.rsplit('/').next())
+            .filter(|it| !it.is_empty())
+            .unwrap_or("sheet.pdf");
+        let path = self.path.join(file);
+        let download = Curl::new(&url)
+            .output(&path)
+            .follow_redirects()
+            .send(format!("Failed to download sheet from '{}'", url));
+        if let Err(err) = download {
+            // curl may leave an empty file behind
+            let _ = effects::remove_file(&path);
+            return Err(err);
+        }
+        Ok(path)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// The trailing number of the name, e.g. 3 for `ex03`.
+    pub fn number(&self) -> Option<u32> {
+        let digits = self
+            .name
+            .chars()
+            .rev()
+            .take_while(|it| it.is_ascii_digit())
+            .collect::<Vec<_>>();
+        digits.into_iter().rev().collect::<String>().parse().ok()
+    }
+}
+
+impl SheetPoints {
+    /// Parses points like `18/20` or `18.5/20`.
+    pub fn parse(sheet: &str, points: &str) -> Result<SheetPoints> {
+        let (reached, max) = points
+            .split_once('/')
+            .ok_or_else(|| anyhow!("Expected points like 18/20 but got: {}", points))?;
+        let parse = |it: &str| {
+            it.trim()
+                .replace(',', ".")
+                .parse::<f32>()
+                .map_err(|_| anyhow!("Invalid points: {}", points))
+        };
+        let (points, max) = (parse(reached)?, parse(max)?);
+        if max <= 0.0 || points < 0.0 {
+            bail!("Invalid points: {}", points);
+        }
+        Ok(SheetPoints {
+            sheet: sheet.to_string(),
+            points,
+            max,
+        })
+    }
+
+    pub fn sheet(&self) -> &str {
+        &self.sheet
+    }
+
+    pub fn points(&self) -> f32 {
+        self.points
+    }
+
+    pub fn max(&self) -> f32 {
+        self.max
+    }
+}
+
+/// Percentage of all reached points, `None` without any recorded sheet.
+pub fn percentage(points: &[SheetPoints]) -> Option<f32> {
+    let max = points.iter().map(|it| it.max).sum::<f32>();
+    (max > 0.0).then(|| points.iter().map(|it| it.points).sum::<f32>() / max * 100.0)
+}
+
+fn serialize_points<S>(points: &f32, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    serialize_grade(&Some(*points), serializer)
+}
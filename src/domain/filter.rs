@@ -0,0 +1,99 @@
+use std::cmp::Ordering;
+
+use crate::cli::CourseSortDO;
+
+use super::{
+    course::{Course, CourseStatus},
+    semester::{Semester, StudyCycle},
+};
+
+/// Which courses a command considers, e.g. `mm course list --graded --degree NAME`. An empty
+/// filter keeps every course.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CourseFilter {
+    pub status: Option<CourseStatus>,
+    /// Only courses with (`true`) or without (`false`) a grade.
+    pub graded: Option<bool>,
+    pub degree: Option<String>,
+    /// Only courses marked with üBK.
+    pub uebk: bool,
+}
+
+impl CourseFilter {
+    pub fn matches(&self, course: &Course) -> bool {
+        self.status.is_none_or(|it| course.lifecycle() == it)
+            && self
+                .graded
+                .is_none_or(|graded| course.grade().is_some() == graded)
+            && self
+                .degree
+                .as_ref()
+                .is_none_or(|degree| course.degrees().contains(degree))
+            && (!self.uebk || course.uebk().unwrap_or(false))
+    }
+
+    pub fn apply<'a, I>(&'a self, courses: I) -> impl Iterator<Item = Course> + 'a
+    where
+        I: Iterator<Item = Course> + 'a,
+    {
+        courses.filter(|course| self.matches(course))
+    }
+}
+
+/// Which semesters a command considers, e.g. `mm status --cycle master --semester m01..m03`. An
+/// empty filter keeps every semester.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SemesterFilter {
+    pub study_cycle: Option<StudyCycle>,
+    /// First semester of the range, included.
+    pub from: Option<Semester>,
+    /// Last semester of the range, included.
+    pub to: Option<Semester>,
+}
+
+impl SemesterFilter {
+    pub fn matches(&self, semester: &Semester) -> bool {
+        self.study_cycle
+            .is_none_or(|it| semester.study_cycle() == it)
+            && self.from.as_ref().is_none_or(|from| semester >= from)
+            && self.to.as_ref().is_none_or(|to| semester <= to)
+    }
+}
+
+/// Order of courses, e.g. `mm course list --sort grade`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CourseSort {
+    #[default]
+    Name,
+    /// Best grade first, ungraded courses last.
+    Grade,
+    /// Most ECTS first.
+    Ects,
+    /// By lifecycle from planned to dropped.
+    Status,
+}
+
+impl CourseSort {
+    pub fn from_do(sort: CourseSortDO) -> CourseSort {
+        match sort {
+            CourseSortDO::Name => CourseSort::Name,
+            CourseSortDO::Grade => CourseSort::Grade,
+            CourseSortDO::Ects => CourseSort::Ects,
+            CourseSortDO::Status => CourseSort::Status,
+        }
+    }
+
+    /// Compares by the sort key, then by name.
+    pub fn compare(&self, a: &Course, b: &Course) -> Ordering {
+        let by_key = match self {
+            CourseSort::Name => Ordering::Equal,
+            CourseSort::Grade => match (a.grade(), b.grade()) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            },
+            CourseSort::Ects => b.ects().cmp(&a.ects()),
+            CourseSort::Status => a.lifecycle().cmp(&b.lifecycle()),
+        };
+        by_key.then_with(|| a.name().cmp(&b.name()))
+    }
+}
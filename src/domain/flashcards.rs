@@ -0,0 +1,111 @@
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+
+use super::{note::Note, paths::CoursePath};
+
+/// Study card written as `Q:`/`A:` lines in the notes of a course or listed in its
+/// `flashcards.toml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Flashcard {
+    front: String,
+    back: String,
+    /// Where the card comes from, exported as Anki tag.
+    source: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct FlashcardsDO {
+    #[serde(default)]
+    cards: Vec<CardDO>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CardDO {
+    front: String,
+    back: String,
+}
+
+impl Flashcard {
+    /// Cards of `flashcards.toml` followed by the cards of all notes, oldest note first.
+    pub fn collect(course: &CoursePath) -> Result<Vec<Flashcard>> {
+        let mut cards = Vec::new();
+        let file = course.join("flashcards.toml");
+        if file.is_file() {
+            let content = std::fs::read_to_string(&file)
+                .with_context(|| anyhow!("Failed to read: {}", file.display()))?;
+            let flashcards = toml_edit::de::from_str::<FlashcardsDO>(&content)
+                .with_context(|| anyhow!("Failed to parse flashcards from: {}", file.display()))?;
+            cards.extend(flashcards.cards.into_iter().map(|it| Flashcard {
+                front: it.front,
+                back: it.back,
+                source: "flashcards".into(),
+            }));
+        }
+        for note in Note::list(course)? {
+            // compiled PDFs and other binary files in the notes folder are skipped
+            let Ok(content) = std::fs::read_to_string(note.path()) else {
+                continue;
+            };
+            cards.extend(parse(&content, note.title()));
+        }
+        Ok(cards)
+    }
+}
+
+/// Cards of a note. A card starts with a `Q:` line and an `A:` line, the answer continues until
+/// the next blank line or question. Comment and list markers before `Q:` and `A:` are ignored,
+/// so LaTeX notes can use `% Q:`.
+fn parse(content: &str, source: &str) -> Vec<Flashcard> {
+    let mut cards = Vec::new();
+    let mut front: Option<String> = None;
+    let mut back: Option<Vec<String>> = None;
+    let mut finish = |front: &mut Option<String>, back: &mut Option<Vec<String>>| {
+        if let (Some(question), Some(answer)) = (front.take(), back.take()) {
+            cards.push(Flashcard {
+                front: question,
+                back: answer.join("\n"),
+                source: source.to_string(),
+            });
+        }
+    };
+
+    for line in content.lines() {
+        let text = line.trim_start_matches(|c: char| c.is_whitespace() || "%->*".contains(c));
+        if let Some(question) = text.strip_prefix("Q:") {
+            finish(&mut front, &mut back);
+            front = Some(question.trim().to_string());
+        } else if let (Some(answer), Some(_)) = (text.strip_prefix("A:"), &front) {
+            back = Some(vec![answer.trim().to_string()]);
+        } else if line.trim().is_empty() {
+            finish(&mut front, &mut back);
+        } else if let Some(answer) = back.as_mut() {
+            answer.push(text.trim().to_string());
+        }
+    }
+    finish(&mut front, &mut back);
+    cards
+}
+
+/// Tab separated file for the Anki import with front, back and tags. The header lines tell Anki
+/// about the separator, HTML line breaks and the tags column.
+pub fn to_anki_tsv(cards: &[Flashcard], tag: &str) -> String {
+    let field = |text: &str| {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('\t', " ")
+            .replace('\n', "<br>")
+    };
+    let tag_of = |text: &str| text.split_whitespace().collect::<Vec<_>>().join("_");
+    let mut tsv = String::from("#separator:tab\n#html:true\n#tags column:3\n");
+    for card in cards {
+        tsv.push_str(&format!(
+            "{}\t{}\t{} {}\n",
+            field(&card.front),
+            field(&card.back),
+            tag_of(tag),
+            tag_of(&card.source)
+        ));
+    }
+    tsv
+}
@@ -0,0 +1,202 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use super::progress::Progress;
+
+/// Header names of the columns in exports of HISinOne, CampusOnline and similar systems.
+const CODE_HEADERS: [&str; 7] = [
+    "prüfungsnr",
+    "prüfungsnummer",
+    "modulnr",
+    "nr",
+    "number",
+    "code",
+    "id",
+];
+const TITLE_HEADERS: [&str; 6] = [
+    "prüfungstext",
+    "bezeichnung",
+    "titel",
+    "title",
+    "name",
+    "text",
+];
+const GRADE_HEADERS: [&str; 2] = ["note", "grade"];
+const ECTS_HEADERS: [&str; 5] = ["ects", "cp", "lp", "credits", "bonus"];
+
+/// A row of a grade export.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradeRecord {
+    code: Option<String>,
+    title: String,
+    grade: Option<f32>,
+    ects: Option<u8>,
+}
+
+impl GradeRecord {
+    /// Reads a CSV export. Excel files are converted to CSV with LibreOffice first.
+    pub fn read(path: &Path) -> Result<Vec<GradeRecord>> {
+        let extension = path
+            .extension()
+            .map(|it| it.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let content = match extension.as_str() {
+            "xlsx" | "xls" | "ods" => convert_to_csv(path)?,
+            _ => {
+                let bytes = std::fs::read(path)
+                    .with_context(|| anyhow!("Failed to read: {}", path.display()))?;
+                decode(&bytes)
+            }
+        };
+        GradeRecord::parse(&content)
+    }
+
+    /// Parses a CSV export with `;`, `,` or tab as separator. Columns are found by their header,
+    /// rows without title are skipped.
+    pub fn parse(content: &str) -> Result<Vec<GradeRecord>> {
+        let mut lines = content.lines().filter(|it| !it.trim().is_empty());
+        let header = lines.next().ok_or_else(|| anyhow!("The export is empty"))?;
+        let separator = [';', '\t', ',']
+            .into_iter()
+            .max_by_key(|it| header.matches(*it).count())
+            .unwrap_or(';');
+        let header = split_row(header, separator)
+            .into_iter()
+            .map(|it| it.to_lowercase())
+            .collect::<Vec<_>>();
+
+        let column = |names: &[&str]| {
+            names
+                .iter()
+                .find_map(|name| header.iter().position(|it| it == name))
+                .or_else(|| {
+                    names
+                        .iter()
+                        .find_map(|name| header.iter().position(|it| it.starts_with(name)))
+                })
+        };
+        let title = column(&TITLE_HEADERS).ok_or_else(|| {
+            anyhow!(
+                "No title column found. Expected one of: {}",
+                TITLE_HEADERS.join(", ")
+            )
+        })?;
+        let grade = column(&GRADE_HEADERS).ok_or_else(|| {
+            anyhow!(
+                "No grade column found. Expected one of: {}",
+                GRADE_HEADERS.join(", ")
+            )
+        })?;
+        let code = column(&CODE_HEADERS);
+        let ects = column(&ECTS_HEADERS);
+
+        let records = lines
+            .map(|line| split_row(line, separator))
+            .filter_map(|row| {
+                let cell = |idx: Option<usize>| {
+                    idx.and_then(|it| row.get(it))
+                        .map(|it| it.trim().to_string())
+                        .filter(|it| !it.is_empty())
+                };
+                Some(GradeRecord {
+                    title: cell(Some(title))?,
+                    code: cell(code),
+                    grade: cell(Some(grade)).and_then(|it| parse_number(&it)),
+                    ects: cell(ects)
+                        .and_then(|it| parse_number(&it))
+                        .map(|it| it.round() as u8),
+                })
+            })
+            .collect();
+        Ok(records)
+    }
+
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn grade(&self) -> Option<f32> {
+        self.grade
+    }
+
+    pub fn ects(&self) -> Option<u8> {
+        self.ects
+    }
+}
+
+/// Splits a CSV row, honoring double quotes.
+fn split_row(line: &str, separator: char) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                cell.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => cells.push(std::mem::take(&mut cell)),
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell);
+    cells
+}
+
+/// Numbers with decimal comma like `1,7` as used in German exports.
+fn parse_number(value: &str) -> Option<f32> {
+    value.replace(',', ".").parse().ok()
+}
+
+/// Exports are UTF-8 or, for older systems, Latin-1.
+fn decode(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(bytes);
+    match std::str::from_utf8(bytes) {
+        Ok(content) => content.to_string(),
+        Err(_) => bytes.iter().map(|b| *b as char).collect(),
+    }
+}
+
+fn convert_to_csv(path: &Path) -> Result<String> {
+    let out_dir = std::env::temp_dir().join(format!("mm-grades-{}", std::process::id()));
+    std::fs::create_dir_all(&out_dir)?;
+    let progress = Progress::spinner(format!("Converting {}", path.display()));
+    let status = Command::new("libreoffice")
+        .args([
+            "--headless",
+            "--convert-to",
+            "csv:Text - txt - csv (StarCalc):59,34,76",
+        ])
+        .arg("--outdir")
+        .arg(&out_dir)
+        .arg(path)
+        .output();
+    drop(progress);
+    let csv: PathBuf = out_dir.join(path.with_extension("csv").file_name().unwrap_or_default());
+    let content = match status {
+        Ok(output) if output.status.success() && csv.is_file() => std::fs::read(&csv)
+            .map(|it| decode(&it))
+            .with_context(|| anyhow!("Failed to read converted export: {}", csv.display())),
+        Ok(_) => Err(anyhow!("LibreOffice failed to convert: {}", path.display())),
+        Err(_) => Err(anyhow!(
+            "Reading spreadsheets requires LibreOffice. Export the grades as CSV instead"
+        )),
+    };
+    let _ = std::fs::remove_dir_all(&out_dir);
+    if let Ok(content) = &content {
+        if content.trim().is_empty() {
+            bail!("The converted export is empty: {}", path.display());
+        }
+    }
+    content
+}
@@ -0,0 +1,156 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+
+use super::effects;
+
+/// Which files of the entry point are versioned with git.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitMode {
+    /// Only `.mm` and `course.toml` files.
+    Metadata,
+    /// Everything inside the entry point.
+    All,
+}
+
+/// Git repository at the entry point which records a commit after every mutating command.
+pub struct History {
+    root: PathBuf,
+    mode: GitMode,
+}
+
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    hash: String,
+    date: String,
+    message: String,
+}
+
+/// Excludes everything but the metadata files in [GitMode::Metadata]. Written to
+/// `.git/info/exclude` so the entry point itself is not cluttered.
+const METADATA_EXCLUDE: &str = "# managed by mm\n*\n!*/\n!.mm\n!course.toml\n";
+const ALL_EXCLUDE: &str = "# managed by mm\n.mm.lock\n";
+
+impl History {
+    pub fn new(root: &Path, mode: GitMode) -> History {
+        History {
+            root: root.to_path_buf(),
+            mode,
+        }
+    }
+
+    /// Commits all changes of the versioned files. The repository is created on first use.
+    /// Returns false if there was nothing to commit.
+    pub fn commit(&self, message: &str) -> Result<bool> {
+        self.init()?;
+        self.git(&["add", "-A"])?;
+        let staged = self.command(&["diff", "--cached", "--quiet"]).output()?;
+        if staged.status.success() {
+            return Ok(false);
+        }
+
+        let mut command = self.command(&[]);
+        // fall back to a local identity if the user has not configured git
+        if !self
+            .command(&["config", "user.email"])
+            .output()?
+            .status
+            .success()
+        {
+            command.args(["-c", "user.name=mm", "-c", "user.email=mm@localhost"]);
+        }
+        command.args(["commit", "-q", "-m", message]);
+        check(command.output(), "commit")?;
+        Ok(true)
+    }
+
+    /// The latest `limit` commits, newest first.
+    pub fn log(&self, limit: usize) -> Result<Vec<HistoryEntry>> {
+        if !self.root.join(".git").exists() {
+            return Ok(Vec::new());
+        }
+        let limit = format!("-n{}", limit);
+        let mut command = self.command(&[
+            "log",
+            &limit,
+            "--date=format:%Y-%m-%d %H:%M",
+            "--format=%h%x09%ad%x09%s",
+        ]);
+        // an empty repository has no HEAD yet
+        let output = match check(command.output(), "log") {
+            Ok(output) => output,
+            Err(_) => return Ok(Vec::new()),
+        };
+        let entries = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut parts = line.splitn(3, '\t');
+                Some(HistoryEntry {
+                    hash: parts.next()?.to_string(),
+                    date: parts.next()?.to_string(),
+                    message: parts.next()?.to_string(),
+                })
+            })
+            .collect();
+        Ok(entries)
+    }
+
+    fn init(&self) -> Result<()> {
+        let git_dir = self.root.join(".git");
+        if !git_dir.exists() {
+            self.git(&["init", "-q"])?;
+        }
+        let exclude = git_dir.join("info").join("exclude");
+        let content = match self.mode {
+            GitMode::Metadata => METADATA_EXCLUDE,
+            GitMode::All => ALL_EXCLUDE,
+        };
+        if std::fs::read_to_string(&exclude).ok().as_deref() != Some(content) {
+            effects::create_dir_all(git_dir.join("info"))?;
+            effects::write(&exclude, content)
+                .with_context(|| anyhow!("Failed to write: {}", exclude.display()))?;
+        }
+        Ok(())
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new("git");
+        command.arg("-C").arg(&self.root).args(args);
+        command
+    }
+
+    fn git(&self, args: &[&str]) -> Result<Output> {
+        check(self.command(args).output(), args[0])
+    }
+}
+
+fn check(output: std::io::Result<Output>, action: &str) -> Result<Output> {
+    let output = output.context("Failed to run 'git'. Is it installed?")?;
+    if !output.status.success() {
+        bail!(
+            "'git {}' failed: {}",
+            action,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output)
+}
+
+impl HistoryEntry {
+    pub fn hash(&self) -> &str {
+        &self.hash
+    }
+
+    pub fn date(&self) -> &str {
+        &self.date
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use regex::Regex;
+
+use super::{effects, exercise::Exercise, paths::CoursePath};
+
+/// Name of the folder inside a course holding lecture slides and recordings.
+pub const LECTURES_DIR: &str = "lectures";
+
+/// Rule folder standing for the latest exercise sheet of the course.
+pub const LATEST_EXERCISE: &str = "exercise";
+
+/// Suffixes of files browsers are still downloading.
+const PARTIAL_SUFFIXES: [&str; 4] = [".part", ".crdownload", ".download", ".tmp"];
+
+/// The folder new downloads land in, configured with `inbox`, together with the
+/// `[[inbox_rules]]` used by `mm watch`. Defaults to the download directory.
+#[derive(Debug, Clone)]
+pub struct Inbox {
+    dir: PathBuf,
+    rules: Vec<InboxRule>,
+}
+
+/// Files whose name matches `pattern` are filed into `folder` of the active course.
+#[derive(Debug, Clone)]
+pub struct InboxRule {
+    pattern: Regex,
+    folder: String,
+    rename: bool,
+}
+
+impl Inbox {
+    pub fn new(dir: PathBuf, rules: Vec<InboxRule>) -> Inbox {
+        Inbox { dir, rules }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// The first rule matching the file name.
+    pub fn rule(&self, file: &Path) -> Option<&InboxRule> {
+        let name = file.file_name()?.to_str()?;
+        self.rules.iter().find(|it| it.pattern.is_match(name))
+    }
+
+    /// The `count` most recently modified files, newest first. Hidden files and unfinished
+    /// downloads are skipped.
+    pub fn newest(&self, count: usize) -> Result<Vec<PathBuf>> {
+        let entries = std::fs::read_dir(&self.dir)
+            .with_context(|| anyhow!("Failed to read inbox at: {}", self.dir.display()))?;
+        let mut files = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| is_complete(&entry.path()))
+            .filter_map(|entry| {
+                let metadata = entry.metadata().ok()?;
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                Some((modified, entry.path()))
+            })
+            .collect::<Vec<_>>();
+        files.sort_by_key(|(modified, _)| std::cmp::Reverse(*modified));
+        Ok(files.into_iter().take(count).map(|(_, it)| it).collect())
+    }
+}
+
+impl InboxRule {
+    pub fn new(pattern: &str, folder: String, rename: bool) -> Result<InboxRule> {
+        let pattern = Regex::new(pattern)
+            .with_context(|| anyhow!("Invalid pattern in inbox rule: {}", pattern))?;
+        Ok(InboxRule {
+            pattern,
+            folder,
+            rename,
+        })
+    }
+
+    pub fn folder(&self) -> &str {
+        &self.folder
+    }
+
+    pub fn rename(&self) -> bool {
+        self.rename
+    }
+}
+
+/// Folder of the course to file into. [LATEST_EXERCISE] stands for the latest exercise sheet,
+/// every other folder is relative to the course folder.
+pub fn target_dir(course: &CoursePath, folder: &str) -> Result<PathBuf> {
+    if folder == LATEST_EXERCISE {
+        let exercise = Exercise::list(course)?
+            .pop()
+            .ok_or_else(|| anyhow!("No exercises found at: {}", course.display()))?;
+        return Ok(exercise.path().clone());
+    }
+    Ok(course.join(folder))
+}
+
+/// Whether the path is a regular, visible file which is not being downloaded anymore.
+pub fn is_complete(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|it| it.to_str()) else {
+        return false;
+    };
+    path.is_file()
+        && !name.starts_with('.')
+        && !PARTIAL_SUFFIXES.iter().any(|it| name.ends_with(it))
+}
+
+/// Moves `file` into `dir`, optionally under its normalized name. Falls back to copying when the
+/// inbox is on another file system.
+pub fn file_into(file: &Path, dir: &Path, normalize: bool) -> Result<PathBuf> {
+    let Some(name) = file.file_name().and_then(|it| it.to_str()) else {
+        bail!("Invalid file name: {}", file.display());
+    };
+    let name = match normalize {
+        true => normalize_file_name(name),
+        false => name.to_string(),
+    };
+    let target = dir.join(&name);
+    if target.exists() {
+        bail!("File '{}' already exists in: {}", name, dir.display());
+    }
+    effects::create_dir_all(dir)
+        .with_context(|| anyhow!("Failed to create folder: {}", dir.display()))?;
+    if effects::rename(file, &target).is_err() {
+        effects::copy(file, &target)
+            .with_context(|| anyhow!("Failed to move file: {}", file.display()))?;
+        effects::remove_file(file)
+            .with_context(|| anyhow!("Failed to remove file: {}", file.display()))?;
+    }
+    Ok(target)
+}
+
+/// Lowercase name with umlauts spelled out and every other run of special characters replaced by
+/// a dash, e.g. `Übung 3 (Lösung).PDF` becomes `uebung-3-loesung.pdf`.
+pub fn normalize_file_name(name: &str) -> String {
+    let (stem, extension) = match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+        _ => (name, None),
+    };
+    let mut normalized = String::new();
+    for c in stem.chars().flat_map(char::to_lowercase) {
+        match c {
+            'ä' => normalized.push_str("ae"),
+            'ö' => normalized.push_str("oe"),
+            'ü' => normalized.push_str("ue"),
+            'ß' => normalized.push_str("ss"),
+            c if c.is_ascii_alphanumeric() => normalized.push(c),
+            _ if normalized.ends_with('-') || normalized.is_empty() => {}
+            _ => normalized.push('-'),
+        }
+    }
+    let normalized = normalized.trim_end_matches('-');
+    match extension {
+        Some(extension) => format!("{}.{}", normalized, extension.to_lowercase()),
+        None => normalized.to_string(),
+    }
+}
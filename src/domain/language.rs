@@ -0,0 +1,28 @@
+use serde::Deserialize;
+
+/// Language of the output, configured with `language` in the config file or taken from the
+/// locale of the environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum Language {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "de")]
+    German,
+}
+
+impl Language {
+    /// The language of the first locale set of `LC_ALL`, `LC_MESSAGES` and `LANG`, e.g.
+    /// `de_DE.UTF-8`. English for other languages.
+    pub fn from_env() -> Language {
+        let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|it| std::env::var(it).ok())
+            .find(|it| !it.is_empty())
+            .unwrap_or_default();
+        match locale.starts_with("de") {
+            true => Language::German,
+            false => Language::English,
+        }
+    }
+}
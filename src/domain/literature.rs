@@ -0,0 +1,584 @@
+use std::{
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{
+    curl::Curl,
+    effects,
+    paths::{CoursePath, ReadWriteDO},
+};
+
+/// Name of the folder inside a course holding downloaded papers and books.
+pub const LITERATURE_DIR: &str = "literature";
+
+/// Bibliography of a course stored in `literature.toml` next to its `course.toml`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Literature {
+    #[serde(default)]
+    references: Vec<Reference>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reference {
+    /// Citation key, e.g. `knuth1997art`.
+    key: String,
+    /// BibTeX entry type like `article`, `book` or `misc`.
+    kind: String,
+    title: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    authors: Vec<String>,
+    year: Option<u16>,
+    /// Journal, proceedings or publisher.
+    venue: Option<String>,
+    doi: Option<String>,
+    url: Option<String>,
+    /// Local PDF, relative to the course folder or absolute for linked attachments.
+    file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LiteratureFile(PathBuf);
+
+impl Literature {
+    pub fn file(course: &CoursePath) -> LiteratureFile {
+        LiteratureFile(course.join("literature.toml"))
+    }
+
+    /// The bibliography of the course or an empty one.
+    pub fn load(course: &CoursePath) -> Result<Literature> {
+        let file = Literature::file(course);
+        match file.is_file() {
+            true => file.read(),
+            false => Ok(Literature::default()),
+        }
+    }
+
+    pub fn save(&self, course: &CoursePath) -> Result<()> {
+        Literature::file(course).write(self)
+    }
+
+    pub fn references(&self) -> &Vec<Reference> {
+        &self.references
+    }
+
+    /// Adds the reference under a key not used yet, appending `a`, `b`, … on collisions.
+    pub fn add(&mut self, mut reference: Reference) -> Result<&Reference> {
+        if let Some(doi) = &reference.doi {
+            if let Some(it) = self.references.iter().find(|it| {
+                it.doi
+                    .as_ref()
+                    .is_some_and(|it| it.eq_ignore_ascii_case(doi))
+            }) {
+                bail!("Reference with DOI {} already exists as '{}'", doi, it.key);
+            }
+        }
+        let base = reference.key.clone();
+        let mut suffix = b'a';
+        while self.references.iter().any(|it| it.key == reference.key) {
+            if suffix > b'z' {
+                bail!("Too many references with key '{}'", base);
+            }
+            reference.key = format!("{}{}", base, suffix as char);
+            suffix += 1;
+        }
+        self.references.push(reference);
+        Ok(self.references.last().expect("just pushed"))
+    }
+
+    /// Whether a reference with the same key or DOI exists.
+    pub fn contains(&self, reference: &Reference) -> bool {
+        self.references.iter().any(|it| {
+            let same_doi = match (&it.doi, &reference.doi) {
+                (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                _ => false,
+            };
+            it.key == reference.key || same_doi
+        })
+    }
+
+    pub fn remove(&mut self, key: &str) -> Result<Reference> {
+        let index = self
+            .references
+            .iter()
+            .position(|it| it.key == key)
+            .ok_or_else(|| anyhow!("No reference found by key: {}", key))?;
+        Ok(self.references.remove(index))
+    }
+
+    pub fn set_file(&mut self, key: &str, file: PathBuf) {
+        if let Some(reference) = self.references.iter_mut().find(|it| it.key == key) {
+            reference.file = Some(file);
+        }
+    }
+
+    /// All references as BibTeX entries.
+    pub fn to_bibtex(&self) -> String {
+        self.references
+            .iter()
+            .map(Reference::to_bibtex)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Reference {
+    /// Fetches the metadata of a DOI (`10.…` or a doi.org link) or a web page. Returns the
+    /// reference together with the URL of its PDF, if one is known.
+    pub fn fetch(source: &str) -> Result<(Reference, Option<String>)> {
+        match parse_doi(source) {
+            Some(doi) => Reference::from_doi(&doi),
+            None if source.starts_with("http://") || source.starts_with("https://") => {
+                Reference::from_url(source)
+            }
+            None => bail!("Expected a DOI or an URL but got: {}", source),
+        }
+    }
+
+    /// Uses the CSL JSON returned by doi.org for content negotiation.
+    fn from_doi(doi: &str) -> Result<(Reference, Option<String>)> {
+        let url = format!("https://doi.org/{}", doi);
+        let content = curl(&url, Some("application/vnd.citationstyles.csl+json"))?;
+        let csl: Value = serde_json::from_slice(&content)
+            .with_context(|| anyhow!("doi.org returned no metadata for: {}", doi))?;
+
+        let text = |value: &Value| match value {
+            Value::Array(values) => values.first().and_then(Value::as_str).map(str::to_string),
+            value => value.as_str().map(str::to_string),
+        };
+        let title = text(&csl["title"]).ok_or_else(|| anyhow!("No title found for: {}", doi))?;
+        let authors = csl["author"]
+            .as_array()
+            .map(|authors| {
+                authors
+                    .iter()
+                    .filter_map(|it| match (it["given"].as_str(), it["family"].as_str()) {
+                        (Some(given), Some(family)) => Some(format!("{} {}", given, family)),
+                        (None, Some(family)) => Some(family.to_string()),
+                        _ => it["literal"].as_str().map(str::to_string),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let year = ["issued", "published-print", "published-online"]
+            .iter()
+            .find_map(|it| csl[it]["date-parts"][0][0].as_u64())
+            .map(|it| it as u16);
+        let kind = match csl["type"].as_str() {
+            Some("journal-article") => "article",
+            Some("book") | Some("monograph") => "book",
+            Some("proceedings-article") => "inproceedings",
+            Some("book-chapter") => "incollection",
+            _ => "misc",
+        };
+        let venue = text(&csl["container-title"])
+            .filter(|it| !it.is_empty())
+            .or_else(|| text(&csl["publisher"]));
+        let pdf_url = csl["link"].as_array().and_then(|links| {
+            links
+                .iter()
+                .find(|it| it["content-type"].as_str() == Some("application/pdf"))
+                .and_then(|it| it["URL"].as_str())
+                .map(str::to_string)
+        });
+
+        let reference = Reference::new(kind, title, authors, year)
+            .with_venue(venue)
+            .with_doi(Some(doi.to_string()))
+            .with_url(text(&csl["URL"]));
+        Ok((reference, pdf_url))
+    }
+
+    /// Reads the `citation_*` meta tags used by publishers and repositories, falling back to the
+    /// page title. Links to PDFs are taken as the PDF itself.
+    fn from_url(url: &str) -> Result<(Reference, Option<String>)> {
+        let path = url.split(['?', '#']).next().unwrap_or(url);
+        if path.to_lowercase().ends_with(".pdf") {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            let title = name[..name.len() - 4].replace(['_', '-'], " ");
+            let reference =
+                Reference::new("misc", title, Vec::new(), None).with_url(Some(url.to_string()));
+            return Ok((reference, Some(url.to_string())));
+        }
+
+        let html = String::from_utf8_lossy(&curl(url, None)?).to_string();
+        let meta = meta_tags(&html);
+        let first = |names: &[&str]| {
+            names.iter().find_map(|name| {
+                meta.iter()
+                    .find(|(it, _)| it.eq_ignore_ascii_case(name))
+                    .map(|(_, content)| content.clone())
+            })
+        };
+        let title = first(&["citation_title", "dc.title", "og:title"])
+            .or_else(|| {
+                let regex = Regex::new(r"(?is)<title[^>]*>(.*?)</title>").expect("valid regex");
+                regex
+                    .captures(&html)
+                    .map(|it| decode_entities(it[1].trim()))
+            })
+            .ok_or_else(|| anyhow!("No title found at: {}", url))?;
+        let authors = meta
+            .iter()
+            .filter(|(name, _)| name.eq_ignore_ascii_case("citation_author"))
+            .map(|(_, content)| content.clone())
+            .collect();
+        let year = first(&[
+            "citation_publication_date",
+            "citation_date",
+            "citation_year",
+        ])
+        .and_then(|it| it.get(..4).and_then(|it| it.parse().ok()));
+        let venue = first(&["citation_journal_title", "citation_conference_title"]);
+        let kind = match (
+            first(&["citation_journal_title"]),
+            first(&["citation_conference_title"]),
+        ) {
+            (Some(_), _) => "article",
+            (_, Some(_)) => "inproceedings",
+            _ => "misc",
+        };
+
+        let reference = Reference::new(kind, title, authors, year)
+            .with_venue(venue)
+            .with_doi(first(&["citation_doi"]).and_then(|it| parse_doi(&it)))
+            .with_url(Some(url.to_string()));
+        Ok((reference, first(&["citation_pdf_url"])))
+    }
+
+    /// Parses the entries of a BibTeX file like a Zotero export. Attachments in `file` fields are
+    /// linked if they exist, relative paths are resolved against `base`.
+    pub fn parse_bibtex(content: &str, base: &Path) -> Result<Vec<Reference>> {
+        let mut references = Vec::new();
+        let mut rest = content;
+        while let Some(start) = rest.find('@') {
+            rest = &rest[start + 1..];
+            let Some(open) = rest.find(['{', '(']) else {
+                break;
+            };
+            let kind = rest[..open].trim().to_lowercase();
+            let Some(end) = closing(rest, open) else {
+                bail!("Unbalanced braces in BibTeX entry of type '{}'", kind);
+            };
+            let body = &rest[open + 1..end];
+            rest = &rest[end + 1..];
+            if matches!(kind.as_str(), "comment" | "string" | "preamble") {
+                continue;
+            }
+
+            let Some((key, fields)) = body.split_once(',') else {
+                continue;
+            };
+            let fields = bibtex_fields(fields);
+            let field = |name: &str| {
+                fields
+                    .iter()
+                    .find(|(it, _)| it == name)
+                    .map(|(_, value)| value.clone())
+                    .filter(|it| !it.is_empty())
+            };
+            let Some(title) = field("title") else {
+                continue;
+            };
+            let authors = field("author")
+                .map(|it| {
+                    it.split(" and ")
+                        .map(|author| match author.split_once(',') {
+                            Some((last, first)) => format!("{} {}", first.trim(), last.trim()),
+                            None => author.trim().to_string(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let year = field("year").and_then(|it| it.get(..4).and_then(|it| it.parse().ok()));
+            let file = field("file").and_then(|it| attachment(&it, base));
+
+            let mut reference = Reference::new(&kind, title, authors, year)
+                .with_venue(
+                    field("journal")
+                        .or_else(|| field("booktitle"))
+                        .or_else(|| field("publisher")),
+                )
+                .with_doi(field("doi").and_then(|it| parse_doi(&it)))
+                .with_url(field("url"));
+            reference.key = key.trim().to_string();
+            reference.file = file;
+            references.push(reference);
+        }
+        Ok(references)
+    }
+
+    /// A reference with the key built from the first author, the year and the first word of
+    /// the title, e.g. `knuth1997art`.
+    pub fn new(kind: &str, title: String, authors: Vec<String>, year: Option<u16>) -> Reference {
+        let author = authors
+            .first()
+            .and_then(|it| it.split_whitespace().last())
+            .unwrap_or("anon");
+        let word = title
+            .split(|c: char| !c.is_alphanumeric())
+            .find(|it| it.len() > 3)
+            .unwrap_or("");
+        let key = [
+            author,
+            &year.map(|it| it.to_string()).unwrap_or_default(),
+            word,
+        ]
+        .concat()
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+        Reference {
+            key,
+            kind: kind.to_string(),
+            title,
+            authors,
+            year,
+            venue: None,
+            doi: None,
+            url: None,
+            file: None,
+        }
+    }
+
+    fn with_venue(mut self, venue: Option<String>) -> Reference {
+        self.venue = venue;
+        self
+    }
+
+    fn with_doi(mut self, doi: Option<String>) -> Reference {
+        self.doi = doi;
+        self
+    }
+
+    fn with_url(mut self, url: Option<String>) -> Reference {
+        self.url = url;
+        self
+    }
+
+    /// Downloads the PDF to `literature/<key>.pdf` and returns the path relative to the course.
+    pub fn download(&self, course: &CoursePath, pdf_url: &str) -> Result<PathBuf> {
+        let relative = Path::new(LITERATURE_DIR).join(format!("{}.pdf", self.key));
+        let path = course.join(&relative);
+        if path.exists() {
+            bail!("File already exists: {}", path.display());
+        }
+        let dir = course.join(LITERATURE_DIR);
+        effects::create_dir_all(&dir)
+            .with_context(|| anyhow!("Failed to create folder: {}", dir.display()))?;
+        let content = curl(pdf_url, Some("application/pdf"))?;
+        if !content.starts_with(b"%PDF") {
+            bail!("No PDF found at: {}", pdf_url);
+        }
+        effects::write(&path, content)
+            .with_context(|| anyhow!("Failed to write: {}", path.display()))?;
+        Ok(relative)
+    }
+
+    fn to_bibtex(&self) -> String {
+        let venue = match self.kind.as_str() {
+            "article" => "journal",
+            "inproceedings" | "incollection" => "booktitle",
+            _ => "publisher",
+        };
+        let fields = [
+            ("title", Some(self.title.clone())),
+            (
+                "author",
+                (!self.authors.is_empty()).then(|| self.authors.join(" and ")),
+            ),
+            ("year", self.year.map(|it| it.to_string())),
+            (venue, self.venue.clone()),
+            ("doi", self.doi.clone()),
+            ("url", self.url.clone()),
+            (
+                "file",
+                self.file.as_ref().map(|it| it.display().to_string()),
+            ),
+        ];
+        let fields = fields
+            .into_iter()
+            .filter_map(|(name, value)| Some(format!("  {} = {{{}}}", name, value?)))
+            .collect::<Vec<_>>()
+            .join(",\n");
+        format!("@{}{{{},\n{}\n}}\n", self.kind, self.key, fields)
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn authors(&self) -> &Vec<String> {
+        &self.authors
+    }
+
+    pub fn year(&self) -> Option<u16> {
+        self.year
+    }
+
+    pub fn file(&self) -> Option<&PathBuf> {
+        self.file.as_ref()
+    }
+}
+
+impl Deref for LiteratureFile {
+    type Target = PathBuf;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl ReadWriteDO for LiteratureFile {
+    type Object = Literature;
+
+    fn write(&self, object: &Self::Object) -> Result<()> {
+        let data = toml_edit::ser::to_string_pretty(&object).with_context(|| {
+            anyhow!(
+                "Failed to serialize data to toml for: {}",
+                self.deref().display()
+            )
+        })?;
+        effects::write(self.deref(), data)
+            .with_context(|| anyhow!("Failed to write data to file: {}", self.deref().display()))
+    }
+}
+
+/// Index of the brace or parenthesis closing the one at `open`.
+fn closing(text: &str, open: usize) -> Option<usize> {
+    let (opening, closing) = match text[open..].starts_with('(') {
+        true => ('(', ')'),
+        false => ('{', '}'),
+    };
+    let mut depth = 0;
+    for (i, c) in text[open..].char_indices() {
+        if c == opening {
+            depth += 1;
+        } else if c == closing {
+            depth -= 1;
+            if depth == 0 {
+                return Some(open + i);
+            }
+        }
+    }
+    None
+}
+
+/// The `name = value` pairs of an entry with lowercase names. Values lose their delimiters and
+/// the braces protecting capitalization.
+fn bibtex_fields(text: &str) -> Vec<(String, String)> {
+    let mut fields = Vec::new();
+    let mut rest = text;
+    while let Some(eq) = rest.find('=') {
+        let name = rest[..eq]
+            .trim()
+            .trim_start_matches(',')
+            .trim()
+            .to_lowercase();
+        let value = rest[eq + 1..].trim_start();
+        let (raw, remaining) = match value.chars().next() {
+            Some('{') => match closing(value, 0) {
+                Some(end) => (&value[1..end], &value[end + 1..]),
+                None => (&value[1..], ""),
+            },
+            Some('"') => match value[1..].find('"') {
+                Some(end) => (&value[1..end + 1], &value[end + 2..]),
+                None => (&value[1..], ""),
+            },
+            _ => match value.find(',') {
+                Some(end) => (&value[..end], &value[end..]),
+                None => (value, ""),
+            },
+        };
+        let value = raw
+            .replace(['{', '}'], "")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        fields.push((name, value));
+        rest = remaining;
+    }
+    fields
+}
+
+/// The first existing PDF of a `file` field. Zotero writes `description:path:mime` entries
+/// separated by `;` and escapes colons in paths.
+fn attachment(field: &str, base: &Path) -> Option<PathBuf> {
+    field
+        .replace("\\:", "\u{1}")
+        .split(';')
+        .filter_map(|entry| {
+            let parts = entry.split(':').collect::<Vec<_>>();
+            let path = match parts.len() {
+                0..=2 => entry.to_string(),
+                len => parts[1..len - 1].join(":"),
+            };
+            let path = path.replace('\u{1}', ":").replace("\\\\", "\\");
+            let path = base.join(path.trim());
+            let is_pdf = path
+                .extension()
+                .is_some_and(|it| it.eq_ignore_ascii_case("pdf"));
+            (is_pdf && path.is_file()).then_some(path)
+        })
+        .next()
+}
+
+/// The DOI of `10.…` or a doi.org link.
+fn parse_doi(source: &str) -> Option<String> {
+    let source = source.trim();
+    let doi = source
+        .split_once("doi.org/")
+        .map(|(_, doi)| doi)
+        .unwrap_or(source);
+    doi.starts_with("10.").then(|| doi.to_string())
+}
+
+/// Name and content of all `<meta>` tags, using `property` where `name` is missing.
+fn meta_tags(html: &str) -> Vec<(String, String)> {
+    let tag = Regex::new(r"(?is)<meta\s[^>]*>").expect("valid regex");
+    let attribute =
+        Regex::new(r#"(?is)([a-z:-]+)\s*=\s*("([^"]*)"|'([^']*)')"#).expect("valid regex");
+    tag.find_iter(html)
+        .filter_map(|tag| {
+            let mut name = None;
+            let mut content = None;
+            for caps in attribute.captures_iter(tag.as_str()) {
+                let value = caps.get(3).or(caps.get(4)).map(|it| it.as_str());
+                match caps[1].to_lowercase().as_str() {
+                    "name" => name = value,
+                    "property" if name.is_none() => name = value,
+                    "content" => content = value,
+                    _ => {}
+                }
+            }
+            Some((name?.to_string(), decode_entities(content?.trim())))
+        })
+        .collect()
+}
+
+fn decode_entities(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+fn curl(url: &str, accept: Option<&str>) -> Result<Vec<u8>> {
+    let mut curl = Curl::new(url).follow_redirects();
+    if let Some(accept) = accept {
+        curl = curl.header(format!("Accept: {}", accept));
+    }
+    curl.send(format!("Failed to download '{}'", url))
+}
@@ -0,0 +1,145 @@
+use std::{
+    fmt,
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    sync::{Mutex, OnceLock},
+};
+
+use anyhow::{Context, Result};
+use tracing::Level;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{
+    filter::filter_fn,
+    fmt::{format::Writer, time::FormatTime},
+    layer::SubscriberExt,
+    reload,
+    util::SubscriberInitExt,
+    Layer, Registry,
+};
+
+use super::{date::now, stats::is_verbose};
+
+/// Number of days logged by the default log, one file per day.
+const KEEP: usize = 7;
+/// Link to the log of today next to the rotated logs.
+const LATEST: &str = "mm.log";
+
+type FileLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// Switches the file layer of the subscriber once the log file is known, see [set_log_file].
+static FILE_LAYER: OnceLock<reload::Handle<Option<FileLayer>, Registry>> = OnceLock::new();
+/// Log file of `mm --log-file` or `log = true` in the config.
+static LOG_FILE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// `mm/logs` in the config directory, holding a log for each day and `mm.log` linking to the
+/// latest one.
+pub fn default_log_dir() -> Result<PathBuf> {
+    let dir = dirs::config_dir().context("Failed to find config directory on your system.")?;
+    Ok(dir.join("mm").join("logs"))
+}
+
+/// Installs the subscriber of mm. Trace events are printed to stderr while running with `-v`,
+/// all events of mm are appended to the log file once there is one. Only the first call
+/// installs it, so every entry point of a run can call it before its first event.
+pub fn init_tracing() {
+    FILE_LAYER.get_or_init(|| {
+        let (file, handle) = reload::Layer::new(None);
+        // filters inside of a reloaded layer are not registered with the subscriber
+        let file = file.with_filter(filter_fn(|metadata| is_own(metadata.target())));
+        let stderr = tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_ansi(std::io::stderr().is_terminal())
+            .without_time()
+            .with_target(false)
+            // the verbosity is read for every event, it is set after the subscriber
+            .with_filter(filter_fn(|metadata| {
+                is_verbose() && *metadata.level() == Level::TRACE && is_own(metadata.target())
+            }));
+        // another subscriber of an application embedding mm is kept
+        let _ = tracing_subscriber::registry()
+            .with(file)
+            .with(stderr)
+            .try_init();
+        handle
+    });
+}
+
+/// Appends the events of mm to `path`, or for `None` to the log of today in [default_log_dir],
+/// of which the last 7 days are kept. Failing to open the log never fails the command.
+pub fn set_log_file(path: Option<PathBuf>) {
+    init_tracing();
+    let (appender, path) = match path {
+        Some(path) => {
+            let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let appender = RollingFileAppender::builder()
+                .rotation(Rotation::NEVER)
+                .filename_prefix(name)
+                .build(&dir);
+            (appender, path)
+        }
+        None => {
+            let Ok(dir) = default_log_dir() else {
+                return;
+            };
+            // old logs are pruned before the appender creates the directory
+            let _ = std::fs::create_dir_all(&dir);
+            let appender = RollingFileAppender::builder()
+                .rotation(Rotation::DAILY)
+                .filename_prefix("mm")
+                .filename_suffix("log")
+                .max_log_files(KEEP)
+                .latest_symlink(LATEST)
+                .build(&dir);
+            (appender, dir.join(LATEST))
+        }
+    };
+    let Ok(appender) = appender else {
+        return;
+    };
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(appender)
+        .with_ansi(false)
+        .with_timer(LocalTime)
+        .with_target(false)
+        .with_level(false)
+        .boxed();
+    if let Some(handle) = FILE_LAYER.get() {
+        if handle.reload(Some(layer)).is_ok() {
+            *LOG_FILE.lock().unwrap_or_else(|err| err.into_inner()) = Some(path);
+        }
+    }
+}
+
+/// The log file of this run, otherwise the default one if it exists.
+pub fn log_file() -> Option<PathBuf> {
+    let path = LOG_FILE
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .clone();
+    path.or_else(|| {
+        default_log_dir()
+            .ok()
+            .map(|it| it.join(LATEST))
+            .filter(|it| it.is_file())
+    })
+}
+
+pub fn is_logging() -> bool {
+    LOG_FILE.lock().map(|it| it.is_some()).unwrap_or(false)
+}
+
+/// Events of mm, not of its dependencies.
+fn is_own(target: &str) -> bool {
+    target.starts_with(env!("CARGO_CRATE_NAME"))
+}
+
+/// Local time and process id in front of every line of the log.
+struct LocalTime;
+
+impl FormatTime for LocalTime {
+    fn format_time(&self, w: &mut Writer<'_>) -> fmt::Result {
+        let (date, time) = now();
+        write!(w, "{} {} [{}]", date, time, std::process::id())
+    }
+}
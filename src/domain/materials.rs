@@ -0,0 +1,255 @@
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{Read, Seek, SeekFrom},
+    ops::Deref,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use walkdir::WalkDir;
+
+use super::{
+    effects,
+    paths::{CoursePath, ReadWriteDO},
+};
+
+/// Name of the folder inside a course holding lecture slides, sheets and other downloads.
+pub const MATERIALS_DIR: &str = "materials";
+
+/// The materials folder of a course together with its checksum manifest. The manifest lives next
+/// to the folder in `.materials.toml`, so it is not part of the checked files.
+pub struct Materials {
+    dir: PathBuf,
+    manifest: ManifestFile,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ManifestFile(PathBuf);
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ManifestDO {
+    #[serde(default)]
+    files: BTreeMap<String, FileEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct FileEntry {
+    size: u64,
+    hash: String,
+}
+
+/// A difference between the manifest and the files on disk.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Discrepancy {
+    /// Content differs from the recorded checksum.
+    Changed(String),
+    /// Recorded in the manifest but no longer on disk.
+    Missing(String),
+    /// The file looks broken, e.g. a truncated PDF.
+    Corrupted(String),
+    /// A copy created by a sync client after a conflict.
+    Conflict(String),
+    /// Not yet recorded. New files are added to the manifest while verifying.
+    New(String),
+}
+
+/// File name fragments used by common sync clients for conflict copies.
+const CONFLICT_MARKERS: [&str; 4] = ["conflicted copy", "sync-conflict", "(conflict", "konflikt"];
+
+impl Materials {
+    pub fn of(course: &CoursePath) -> Materials {
+        Materials {
+            dir: course.join(MATERIALS_DIR),
+            manifest: ManifestFile(course.join(".materials.toml")),
+        }
+    }
+
+    pub fn exists(&self) -> bool {
+        self.dir.is_dir()
+    }
+
+    /// Compares the files on disk with the manifest. New files are recorded, changed files are
+    /// reported but their entry is kept until [Materials::update] accepts them.
+    pub fn verify(&self) -> Result<Vec<Discrepancy>> {
+        let mut manifest = self.read_manifest()?;
+        let current = self.scan()?;
+        let mut discrepancies = Vec::new();
+        let mut recorded_new = false;
+
+        for (name, (entry, corrupted)) in &current {
+            let lower = name.to_lowercase();
+            if CONFLICT_MARKERS.iter().any(|it| lower.contains(it)) {
+                discrepancies.push(Discrepancy::Conflict(name.clone()));
+            }
+            if *corrupted {
+                discrepancies.push(Discrepancy::Corrupted(name.clone()));
+            }
+            match manifest.files.get(name) {
+                Some(recorded) if recorded != entry => {
+                    discrepancies.push(Discrepancy::Changed(name.clone()))
+                }
+                Some(_) => {}
+                None => {
+                    discrepancies.push(Discrepancy::New(name.clone()));
+                    manifest.files.insert(name.clone(), entry.clone());
+                    recorded_new = true;
+                }
+            }
+        }
+        for name in manifest.files.keys() {
+            if !current.contains_key(name) {
+                discrepancies.push(Discrepancy::Missing(name.clone()));
+            }
+        }
+
+        if recorded_new {
+            self.manifest.write(&manifest)?;
+        }
+        discrepancies.sort_by(|a, b| a.file().cmp(b.file()).then(a.cmp(b)));
+        Ok(discrepancies)
+    }
+
+    /// Replaces the manifest with the current state of the files and returns the number of
+    /// recorded files.
+    pub fn update(&self) -> Result<usize> {
+        let files = self
+            .scan()?
+            .into_iter()
+            .map(|(name, (entry, _))| (name, entry))
+            .collect::<BTreeMap<_, _>>();
+        let count = files.len();
+        self.manifest.write(&ManifestDO { files })?;
+        Ok(count)
+    }
+
+    fn read_manifest(&self) -> Result<ManifestDO> {
+        if self.manifest.is_file() {
+            self.manifest.read()
+        } else {
+            Ok(ManifestDO::default())
+        }
+    }
+
+    /// Hashes all files below the materials folder, keyed by their relative path.
+    fn scan(&self) -> Result<BTreeMap<String, (FileEntry, bool)>> {
+        let mut files = BTreeMap::new();
+        for entry in WalkDir::new(&self.dir).min_depth(1) {
+            let entry = entry
+                .with_context(|| anyhow!("Failed to read materials at: {}", self.dir.display()))?;
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let name = entry
+                .path()
+                .strip_prefix(&self.dir)
+                .unwrap_or(entry.path())
+                .components()
+                .map(|it| it.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            let (entry, corrupted) = hash_file(entry.path())?;
+            files.insert(name, (entry, corrupted));
+        }
+        Ok(files)
+    }
+}
+
+/// Hashes a file with 64 bit FNV-1a and checks PDFs for a header and a trailing `%%EOF` marker,
+/// which is missing in truncated files.
+fn hash_file(path: &Path) -> Result<(FileEntry, bool)> {
+    let context = || anyhow!("Failed to read file: {}", path.display());
+    let mut file = std::fs::File::open(path).with_context(context)?;
+
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut size = 0;
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).with_context(context)?;
+        if read == 0 {
+            break;
+        }
+        if head.len() < 8 {
+            head.extend_from_slice(&buffer[..read.min(8)]);
+        }
+        for byte in &buffer[..read] {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        size += read as u64;
+    }
+
+    let is_pdf = path
+        .extension()
+        .is_some_and(|it| it.eq_ignore_ascii_case("pdf"));
+    let corrupted = is_pdf && (!head.starts_with(b"%PDF-") || !has_pdf_trailer(&mut file, size)?);
+
+    let entry = FileEntry {
+        size,
+        hash: format!("{:016x}", hash),
+    };
+    Ok((entry, corrupted))
+}
+
+fn has_pdf_trailer(file: &mut std::fs::File, size: u64) -> Result<bool> {
+    let tail = size.min(1024);
+    file.seek(SeekFrom::Start(size - tail))?;
+    let mut buffer = Vec::new();
+    file.read_to_end(&mut buffer)?;
+    Ok(buffer.windows(5).any(|it| it == b"%%EOF"))
+}
+
+impl Discrepancy {
+    pub fn file(&self) -> &str {
+        match self {
+            Discrepancy::Changed(file)
+            | Discrepancy::Missing(file)
+            | Discrepancy::Corrupted(file)
+            | Discrepancy::Conflict(file)
+            | Discrepancy::New(file) => file,
+        }
+    }
+
+    /// New files are only informational.
+    pub fn is_problem(&self) -> bool {
+        !matches!(self, Discrepancy::New(_))
+    }
+}
+
+impl fmt::Display for Discrepancy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Discrepancy::Changed(_) => "changed",
+            Discrepancy::Missing(_) => "missing",
+            Discrepancy::Corrupted(_) => "corrupted",
+            Discrepancy::Conflict(_) => "sync conflict",
+            Discrepancy::New(_) => "new",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+impl ReadWriteDO for ManifestFile {
+    type Object = ManifestDO;
+
+    fn write(&self, object: &Self::Object) -> Result<()> {
+        let data = toml_edit::ser::to_string_pretty(&object).with_context(|| {
+            anyhow!(
+                "Failed to serialize data to toml for: {}",
+                self.deref().display()
+            )
+        })?;
+        effects::write(self.deref(), data)
+            .with_context(|| anyhow!("Failed to write data to file: {}", self.deref().display()))
+    }
+}
+
+impl Deref for ManifestFile {
+    type Target = PathBuf;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
@@ -0,0 +1,71 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+
+use super::{
+    config::SemesterNames,
+    effects,
+    paths::{
+        new_data_file, EntryPoint, ReadWriteDO, COURSE_TEMPLATE, DATA_VERSION, LEGACY_COURSE_FILE,
+    },
+};
+
+/// A change made by [migrate] to a data file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    pub path: PathBuf,
+    pub change: String,
+}
+
+/// Upgrades the data files of the store to the current layout: legacy `.course.toml` files are
+/// renamed to `course.toml` and files without the current `version` are rewritten.
+pub fn migrate(entry_point: &EntryPoint, semester_names: &SemesterNames) -> Result<Vec<Migration>> {
+    let mut migrations = Vec::new();
+    upgrade(entry_point.data_file()?, &mut migrations)?;
+    for semester in entry_point.semester_paths(semester_names) {
+        upgrade(semester.data_file()?, &mut migrations)?;
+        for course in semester.course_paths() {
+            migrations.extend(rename_legacy_file(&course.join(LEGACY_COURSE_FILE))?);
+            upgrade(course.data_file()?, &mut migrations)?;
+        }
+    }
+    Ok(migrations)
+}
+
+fn upgrade<F>(file: F, migrations: &mut Vec<Migration>) -> Result<()>
+where
+    F: ReadWriteDO,
+{
+    if file.upgrade()? {
+        migrations.push(Migration {
+            path: file.to_path_buf(),
+            change: format!("upgraded to data version {}", DATA_VERSION),
+        });
+    }
+    Ok(())
+}
+
+/// Renames the legacy file unless the course already has a `course.toml` with content.
+fn rename_legacy_file(legacy: &Path) -> Result<Option<Migration>> {
+    if !legacy.is_file() {
+        return Ok(None);
+    }
+    let path = legacy.with_file_name("course.toml");
+    let unused = std::fs::read_to_string(&path)
+        .map(|it| {
+            it == new_data_file(COURSE_TEMPLATE) || it == COURSE_TEMPLATE || it.trim().is_empty()
+        })
+        .unwrap_or(true);
+    if !unused {
+        return Ok(Some(Migration {
+            path: legacy.to_path_buf(),
+            change: "kept, merge it into course.toml by hand".into(),
+        }));
+    }
+    effects::rename(legacy, &path)
+        .with_context(|| anyhow!("Failed to rename: {}", legacy.display()))?;
+    Ok(Some(Migration {
+        path,
+        change: format!("renamed from {}", LEGACY_COURSE_FILE),
+    }))
+}
@@ -1,17 +1,92 @@
+mod audit;
+mod backup;
+mod catalog;
+mod component;
 mod config;
 mod course;
+mod curl;
+mod date;
+mod deadline;
+mod degree;
+pub mod effects;
+mod exam;
+mod exercise;
+mod filter;
+mod flashcards;
+mod grades;
+mod history;
+mod inbox;
+mod language;
+mod literature;
+mod log;
+mod materials;
+mod migrate;
+mod moodle;
+mod note;
+mod notify;
+mod parallel;
 mod paths;
+mod plan;
+mod progress;
+mod recovery;
+mod repository;
+mod scale;
+mod search;
 mod semester;
+mod session;
+mod slides;
+mod slot;
+pub mod stats;
 mod store;
+mod sync;
+mod task;
+mod theme;
 
-pub(crate) use config::Config;
-pub(crate) use store::Store;
+pub use audit::{AuditEntry, AuditLog, AUDIT_LOG};
+pub use backup::{Backup, BackupSettings, Snapshot};
+pub use catalog::{Catalog, Module};
+pub use config::Config;
+pub use store::Store;
 
-pub(crate) use course::Course;
-pub(crate) use semester::Semester;
-pub(crate) use semester::StudyCycle;
+pub use course::{missing_prerequisites, normalize_name, Course, CourseKind, CourseStatus};
+pub use curl::Curl;
+pub use semester::Semester;
+pub use semester::StudyCycle;
 
-pub(crate) use paths::EntryPoint;
-pub(crate) use paths::MaybeSymLinkable;
+pub use date::{now, now_utc, to_local, utc_offset, zone_to_local, Date, Time, Weekday};
+pub use deadline::{Deadline, DeadlineKind};
+pub use degree::Degree;
+pub use exam::{AttemptPolicy, Exam};
+pub use exercise::{percentage, Exercise, SheetPoints};
+pub use filter::{CourseFilter, CourseSort, SemesterFilter};
+pub use flashcards::{to_anki_tsv, Flashcard};
+pub use grades::GradeRecord;
+pub use history::{GitMode, History};
+pub use inbox::{file_into, is_complete, target_dir, Inbox, LATEST_EXERCISE, LECTURES_DIR};
+pub use language::Language;
+pub use literature::{Literature, Reference};
+pub use log::{default_log_dir, init_tracing, is_logging, log_file, set_log_file};
+pub use materials::{Discrepancy, Materials, MATERIALS_DIR};
+pub use migrate::{migrate, Migration};
+pub use moodle::{MoodleCourse, MoodleSettings};
+pub use note::{compile_latex, Note, NoteFormat, MAIN_DOCUMENT};
+pub use notify::NotificationSettings;
+pub use plan::{Plan, PlannedCourse, PlannedSemester};
+pub use progress::{set_progress, Progress};
+pub use recovery::DamagedFile;
+pub use repository::{Repository, RepositoryUpdate};
+pub use scale::GradeScale;
+pub use search::{search_content, ContentHit, Position};
+pub use session::Session;
+pub use slides::{apply_renames, plan_normalize};
+pub use slot::{Slot, SlotKind};
+pub use sync::{Direction, SyncSettings};
+pub use task::Task;
+pub use theme::Theme;
 
-pub(crate) use config::SemesterNames;
+pub use paths::{
+    fold_name, is_read_only, set_read_only, EntryPoint, StoreLock, DATA_VERSION, LOCK_FILE,
+};
+pub use paths::{Link, LinkStrategy, LinkTarget, MaybeSymLinkable};
+
+pub use config::SemesterNames;
@@ -0,0 +1,178 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{course::normalize_name, curl::Curl, date::Date};
+
+/// Moodle instance and web service token from the `[moodle]` config table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MoodleSettings {
+    url: String,
+    token: String,
+}
+
+/// A course the user is enrolled in.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MoodleCourse {
+    id: u64,
+    shortname: String,
+    fullname: String,
+}
+
+/// An assignment of a Moodle course. Assignments without due date have no `due`.
+#[derive(Debug, Clone)]
+pub struct MoodleAssignment {
+    course: u64,
+    name: String,
+    due: Option<Date>,
+}
+
+impl MoodleSettings {
+    pub fn new(url: String, token: String) -> MoodleSettings {
+        let url = url.trim_end_matches('/').to_string();
+        MoodleSettings { url, token }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Courses the owner of the token is enrolled in.
+    pub fn courses(&self) -> Result<Vec<MoodleCourse>> {
+        let site = self.call("core_webservice_get_site_info", &[])?;
+        let user_id = site
+            .get("userid")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| anyhow!("Moodle did not return the user id of the token"))?;
+        let courses = self.call(
+            "core_enrol_get_users_courses",
+            &[("userid".into(), user_id.to_string())],
+        )?;
+        serde_json::from_value(courses).context("Failed to read courses returned by Moodle")
+    }
+
+    /// Assignments of the given Moodle courses.
+    pub fn assignments(&self, course_ids: &[u64]) -> Result<Vec<MoodleAssignment>> {
+        if course_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let params = course_ids
+            .iter()
+            .enumerate()
+            .map(|(idx, id)| (format!("courseids[{}]", idx), id.to_string()))
+            .collect::<Vec<_>>();
+        let response = self.call("mod_assign_get_assignments", &params)?;
+
+        let mut assignments = Vec::new();
+        let courses = response
+            .get("courses")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("Failed to read assignments returned by Moodle"))?;
+        for course in courses {
+            let Some(course_id) = course.get("id").and_then(Value::as_u64) else {
+                continue;
+            };
+            let entries = course.get("assignments").and_then(Value::as_array);
+            for assignment in entries.into_iter().flatten() {
+                let Some(name) = assignment.get("name").and_then(Value::as_str) else {
+                    continue;
+                };
+                // moodle uses 0 for assignments without due date
+                let due = assignment
+                    .get("duedate")
+                    .and_then(Value::as_i64)
+                    .filter(|it| *it > 0)
+                    .map(Date::from_unix);
+                assignments.push(MoodleAssignment {
+                    course: course_id,
+                    name: name.to_string(),
+                    due,
+                });
+            }
+        }
+        Ok(assignments)
+    }
+
+    /// Calls a web service function. The token is sent in the body, which [Curl] passes on stdin
+    /// so it does not show up in the process list.
+    fn call(&self, function: &str, params: &[(String, String)]) -> Result<Value> {
+        let mut body = format!(
+            "wstoken={}&wsfunction={}&moodlewsrestformat=json",
+            encode(&self.token),
+            function
+        );
+        for (key, value) in params {
+            body.push_str(&format!("&{}={}", encode(key), encode(value)));
+        }
+
+        let url = format!("{}/webservice/rest/server.php", self.url);
+        let output = Curl::new(&url)
+            .body(body)
+            .send(format!("Failed to reach Moodle at '{}'", self.url))?;
+
+        let value: Value = serde_json::from_slice(&output)
+            .with_context(|| anyhow!("Moodle returned an invalid response for '{}'", function))?;
+        if value.get("exception").is_some() {
+            let message = value
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown error");
+            bail!("Moodle rejected '{}': {}", function, message);
+        }
+        Ok(value)
+    }
+}
+
+impl MoodleCourse {
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn shortname(&self) -> &str {
+        &self.shortname
+    }
+
+    pub fn fullname(&self) -> &str {
+        &self.fullname
+    }
+
+    /// Whether a local course name refers to this course. Case, spaces and punctuation are
+    /// ignored and either name may contain the other.
+    pub fn matches(&self, name: &str) -> bool {
+        let name = normalize_name(name);
+        if name.is_empty() {
+            return false;
+        }
+        [&self.fullname, &self.shortname]
+            .iter()
+            .map(|it| normalize_name(it))
+            .any(|it| !it.is_empty() && (it.contains(&name) || name.contains(&it)))
+    }
+}
+
+impl MoodleAssignment {
+    pub fn course(&self) -> u64 {
+        self.course
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn due(&self) -> Option<Date> {
+        self.due
+    }
+}
+
+/// Percent-encoding for `application/x-www-form-urlencoded` values.
+pub(super) fn encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            b => format!("%{:02X}", b),
+        })
+        .collect()
+}
@@ -0,0 +1,245 @@
+use std::{
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::cli::NoteFormatDO;
+
+use super::{date::Date, effects, paths::CoursePath};
+
+/// Name of the folder inside a course holding the notes.
+pub const NOTES_DIR: &str = "notes";
+
+/// Main document of LaTeX courses, placed in the course folder.
+pub const MAIN_DOCUMENT: &str = "main.tex";
+
+/// LaTeX engines in order of preference with their arguments.
+const LATEX_ENGINES: [(&str, &[&str]); 2] = [
+    (
+        "latexmk",
+        &[
+            "-pdf",
+            "-interaction=nonstopmode",
+            "-halt-on-error",
+            "-file-line-error",
+            "-cd",
+        ],
+    ),
+    ("tectonic", &[]),
+];
+
+/// A dated note file like `notes/2024-11-05-lecture.md` inside a course folder.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Note {
+    path: PathBuf,
+    date: Option<Date>,
+    title: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteFormat {
+    Markdown,
+    Latex,
+}
+
+impl Note {
+    /// All notes of a course, oldest first. Notes of the same day are ordered by modification.
+    pub fn list(course: &CoursePath) -> Result<Vec<Note>> {
+        let dir = course.join(NOTES_DIR);
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut notes = std::fs::read_dir(&dir)
+            .with_context(|| anyhow!("Failed to read notes at: {}", dir.display()))?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .filter_map(|entry| {
+                let modified = entry.metadata().and_then(|it| it.modified()).ok();
+                Some((Note::from_path(entry.path())?, modified))
+            })
+            .collect::<Vec<_>>();
+        notes.sort_by(|(a, a_modified), (b, b_modified)| {
+            (a.date, a_modified, a.file_name()).cmp(&(b.date, b_modified, b.file_name()))
+        });
+        Ok(notes.into_iter().map(|(note, _)| note).collect())
+    }
+
+    /// Creates `<date>-<slug>.<ext>` in the notes folder of the course. A counter is appended if
+    /// a note with this name already exists.
+    pub fn create(
+        course: &CoursePath,
+        date: Date,
+        title: &str,
+        format: NoteFormat,
+        content: &str,
+    ) -> Result<Note> {
+        let dir = course.join(NOTES_DIR);
+        effects::create_dir_all(&dir)
+            .with_context(|| anyhow!("Failed to create notes folder at: {}", dir.display()))?;
+
+        let stem = format!("{}-{}", date, slug(title));
+        let mut path = dir.join(format!("{}.{}", stem, format.extension()));
+        let mut counter = 2;
+        while path.exists() {
+            path = dir.join(format!("{}-{}.{}", stem, counter, format.extension()));
+            counter += 1;
+        }
+        effects::write(&path, content)
+            .with_context(|| anyhow!("Failed to write note to: {}", path.display()))?;
+        Note::from_path(path).ok_or_else(|| anyhow!("Failed to read created note"))
+    }
+
+    fn from_path(path: PathBuf) -> Option<Note> {
+        let stem = path.file_stem()?.to_string_lossy().to_string();
+        let date = stem.get(..10).and_then(|it| it.parse::<Date>().ok());
+        let title = match date {
+            Some(_) => stem[10..].trim_start_matches('-').replace('-', " "),
+            None => stem,
+        };
+        Some(Note { path, date, title })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn file_name(&self) -> &str {
+        self.path
+            .file_name()
+            .and_then(|it| it.to_str())
+            .unwrap_or_default()
+    }
+
+    pub fn date(&self) -> Option<Date> {
+        self.date
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+}
+
+/// Compiles a LaTeX document with `latexmk` or, if not installed, `tectonic` and returns the path
+/// of the PDF. On failure the error contains the errors reported in the output of the engine.
+pub fn compile_latex(path: &Path) -> Result<PathBuf> {
+    let (engine, output) = run_latex_engine(path)?;
+    if !output.status.success() {
+        let log = String::from_utf8_lossy(&output.stdout).to_string()
+            + &String::from_utf8_lossy(&output.stderr);
+        let errors = log
+            .lines()
+            .filter(|line| is_latex_error(line))
+            .take(10)
+            .collect::<Vec<_>>();
+        let file = path.file_name().unwrap_or_default().to_string_lossy();
+        if errors.is_empty() {
+            bail!("'{}' failed to compile '{}'", engine, file);
+        }
+        bail!(
+            "'{}' failed to compile '{}':\n  {}",
+            engine,
+            file,
+            errors.join("\n  ")
+        );
+    }
+    Ok(path.with_extension("pdf"))
+}
+
+fn run_latex_engine(path: &Path) -> Result<(&'static str, Output)> {
+    for (engine, args) in LATEX_ENGINES {
+        let mut command = Command::new(engine);
+        // both engines write the PDF next to the document
+        command.args(args).arg(path);
+        match effects::output(&mut command) {
+            Ok(output) => return Ok((engine, output)),
+            Err(err) if err.kind() == ErrorKind::NotFound => continue,
+            Err(err) => return Err(err).with_context(|| anyhow!("Failed to run '{}'", engine)),
+        }
+    }
+    bail!("Neither 'latexmk' nor 'tectonic' is installed")
+}
+
+/// Matches `! message` lines of TeX, `file:line: message` lines of `-file-line-error` and the
+/// `error:` lines of tectonic.
+fn is_latex_error(line: &str) -> bool {
+    line.starts_with("! ")
+        || line.starts_with("error:")
+        || line
+            .split(':')
+            .nth(1)
+            .is_some_and(|it| !it.is_empty() && it.chars().all(|c| c.is_ascii_digit()))
+}
+
+impl NoteFormat {
+    pub fn from_do(format: NoteFormatDO) -> NoteFormat {
+        match format {
+            NoteFormatDO::Md => NoteFormat::Markdown,
+            NoteFormatDO::Tex => NoteFormat::Latex,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            NoteFormat::Markdown => "md",
+            NoteFormat::Latex => "tex",
+        }
+    }
+
+    /// Template of the main document of LaTeX courses if the template directory does not provide
+    /// `main.tex`.
+    pub fn main_template() -> &'static str {
+        concat!(
+            "\\documentclass{article}\n",
+            "\\usepackage[utf8]{inputenc}\n\n",
+            "% course: {{course}}\n",
+            "% semester: {{semester}}\n",
+            "% ects: {{ects}}\n",
+            "% degrees: {{degrees}}\n\n",
+            "\\title{{{course}}}\n",
+            "\\author{{{semester}}}\n",
+            "\\date{{{date}}}\n\n",
+            "\\begin{document}\n",
+            "\\maketitle\n\n",
+            "\\end{document}\n"
+        )
+    }
+
+    /// Template used if the template directory does not provide `note.<ext>`.
+    pub fn default_template(&self) -> &'static str {
+        match self {
+            NoteFormat::Markdown => "# {{title}}\n\n{{course}} ({{semester}}), {{date}}\n\n",
+            NoteFormat::Latex => concat!(
+                "\\documentclass{article}\n",
+                "\\usepackage[utf8]{inputenc}\n\n",
+                "\\title{{{title}}}\n",
+                "\\author{{{course}} ({{semester}})}\n",
+                "\\date{{{date}}}\n\n",
+                "\\begin{document}\n",
+                "\\maketitle\n\n",
+                "\\end{document}\n"
+            ),
+        }
+    }
+}
+
+/// Lowercase file name fragment with dashes instead of spaces and special characters.
+fn slug(title: &str) -> String {
+    let slug = title
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>();
+    let slug = slug
+        .split('-')
+        .filter(|it| !it.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "note".into()
+    } else {
+        slug
+    }
+}
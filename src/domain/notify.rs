@@ -0,0 +1,105 @@
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{
+    curl::Curl,
+    effects::{self, Effect},
+    moodle::encode,
+};
+
+/// A channel reminders are delivered through, e.g. a push service reaching the phone.
+pub trait Notifier {
+    fn send(&self, title: &str, message: &str) -> Result<()>;
+}
+
+/// Service behind the `url` of the `[notifications]` config table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationBackend {
+    /// A topic URL of ntfy.sh or a self-hosted ntfy server.
+    #[default]
+    Ntfy,
+    /// Any URL accepting a JSON body with `title` and `message`.
+    Webhook,
+}
+
+/// Where reminders are sent and how many days ahead deadlines are announced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationSettings {
+    backend: NotificationBackend,
+    url: String,
+    /// Sent as bearer token, e.g. an ntfy access token.
+    token: Option<String>,
+    days: i64,
+}
+
+struct Ntfy {
+    url: String,
+    token: Option<String>,
+}
+
+struct Webhook {
+    url: String,
+    token: Option<String>,
+}
+
+impl NotificationSettings {
+    pub fn new(
+        backend: NotificationBackend,
+        url: String,
+        token: Option<String>,
+        days: i64,
+    ) -> NotificationSettings {
+        NotificationSettings {
+            backend,
+            url,
+            token,
+            days,
+        }
+    }
+
+    pub fn days(&self) -> i64 {
+        self.days
+    }
+
+    pub fn notifier(&self) -> Box<dyn Notifier> {
+        let (url, token) = (self.url.clone(), self.token.clone());
+        match self.backend {
+            NotificationBackend::Ntfy => Box::new(Ntfy { url, token }),
+            NotificationBackend::Webhook => Box::new(Webhook { url, token }),
+        }
+    }
+}
+
+impl Notifier for Ntfy {
+    /// ntfy takes the message as body. The title is passed as query parameter as headers may
+    /// not contain umlauts.
+    fn send(&self, title: &str, message: &str) -> Result<()> {
+        let separator = if self.url.contains('?') { '&' } else { '?' };
+        let url = format!("{}{}title={}&tags=date", self.url, separator, encode(title));
+        post(&url, &[], self.token.as_deref(), message)
+    }
+}
+
+impl Notifier for Webhook {
+    fn send(&self, title: &str, message: &str) -> Result<()> {
+        let body = json!({ "title": title, "message": message }).to_string();
+        let headers = ["Content-Type: application/json".to_string()];
+        post(&self.url, &headers, self.token.as_deref(), &body)
+    }
+}
+
+/// Posts the body with [Curl], which keeps the token out of the process list.
+fn post(url: &str, headers: &[String], token: Option<&str>, body: &str) -> Result<()> {
+    if effects::plan(Effect::Send(url.to_string())) {
+        return Ok(());
+    }
+    let curl = headers
+        .iter()
+        .fold(Curl::new(url), |curl, header| curl.header(header.as_str()))
+        .bearer(token)
+        .body(body);
+    curl.send(format!("Failed to send notification to '{}'", url))?;
+    Ok(())
+}
@@ -0,0 +1,12 @@
+use rayon::prelude::*;
+
+/// Maps the items on the global thread pool of rayon and keeps their order. Parsing the data
+/// files of a store is mostly waiting for the file system, which adds up on network file systems.
+pub(super) fn parallel_map<T, U, F>(items: Vec<T>, f: F) -> Vec<U>
+where
+    T: Send,
+    U: Send,
+    F: Fn(T) -> U + Sync + Send,
+{
+    items.into_par_iter().map(f).collect()
+}
@@ -1,35 +1,102 @@
 use std::{
+    fs::{File, TryLockError},
+    io::Write,
     ops::Deref,
     path::{Path, PathBuf},
     str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+    time::{Duration, Instant},
 };
 
 use anyhow::{anyhow, bail, Context, Ok, Result};
-use serde::{de::DeserializeOwned, Serialize};
+use caseless::default_case_fold_str;
+use regex::Regex;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table};
+use unicode_normalization::UnicodeNormalization;
 use walkdir::WalkDir;
 
-use super::{config::SemesterNames, StudyCycle};
+use super::{
+    config::SemesterNames,
+    effects::{self, Effect},
+    progress::Progress,
+    stats::{measure, touch, Operation},
+};
+use crate::MmError;
+
+/// Version of the layout of the store, semester and course data files written by this version
+/// of mm. Older files are upgraded with `mm migrate`.
+pub const DATA_VERSION: u32 = 1;
+
+/// Name of the course data file written before `course.toml`, renamed by `mm migrate`.
+pub(super) const LEGACY_COURSE_FILE: &str = ".course.toml";
+
+/// Content of new `course.toml` files, with all options documented as comments.
+pub(super) const COURSE_TEMPLATE: &str = include_str!("../../course.toml");
+
+/// Content of a new versioned data file, the current version followed by `template`.
+pub(super) fn new_data_file(template: &str) -> String {
+    format!("version = {}\n{}", DATA_VERSION, template)
+}
 
 /// The entry point to the university data.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub(crate) struct EntryPoint(PathBuf);
+pub struct EntryPoint(PathBuf, Ignore);
+
+/// Folder names skipped when looking for semesters and courses, set by `ignore` in the config.
+/// `*` matches any number of characters and `?` a single one.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ignore(Vec<String>);
+
+impl Ignore {
+    pub fn new(patterns: Option<Vec<String>>) -> Ignore {
+        Ignore(patterns.unwrap_or_else(|| vec![".*".into(), "node_modules".into()]))
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        let name = name.chars().collect::<Vec<_>>();
+        self.0
+            .iter()
+            .any(|it| wildcard(&it.chars().collect::<Vec<_>>(), &name))
+    }
+}
+
+fn wildcard(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, _) => name.is_empty(),
+        (Some('*'), _) => {
+            wildcard(&pattern[1..], name) || (!name.is_empty() && wildcard(pattern, &name[1..]))
+        }
+        (Some('?'), Some(_)) => wildcard(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => wildcard(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct StoreDataFile(PathBuf);
 
+/// Exclusive lock on the store, released when dropped or when the process exits.
+#[derive(Debug)]
+pub struct StoreLock(File);
+
+/// Name of the lock file in the entry point, see [EntryPoint::lock].
+pub const LOCK_FILE: &str = ".mm.lock";
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
 impl EntryPoint {
-    pub fn new(path: &str) -> Result<EntryPoint> {
+    pub fn new(path: &str, ignore: Ignore) -> Result<EntryPoint> {
         let path = PathBuf::from_str(path)?;
-        Self::from_path(path)
+        Self::from_path(path, ignore)
     }
 
-    pub fn from_path<P>(path: P) -> Result<EntryPoint>
+    pub fn from_path<P>(path: P, ignore: Ignore) -> Result<EntryPoint>
     where
         P: AsRef<Path>,
     {
         let path = path.as_ref();
         if path.exists() && path.is_dir() {
-            Ok(EntryPoint(path.to_path_buf()))
+            Ok(EntryPoint(path.to_path_buf(), ignore))
         } else {
             bail!(
                 "The entry point '{}' is not a valid directory.",
@@ -38,69 +105,99 @@ impl EntryPoint {
         }
     }
 
+    /// Waits until no other mm process holds the lock of the store, e.g. a `mm switch` of a shell
+    /// hook running at the same time. Gives up after 10 seconds.
+    pub fn lock(&self) -> Result<StoreLock> {
+        let path = self.0.join(LOCK_FILE);
+        let file = File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| anyhow!("Failed to open lock file at: {}", path.display()))?;
+        let start = Instant::now();
+        loop {
+            match file.try_lock() {
+                Result::Ok(()) => return Ok(StoreLock(file)),
+                Err(TryLockError::WouldBlock) if start.elapsed() < LOCK_TIMEOUT => {
+                    std::thread::sleep(Duration::from_millis(50))
+                }
+                Err(TryLockError::WouldBlock) => {
+                    bail!("Another mm process is still changing the store, try again later")
+                }
+                Err(TryLockError::Error(err)) => {
+                    return Err(err).with_context(|| {
+                        anyhow!("Failed to lock the store at: {}", path.display())
+                    })
+                }
+            }
+        }
+    }
+
     /// Returns the path to the store data file.
-    /// If the file does not exist, it will be created.
+    /// The file is created by the first write, a missing file reads as empty.
     pub fn data_file(&self) -> Result<StoreDataFile> {
-        let path = self.0.join(".mm");
-        if !path.exists() && !path.is_file() {
-            std::fs::write(&path, "").with_context(|| {
-                anyhow!("Failed to create store data file at: {}", path.display())
-            })?;
-        }
-        Ok(StoreDataFile(path))
+        Ok(StoreDataFile(self.0.join(".mm")))
     }
 
+    /// The semester folder named `name`, otherwise the first one equal to it by [fold_name].
     pub fn semester_path(
         &self,
         name: &str,
         semester_names: &SemesterNames,
     ) -> Option<SemesterPath> {
-        if semester_names.is_name(name) {
+        if semester_names.is_name(name) && !self.1.matches(name) {
             let path = self.0.join(name);
-            if path.exists() && path.is_dir() {
-                return Some(SemesterPath(path, name.to_string()));
+            if path.is_dir() {
+                return Some(SemesterPath(path, name.to_string(), self.1.clone()));
             }
         }
-        None
+        let folded = fold_name(name);
+        self.semester_paths(semester_names)
+            .find(|it| fold_name(&it.1) == folded)
     }
 
-    pub fn create_semester_path(
-        &self,
-        semester_number: u16,
-        study_cycle: StudyCycle,
-    ) -> Result<SemesterPath> {
-        let name = format!("{}{}", study_cycle, semester_number);
-        let path = self.0.join(&name);
+    /// Creates the folder of a new semester, named by [SemesterNames::serialize].
+    pub fn create_semester_path(&self, name: &str) -> Result<SemesterPath> {
+        let path = self.0.join(name);
         if path.exists() {
-            bail!("The semester path '{}' already exists.", path.display());
+            bail!(MmError::SemesterAlreadyExists(path));
         }
-        std::fs::create_dir(&path)
+        effects::create_dir(&path)
             .with_context(|| anyhow!("Failed to create semester path at: {}", path.display()))?;
-        Ok(SemesterPath(path, name))
+        Ok(SemesterPath(path, name.to_string(), self.1.clone()))
     }
 
     pub fn semester_paths<'a>(
         &'a self,
         semester_names: &'a SemesterNames,
     ) -> impl Iterator<Item = SemesterPath> + 'a {
-        WalkDir::new(&self.0)
-            .max_depth(1)
-            .min_depth(1)
-            .into_iter()
-            .filter_map(move |entry| {
-                let entry = entry.ok()?;
-                let name = entry.file_name().to_string_lossy().to_string();
-                if semester_names.is_name(&name) {
-                    Some(SemesterPath(entry.path().to_path_buf(), name))
-                } else {
-                    None
-                }
-            })
+        let paths = measure(Operation::Scan, || {
+            WalkDir::new(&self.0)
+                .max_depth(1)
+                .min_depth(1)
+                .into_iter()
+                .filter_map(move |entry| {
+                    let entry = entry.ok()?;
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    if semester_names.is_name(&name) && !self.1.matches(&name) {
+                        Some(SemesterPath(
+                            entry.path().to_path_buf(),
+                            name,
+                            self.1.clone(),
+                        ))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+        });
+        paths.into_iter()
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct SemesterPath(PathBuf, String);
+pub struct SemesterPath(PathBuf, String, Ignore);
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct SemesterDataFile(PathBuf);
@@ -114,53 +211,124 @@ impl SemesterPath {
         &self.0
     }
 
+    /// The file is created by the first write, a missing file reads as empty.
     pub fn data_file(&self) -> Result<SemesterDataFile> {
-        let path = self.0.join(".mm");
-        if !path.exists() && !path.is_file() {
-            std::fs::write(&path, "").with_context(|| {
-                anyhow!("Failed to create semester data file at: {}", path.display())
-            })?;
-        }
-        Ok(SemesterDataFile(path))
+        Ok(SemesterDataFile(self.0.join(".mm")))
     }
 
     pub fn course_paths(&self) -> impl Iterator<Item = CoursePath> {
-        WalkDir::new(&self.0)
-            .max_depth(1)
-            .min_depth(1)
-            .into_iter()
-            .filter_map(|entry| {
-                let entry = entry.ok()?;
-                if entry.file_type().is_dir() {
+        let paths = measure(Operation::Scan, || {
+            WalkDir::new(&self.0)
+                .max_depth(1)
+                .min_depth(1)
+                .into_iter()
+                .filter_map(|entry| {
+                    let entry = entry.ok()?;
                     let name = entry.file_name().to_string_lossy().to_string();
-                    Some(CoursePath(entry.path().to_path_buf(), name))
-                } else {
-                    None
-                }
-            })
+                    if entry.file_type().is_dir() && !self.2.matches(&name) {
+                        Some(CoursePath(entry.path().to_path_buf(), name))
+                    } else {
+                        None
+                    }
+                })
+                .collect::<Vec<_>>()
+        });
+        paths.into_iter()
     }
 
+    /// The course folder named `name`, otherwise the first one equal to it by [fold_name].
     pub fn course_path(&self, name: &str) -> Option<CoursePath> {
-        let path = self.0.join(name);
-        if path.exists() && path.is_dir() {
-            Some(CoursePath(path, name.to_string()))
-        } else {
-            None
-        }
+        let paths = self.course_paths().collect::<Vec<_>>();
+        let folded = fold_name(name);
+        paths
+            .iter()
+            .find(|it| it.1 == name)
+            .or_else(|| paths.iter().find(|it| fold_name(&it.1) == folded))
+            .cloned()
     }
 
     pub fn remove(self) -> Result<()> {
-        std::fs::remove_dir_all(&self.0)
+        effects::remove_dir_all(&self.0)
             .with_context(|| anyhow!("Failed to remove semester path at: {}", self.0.display()))?;
         Ok(())
     }
 
+    /// Path of the semester report written by [SemesterPath::write_report].
+    pub fn report(&self) -> PathBuf {
+        self.0.join("report.md")
+    }
+
+    /// Writes the semester report into the semester folder and returns its path. An existing
+    /// report is replaced.
+    pub fn write_report(&self, content: &str) -> Result<PathBuf> {
+        let path = self.report();
+        effects::write(&path, content)
+            .with_context(|| anyhow!("Failed to write semester report to: {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Compresses the semester folder into `<target>/<name>.tar.gz` using the system `tar`.
+    pub fn archive<P>(&self, target: P) -> Result<PathBuf>
+    where
+        P: AsRef<Path>,
+    {
+        let target = target.as_ref();
+        effects::create_dir_all(target).with_context(|| {
+            anyhow!(
+                "Failed to create archive directory at: {}",
+                target.display()
+            )
+        })?;
+        let archive = target.join(format!("{}.tar.gz", self.1));
+        let parent = self
+            .0
+            .parent()
+            .ok_or_else(|| anyhow!("Semester path has no parent: {}", self.0.display()))?;
+        let progress = Progress::spinner(format!("Archiving semester {}", self.1));
+        let status = effects::status(
+            std::process::Command::new("tar")
+                .arg("-czf")
+                .arg(&archive)
+                .arg("-C")
+                .arg(parent)
+                .arg(&self.1),
+        )
+        .context("Failed to run 'tar'. Is it installed?")?;
+        drop(progress);
+        if !status.success() {
+            bail!("'tar' failed to archive semester '{}'", self.1);
+        }
+        Ok(archive)
+    }
+
+    /// Moves the course folder into this semester, keeping its name.
+    pub fn move_course_path(&self, course: &CoursePath) -> Result<CoursePath> {
+        let path = self.0.join(&course.1);
+        if path.exists() {
+            bail!(MmError::CourseAlreadyExists(path));
+        }
+        effects::rename(&course.0, &path).with_context(|| {
+            anyhow!(
+                "Failed to move course from {} to: {}",
+                course.0.display(),
+                path.display()
+            )
+        })?;
+        Ok(CoursePath(path, course.1.clone()))
+    }
+
     pub fn create_course_path(&self, name: &str) -> Result<CoursePath> {
-        let path = self.0.join(&name);
+        let path = self.0.join(name);
         if path.exists() {
-            bail!("The course path '{}' already exists.", path.display());
+            bail!(MmError::CourseAlreadyExists(path));
+        }
+        if self.2.matches(name) {
+            bail!(
+                "The course name '{}' matches the ignore list of the config",
+                name
+            )
         }
-        std::fs::create_dir(&path)
+        effects::create_dir(&path)
             .with_context(|| anyhow!("Failed to create semester path at: {}", path.display()))?;
 
         Ok(CoursePath(path, name.into()))
@@ -174,18 +342,26 @@ pub struct CoursePath(PathBuf, String);
 pub struct CourseDataFile(PathBuf);
 
 impl CoursePath {
+    /// The file is created by the first write or [Self::create_data_file], a missing file reads
+    /// as empty.
     pub fn data_file(&self) -> Result<CourseDataFile> {
-        let path = self.0.join("course.toml");
-        if !path.exists() && !path.is_file() {
-            std::fs::write(&path, include_str!("../../course.toml")).with_context(|| {
-                anyhow!("Failed to create course data file at: {}", path.display())
+        Ok(CourseDataFile(self.0.join("course.toml")))
+    }
+
+    /// Writes the `course.toml` template with all options as comments, unless the course already
+    /// has a data file, e.g. from a course template.
+    pub fn create_data_file(&self) -> Result<CourseDataFile> {
+        let file = self.data_file()?;
+        if !file.exists() {
+            write_atomic(&file, &new_data_file(COURSE_TEMPLATE)).with_context(|| {
+                anyhow!("Failed to create course data file at: {}", file.display())
             })?;
         }
-        Ok(CourseDataFile(path))
+        Ok(file)
     }
 
     pub fn remove(self) -> Result<()> {
-        std::fs::remove_dir_all(&self.0)
+        effects::remove_dir_all(&self.0)
             .with_context(|| anyhow!("Failed to remove course path at: {}", self.0.display()))?;
         Ok(())
     }
@@ -193,6 +369,133 @@ impl CoursePath {
     pub fn name(&self) -> &str {
         &self.1
     }
+
+    /// Path of a course folder which does not exist, e.g. for courses in tests.
+    #[cfg(test)]
+    pub(super) fn detached(name: &str) -> CoursePath {
+        CoursePath(PathBuf::from(name), name.to_string())
+    }
+
+    /// Creates the folders of this course in `target`, without files and hidden folders like
+    /// `.git`. Returns the number of created folders.
+    pub fn copy_skeleton(&self, target: &CoursePath) -> Result<usize> {
+        let mut created = 0;
+        let entries = WalkDir::new(&self.0)
+            .min_depth(1)
+            .into_iter()
+            .filter_entry(|it| !it.file_name().to_string_lossy().starts_with('.'));
+        for entry in entries {
+            let entry = entry
+                .with_context(|| anyhow!("Failed to read course folder: {}", self.0.display()))?;
+            if !entry.file_type().is_dir() {
+                continue;
+            }
+            let relative = entry.path().strip_prefix(&self.0)?;
+            let dir = target.0.join(relative);
+            effects::create_dir_all(&dir)
+                .with_context(|| anyhow!("Failed to create folder at: {}", dir.display()))?;
+            created += 1;
+        }
+        Ok(created)
+    }
+
+    /// Copies the directory tree of `template` into the course folder, see [apply_template()].
+    pub fn apply_template<P>(&self, template: P, render: impl Fn(&str) -> String) -> Result<usize>
+    where
+        P: AsRef<Path>,
+    {
+        apply_template(template.as_ref(), &self.0, render)
+    }
+}
+
+/// `target` relative to the directory `from`, e.g. `../b03` from `/uni/links` to `/uni/b03`. Both
+/// paths are compared as given, so they should both be absolute.
+fn relative_path(from: &Path, target: &Path) -> PathBuf {
+    let from = from.components().collect::<Vec<_>>();
+    let target = target.components().collect::<Vec<_>>();
+    let common = from.iter().zip(&target).take_while(|(a, b)| a == b).count();
+    std::iter::repeat_n(std::path::Component::ParentDir, from.len() - common)
+        .chain(target[common..].iter().copied())
+        .collect()
+}
+
+#[cfg(windows)]
+fn junction(original: &Path, link: &Path) -> std::io::Result<()> {
+    let status = effects::status(
+        std::process::Command::new("cmd")
+            .args(["/C", "mklink", "/J"])
+            .arg(link)
+            .arg(original),
+    )?;
+    match status.success() {
+        true => std::io::Result::Ok(()),
+        false => Err(std::io::Error::other("mklink /J failed")),
+    }
+}
+
+#[cfg(not(windows))]
+fn junction(original: &Path, link: &Path) -> std::io::Result<()> {
+    effects::symlink(original, link)
+}
+
+/// Case folded name in NFC, so `Einführung` typed on any system equals `einführung` read from a
+/// filesystem storing decomposed names (like macOS).
+pub fn fold_name(name: &str) -> String {
+    // folding a decomposed name catches letters whose folded form only exists decomposed
+    default_case_fold_str(&name.nfd().collect::<String>())
+        .nfc()
+        .collect()
+}
+
+/// Replaces `{name}` in the pattern with the number, padded with zeros for `{name:02}`.
+pub(super) fn fill_number(pattern: &str, name: &str, value: u32) -> String {
+    let placeholder =
+        Regex::new(&format!(r"\{{{}(?::0(\d+))?\}}", regex::escape(name))).expect("valid regex");
+    placeholder
+        .replace_all(pattern, |caps: &regex::Captures| {
+            let width = caps
+                .get(1)
+                .and_then(|it| it.as_str().parse().ok())
+                .unwrap_or(0);
+            format!("{:0width$}", value, width = width)
+        })
+        .to_string()
+}
+
+/// Copies the directory tree of `template` into `target`. `render` is applied to file and folder
+/// names as well as to the content of text files. Existing files are kept. Returns the number of
+/// created files.
+pub(super) fn apply_template(
+    template: &Path,
+    target: &Path,
+    render: impl Fn(&str) -> String,
+) -> Result<usize> {
+    let mut created = 0;
+    for entry in WalkDir::new(template).min_depth(1) {
+        let entry =
+            entry.with_context(|| anyhow!("Failed to read template at: {}", template.display()))?;
+        let relative = entry.path().strip_prefix(template)?;
+        let target = target.join(render(&relative.to_string_lossy()));
+
+        if entry.file_type().is_dir() {
+            effects::create_dir_all(&target)
+                .with_context(|| anyhow!("Failed to create folder at: {}", target.display()))?;
+            continue;
+        }
+        if target.exists() {
+            continue;
+        }
+        let content = std::fs::read(entry.path())
+            .with_context(|| anyhow!("Failed to read template: {}", entry.path().display()))?;
+        let content = match String::from_utf8(content) {
+            std::result::Result::Ok(text) => render(&text).into_bytes(),
+            Err(err) => err.into_bytes(),
+        };
+        effects::write(&target, content)
+            .with_context(|| anyhow!("Failed to write file at: {}", target.display()))?;
+        created += 1;
+    }
+    Ok(created)
 }
 
 impl Deref for EntryPoint {
@@ -211,29 +514,44 @@ impl Deref for CoursePath {
     }
 }
 
+/// How [MaybeSymLinkable] links to a folder, set by `link_strategy` in the config.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkStrategy {
+    /// Symlink to the absolute path of the folder.
+    #[default]
+    Absolute,
+    /// Symlink to the path of the folder relative to the link, which survives moving both.
+    Relative,
+    /// NTFS junction on Windows, which unlike symlinks needs no admin rights. Absolute symlink on
+    /// other platforms.
+    Junction,
+    /// Text file holding the path of the folder, for filesystems without symlinks.
+    Marker,
+}
+
 /// A path that may can be turned into a symlink.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub(crate) struct MaybeSymLinkable(Option<PathBuf>);
+pub struct MaybeSymLinkable(Option<PathBuf>, LinkStrategy);
 
 impl MaybeSymLinkable {
-    pub fn new<P>(path: Option<P>) -> Result<MaybeSymLinkable>
+    pub fn new<P>(path: Option<P>, strategy: LinkStrategy) -> Result<MaybeSymLinkable>
     where
         P: AsRef<Path>,
     {
         let path = path.map(|p| p.as_ref().to_path_buf());
 
         if let Some(p) = &path {
-            if p.exists() && p.is_symlink() {
-                return Ok(MaybeSymLinkable(path));
-            } else {
+            let is_marker = strategy == LinkStrategy::Marker && is_marker(p);
+            // a dangling link does not exist but is still a link
+            if p.exists() && !p.is_symlink() && !is_marker {
                 bail!(
                     "The path '{}' already exists and is not a symblink",
                     p.display()
                 )
             }
-        } else {
-            Ok(MaybeSymLinkable(None))
         }
+        Ok(MaybeSymLinkable(path, strategy))
     }
 
     pub fn link_from<P>(&self, original: P) -> Result<()>
@@ -241,50 +559,344 @@ impl MaybeSymLinkable {
         P: AsRef<Path>,
     {
         self.remove_link()?;
-        if let Some(path) = &self.0 {
-            #[cfg(unix)]
-            {
-                std::os::unix::fs::symlink(&original, path)?;
+        let Some(path) = &self.0 else {
+            return Ok(());
+        };
+        let original = original.as_ref();
+        measure(Operation::Symlink, || match self.1 {
+            LinkStrategy::Absolute => effects::symlink(original, path),
+            LinkStrategy::Relative => {
+                let parent = path.parent().unwrap_or(Path::new(""));
+                effects::symlink(relative_path(parent, original), path)
             }
+            LinkStrategy::Junction => junction(original, path),
+            LinkStrategy::Marker => effects::write(path, format!("{}\n", original.display())),
+        })
+        .with_context(|| {
+            anyhow!(
+                "Failed to link '{}' to: {}",
+                path.display(),
+                original.display()
+            )
+        })?;
+        Ok(())
+    }
 
+    pub fn remove_link(&self) -> Result<()> {
+        let Some(path) = &self.0 else {
+            return Ok(());
+        };
+        if path.is_symlink() {
+            // junctions and directory symlinks on Windows are directories
             #[cfg(windows)]
-            {
-                std::os::windows::fs::symlink_dir(&original, path)?;
-            }
+            measure(Operation::Symlink, || effects::remove_dir(path))?;
+            #[cfg(not(windows))]
+            measure(Operation::Symlink, || effects::remove_file(path))?;
+        } else if self.1 == LinkStrategy::Marker && is_marker(path) {
+            measure(Operation::Symlink, || effects::remove_file(path))?;
         }
         Ok(())
     }
+}
 
-    pub fn remove_link(&self) -> Result<()> {
-        if let Some(path) = &self.0 {
-            if path.is_symlink() {
-                std::fs::remove_file(path)?;
-            }
+/// Whether the file is empty or holds a single absolute path like the markers written by
+/// [MaybeSymLinkable::link_from], so other files at the link path are never overwritten.
+fn is_marker(path: &Path) -> bool {
+    // a path is short, larger files are no markers and are not read
+    if !std::fs::metadata(path).is_ok_and(|it| it.is_file() && it.len() <= 4096) {
+        return false;
+    }
+    let Result::Ok(content) = std::fs::read_to_string(path) else {
+        return false;
+    };
+    match content.strip_suffix('\n') {
+        Some(line) => !line.contains('\n') && Path::new(line).is_absolute(),
+        None => content.is_empty(),
+    }
+}
+
+/// Which folder a [Link] points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkTarget {
+    Semester,
+    Course,
+}
+
+/// A link from `links` in the config, updated by `mm switch`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Link {
+    pub name: String,
+    pub target: LinkTarget,
+    pub link: MaybeSymLinkable,
+}
+
+impl Link {
+    /// Points the link to `folder`, or removes it for `None`.
+    pub fn update(&self, folder: Option<&Path>) -> Result<()> {
+        match folder {
+            Some(folder) => tracing::trace!("link '{}' to {}", self.name, folder.display()),
+            None => tracing::trace!("unlink '{}'", self.name),
         }
-        Ok(())
+        match folder {
+            Some(folder) => self.link.link_from(folder),
+            None => self.link.remove_link(),
+        }
+        .with_context(|| anyhow!("Failed to update the link '{}'", self.name))
     }
 }
 
-pub(crate) trait ReadWriteDO: Deref<Target = PathBuf> {
+pub trait ReadWriteDO: Deref<Target = PathBuf> {
     type Object: DeserializeOwned + Serialize;
     fn read(&self) -> Result<Self::Object> {
-        let content = std::fs::read_to_string(self.deref())
-            .with_context(|| anyhow!("Failed to read file at: {}", self.deref().display()))?;
-        let it: Self::Object = toml_edit::de::from_str::<Self::Object>(&content)
-            .with_context(|| anyhow!("Failed to parse data from: {}", self.deref().display()))?;
-        Ok(it)
+        touch(self.deref());
+        measure(Operation::Parse, || {
+            let content = match self.deref().exists() {
+                true => std::fs::read_to_string(self.deref()).with_context(|| {
+                    anyhow!("Failed to read file at: {}", self.deref().display())
+                })?,
+                false => String::new(),
+            };
+            let it: Self::Object = toml_edit::de::from_str::<Self::Object>(&content)
+                .with_context(|| MmError::CorruptDataFile(self.deref().clone()))?;
+            if let Some(version) = Self::version(&it).filter(|it| *it > DATA_VERSION) {
+                bail!(MmError::UnsupportedDataVersion {
+                    path: self.deref().clone(),
+                    version,
+                });
+            }
+            Ok(it)
+        })
     }
 
-    fn write(&self, object: &Self::Object) -> Result<()> {
-        let data = toml_edit::ser::to_string(&object).with_context(|| {
+    /// The `version` of versioned data files, 0 for files written before it was introduced.
+    /// Versioned files are written with [DATA_VERSION] and can not be read if they are newer.
+    fn version(_object: &Self::Object) -> Option<u32> {
+        None
+    }
+
+    /// Rewrites a versioned file written by an older version of mm with [DATA_VERSION]. Returns
+    /// false if the file is up to date.
+    fn upgrade(&self) -> Result<bool> {
+        if !self.deref().exists() {
+            return Ok(false);
+        }
+        let object = self.read()?;
+        match Self::version(&object) {
+            Some(version) if version < DATA_VERSION => {
+                self.write(&object)?;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn to_document(&self, object: &Self::Object) -> Result<DocumentMut> {
+        toml_edit::ser::to_document(object).with_context(|| {
             anyhow!(
                 "Failed to serialize data to toml for: {}",
                 self.deref().display()
             )
-        })?;
-        std::fs::write(self.deref(), data)
-            .with_context(|| anyhow!("Failed to write data to file: {}", self.deref().display()))?;
+        })
+    }
+
+    /// Writes the object into the existing file, so comments, formatting and keys unknown to
+    /// [Self::Object] are kept. Only the values which changed are replaced.
+    fn write(&self, object: &Self::Object) -> Result<()> {
+        touch(self.deref());
+        measure(Operation::Write, || {
+            let mut new = self.to_document(object)?;
+            if Self::version(object).is_some() {
+                new = with_version(new);
+            }
+            let data = match self.existing_document()? {
+                Some((mut document, known)) => {
+                    let unversioned = !document.contains_key("version");
+                    merge_table(document.as_table_mut(), known.as_table(), new.as_table());
+                    if unversioned && new.contains_key("version") {
+                        // the sort is stable, so all other keys keep their order
+                        document.sort_values_by(|key, _, other, _| {
+                            (other.get() == "version").cmp(&(key.get() == "version"))
+                        });
+                    }
+                    document.to_string()
+                }
+                None => new.to_string(),
+            };
+            write_atomic(self.deref(), &data).with_context(|| {
+                anyhow!("Failed to write data to file: {}", self.deref().display())
+            })?;
+            Ok(())
+        })
+    }
+
+    /// The current document of the file and the part of it [Self::Object] knows about. An
+    /// unreadable or invalid file is replaced as a whole.
+    fn existing_document(&self) -> Result<Option<(DocumentMut, DocumentMut)>> {
+        let Result::Ok(content) = std::fs::read_to_string(self.deref()) else {
+            return Ok(None);
+        };
+        let (Result::Ok(document), Result::Ok(object)) = (
+            content.parse::<DocumentMut>(),
+            toml_edit::de::from_str::<Self::Object>(&content),
+        ) else {
+            return Ok(None);
+        };
+        let known = self.to_document(&object)?;
+        Ok(Some((document, known)))
+    }
+}
+
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// Makes every following write of a data file fail, see `mm --read-only`.
+pub fn set_read_only(read_only: bool) {
+    READ_ONLY.store(read_only, Ordering::Relaxed);
+}
+
+pub fn is_read_only() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
+
+/// Copy of the document with `version` as first key.
+fn with_version(document: DocumentMut) -> DocumentMut {
+    let mut versioned = DocumentMut::new();
+    versioned.insert("version", toml_edit::value(i64::from(DATA_VERSION)));
+    for (key, item) in document.iter() {
+        versioned.insert(key, item.clone());
+    }
+    versioned
+}
+
+/// Writes into a temporary file next to `path` which replaces `path` once it is synced to disk, so
+/// an interrupted write never leaves a truncated file behind.
+pub(super) fn write_atomic(path: &Path, data: &str) -> Result<()> {
+    if effects::plan(Effect::Write(path.to_path_buf())) {
+        return Ok(());
+    }
+    if is_read_only() {
+        bail!(MmError::ReadOnly);
+    }
+    let name = path
+        .file_name()
+        .map(|it| it.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.mm-tmp", name));
+    let write = || -> Result<()> {
+        let mut file = File::create(&tmp)?;
+        file.write_all(data.as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)?;
+        // persists the rename itself, not supported on every platform
+        if let Some(dir) = path.parent().and_then(|it| File::open(it).ok()) {
+            let _ = dir.sync_all();
+        }
         Ok(())
+    };
+    let res = write();
+    if res.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    res
+}
+
+/// Updates `old` to the values of `new`. `known` holds the keys of `old` which are part of the
+/// data object, so keys missing in `new` are only removed if they are known. Unchanged values keep
+/// their formatting and comments.
+fn merge_table(old: &mut Table, known: &Table, new: &Table) {
+    let removed = old
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| known.contains_key(key) && !new.contains_key(key))
+        .collect::<Vec<_>>();
+    for key in removed {
+        old.remove(&key);
+    }
+
+    for (key, item) in new.iter() {
+        let known_item = known.get(key);
+        let Some(old_item) = old.get_mut(key) else {
+            old.insert(key, item.clone());
+            continue;
+        };
+        if known_item.is_some_and(|it| it.to_string() == item.to_string()) {
+            continue;
+        }
+        match (old_item, item) {
+            (Item::Table(old_table), Item::Table(table)) => {
+                let empty = Table::new();
+                let known_table = known_item.and_then(Item::as_table).unwrap_or(&empty);
+                merge_table(old_table, known_table, table)
+            }
+            (Item::ArrayOfTables(old_array), Item::ArrayOfTables(array)) => {
+                let known_array = known_item.and_then(Item::as_array_of_tables);
+                merge_array(old_array, known_array, array)
+            }
+            (Item::Value(old_value), Item::Value(value)) => {
+                let decor = old_value.decor().clone();
+                *old_value = value.clone();
+                *old_value.decor_mut() = decor;
+            }
+            (old_item, item) => *old_item = item.clone(),
+        }
+    }
+}
+
+/// Updates the tables of `old` to `new`, e.g. the `[[tasks]]` of a course. Unchanged tables are
+/// looked up by content so they keep their comments even if the entries were reordered. Changed
+/// tables are merged with the table at the same position.
+fn merge_array(old: &mut ArrayOfTables, known: Option<&ArrayOfTables>, new: &ArrayOfTables) {
+    let known = known
+        .filter(|it| it.len() == old.len())
+        .map(|it| it.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let mut used = vec![false; old.len()];
+    let mut tables = new.iter().map(|_| None).collect::<Vec<Option<Table>>>();
+
+    for (table, slot) in new.iter().zip(tables.iter_mut()) {
+        let content = table.to_string();
+        if let Some(index) = (0..known.len()).find(|&i| !used[i] && known[i].to_string() == content)
+        {
+            used[index] = true;
+            *slot = old.get(index).cloned();
+        }
+    }
+    let empty = Table::new();
+    for (index, (table, slot)) in new.iter().zip(tables.iter_mut()).enumerate() {
+        if slot.is_some() {
+            continue;
+        }
+        *slot = match old.get(index).filter(|_| !used[index]) {
+            Some(old_table) => {
+                used[index] = true;
+                let mut merged = old_table.clone();
+                merge_table(
+                    &mut merged,
+                    known.get(index).copied().unwrap_or(&empty),
+                    table,
+                );
+                Some(merged)
+            }
+            None => Some(table.clone()),
+        };
+    }
+
+    // tables are written in the order of their position in the document, so the new order takes
+    // over the positions of the old tables
+    let mut positions = old.iter().filter_map(Table::position).collect::<Vec<_>>();
+    positions.sort();
+    old.clear();
+    for (index, mut table) in tables.into_iter().flatten().enumerate() {
+        if let Some(position) = positions.get(index).or(positions.last()) {
+            table.set_position(*position);
+        }
+        old.push(table);
+    }
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        let _ = self.0.unlock();
     }
 }
 
@@ -311,3 +923,84 @@ impl Deref for StoreDataFile {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use toml_edit::DocumentMut;
+
+    use super::{fold_name, merge_table};
+
+    /// Merges `new` into `old` like writing a data file which was read as `known`.
+    fn merge(old: &str, known: &str, new: &str) -> String {
+        let mut old = old.parse::<DocumentMut>().unwrap();
+        let known = known.parse::<DocumentMut>().unwrap();
+        let new = new.parse::<DocumentMut>().unwrap();
+        merge_table(old.as_table_mut(), known.as_table(), new.as_table());
+        old.to_string()
+    }
+
+    #[test]
+    fn merge_table_keeps_comments_of_values() {
+        let old = "# Einführung\nname = \"Info I\" # short\ngrade = 1.3 # first try\n";
+        let known = "name = \"Info I\"\ngrade = 1.3\n";
+        let new = "name = \"Info I\"\ngrade = 1.0\n";
+        assert_eq!(
+            merge(old, known, new),
+            "# Einführung\nname = \"Info I\" # short\ngrade = 1.0 # first try\n"
+        );
+    }
+
+    #[test]
+    fn merge_table_removes_only_known_keys() {
+        let old = "name = \"Info I\"\nlocked = true\nnote = \"by hand\"\n";
+        let known = "name = \"Info I\"\nlocked = true\n";
+        let new = "name = \"Info I\"\nects = 9\n";
+        assert_eq!(
+            merge(old, known, new),
+            "name = \"Info I\"\nnote = \"by hand\"\nects = 9\n"
+        );
+    }
+
+    #[test]
+    fn merge_table_merges_nested_tables() {
+        let old = "[repository]\n# mirror\nurl = \"a\"\npath = \".\"\n";
+        let known = "[repository]\nurl = \"a\"\npath = \".\"\n";
+        let new = "[repository]\nurl = \"b\"\npath = \".\"\n";
+        assert_eq!(
+            merge(old, known, new),
+            "[repository]\n# mirror\nurl = \"b\"\npath = \".\"\n"
+        );
+    }
+
+    #[test]
+    fn merge_array_follows_reordered_tables() {
+        let old = "# first\n[[tasks]]\ntitle = \"a\"\n# second\n[[tasks]]\ntitle = \"b\"\n";
+        let known = "[[tasks]]\ntitle = \"a\"\n\n[[tasks]]\ntitle = \"b\"\n";
+        let new = "[[tasks]]\ntitle = \"b\"\n\n[[tasks]]\ntitle = \"a\"\n";
+        assert_eq!(
+            merge(old, known, new),
+            "# second\n[[tasks]]\ntitle = \"b\"\n# first\n[[tasks]]\ntitle = \"a\"\n"
+        );
+    }
+
+    #[test]
+    fn merge_array_merges_changed_tables_by_position() {
+        let old = "[[tasks]]\n# urgent\ntitle = \"a\"\ndone = false\n";
+        let known = "[[tasks]]\ntitle = \"a\"\ndone = false\n";
+        let new = "[[tasks]]\ntitle = \"a\"\ndone = true\n\n[[tasks]]\ntitle = \"c\"\n";
+        assert_eq!(
+            merge(old, known, new),
+            "[[tasks]]\n# urgent\ntitle = \"a\"\ndone = true\n\n[[tasks]]\ntitle = \"c\"\n"
+        );
+    }
+
+    #[test]
+    fn fold_name_composes_and_lowercases() {
+        assert_eq!(fold_name("Einfu\u{308}hrung"), "einführung");
+        assert_eq!(fold_name("Einführung"), "einführung");
+        assert_eq!(fold_name("U\u{308}BUNG"), "übung");
+        assert_eq!(fold_name("Analysis I"), "analysis i");
+        assert_eq!(fold_name("Ånge\u{301}"), "ångé");
+        assert_eq!(fold_name("STRASSE"), fold_name("Straße"));
+    }
+}
@@ -0,0 +1,187 @@
+use std::{ops::Deref, path::PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    course::normalize_name,
+    effects,
+    paths::{EntryPoint, ReadWriteDO},
+};
+
+/// Courses planned for semesters which do not exist yet, stored in `plan.toml` at the entry
+/// point. No folders are created until a semester is applied with `mm plan apply`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Plan {
+    #[serde(default)]
+    semesters: Vec<PlannedSemester>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedSemester {
+    /// Folder name the semester gets, e.g. `b04`.
+    name: String,
+    #[serde(default)]
+    courses: Vec<PlannedCourse>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedCourse {
+    name: String,
+    ects: Option<u8>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    degrees: Vec<String>,
+    module_id: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PlanFile(PathBuf);
+
+impl Plan {
+    pub fn file(entry_point: &EntryPoint) -> PlanFile {
+        PlanFile(entry_point.join("plan.toml"))
+    }
+
+    /// The saved plan or an empty one.
+    pub fn load(entry_point: &EntryPoint) -> Result<Plan> {
+        Plan::file(entry_point).read()
+    }
+
+    pub fn save(&self, entry_point: &EntryPoint) -> Result<()> {
+        Plan::file(entry_point).write(self)
+    }
+
+    pub fn semesters(&self) -> &Vec<PlannedSemester> {
+        &self.semesters
+    }
+
+    pub fn semester(&self, name: &str) -> Option<&PlannedSemester> {
+        self.semesters.iter().find(|it| it.name == name)
+    }
+
+    /// Adds the course to the semester, which is planned if it is not yet.
+    pub fn add(&mut self, semester: &str, course: PlannedCourse) -> Result<()> {
+        let idx = match self.semesters.iter().position(|it| it.name == semester) {
+            Some(idx) => idx,
+            None => {
+                self.semesters.push(PlannedSemester {
+                    name: semester.to_string(),
+                    courses: Vec::new(),
+                });
+                self.semesters.len() - 1
+            }
+        };
+        let planned = &mut self.semesters[idx];
+        if planned.course(&course.name).is_some() {
+            bail!("'{}' is already planned for {}", course.name, semester);
+        }
+        planned.courses.push(course);
+        Ok(())
+    }
+
+    /// Removes a course of the semester or, without course, the whole semester. A semester
+    /// without courses is removed as well.
+    pub fn remove(&mut self, semester: &str, course: Option<&str>) -> Result<()> {
+        let idx = self
+            .semesters
+            .iter()
+            .position(|it| it.name == semester)
+            .ok_or_else(|| anyhow!("No semester '{}' is planned", semester))?;
+        if let Some(course) = course {
+            let planned = &mut self.semesters[idx];
+            let len = planned.courses.len();
+            planned
+                .courses
+                .retain(|it| normalize_name(&it.name) != normalize_name(course));
+            if planned.courses.len() == len {
+                bail!("'{}' is not planned for {}", course, semester);
+            }
+            if !planned.courses.is_empty() {
+                return Ok(());
+            }
+        }
+        self.semesters.remove(idx);
+        Ok(())
+    }
+}
+
+impl PlannedSemester {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn courses(&self) -> &Vec<PlannedCourse> {
+        &self.courses
+    }
+
+    /// The planned course by name, case and punctuation insensitive.
+    pub fn course(&self, name: &str) -> Option<&PlannedCourse> {
+        self.courses
+            .iter()
+            .find(|it| normalize_name(&it.name) == normalize_name(name))
+    }
+
+    /// Sum of the ECTS of the planned courses.
+    pub fn ects(&self) -> u32 {
+        self.courses
+            .iter()
+            .filter_map(|it| it.ects)
+            .map(u32::from)
+            .sum()
+    }
+}
+
+impl PlannedCourse {
+    pub fn new(
+        name: String,
+        ects: Option<u8>,
+        degrees: Vec<String>,
+        module_id: Option<String>,
+    ) -> PlannedCourse {
+        PlannedCourse {
+            name,
+            ects,
+            degrees,
+            module_id,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn ects(&self) -> Option<u8> {
+        self.ects
+    }
+
+    pub fn degrees(&self) -> &Vec<String> {
+        &self.degrees
+    }
+
+    pub fn module_id(&self) -> Option<&str> {
+        self.module_id.as_deref()
+    }
+}
+
+impl Deref for PlanFile {
+    type Target = PathBuf;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl ReadWriteDO for PlanFile {
+    type Object = Plan;
+
+    fn write(&self, object: &Self::Object) -> Result<()> {
+        let data = toml_edit::ser::to_string_pretty(&object).with_context(|| {
+            anyhow!(
+                "Failed to serialize data to toml for: {}",
+                self.deref().display()
+            )
+        })?;
+        effects::write(self.deref(), data)
+            .with_context(|| anyhow!("Failed to write data to file: {}", self.deref().display()))
+    }
+}
@@ -0,0 +1,63 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+/// Shows progress of slow operations, disabled by `mm --quiet`.
+pub fn set_progress(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Spinner or bar on stderr for operations which take a while, like creating archives or
+/// running `rsync`, so mm does not appear hung. Only drawn if stderr is a terminal and removed
+/// again when dropped.
+pub struct Progress(ProgressBar);
+
+impl Progress {
+    pub fn spinner(message: impl Into<String>) -> Progress {
+        let progress = Progress::new(
+            ProgressBar::new_spinner()
+                .with_style(
+                    ProgressStyle::with_template("{spinner:.dim} {msg:.dim}")
+                        .expect("valid template"),
+                )
+                .with_message(message.into()),
+        );
+        progress.0.enable_steady_tick(Duration::from_millis(100));
+        progress
+    }
+
+    pub fn bar(message: impl Into<String>, total: u64) -> Progress {
+        let bar = ProgressBar::new(total)
+            .with_style(
+                ProgressStyle::with_template("[{bar:20.dim}] {pos}/{len} {msg:.dim}")
+                    .expect("valid template")
+                    .progress_chars("#-"),
+            )
+            .with_message(message.into());
+        Progress::new(bar)
+    }
+
+    fn new(bar: ProgressBar) -> Progress {
+        if !ENABLED.load(Ordering::Relaxed) {
+            bar.set_draw_target(ProgressDrawTarget::hidden());
+        }
+        Progress(bar)
+    }
+
+    /// Advances the bar by one step and shows `message`, e.g. the item being processed.
+    pub fn step(&self, message: impl Into<String>) {
+        self.0.inc(1);
+        self.0.set_message(message.into());
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.0.finish_and_clear();
+    }
+}
@@ -0,0 +1,82 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+
+use super::{
+    config::SemesterNames,
+    effects,
+    paths::{new_data_file, write_atomic, EntryPoint, ReadWriteDO, COURSE_TEMPLATE},
+};
+
+/// A data file of the store which is empty or can not be parsed, e.g. after a crash of an older
+/// version of mm which wrote the files in place.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DamagedFile {
+    path: PathBuf,
+    reason: String,
+    /// Template of a regenerated file.
+    template: &'static str,
+}
+
+impl DamagedFile {
+    /// Checks the `.mm` files of the store and its semesters and the `course.toml` of every
+    /// course. The `.mm` files are empty until something is active, so only empty course files
+    /// are reported.
+    pub fn scan(
+        entry_point: &EntryPoint,
+        semester_names: &SemesterNames,
+    ) -> Result<Vec<DamagedFile>> {
+        let mut damaged = Vec::new();
+        damaged.extend(check(&entry_point.data_file()?, false, ""));
+        for semester in entry_point.semester_paths(semester_names) {
+            damaged.extend(check(&semester.data_file()?, false, ""));
+            for course in semester.course_paths() {
+                damaged.extend(check(&course.data_file()?, true, COURSE_TEMPLATE));
+            }
+        }
+        Ok(damaged)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    /// Moves the damaged file to `<name>.corrupt` and writes a fresh file in its place. The
+    /// path of the moved file is returned.
+    pub fn regenerate(&self) -> Result<PathBuf> {
+        let name = self
+            .path
+            .file_name()
+            .map(|it| it.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let backup = self.path.with_file_name(format!("{}.corrupt", name));
+        effects::rename(&self.path, &backup)
+            .with_context(|| anyhow!("Failed to move: {}", self.path.display()))?;
+        write_atomic(&self.path, &new_data_file(self.template))
+            .with_context(|| anyhow!("Failed to regenerate: {}", self.path.display()))?;
+        Ok(backup)
+    }
+}
+
+fn check<F>(file: &F, require_content: bool, template: &'static str) -> Option<DamagedFile>
+where
+    F: ReadWriteDO,
+{
+    if !file.exists() {
+        return None;
+    }
+    let reason = match std::fs::read_to_string(file.as_path()) {
+        Ok(content) if require_content && content.trim().is_empty() => "empty".to_string(),
+        Ok(_) => format!("{:#}", file.read().err()?.root_cause()),
+        Err(err) => err.to_string(),
+    };
+    Some(DamagedFile {
+        path: file.to_path_buf(),
+        reason,
+        template,
+    })
+}
@@ -0,0 +1,99 @@
+use std::{path::PathBuf, process::Command};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{effects, paths::CoursePath};
+
+/// Folder a material repository is cloned into if none is given.
+pub const DEFAULT_REPOSITORY_DIR: &str = "material";
+
+/// A git repository with course material, cloned into a folder of the course.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Repository {
+    url: String,
+    /// Folder relative to the course. `.` is the course folder itself.
+    path: String,
+}
+
+/// Outcome of [Repository::update].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepositoryUpdate {
+    Cloned,
+    Updated,
+    UpToDate,
+}
+
+impl Repository {
+    pub fn new(url: String, path: Option<String>) -> Repository {
+        let path = path.unwrap_or_else(|| DEFAULT_REPOSITORY_DIR.into());
+        Repository { url, path }
+    }
+
+    pub fn dir(&self, course: &CoursePath) -> PathBuf {
+        match self.path.as_str() {
+            "." => course.to_path_buf(),
+            path => course.join(path),
+        }
+    }
+
+    /// Clones the repository. The target folder must not exist or be empty.
+    pub fn clone_into(&self, course: &CoursePath) -> Result<()> {
+        let dir = self.dir(course);
+        let output = effects::output(
+            Command::new("git")
+                .args(["clone", "--quiet", &self.url])
+                .arg(&dir),
+        )
+        .context("Failed to run 'git'. Is it installed?")?;
+        if !output.status.success() {
+            bail!(
+                "Failed to clone '{}': {}",
+                self.url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        Ok(())
+    }
+
+    /// Pulls new commits with fast-forward only. Clones the repository if its folder is missing,
+    /// e.g. after syncing the store to another machine.
+    pub fn update(&self, course: &CoursePath) -> Result<RepositoryUpdate> {
+        let dir = self.dir(course);
+        if !dir.join(".git").exists() {
+            if dir.is_dir() && dir.read_dir()?.next().is_some() {
+                bail!("'{}' exists but is not a git repository", dir.display());
+            }
+            self.clone_into(course)?;
+            return Ok(RepositoryUpdate::Cloned);
+        }
+
+        let head = |dir: &PathBuf| -> Result<String> {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(dir)
+                .args(["rev-parse", "HEAD"])
+                .output()
+                .context("Failed to run 'git'. Is it installed?")?;
+            Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+        };
+        let before = head(&dir)?;
+        let output = effects::output(Command::new("git").arg("-C").arg(&dir).args([
+            "pull",
+            "--quiet",
+            "--ff-only",
+        ]))
+        .context("Failed to run 'git'. Is it installed?")?;
+        if !output.status.success() {
+            bail!(
+                "Failed to pull '{}': {}",
+                self.url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        match head(&dir)? == before {
+            true => Ok(RepositoryUpdate::UpToDate),
+            false => Ok(RepositoryUpdate::Updated),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+/// Conversion of grades into other scales for applications abroad, configured with
+/// `[grade_scale]` in the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct GradeScale {
+    /// Best grade of the local scale.
+    best: f32,
+    /// Worst passing grade of the local scale.
+    pass: f32,
+    /// Best grade of the GPA scale.
+    gpa_max: f32,
+    /// Worst passing grade of the GPA scale.
+    gpa_min: f32,
+    /// Worst grade which still gets an ECTS letter.
+    ects: BTreeMap<String, f32>,
+}
+
+impl Default for GradeScale {
+    fn default() -> Self {
+        let ects = [("A", 1.5), ("B", 2.0), ("C", 3.0), ("D", 3.5), ("E", 4.0)]
+            .into_iter()
+            .map(|(letter, grade)| (letter.to_string(), grade))
+            .collect();
+        GradeScale {
+            best: 1.0,
+            pass: 4.0,
+            gpa_max: 4.0,
+            gpa_min: 1.0,
+            ects,
+        }
+    }
+}
+
+impl GradeScale {
+    /// Converts a grade linearly with the modified Bavarian formula, solved for the foreign
+    /// grade. Failing grades become 0.
+    pub fn gpa(&self, grade: f32) -> f32 {
+        if grade > self.pass {
+            return 0.0;
+        }
+        let grade = grade.max(self.best);
+        self.gpa_max - (grade - self.best) * (self.gpa_max - self.gpa_min) / (self.pass - self.best)
+    }
+
+    /// The ECTS letter with the lowest threshold the grade reaches, "F" for failing grades.
+    pub fn ects_letter(&self, grade: f32) -> String {
+        // Averages are rounded to two decimals before comparing to avoid float noise.
+        let grade = (grade * 100.0).round() / 100.0;
+        let mut letters = self.ects.iter().collect::<Vec<_>>();
+        letters.sort_by(|a, b| a.1.total_cmp(b.1));
+        letters
+            .into_iter()
+            .find(|(_, threshold)| grade <= **threshold)
+            .map(|(letter, _)| letter.clone())
+            .unwrap_or_else(|| "F".into())
+    }
+}
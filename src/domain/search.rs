@@ -0,0 +1,134 @@
+use std::{
+    fmt,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use walkdir::WalkDir;
+
+use super::{
+    note::{Note, MAIN_DOCUMENT},
+    paths::CoursePath,
+};
+
+/// Line of a note or page of a PDF containing the query of `mm search --content`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentHit {
+    /// Path relative to the course folder.
+    pub file: PathBuf,
+    pub position: Position,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Position {
+    Line(usize),
+    Page(usize),
+}
+
+/// Lines of the notes and the main document of the course containing the query, case
+/// insensitive. With `pdfs` the text of the PDFs in the course folder is searched as well, which
+/// requires `pdftotext` of poppler.
+pub fn search_content(course: &CoursePath, query: &str, pdfs: bool) -> Result<Vec<ContentHit>> {
+    let query = query.to_lowercase();
+    let mut files = Note::list(course)?
+        .into_iter()
+        .map(|it| it.path().to_path_buf())
+        .filter(|it| !is_pdf(it))
+        .collect::<Vec<_>>();
+    let main = course.join(MAIN_DOCUMENT);
+    if main.is_file() {
+        files.insert(0, main);
+    }
+
+    let mut hits = Vec::new();
+    for file in files {
+        // binary files in the notes folder are skipped
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        let lines = content
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| (Position::Line(idx + 1), line));
+        hits.extend(matching(course, &file, lines, &query));
+    }
+
+    if pdfs {
+        for file in pdf_files(course) {
+            let Some(text) = pdf_text(&file)? else {
+                continue;
+            };
+            // pages are separated by form feeds
+            let lines = text.split('\u{c}').enumerate().flat_map(|(idx, page)| {
+                page.lines()
+                    .map(move |line| (Position::Page(idx + 1), line))
+            });
+            hits.extend(matching(course, &file, lines, &query));
+        }
+    }
+    Ok(hits)
+}
+
+fn matching<'a>(
+    course: &CoursePath,
+    file: &Path,
+    lines: impl Iterator<Item = (Position, &'a str)>,
+    query: &str,
+) -> Vec<ContentHit> {
+    let relative = file.strip_prefix(course.as_path()).unwrap_or(file);
+    lines
+        .filter(|(_, line)| line.to_lowercase().contains(query))
+        .map(|(position, line)| ContentHit {
+            file: relative.to_path_buf(),
+            position,
+            text: line.trim().to_string(),
+        })
+        .collect()
+}
+
+fn is_pdf(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|it| it.eq_ignore_ascii_case("pdf"))
+}
+
+/// PDFs in the course folder and its subfolders, hidden folders like `.git` are skipped.
+fn pdf_files(course: &CoursePath) -> Vec<PathBuf> {
+    let mut files = WalkDir::new(course.as_path())
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|it| !it.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file() && is_pdf(entry.path()))
+        .map(|entry| entry.into_path())
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+/// Text of the PDF extracted by `pdftotext`, `None` if the PDF cannot be read, e.g. because it
+/// is encrypted or damaged.
+fn pdf_text(path: &Path) -> Result<Option<String>> {
+    let output = match Command::new("pdftotext").arg(path).arg("-").output() {
+        Ok(output) => output,
+        Err(err) if err.kind() == ErrorKind::NotFound => {
+            bail!("'pdftotext' is not installed, it is needed to search PDFs")
+        }
+        Err(err) => return Err(err).with_context(|| anyhow!("Failed to run 'pdftotext'")),
+    };
+    if !output.status.success() {
+        return Ok(None);
+    }
+    Ok(Some(String::from_utf8_lossy(&output.stdout).to_string()))
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Position::Line(line) => write!(f, "{}", line),
+            Position::Page(page) => write!(f, "p. {}", page),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use core::fmt;
+use std::str::FromStr;
 
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
 use serde::{Deserialize, Serialize};
 
 use crate::cli::StudyCycleDO;
@@ -8,20 +9,31 @@ use crate::cli::StudyCycleDO;
 use super::{
     config::SemesterNames,
     course::Course,
-    paths::{CoursePath, ReadWriteDO, SemesterDataFile, SemesterPath},
+    date::Date,
+    parallel::parallel_map,
+    paths::{CoursePath, ReadWriteDO, SemesterDataFile, SemesterPath, DATA_VERSION},
 };
 
+/// Semesters are ordered by study cycle and number.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone)]
 pub struct Semester {
-    semester_number: u16,
     study_cycle: StudyCycle,
+    semester_number: u16,
     path: SemesterPath,
     active_course: Option<CoursePath>,
+    start: Option<Date>,
+    end: Option<Date>,
+    ects_budget: Option<u32>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SemesterDO {
+    #[serde(default, skip_serializing)]
+    version: u32,
     active_course: Option<String>,
+    start: Option<Date>,
+    end: Option<Date>,
+    ects_budget: Option<u32>,
 }
 
 impl Semester {
@@ -30,14 +42,16 @@ impl Semester {
         let semester_do = data_file.read()?;
         let active_course = semester_do
             .active_course
-            .map(|it| path.course_path(&it))
-            .flatten();
+            .and_then(|it| path.course_path(&it));
         let (semester_number, study_cycle) = semester_names.deserialize(path.name())?;
         let semester = Semester {
             semester_number,
             study_cycle,
             path,
             active_course,
+            start: semester_do.start,
+            end: semester_do.end,
+            ects_budget: semester_do.ects_budget,
         };
         Ok(semester)
     }
@@ -45,21 +59,20 @@ impl Semester {
     pub fn active_course(&self) -> Option<Course> {
         self.active_course
             .as_ref()
-            .map(|it| Course::from_path(it.clone()).ok())
-            .flatten()
+            .and_then(|it| Course::from_path(it.clone()).ok())
     }
 
     pub fn courses(&self) -> impl Iterator<Item = Course> {
-        self.path
-            .course_paths()
-            .filter_map(|path| Course::from_path(path).ok())
+        let paths = self.path.course_paths().collect();
+        parallel_map(paths, |path| Course::from_path(path).ok())
+            .into_iter()
+            .flatten()
     }
 
     pub fn course(&self, name: &str) -> Option<Course> {
         self.path
             .course_path(name)
-            .map(|path| Course::from_path(path).ok())
-            .flatten()
+            .and_then(|path| Course::from_path(path).ok())
     }
 
     /// Does not perform symlink operations.
@@ -69,32 +82,105 @@ impl Semester {
         self.path.data_file()?.write(&self.to_do())
     }
 
+    pub fn set_dates(&mut self, start: Option<Date>, end: Option<Date>) -> Result<()> {
+        if let (Some(start), Some(end)) = (start, end) {
+            if end < start {
+                bail!("The semester ends ({}) before it starts ({})", end, start);
+            }
+        }
+        self.start = start;
+        self.end = end;
+        self.path.data_file()?.write(&self.to_do())
+    }
+
+    /// First and last day of the semester. Without `start` and `end` in its data file a semester
+    /// named by its term lasts from October to March (`WS`) or from April to September (`SS`).
+    pub fn dates(&self) -> Option<(Date, Date)> {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => Some((start, end)),
+            (None, None) => self.path.name().parse::<Term>().ok()?.dates(),
+            _ => None,
+        }
+    }
+
+    /// ECTS to enroll in during the semester, set by `ects_budget` in its data file. Falls back to
+    /// `ects_budget` of the config, see [StoreProvider::ects_budget](crate::StoreProvider).
+    pub fn ects_budget(&self) -> Option<u32> {
+        self.ects_budget
+    }
+
+    pub fn set_ects_budget(&mut self, ects_budget: Option<u32>) -> Result<()> {
+        self.ects_budget = ects_budget;
+        self.path.data_file()?.write(&self.to_do())
+    }
+
+    /// ECTS of the courses the semester is enrolled in, see [Course::is_enrolled].
+    pub fn enrolled_ects(&self) -> u32 {
+        self.courses()
+            .filter(Course::is_enrolled)
+            .filter_map(|it| it.ects())
+            .map(u32::from)
+            .sum()
+    }
+
+    /// Whether the semester lasts over `date`.
+    pub fn contains(&self, date: &Date) -> bool {
+        self.dates()
+            .is_some_and(|(start, end)| (start..=end).contains(date))
+    }
+
     fn to_do(&self) -> SemesterDO {
         let active_course = self.active_course.as_ref().map(|it| it.name().to_string());
-        SemesterDO { active_course }
+        SemesterDO {
+            version: DATA_VERSION,
+            active_course,
+            start: self.start,
+            end: self.end,
+            ects_budget: self.ects_budget,
+        }
     }
 
     pub fn path(&self) -> &SemesterPath {
         &self.path
     }
 
+    /// The folder name, formatted by the configured semester names.
     pub fn name(&self) -> String {
-        format!(
-            "{}{:02}",
-            self.study_cycle.abbreviation(),
-            self.semester_number
-        )
+        self.path.name().to_string()
+    }
+
+    pub fn number(&self) -> u16 {
+        self.semester_number
     }
 
     pub fn study_cycle(&self) -> StudyCycle {
         self.study_cycle
     }
+
+    /// Position within the study cycle, e.g. `3rd Bachelor semester`.
+    pub fn ordinal(&self) -> String {
+        let suffix = match (self.semester_number % 10, self.semester_number % 100) {
+            (_, 11..=13) => "th",
+            (1, _) => "st",
+            (2, _) => "nd",
+            (3, _) => "rd",
+            _ => "th",
+        };
+        format!(
+            "{}{} {} semester",
+            self.semester_number, suffix, self.study_cycle
+        )
+    }
 }
 
 impl SemesterDO {}
 
 impl ReadWriteDO for SemesterDataFile {
     type Object = SemesterDO;
+
+    fn version(object: &Self::Object) -> Option<u32> {
+        Some(object.version)
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -125,12 +211,67 @@ impl fmt::Display for StudyCycle {
     }
 }
 
-impl StudyCycle {
-    pub fn abbreviation(&self) -> &'static str {
-        match self {
-            StudyCycle::Bachelor => "b",
-            StudyCycle::Master => "m",
-            StudyCycle::Doctorate => "d",
+/// A winter or summer term named like `WS2024` or `SS2025`. The winter term starts in autumn of
+/// its year and follows the summer term of the same year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(super) struct Term {
+    year: u16,
+    winter: bool,
+}
+
+impl Term {
+    /// The term `offset` terms after this one.
+    pub(super) fn after(&self, offset: u16) -> Term {
+        let index = self.index() + u32::from(offset);
+        Term {
+            year: (index / 2) as u16,
+            winter: index % 2 == 1,
+        }
+    }
+
+    /// Number of terms from `start` to this term, `None` if it is before `start`.
+    pub(super) fn since(&self, start: &Term) -> Option<u16> {
+        self.index()
+            .checked_sub(start.index())
+            .and_then(|it| u16::try_from(it).ok())
+    }
+
+    fn dates(&self) -> Option<(Date, Date)> {
+        let year = i32::from(self.year);
+        match self.winter {
+            true => Some((
+                Date::new(year, 10, 1).ok()?,
+                Date::new(year + 1, 3, 31).ok()?,
+            )),
+            false => Some((Date::new(year, 4, 1).ok()?, Date::new(year, 9, 30).ok()?)),
+        }
+    }
+
+    fn index(&self) -> u32 {
+        u32::from(self.year) * 2 + u32::from(self.winter)
+    }
+}
+
+impl FromStr for Term {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (winter, year) = match s.split_at_checked(2) {
+            Some(("WS", year)) => (true, year),
+            Some(("SS", year)) => (false, year),
+            _ => return Err(anyhow!("Expected a term like WS2024 or SS2025, got: {}", s)),
+        };
+        if year.len() != 4 || !year.chars().all(|it| it.is_ascii_digit()) {
+            return Err(anyhow!("Expected a term like WS2024 or SS2025, got: {}", s));
         }
+        let year = year.parse()?;
+        Ok(Term { year, winter })
+    }
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let season = if self.winter { "WS" } else { "SS" };
+        write!(f, "{}{:04}", season, self.year)
     }
 }
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+use super::date::{Date, Time};
+
+/// A tracked study session stored in the `course.toml` of a course. A session without minutes is
+/// still running.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Serialize, Deserialize)]
+pub struct Session {
+    /// Local date the session started on, which decides the week it is reported in.
+    date: Date,
+    /// Local time the session started at.
+    start: Option<Time>,
+    minutes: Option<u32>,
+}
+
+impl Session {
+    pub fn start(date: Date, start: Time) -> Session {
+        Session {
+            date,
+            start: Some(start),
+            minutes: None,
+        }
+    }
+
+    /// A manually entered session without start time.
+    pub fn manual(date: Date, minutes: u32) -> Session {
+        Session {
+            date,
+            start: None,
+            minutes: Some(minutes),
+        }
+    }
+
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    pub fn start_time(&self) -> Option<Time> {
+        self.start
+    }
+
+    pub fn minutes(&self) -> Option<u32> {
+        self.minutes
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.minutes.is_none()
+    }
+
+    /// Minutes elapsed between the start of the session and the given point in time.
+    pub fn elapsed(&self, date: &Date, time: &Time) -> u32 {
+        let start = self.start.map(minute_of_day).unwrap_or(0);
+        let minutes = self.date.days_until(date) * 24 * 60 + minute_of_day(*time) - start;
+        minutes.max(0) as u32
+    }
+
+    pub(super) fn stop(&mut self, minutes: u32) {
+        self.minutes = Some(minutes);
+    }
+}
+
+fn minute_of_day(time: Time) -> i64 {
+    time.hour() as i64 * 60 + time.minute() as i64
+}
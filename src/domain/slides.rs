@@ -0,0 +1,143 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use super::{effects, inbox::normalize_file_name, paths::fill_number};
+
+/// Words of slide names which describe the kind of file rather than its topic, e.g. `VL03`.
+const KIND_WORDS: [&str; 10] = [
+    "vl",
+    "lecture",
+    "lec",
+    "vorlesung",
+    "slides",
+    "folien",
+    "week",
+    "woche",
+    "kapitel",
+    "chapter",
+];
+
+/// A planned rename of a slide PDF.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlideRename {
+    pub from: PathBuf,
+    pub to: PathBuf,
+}
+
+/// Plans renaming the PDFs in `dir` after `pattern` with the placeholders `{course}`, `{title}`
+/// and `{week}` (or padded like `{week:02}`). The week is the first standalone number of a name,
+/// e.g. 3 for `VL 03 Intro.pdf` or `lecture3.pdf`. Files without a number get the weeks after the
+/// highest detected one in the order they were modified. Already normalized files are skipped.
+pub fn plan_normalize(dir: &Path, course: &str, pattern: &str) -> Result<Vec<SlideRename>> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| anyhow!("Failed to read slides at: {}", dir.display()))?;
+    let mut slides = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|it| it.eq_ignore_ascii_case("pdf"))
+        })
+        .map(|path| {
+            let modified = path
+                .metadata()
+                .and_then(|it| it.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            (modified, path)
+        })
+        .collect::<Vec<_>>();
+    slides.sort();
+
+    let parsed = slides
+        .into_iter()
+        .map(|(_, path)| {
+            let stem = path
+                .file_stem()
+                .map(|it| it.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let (week, title) = parse_name(&stem, course);
+            (path, week, title)
+        })
+        .collect::<Vec<_>>();
+    let mut next = parsed.iter().filter_map(|it| it.1).max().unwrap_or(0);
+
+    let mut renames = Vec::new();
+    let mut targets = BTreeSet::new();
+    for (path, week, title) in parsed {
+        let week = week.unwrap_or_else(|| {
+            next += 1;
+            next
+        });
+        let name = fill_number(pattern, "week", week)
+            .replace("{course}", course)
+            .replace("{title}", &title);
+        let to = dir.join(name);
+        if !targets.insert(to.clone()) {
+            bail!(
+                "Several slides would be renamed to '{}', rename them manually first",
+                to.display()
+            );
+        }
+        if to != path {
+            renames.push(SlideRename { from: path, to });
+        }
+    }
+
+    let sources = renames.iter().map(|it| &it.from).collect::<BTreeSet<_>>();
+    if let Some(taken) = renames
+        .iter()
+        .find(|it| it.to.exists() && !sources.contains(&it.to))
+    {
+        bail!("File already exists: {}", taken.to.display());
+    }
+    Ok(renames)
+}
+
+/// Renames in two steps so slides can swap their names.
+pub fn apply_renames(renames: &[SlideRename]) -> Result<()> {
+    let mut staged = Vec::new();
+    for (i, rename) in renames.iter().enumerate() {
+        let tmp = rename.from.with_file_name(format!(".mm-rename-{}", i));
+        effects::rename(&rename.from, &tmp)
+            .with_context(|| anyhow!("Failed to rename: {}", rename.from.display()))?;
+        staged.push((tmp, &rename.to));
+    }
+    for (tmp, to) in staged {
+        effects::rename(&tmp, to)
+            .with_context(|| anyhow!("Failed to rename to: {}", to.display()))?;
+    }
+    Ok(())
+}
+
+/// The week number and the normalized title of a slide name.
+fn parse_name(stem: &str, course: &str) -> (Option<u32>, String) {
+    let mut week = None;
+    let mut title = Vec::new();
+    for word in stem.split(|c: char| !c.is_alphanumeric()) {
+        if word.is_empty() || word.eq_ignore_ascii_case(course) {
+            continue;
+        }
+        let lower = word.to_lowercase();
+        let digits = lower.trim_start_matches(|c: char| c.is_alphabetic());
+        let prefix = &lower[..lower.len() - digits.len()];
+        let number = (!digits.is_empty() && (prefix.is_empty() || KIND_WORDS.contains(&prefix)))
+            .then(|| digits.parse::<u32>().ok())
+            .flatten();
+        match number {
+            Some(number) if week.is_none() => week = Some(number),
+            _ if KIND_WORDS.contains(&lower.as_str()) => {}
+            _ => title.push(word),
+        }
+    }
+    let title = normalize_file_name(&title.join(" "));
+    match title.is_empty() {
+        true => (week, "slides".into()),
+        false => (week, title),
+    }
+}
@@ -0,0 +1,85 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli::SlotKindDO;
+
+use super::date::{Time, Weekday};
+
+/// A weekly recurring lecture or tutorial slot stored in the `course.toml` of a course.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Serialize, Deserialize)]
+pub struct Slot {
+    weekday: Weekday,
+    start: Time,
+    end: Time,
+    room: Option<String>,
+    #[serde(default)]
+    kind: SlotKind,
+}
+
+#[derive(
+    Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum SlotKind {
+    #[default]
+    Lecture,
+    Tutorial,
+}
+
+impl Slot {
+    pub fn new(
+        weekday: Weekday,
+        start: Time,
+        end: Time,
+        room: Option<String>,
+        kind: SlotKind,
+    ) -> Slot {
+        Slot {
+            weekday,
+            start,
+            end,
+            room,
+            kind,
+        }
+    }
+
+    pub fn weekday(&self) -> Weekday {
+        self.weekday
+    }
+
+    pub fn start(&self) -> Time {
+        self.start
+    }
+
+    pub fn end(&self) -> Time {
+        self.end
+    }
+
+    pub fn room(&self) -> Option<&str> {
+        self.room.as_deref()
+    }
+
+    pub fn kind(&self) -> SlotKind {
+        self.kind
+    }
+}
+
+impl SlotKind {
+    pub fn from_do(kind: SlotKindDO) -> SlotKind {
+        match kind {
+            SlotKindDO::Lecture => SlotKind::Lecture,
+            SlotKindDO::Tutorial => SlotKind::Tutorial,
+        }
+    }
+}
+
+impl fmt::Display for SlotKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind_str = match self {
+            SlotKind::Lecture => "Lecture",
+            SlotKind::Tutorial => "Tutorial",
+        };
+        write!(f, "{}", kind_str)
+    }
+}
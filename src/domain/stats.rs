@@ -0,0 +1,170 @@
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Store operations whose duration and number of calls are recorded for the running command.
+#[derive(Debug, Clone, Copy)]
+pub enum Operation {
+    /// Listing semester and course folders.
+    Scan,
+    /// Reading and parsing data files.
+    Parse,
+    /// Serializing and writing data files.
+    Write,
+    /// Creating and removing the current semester and course links.
+    Symlink,
+}
+
+impl Operation {
+    const ALL: [Operation; 4] = [
+        Operation::Scan,
+        Operation::Parse,
+        Operation::Write,
+        Operation::Symlink,
+    ];
+
+    fn label(&self) -> &'static str {
+        match self {
+            Operation::Scan => "store scan",
+            Operation::Parse => "toml parse",
+            Operation::Write => "toml write",
+            Operation::Symlink => "symlink",
+        }
+    }
+}
+
+static NANOS: [AtomicU64; 4] = [const { AtomicU64::new(0) }; 4];
+static CALLS: [AtomicU64; 4] = [const { AtomicU64::new(0) }; 4];
+static FILES: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+static VERBOSE: AtomicBool = AtomicBool::new(false);
+
+/// Prints what mm does to stderr while running with `-v`, see [init_tracing](super::init_tracing).
+pub fn set_verbose(verbose: bool) {
+    VERBOSE.store(verbose, Ordering::Relaxed);
+}
+
+pub fn is_verbose() -> bool {
+    VERBOSE.load(Ordering::Relaxed)
+}
+
+/// Runs `f` and adds its duration to the counters of `operation`.
+pub fn measure<T>(operation: Operation, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let out = f();
+    let idx = operation as usize;
+    NANOS[idx].fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    CALLS[idx].fetch_add(1, Ordering::Relaxed);
+    out
+}
+
+/// Records that a data file has been read or written.
+pub fn touch(path: &Path) {
+    if let Ok(mut files) = FILES.lock() {
+        files.insert(path.to_path_buf());
+    }
+}
+
+/// Timings of a single command, stored in the cache directory when running with `-v`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Timings {
+    command: String,
+    total_micros: u64,
+    files_touched: u64,
+    #[serde(default)]
+    operations: Vec<OperationTiming>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OperationTiming {
+    name: String,
+    calls: u64,
+    micros: u64,
+}
+
+impl Timings {
+    /// Collects the counters recorded so far.
+    pub fn collect(command: String, total: Duration) -> Timings {
+        let operations = Operation::ALL
+            .iter()
+            .map(|operation| OperationTiming {
+                name: operation.label().to_string(),
+                calls: CALLS[*operation as usize].load(Ordering::Relaxed),
+                micros: NANOS[*operation as usize].load(Ordering::Relaxed) / 1000,
+            })
+            .collect::<Vec<_>>();
+        let files_touched = FILES.lock().map(|it| it.len() as u64).unwrap_or(0);
+        Timings {
+            command,
+            total_micros: total.as_micros() as u64,
+            files_touched,
+            operations,
+        }
+    }
+
+    fn path() -> Result<PathBuf> {
+        let dir = dirs::cache_dir().context("Failed to find cache directory on your system")?;
+        Ok(dir.join("mm").join("timings.toml"))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let data = toml_edit::ser::to_string_pretty(self)?;
+        std::fs::write(&path, data)
+            .with_context(|| anyhow!("Failed to write timings to: {}", path.display()))
+    }
+
+    /// The timings of the last command run with `-v`, if any.
+    pub fn last() -> Result<Option<Timings>> {
+        let path = Self::path()?;
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| anyhow!("Failed to read timings from: {}", path.display()))?;
+        let timings = toml_edit::de::from_str(&content)
+            .with_context(|| anyhow!("Failed to parse timings from: {}", path.display()))?;
+        Ok(Some(timings))
+    }
+
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    pub fn total(&self) -> Duration {
+        Duration::from_micros(self.total_micros)
+    }
+
+    pub fn files_touched(&self) -> u64 {
+        self.files_touched
+    }
+
+    pub fn operations(&self) -> &Vec<OperationTiming> {
+        &self.operations
+    }
+}
+
+impl OperationTiming {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn calls(&self) -> u64 {
+        self.calls
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::from_micros(self.micros)
+    }
+}
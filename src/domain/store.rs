@@ -1,26 +1,58 @@
-use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Result};
 use serde::{Deserialize, Serialize};
 
 use crate::{ConfigProvider, StoreProvider};
 
 use super::{
+    backup::BackupSettings,
     config::SemesterNames,
     course::Course,
-    paths::{EntryPoint, MaybeSymLinkable, ReadWriteDO, SemesterPath, StoreDataFile},
+    degree::Degree,
+    exam::AttemptPolicy,
+    history::GitMode,
+    inbox::Inbox,
+    language::Language,
+    moodle::MoodleSettings,
+    notify::NotificationSettings,
+    parallel::parallel_map,
+    paths::{EntryPoint, Link, LinkTarget, ReadWriteDO, SemesterPath, StoreDataFile, DATA_VERSION},
+    scale::GradeScale,
     semester::Semester,
+    sync::SyncSettings,
+    theme::Theme,
 };
 
+/// Access to the semesters and courses below the entry point of a [Config](super::Config).
 #[derive(Debug)]
-pub(crate) struct Store {
+pub struct Store {
     active_semester: Option<SemesterPath>,
     entry_point: EntryPoint,
     semester_names: SemesterNames,
-    current_semester_link: MaybeSymLinkable,
-    current_course_link: MaybeSymLinkable,
+    links: Vec<Link>,
+    template_dir: PathBuf,
+    backup: BackupSettings,
+    sync: Option<SyncSettings>,
+    git: Option<GitMode>,
+    moodle: Option<MoodleSettings>,
+    degrees: Vec<Degree>,
+    grade_scale: GradeScale,
+    theme: Theme,
+    language: Option<Language>,
+    attempt_policy: AttemptPolicy,
+    inbox: Inbox,
+    slide_pattern: String,
+    notifications: Option<NotificationSettings>,
+    auto_switch: bool,
+    log: bool,
+    ects_budget: Option<u32>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
-pub(crate) struct StoreDO {
+pub struct StoreDO {
+    #[serde(default, skip_serializing)]
+    version: u32,
     active_semester: Option<String>,
 }
 
@@ -31,40 +63,94 @@ impl Store {
     {
         let entry_point = config.entry_point();
         let semester_names = config.semester_names();
-        let current_semester_link = config.current_semester_link();
-        let current_course_link = config.current_course_link();
-
-        let file = entry_point.data_file()?;
-        let store_do = file.read()?;
+        let links = config.links();
+        let template_dir = config.template_dir();
+        let backup = config.backup_settings();
+        let sync = config.sync_settings();
+        let git = config.git_mode();
+        let moodle = config.moodle_settings();
+        let degrees = config.degrees();
+        let grade_scale = config.grade_scale();
+        let theme = config.theme();
+        let language = config.language();
+        let attempt_policy = config.attempt_policy();
+        let inbox = config.inbox();
+        let slide_pattern = config.slide_pattern();
+        let notifications = config.notification_settings();
+        let auto_switch = config.auto_switch();
+        let log = config.log();
+        let ects_budget = config.ects_budget();
 
-        let active_semester = store_do
-            .active_semester
-            .map(|name| entry_point.semester_path(&name, &semester_names))
-            .flatten();
+        let active_semester = read_active_semester(&entry_point, &semester_names)?;
 
         let store = Store {
             entry_point,
             semester_names,
-            current_course_link,
-            current_semester_link,
+            links,
             active_semester,
+            template_dir,
+            backup,
+            sync,
+            git,
+            moodle,
+            degrees,
+            grade_scale,
+            theme,
+            language,
+            attempt_policy,
+            inbox,
+            slide_pattern,
+            notifications,
+            auto_switch,
+            log,
+            ects_budget,
         };
         Ok(store)
     }
+
+    fn update_links(&self, target: LinkTarget, folder: Option<&Path>) -> Result<()> {
+        self.links
+            .iter()
+            .filter(|it| it.target == target)
+            .try_for_each(|it| it.update(folder))
+    }
+}
+
+fn read_active_semester(
+    entry_point: &EntryPoint,
+    semester_names: &SemesterNames,
+) -> Result<Option<SemesterPath>> {
+    let store_do = entry_point.data_file()?.read()?;
+    Ok(store_do
+        .active_semester
+        .and_then(|name| entry_point.semester_path(&name, semester_names)))
 }
 
 impl StoreProvider for Store {
     fn semesters(&self) -> impl Iterator<Item = Semester> {
-        self.entry_point
+        let paths = self
+            .entry_point
             .semester_paths(&self.semester_names)
-            .filter_map(|path| Semester::from_path(path, &self.semester_names).ok())
+            .collect();
+        parallel_map(paths, |path| {
+            Semester::from_path(path, &self.semester_names).ok()
+        })
+        .into_iter()
+        .flatten()
     }
 
     fn courses(&self) -> impl Iterator<Item = Course> {
-        self.entry_point
+        let semesters = self
+            .entry_point
             .semester_paths(&self.semester_names)
-            .flat_map(|path| path.course_paths())
-            .filter_map(|path| Course::from_path(path).ok())
+            .collect();
+        let paths = parallel_map(semesters, |path| path.course_paths().collect::<Vec<_>>())
+            .into_iter()
+            .flatten()
+            .collect();
+        parallel_map(paths, |path| Course::from_path(path).ok())
+            .into_iter()
+            .flatten()
     }
 
     fn semester_courses(&self, semester: Semester) -> impl Iterator<Item = Course> {
@@ -74,52 +160,158 @@ impl StoreProvider for Store {
     fn get_semester(&self, name: &str) -> Option<Semester> {
         self.entry_point
             .semester_path(name, &self.semester_names)
-            .map(|path| Semester::from_path(path, &self.semester_names).ok())
-            .flatten()
+            .and_then(|path| Semester::from_path(path, &self.semester_names).ok())
     }
 
     fn current_semester(&self) -> Option<Semester> {
         self.active_semester
             .as_ref()
-            .map(|it| Semester::from_path(it.clone(), &self.semester_names).ok())
-            .flatten()
+            .and_then(|it| Semester::from_path(it.clone(), &self.semester_names).ok())
     }
 
     fn current_course(&self) -> Option<Course> {
         self.current_semester()
-            .map(|semester| semester.active_course())
-            .flatten()
+            .and_then(|semester| semester.active_course())
     }
 
     fn set_current_semester(&mut self, semester: Option<&Semester>) -> Result<()> {
         self.active_semester = semester.as_ref().map(|it| it.path().clone());
         let store_do = StoreDO {
+            version: DATA_VERSION,
             active_semester: semester.map(|it| it.path().name().to_string()),
         };
         self.entry_point.data_file()?.write(&store_do)?;
         if let Some(semester) = self.active_semester.as_ref() {
-            self.current_semester_link.link_from(semester.path())?;
+            self.update_links(LinkTarget::Semester, Some(semester.path()))?;
         } else {
-            self.current_semester_link.remove_link()?;
-            self.current_course_link.remove_link()?;
+            self.update_links(LinkTarget::Semester, None)?;
+            self.update_links(LinkTarget::Course, None)?;
         }
         Ok(())
     }
 
     fn set_current_course(&self, semester: &mut Semester, course: Option<&Course>) -> Result<()> {
         semester.set_active(course)?;
-        if let Some(course) = course.as_ref() {
-            self.current_course_link.link_from(course.path().as_path())
-        } else {
-            self.current_course_link.remove_link()
+        self.update_links(LinkTarget::Course, course.map(|it| it.path().as_path()))
+    }
+
+    /// Moves the course folder into the `target` semester. An active course stops being active in
+    /// its old semester, the current course stays current and switches to the target semester.
+    fn move_course(&mut self, course: &Course, target: &mut Semester) -> Result<Course> {
+        let parent = course.path().as_path().parent();
+        let mut source = self
+            .semesters()
+            .find(|it| Some(it.path().path().as_path()) == parent)
+            .ok_or_else(|| anyhow!("No semester found for course: {}", course.name()))?;
+        if source.path() == target.path() {
+            bail!(
+                "Course '{}' already belongs to semester {}",
+                course.name(),
+                target.name()
+            );
+        }
+        let is_active = source
+            .active_course()
+            .is_some_and(|it| it.path() == course.path());
+        let is_current = is_active
+            && self
+                .current_semester()
+                .is_some_and(|it| it.path() == source.path());
+
+        let path = target.path().move_course_path(course.path())?;
+        if is_active {
+            source.set_active(None)?;
+        }
+        let moved = Course::from_path(path)?;
+        if is_current {
+            self.set_current_semester(Some(target))?;
+            self.set_current_course(target, Some(&moved))?;
         }
+        Ok(moved)
+    }
+
+    fn reload(&mut self) -> Result<()> {
+        self.active_semester = read_active_semester(&self.entry_point, &self.semester_names)?;
+        Ok(())
     }
 
     fn entry_point(&self) -> EntryPoint {
         self.entry_point.clone()
     }
+
+    fn semester_names(&self) -> SemesterNames {
+        self.semester_names.clone()
+    }
+
+    fn template_dir(&self) -> PathBuf {
+        self.template_dir.clone()
+    }
+
+    fn backup_settings(&self) -> BackupSettings {
+        self.backup.clone()
+    }
+
+    fn sync_settings(&self) -> Option<SyncSettings> {
+        self.sync.clone()
+    }
+
+    fn git_mode(&self) -> Option<GitMode> {
+        self.git
+    }
+
+    fn moodle_settings(&self) -> Option<MoodleSettings> {
+        self.moodle.clone()
+    }
+
+    fn degrees(&self) -> Vec<Degree> {
+        self.degrees.clone()
+    }
+
+    fn grade_scale(&self) -> GradeScale {
+        self.grade_scale.clone()
+    }
+
+    fn theme(&self) -> Theme {
+        self.theme.clone()
+    }
+
+    fn language(&self) -> Option<Language> {
+        self.language
+    }
+
+    fn attempt_policy(&self) -> AttemptPolicy {
+        self.attempt_policy
+    }
+
+    fn inbox(&self) -> Inbox {
+        self.inbox.clone()
+    }
+
+    fn slide_pattern(&self) -> String {
+        self.slide_pattern.clone()
+    }
+
+    fn notification_settings(&self) -> Option<NotificationSettings> {
+        self.notifications.clone()
+    }
+
+    fn auto_switch(&self) -> bool {
+        self.auto_switch
+    }
+
+    fn log(&self) -> bool {
+        self.log
+    }
+
+    fn ects_budget(&self) -> Option<u32> {
+        self.ects_budget
+    }
 }
 
 impl ReadWriteDO for StoreDataFile {
     type Object = StoreDO;
+
+    fn version(object: &Self::Object) -> Option<u32> {
+        Some(object.version)
+    }
 }
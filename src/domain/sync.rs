@@ -0,0 +1,238 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{effects, paths::EntryPoint, progress::Progress};
+
+/// Program used to transfer the store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncTool {
+    /// A local path or `host:path` reachable by rsync.
+    #[default]
+    Rsync,
+    /// A configured rclone remote like `drive:uni`.
+    Rclone,
+    /// A WebDAV URL, transferred with rclone. Credentials are read by rclone from
+    /// `RCLONE_WEBDAV_USER` and `RCLONE_WEBDAV_PASS`.
+    Webdav,
+}
+
+/// The remote the whole entry point is synchronized with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SyncSettings {
+    tool: SyncTool,
+    remote: String,
+}
+
+/// Direction of a transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Push,
+    Pull,
+}
+
+/// Result of a push or pull. Nothing has been transferred if `conflicts` is not empty.
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    /// Files copied to the other side.
+    pub transferred: Vec<String>,
+    /// Files changed on both sides since the last sync.
+    pub conflicts: Vec<String>,
+    /// Files newer on the other side which have been left untouched by a push.
+    pub skipped: Vec<String>,
+}
+
+/// Time of the last successful sync per remote, stored in the local data directory.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SyncState {
+    #[serde(default)]
+    last_sync: BTreeMap<String, u64>,
+}
+
+impl SyncSettings {
+    pub fn new(tool: SyncTool, remote: String) -> SyncSettings {
+        SyncSettings { tool, remote }
+    }
+
+    pub fn remote(&self) -> &str {
+        &self.remote
+    }
+
+    /// Files which differ between both sides without transferring anything. Returns the files
+    /// newer on the remote and the files newer locally.
+    pub fn status(&self, entry_point: &EntryPoint) -> Result<(Vec<String>, Vec<String>)> {
+        let local = local_target(entry_point);
+        let incoming = self.copy(&self.target(), &local, true, true)?;
+        let outgoing = self.copy(&local, &self.target(), true, true)?;
+        Ok((incoming, outgoing))
+    }
+
+    /// Copies the entry point to the remote or back. Files are never deleted and files newer on
+    /// the receiving side are kept. A file changed on both sides since the last sync is a
+    /// conflict and aborts the transfer unless `force` is set, in which case the sending side
+    /// wins.
+    pub fn sync(
+        &self,
+        entry_point: &EntryPoint,
+        direction: Direction,
+        force: bool,
+    ) -> Result<SyncReport> {
+        let local = local_target(entry_point);
+        let remote = self.target();
+        let mut state = SyncState::load()?;
+        let last_sync = state.last_sync.get(&self.remote).copied().unwrap_or(0);
+
+        // rsync and rclone compare the modification times of both sides, so every file listed
+        // here is newer on the remote or missing locally
+        let incoming = self.copy(&remote, &local, true, true)?;
+        let (conflicts, remote_changes): (Vec<_>, Vec<_>) = incoming
+            .into_iter()
+            .partition(|file| modified_since(&entry_point.join(file), last_sync));
+
+        let mut report = SyncReport::default();
+        if !conflicts.is_empty() && !force {
+            report.conflicts = conflicts;
+            return Ok(report);
+        }
+
+        report.transferred = match direction {
+            Direction::Push => {
+                report.skipped = remote_changes;
+                self.copy(&local, &remote, !force, effects::is_dry_run())?
+            }
+            Direction::Pull => self.copy(&remote, &local, !force, effects::is_dry_run())?,
+        };
+
+        state.last_sync.insert(self.remote.clone(), unix_now());
+        state.save()?;
+        Ok(report)
+    }
+
+    /// Source or destination argument for the remote.
+    fn target(&self) -> String {
+        match self.tool {
+            SyncTool::Rsync => format!("{}/", self.remote.trim_end_matches('/')),
+            SyncTool::Rclone => self.remote.clone(),
+            SyncTool::Webdav => format!(":webdav,url='{}':", self.remote),
+        }
+    }
+
+    /// Copies `from` to `to` and returns the copied files relative to the entry point. With
+    /// `update` files newer at `to` are skipped.
+    fn copy(&self, from: &str, to: &str, update: bool, dry_run: bool) -> Result<Vec<String>> {
+        let (program, mut command) = match self.tool {
+            SyncTool::Rsync => {
+                let mut command = Command::new("rsync");
+                command.args(["-a", "--out-format=%n"]);
+                ("rsync", command)
+            }
+            SyncTool::Rclone | SyncTool::Webdav => {
+                let mut command = Command::new("rclone");
+                command.args(["copy", "-v"]);
+                ("rclone", command)
+            }
+        };
+        if update {
+            command.arg("--update");
+        }
+        if dry_run {
+            command.arg("--dry-run");
+        }
+        command.arg(from).arg(to);
+
+        let progress = Progress::spinner(format!("Copying {} to {}", from, to));
+        let output = command
+            .output()
+            .with_context(|| anyhow!("Failed to run '{}'. Is it installed?", program))?;
+        drop(progress);
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!(
+                "'{}' failed to copy '{}' to '{}':\n  {}",
+                program,
+                from,
+                to,
+                stderr.trim()
+            );
+        }
+
+        let mut files = match self.tool {
+            // directories are listed with a trailing slash
+            SyncTool::Rsync => String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter(|line| !line.is_empty() && !line.ends_with('/'))
+                .map(str::to_string)
+                .collect::<Vec<_>>(),
+            // rclone logs `<date> <time> LEVEL : <file>: <message>` to stderr
+            SyncTool::Rclone | SyncTool::Webdav => String::from_utf8_lossy(&output.stderr)
+                .lines()
+                .filter_map(|line| line.split_once(": ")?.1.rsplit_once(": "))
+                .filter(|(_, message)| {
+                    message.starts_with("Copied") || message.starts_with("Skipped copy")
+                })
+                .map(|(file, _)| file.to_string())
+                .collect::<Vec<_>>(),
+        };
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+}
+
+impl SyncState {
+    fn path() -> Result<PathBuf> {
+        let dir = dirs::data_local_dir().context("Failed to find data directory on your system")?;
+        Ok(dir.join("mm").join("sync.toml"))
+    }
+
+    fn load() -> Result<SyncState> {
+        let path = Self::path()?;
+        if !path.is_file() {
+            return Ok(SyncState::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| anyhow!("Failed to read sync state from: {}", path.display()))?;
+        toml_edit::de::from_str(&content)
+            .with_context(|| anyhow!("Failed to parse sync state from: {}", path.display()))
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            effects::create_dir_all(parent)?;
+        }
+        let data = toml_edit::ser::to_string_pretty(self)?;
+        effects::write(&path, data)
+            .with_context(|| anyhow!("Failed to write sync state to: {}", path.display()))
+    }
+}
+
+fn local_target(entry_point: &EntryPoint) -> String {
+    format!(
+        "{}/",
+        entry_point.display().to_string().trim_end_matches('/')
+    )
+}
+
+/// Whether the local file exists and has been modified after `since` (seconds since epoch).
+fn modified_since(path: &Path, since: u64) -> bool {
+    path.metadata()
+        .and_then(|it| it.modified())
+        .ok()
+        .and_then(|it| it.duration_since(UNIX_EPOCH).ok())
+        .is_some_and(|it| it.as_secs() > since)
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|it| it.as_secs())
+        .unwrap_or(0)
+}
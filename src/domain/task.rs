@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use super::date::Date;
+
+/// A homework or reminder entry stored in the `course.toml` of a course.
+#[derive(Debug, PartialEq, PartialOrd, Clone, Serialize, Deserialize)]
+pub struct Task {
+    title: String,
+    due: Option<Date>,
+    #[serde(default)]
+    done: bool,
+}
+
+impl Task {
+    pub fn new(title: String, due: Option<Date>) -> Task {
+        Task {
+            title,
+            due,
+            done: false,
+        }
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    pub fn due(&self) -> Option<Date> {
+        self.due
+    }
+
+    pub fn done(&self) -> bool {
+        self.done
+    }
+
+    pub fn is_overdue(&self, today: &Date) -> bool {
+        !self.done && self.due.is_some_and(|due| due < *today)
+    }
+
+    pub(super) fn set_done(&mut self, done: bool) {
+        self.done = done;
+    }
+}
@@ -0,0 +1,89 @@
+use colored::Color;
+use serde::Deserialize;
+
+/// Colors of the output, configured with `[theme]` in the config file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    success: ThemeColor,
+    error: ThemeColor,
+    info: ThemeColor,
+    /// Colors of grades, the first rule whose `max` the grade reaches wins.
+    grades: Vec<GradeColor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GradeColor {
+    /// Worst grade with this color, any grade without.
+    max: Option<f32>,
+    color: ThemeColor,
+}
+
+/// A color name like "green" or "bright blue", or a hex code like "#ff8800".
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct ThemeColor(Color);
+
+impl TryFrom<String> for ThemeColor {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("Invalid color '{}', expected a name or '#rrggbb'", value);
+        let Some(hex) = value.strip_prefix('#') else {
+            return value.parse().map(ThemeColor).map_err(|_| invalid());
+        };
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(invalid());
+        }
+        let channel =
+            |idx: usize| u8::from_str_radix(&hex[idx..idx + 2], 16).map_err(|_| invalid());
+        Ok(ThemeColor(Color::TrueColor {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        }))
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        let grade = |max, color| GradeColor {
+            max,
+            color: ThemeColor(color),
+        };
+        Theme {
+            success: ThemeColor(Color::Green),
+            error: ThemeColor(Color::Red),
+            info: ThemeColor(Color::Yellow),
+            grades: vec![
+                grade(Some(1.5), Color::Green),
+                grade(Some(2.5), Color::Yellow),
+                grade(None, Color::Red),
+            ],
+        }
+    }
+}
+
+impl Theme {
+    pub fn success(&self) -> Color {
+        self.success.0
+    }
+
+    pub fn error(&self) -> Color {
+        self.error.0
+    }
+
+    pub fn info(&self) -> Color {
+        self.info.0
+    }
+
+    /// The color of the first rule the grade reaches, `None` if it reaches none.
+    pub fn grade(&self, grade: f32) -> Option<Color> {
+        // Averages are rounded to two decimals before comparing to avoid float noise.
+        let grade = (grade * 100.0).round() / 100.0;
+        self.grades
+            .iter()
+            .find(|it| it.max.is_none_or(|max| grade <= max))
+            .map(|it| it.color.0)
+    }
+}
@@ -0,0 +1,66 @@
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+/// Failures callers may want to handle differently, e.g. by asking for another reference.
+/// Services return them inside an [anyhow::Error], either directly or as context of the
+/// underlying error, so match on them with `err.downcast_ref::<MmError>()`. All other failures
+/// only carry a message.
+#[derive(Debug, Error)]
+pub enum MmError {
+    #[error("No semester found by reference: {0}")]
+    SemesterNotFound(String),
+    #[error("No course found by reference: {0}")]
+    CourseNotFound(String),
+    #[error("No active semester found")]
+    NoActiveSemester,
+    #[error("No active course found. Switch to a course or provide one")]
+    NoActiveCourse,
+    #[error("The semester path '{}' already exists", .0.display())]
+    SemesterAlreadyExists(PathBuf),
+    #[error("The course path '{}' already exists", .0.display())]
+    CourseAlreadyExists(PathBuf),
+    #[error("Invalid reference '{0}', expected `course` or `semester/course`")]
+    InvalidReference(String),
+    #[error(
+        "The data file '{}' is corrupt. Run `mm debug repair` to regenerate it",
+        .0.display()
+    )]
+    CorruptDataFile(PathBuf),
+    #[error(
+        "The data file '{}' has been written by a newer version of mm (data version {version}). Please update mm",
+        path.display()
+    )]
+    UnsupportedDataVersion { path: PathBuf, version: u32 },
+    #[error("mm runs in read-only mode, the command would change the store")]
+    ReadOnly,
+    #[error("Failed to open config file at: {}", .0.display())]
+    ConfigNotFound(PathBuf),
+    #[error("Could not read config from toml at: {}", path.display())]
+    ConfigInvalid { path: PathBuf },
+}
+
+impl MmError {
+    /// Stable name of the error kind, e.g. for JSON responses.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            MmError::SemesterNotFound(_) => "semester_not_found",
+            MmError::CourseNotFound(_) => "course_not_found",
+            MmError::NoActiveSemester => "no_active_semester",
+            MmError::NoActiveCourse => "no_active_course",
+            MmError::SemesterAlreadyExists(_) => "semester_already_exists",
+            MmError::CourseAlreadyExists(_) => "course_already_exists",
+            MmError::InvalidReference(_) => "invalid_reference",
+            MmError::CorruptDataFile(_) => "corrupt_data_file",
+            MmError::UnsupportedDataVersion { .. } => "unsupported_data_version",
+            MmError::ReadOnly => "read_only",
+            MmError::ConfigNotFound(_) => "config_not_found",
+            MmError::ConfigInvalid { .. } => "config_invalid",
+        }
+    }
+
+    /// The error kind of an [anyhow::Error] created from an [MmError].
+    pub fn kind_of(err: &anyhow::Error) -> Option<&'static str> {
+        err.downcast_ref::<MmError>().map(MmError::kind)
+    }
+}
@@ -0,0 +1,21 @@
+//! Core of `mm`, a tool to manage the semesters and courses of university studies.
+//!
+//! [Config] reads the config file, [Store] gives access to the semester and course folders
+//! below its entry point and the types in [domain] model their content. [Service] is the entry
+//! point to run commands: it takes parsed [cli::Cli] arguments, runs the command of
+//! [cli::Commands] against a store and prints the result, so other tools can run `mm` commands
+//! without shelling out. The services behind it are internal, only [service::format] and
+//! [service::i18n] are public to format and translate output the same way.
+
+#![feature(int_roundings)]
+
+pub mod cli;
+pub mod domain;
+mod error;
+mod provider;
+pub mod service;
+
+pub use domain::{Config, Store};
+pub use error::MmError;
+pub use provider::*;
+pub use service::Service;
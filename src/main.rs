@@ -1,26 +1,21 @@
-#![feature(type_alias_impl_trait)]
-#![feature(int_roundings)]
-
 use anyhow::Result;
-
-mod cli;
-mod domain;
-mod provider;
-mod service;
-
 use clap::Parser;
-use cli::Cli;
-use domain::{Config, Store};
-pub(crate) use provider::*;
-use service::Service;
+
+use uniman::{cli::Cli, service, Config, Service, Store};
 
 fn main() -> Result<()> {
+    service::install_panic_hook();
+    let Some(args) = service::run_standalone(Cli::parse()) else {
+        return Ok(());
+    };
     let config = Config::new()?;
     let store = Store::new(config)?;
-    let args = Cli::parse();
     let mut service = Service::new(store);
 
-    service.run(args);
+    let code = service.run(args);
+    if code != 0 {
+        std::process::exit(code);
+    }
 
     Ok(())
 }
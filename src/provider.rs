@@ -1,8 +1,15 @@
+use std::path::PathBuf;
+
 use anyhow::Result;
 
-use crate::domain::{Course, EntryPoint, MaybeSymLinkable, Semester, SemesterNames};
+use crate::domain::{
+    AttemptPolicy, BackupSettings, Course, Degree, EntryPoint, GitMode, GradeScale, Inbox,
+    Language, Link, MoodleSettings, NotificationSettings, Semester, SemesterNames, SyncSettings,
+    Theme,
+};
 
-pub(crate) trait StoreProvider: Sized {
+/// Semesters, courses and settings the services work on, implemented by [Store](crate::Store).
+pub trait StoreProvider: Sized {
     fn semesters(&self) -> impl Iterator<Item = Semester>;
     fn courses(&self) -> impl Iterator<Item = Course>;
     fn semester_courses(&self, semester: Semester) -> impl Iterator<Item = Course>;
@@ -11,12 +18,50 @@ pub(crate) trait StoreProvider: Sized {
     fn current_course(&self) -> Option<Course>;
     fn set_current_semester(&mut self, semester: Option<&Semester>) -> Result<()>;
     fn set_current_course(&self, semester: &mut Semester, course: Option<&Course>) -> Result<()>;
+    fn move_course(&mut self, course: &Course, target: &mut Semester) -> Result<Course>;
+    /// Reads the active semester again, which another mm process may have changed since the
+    /// store was created.
+    fn reload(&mut self) -> Result<()>;
     fn entry_point(&self) -> EntryPoint;
+    fn semester_names(&self) -> SemesterNames;
+    fn template_dir(&self) -> PathBuf;
+    fn backup_settings(&self) -> BackupSettings;
+    fn sync_settings(&self) -> Option<SyncSettings>;
+    fn git_mode(&self) -> Option<GitMode>;
+    fn moodle_settings(&self) -> Option<MoodleSettings>;
+    fn degrees(&self) -> Vec<Degree>;
+    fn grade_scale(&self) -> GradeScale;
+    fn theme(&self) -> Theme;
+    fn language(&self) -> Option<Language>;
+    fn attempt_policy(&self) -> AttemptPolicy;
+    fn inbox(&self) -> Inbox;
+    fn slide_pattern(&self) -> String;
+    fn notification_settings(&self) -> Option<NotificationSettings>;
+    fn auto_switch(&self) -> bool;
+    fn log(&self) -> bool;
+    fn ects_budget(&self) -> Option<u32>;
 }
 
-pub(crate) trait ConfigProvider {
+/// Settings a [Store](crate::Store) is created from, implemented by [Config](crate::Config).
+pub trait ConfigProvider {
     fn entry_point(&self) -> EntryPoint;
-    fn current_course_link(&self) -> MaybeSymLinkable;
-    fn current_semester_link(&self) -> MaybeSymLinkable;
+    fn links(&self) -> Vec<Link>;
     fn semester_names(&self) -> SemesterNames;
+    fn template_dir(&self) -> PathBuf;
+    fn backup_settings(&self) -> BackupSettings;
+    fn sync_settings(&self) -> Option<SyncSettings>;
+    fn git_mode(&self) -> Option<GitMode>;
+    fn moodle_settings(&self) -> Option<MoodleSettings>;
+    fn prompt_format(&self) -> String;
+    fn degrees(&self) -> Vec<Degree>;
+    fn grade_scale(&self) -> GradeScale;
+    fn theme(&self) -> Theme;
+    fn language(&self) -> Option<Language>;
+    fn attempt_policy(&self) -> AttemptPolicy;
+    fn inbox(&self) -> Inbox;
+    fn slide_pattern(&self) -> String;
+    fn notification_settings(&self) -> Option<NotificationSettings>;
+    fn auto_switch(&self) -> bool;
+    fn log(&self) -> bool;
+    fn ects_budget(&self) -> Option<u32>;
 }
@@ -0,0 +1,60 @@
+use anyhow::bail;
+
+use crate::{
+    domain::{file_into, target_dir, LATEST_EXERCISE, LECTURES_DIR},
+    service::format::IntoFormatType,
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, ServiceResult};
+
+pub(super) struct AttachService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> AttachService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    /// Moves the `count` newest files of the inbox into the course folder, its `lectures` folder
+    /// or its latest exercise sheet.
+    pub fn run(
+        &self,
+        course: Option<String>,
+        count: usize,
+        lectures: bool,
+        exercise: bool,
+        rename: bool,
+    ) -> ServiceResult {
+        let course = resolve_course(self.store, course.as_deref())?;
+        let folder = match (lectures, exercise) {
+            (true, _) => LECTURES_DIR,
+            (_, true) => LATEST_EXERCISE,
+            _ => "",
+        };
+        let dir = target_dir(course.path(), folder)?;
+
+        let inbox = self.store.inbox();
+        let files = inbox.newest(count)?;
+        if files.is_empty() {
+            bail!("No files found in inbox: {}", inbox.path().display());
+        }
+        let mut lines = Vec::new();
+        for file in files {
+            let target = file_into(&file, &dir, rename)?;
+            lines.push(format!("Attached '{}' to: {}", file.display(), target.display()).success());
+        }
+        let msg = lines
+            .into_iter()
+            .reduce(|msg, line| msg.chain("".line()).chain(line))
+            .expect("at least one file");
+        Ok(msg)
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    cli::BackupCommands,
+    domain::{Backup, Snapshot},
+    service::format::{FormatAlignment, FormatType, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::ServiceResult;
+
+/// Creates timestamped tarballs of the store in the configured backup directory.
+pub(super) struct BackupService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> BackupService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self, full: bool, command: Option<BackupCommands>) -> ServiceResult {
+        match command {
+            Some(BackupCommands::List) => self.list(),
+            None => self.create(full),
+        }
+    }
+
+    fn create(&self, full: bool) -> ServiceResult {
+        let settings = self.store.backup_settings();
+        let (backup, removed) = settings.create(&self.store.entry_point(), full)?;
+
+        let msg = format!(
+            "Created backup: {} ({})",
+            backup.path().display(),
+            format_size(backup.size())
+        )
+        .success();
+        if removed.is_empty() {
+            return Ok(msg);
+        }
+        let names = removed
+            .iter()
+            .map(Backup::file_name)
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok(msg
+            .chain("".line())
+            .chain(format!("Removed old backups: {}", names).info()))
+    }
+
+    fn list(&self) -> ServiceResult {
+        let settings = self.store.backup_settings();
+        let backups = settings.list()?;
+        if backups.is_empty() {
+            let msg = format!("No backups found in: {}", settings.dir().display());
+            return Ok(msg.info());
+        }
+
+        let names = backups
+            .iter()
+            .map(|it| it.file_name().to_string())
+            .collect::<Vec<_>>();
+        let kinds = backups
+            .iter()
+            .map(|it| if it.is_full() { "full" } else { "metadata" }.to_string())
+            .collect::<Vec<_>>();
+        let sizes = backups
+            .iter()
+            .map(|it| format_size(it.size()))
+            .collect::<Vec<_>>();
+
+        let header = settings.dir().display().to_string().line();
+        let body = TableBuilder::new()
+            .column("Backup", names, FormatAlignment::Left)
+            .column("Kind", kinds, FormatAlignment::Left)
+            .column("Size", sizes, FormatAlignment::Right)
+            .build();
+        Ok(header.block(body))
+    }
+}
+
+/// Tells where the snapshot of a removed or moved folder is kept and how to restore it.
+pub(super) fn snapshot_info(snapshot: &Snapshot) -> FormatType {
+    format!("Saved a snapshot to: {}", snapshot.path().display())
+        .info()
+        .chain("".line())
+        .chain(format!("Restore it with: {}", snapshot.restore_command()).info())
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.1} KiB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MiB", bytes as f64 / 1_048_576.0),
+    }
+}
@@ -0,0 +1,117 @@
+use std::{io::Read, path::PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Parser;
+
+use crate::cli::{Cli, Commands};
+
+pub(super) struct BatchService;
+
+/// A single validated line of a batch file.
+pub(super) struct BatchLine {
+    pub number: usize,
+    pub source: String,
+    pub command: Commands,
+}
+
+impl BatchService {
+    /// Reads the batch file (or stdin if no file is given) and validates every line before
+    /// anything is executed. Empty lines and lines starting with `#` are skipped. A leading `mm`
+    /// on a line is optional.
+    pub fn parse(file: Option<PathBuf>) -> Result<Vec<BatchLine>> {
+        let content = match file {
+            Some(path) => std::fs::read_to_string(&path)
+                .with_context(|| anyhow!("Failed to read batch file: {}", path.display()))?,
+            None => {
+                let mut content = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut content)
+                    .context("Failed to read batch commands from stdin")?;
+                content
+            }
+        };
+
+        let mut lines = Vec::new();
+        for (idx, line) in content.lines().enumerate() {
+            let number = idx + 1;
+            let source = line.trim();
+            if source.is_empty() || source.starts_with('#') {
+                continue;
+            }
+
+            let mut args = split(source).with_context(|| anyhow!("Line {}: {}", number, source))?;
+            if args.first().is_some_and(|it| it == "mm") {
+                args.remove(0);
+            }
+            let cli = Cli::try_parse_from(std::iter::once("mm".to_string()).chain(args)).map_err(
+                |err| {
+                    anyhow!(
+                        "Line {}: {}\n{}",
+                        number,
+                        source,
+                        err.render().to_string().trim_end()
+                    )
+                },
+            )?;
+            match cli.command {
+                Commands::Batch { .. } => {
+                    bail!("Line {}: batch files can not be nested", number)
+                }
+                Commands::Prompt { .. } | Commands::Exec { .. } | Commands::Config { .. } => {
+                    bail!("Line {}: '{}' can not be run in a batch", number, source)
+                }
+                _ => {}
+            }
+
+            lines.push(BatchLine {
+                number,
+                source: source.to_string(),
+                command: cli.command,
+            });
+        }
+        Ok(lines)
+    }
+}
+
+/// Splits a line into arguments. Supports single and double quotes as well as backslash escapes.
+fn split(line: &str) -> Result<Vec<String>> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quote: Option<char> = None;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_arg = true;
+            }
+            (Some('\''), c) => current.push(c),
+            (_, '\\') => {
+                let escaped = chars.next().ok_or_else(|| anyhow!("Trailing backslash"))?;
+                current.push(escaped);
+                in_arg = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            (_, c) => {
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        bail!("Unterminated quote");
+    }
+    if in_arg {
+        args.push(current);
+    }
+    Ok(args)
+}
@@ -0,0 +1,496 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::{
+    cli::CalendarCommands,
+    domain::{
+        effects, now_utc, to_local, zone_to_local, Course, Curl, Date, Exam, Slot, SlotKind, Time,
+    },
+    service::format::IntoFormatType,
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, ServiceResult};
+
+pub(super) struct CalendarService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s mut Store,
+}
+
+impl<'s, Store> CalendarService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s mut Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&mut self, command: CalendarCommands) -> ServiceResult {
+        match command {
+            CalendarCommands::Export { out } => self.export(out),
+            CalendarCommands::Import { source, course } => self.import(source, course),
+        }
+    }
+
+    /// Exports deadlines and exams of all courses as well as the weekly slots of the active
+    /// semester as an RFC 5545 calendar. Slots repeat from the start to the end of the semester,
+    /// so a semester without dates exports none. Without an output file the calendar is printed.
+    fn export(&self, out: Option<PathBuf>) -> ServiceResult {
+        let mut ics = Ics::new();
+        let current = self.store.current_semester();
+
+        for semester in self.store.semesters() {
+            let is_current = current.as_ref().is_some_and(|it| it == &semester);
+            let lectures = semester.dates().filter(|_| is_current);
+            for course in self.store.semester_courses(semester.clone()) {
+                let uid = format!("{}-{}", semester.path().name(), course.path().name());
+                Self::course_events(&mut ics, &uid, &course, lectures);
+            }
+        }
+
+        let content = ics.finish();
+        match out {
+            Some(path) => {
+                effects::write(&path, content)
+                    .with_context(|| anyhow!("Failed to write calendar to: {}", path.display()))?;
+                Ok(format!("Calendar has been exported to: {}", path.display()).success())
+            }
+            None => Ok(content.raw()),
+        }
+    }
+
+    /// Imports lecture slots and exam dates from an .ics file or URL into a course. Weekly
+    /// recurring events and single events repeating at the same weekday and time become slots.
+    /// Events mentioning an exam become exam dates.
+    fn import(&mut self, source: String, course: Option<String>) -> ServiceResult {
+        let mut course = resolve_course(self.store, course.as_deref())?;
+        let content = read_source(&source)?;
+        let events = parse_events(&content)?;
+
+        // occurrences of each slot and whether one of them repeats weekly by itself
+        let mut slots = BTreeMap::new();
+        let mut exams = Vec::new();
+        let mut attempt = course
+            .exams()
+            .iter()
+            .map(|it| it.attempt())
+            .max()
+            .unwrap_or(0);
+        for event in events {
+            let summary = event.summary.to_lowercase();
+            if EXAM_KEYWORDS.iter().any(|it| summary.contains(it)) {
+                attempt += 1;
+                exams.push(Exam::new(event.date, event.location, attempt));
+                continue;
+            }
+            let (Some(start), Some(end)) = (event.start, event.end) else {
+                continue;
+            };
+            let kind = if TUTORIAL_KEYWORDS.iter().any(|it| summary.contains(it)) {
+                SlotKind::Tutorial
+            } else {
+                SlotKind::Lecture
+            };
+            let slot = slots
+                .entry((event.date.weekday(), start, end, event.location, kind))
+                .or_insert((0, false));
+            slot.0 += 1;
+            slot.1 |= event.weekly;
+        }
+
+        // one-off events like a single guest lecture are no slots
+        let slots = slots
+            .into_iter()
+            .filter(|(_, (count, weekly))| *weekly || *count >= 2)
+            .map(|(slot, _)| slot)
+            .map(|(weekday, start, end, room, kind)| Slot::new(weekday, start, end, room, kind))
+            .collect();
+        let (slots, exams) = course.import_calendar(slots, exams)?;
+        let msg = format!(
+            "Imported {} slots and {} exams into '{}'",
+            slots,
+            exams,
+            course.name()
+        );
+        Ok(msg.success())
+    }
+
+    /// Adds the deadlines and exams of a course and its slots repeating over `lectures`.
+    fn course_events(ics: &mut Ics, uid: &str, course: &Course, lectures: Option<(Date, Date)>) {
+        let name = course.name();
+        for (idx, deadline) in course.deadlines().iter().enumerate() {
+            ics.all_day_event(
+                &format!("{}-deadline-{}", uid, idx),
+                &format!("{}: {} ({})", name, deadline.title(), deadline.kind()),
+                deadline.date(),
+                None,
+            );
+        }
+        for exam in course.exams() {
+            ics.all_day_event(
+                &format!("{}-exam-{}", uid, exam.attempt()),
+                &format!("Exam: {} (attempt {})", name, exam.attempt()),
+                exam.date(),
+                exam.location(),
+            );
+        }
+        let Some((start, end)) = lectures else {
+            return;
+        };
+        for (idx, slot) in course.slots().iter().enumerate() {
+            // first day of the semester on the weekday of the slot
+            let offset = (slot.weekday() as i64 - start.weekday() as i64).rem_euclid(7);
+            let date = start.add_days(offset);
+            if date > end {
+                continue;
+            }
+            ics.weekly_event(
+                &format!("{}-slot-{}", uid, idx),
+                &format!("{} ({})", name, slot.kind()),
+                slot,
+                date,
+                end,
+            );
+        }
+    }
+}
+
+const EXAM_KEYWORDS: [&str; 4] = ["exam", "klausur", "prüfung", "pruefung"];
+const TUTORIAL_KEYWORDS: [&str; 4] = ["übung", "uebung", "tutori", "exercise"];
+
+/// A single VEVENT reduced to the fields relevant for slots and exams.
+struct Event {
+    summary: String,
+    location: Option<String>,
+    date: Date,
+    start: Option<Time>,
+    end: Option<Time>,
+    /// Whether the event repeats weekly by an `RRULE`.
+    weekly: bool,
+}
+
+/// Unparsed properties of a VEVENT while it is being read.
+#[derive(Default)]
+struct RawEvent {
+    summary: Option<String>,
+    location: Option<String>,
+    /// Value and time zone of DTSTART.
+    start: Option<(String, Option<String>)>,
+    end: Option<(String, Option<String>)>,
+    weekly: bool,
+}
+
+fn read_source(source: &str) -> Result<String> {
+    let url = source.replacen("webcal://", "https://", 1);
+    if url.starts_with("http://") || url.starts_with("https://") {
+        let output = Curl::new(&url)
+            .follow_redirects()
+            .send(format!("Failed to download calendar from '{}'", url))?;
+        return Ok(String::from_utf8_lossy(&output).to_string());
+    }
+    std::fs::read_to_string(source).with_context(|| anyhow!("Failed to read calendar: {}", source))
+}
+
+/// Parses the VEVENTs of a calendar. Times in UTC or with a `TZID` are converted to local time,
+/// floating times and times in an unknown zone are taken as written.
+fn parse_events(content: &str) -> Result<Vec<Event>> {
+    // unfold continuation lines
+    let mut lines: Vec<String> = Vec::new();
+    for line in content.lines() {
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+
+    let mut events = Vec::new();
+    let mut current: Option<RawEvent> = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut parameters = name.split(';');
+        let name = parameters.next().unwrap_or_default().to_uppercase();
+        let zone = parameters
+            .find_map(|it| it.strip_prefix("TZID="))
+            .map(|it| it.trim_matches('"').to_string());
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                current = Some(RawEvent::default())
+            }
+            ("SUMMARY", Some(event)) => event.summary = Some(unescape(value)),
+            ("LOCATION", Some(event)) => {
+                event.location = Some(unescape(value)).filter(|it| !it.is_empty())
+            }
+            ("DTSTART", Some(event)) => event.start = Some((value.to_string(), zone)),
+            ("DTEND", Some(event)) => event.end = Some((value.to_string(), zone)),
+            ("RRULE", Some(event)) => {
+                event.weekly = value
+                    .split(';')
+                    .any(|it| it.eq_ignore_ascii_case("FREQ=WEEKLY"))
+            }
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                let Some(RawEvent {
+                    summary,
+                    location,
+                    start: Some(start),
+                    end,
+                    weekly,
+                }) = current.take()
+                else {
+                    continue;
+                };
+                let (date, start) = parse_datetime(&start.0, start.1.as_deref())?;
+                let end = end
+                    .map(|(value, zone)| parse_datetime(&value, zone.as_deref()))
+                    .transpose()?
+                    .and_then(|(_, time)| time);
+                events.push(Event {
+                    summary: summary.unwrap_or_default(),
+                    location,
+                    date,
+                    start,
+                    end,
+                    weekly,
+                });
+            }
+            _ => {}
+        }
+    }
+    Ok(events)
+}
+
+/// Parses `YYYYMMDD` and `YYYYMMDDTHHMMSS[Z]` values. Times in UTC (`Z`) or in the time zone
+/// `zone` are converted to local time.
+fn parse_datetime(value: &str, zone: Option<&str>) -> Result<(Date, Option<Time>)> {
+    let value = value.trim();
+    let invalid = || anyhow!("Invalid calendar date: {}", value);
+    // only digits may be sliced, other characters could be cut in the middle
+    let digits = |it: &&str| it.bytes().all(|byte| byte.is_ascii_digit());
+    let date = value.get(..8).filter(digits).ok_or_else(invalid)?;
+    let date = format!("{}-{}-{}", &date[..4], &date[4..6], &date[6..]).parse::<Date>()?;
+    if value.len() <= 8 {
+        return Ok((date, None));
+    }
+    let time = value.get(9..13).filter(digits).ok_or_else(invalid)?;
+    let time = format!("{}:{}", &time[..2], &time[2..]).parse::<Time>()?;
+    let (date, time) = match zone {
+        _ if value.ends_with(['Z', 'z']) => to_local(date, time),
+        Some(zone) => zone_to_local(date, time, zone).unwrap_or((date, time)),
+        None => (date, time),
+    };
+    Ok((date, Some(time)))
+}
+
+/// Decodes the escapes of a TEXT value in a single pass, so `\\n` is a backslash followed by `n`.
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            unescaped.push(char);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => unescaped.push('\n'),
+            Some(other) => unescaped.push(other),
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+/// Minimal writer for RFC 5545 calendars.
+struct Ics {
+    lines: Vec<String>,
+    stamp: String,
+}
+
+impl Ics {
+    fn new() -> Ics {
+        let (date, time) = now_utc();
+        let lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//mm//Manage my studies//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+        ];
+        Ics {
+            lines,
+            stamp: format!("{}T{}00Z", ics_date(date), ics_time(time)),
+        }
+    }
+
+    fn all_day_event(&mut self, uid: &str, summary: &str, date: Date, location: Option<&str>) {
+        self.begin(uid, summary, location);
+        self.lines
+            .push(format!("DTSTART;VALUE=DATE:{}", ics_date(date)));
+        self.lines
+            .push(format!("DTEND;VALUE=DATE:{}", ics_date(date.add_days(1))));
+        self.lines.push("END:VEVENT".to_string());
+    }
+
+    /// The slot every week from `date` until `until`, inclusive.
+    fn weekly_event(&mut self, uid: &str, summary: &str, slot: &Slot, date: Date, until: Date) {
+        self.begin(uid, summary, slot.room());
+        // floating times are interpreted in the time zone of the calendar client
+        self.lines.push(format!(
+            "DTSTART:{}T{}00",
+            ics_date(date),
+            ics_time(slot.start())
+        ));
+        self.lines.push(format!(
+            "DTEND:{}T{}00",
+            ics_date(date),
+            ics_time(slot.end())
+        ));
+        // UNTIL is floating like DTSTART
+        self.lines.push(format!(
+            "RRULE:FREQ=WEEKLY;UNTIL={}T235959",
+            ics_date(until)
+        ));
+        self.lines.push("END:VEVENT".to_string());
+    }
+
+    fn begin(&mut self, uid: &str, summary: &str, location: Option<&str>) {
+        self.lines.push("BEGIN:VEVENT".to_string());
+        self.lines.push(format!("UID:{}@mm", escape(uid)));
+        self.lines.push(format!("DTSTAMP:{}", self.stamp));
+        self.lines.push(format!("SUMMARY:{}", escape(summary)));
+        if let Some(location) = location {
+            self.lines.push(format!("LOCATION:{}", escape(location)));
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.lines.push("END:VCALENDAR".to_string());
+        self.lines
+            .iter()
+            .map(|line| fold(line))
+            .collect::<Vec<_>>()
+            .join("\r\n")
+            + "\r\n"
+    }
+}
+
+fn ics_date(date: Date) -> String {
+    date.to_string().replace('-', "")
+}
+
+fn ics_time(time: Time) -> String {
+    format!("{:02}{:02}", time.hour(), time.minute())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Content lines must not be longer than 75 octets. Longer lines are continued on the next line
+/// starting with a single space.
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            len = 1;
+        }
+        folded.push(c);
+        len += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::{to_local, Date, Time};
+
+    use super::{fold, parse_datetime, parse_events, unescape};
+
+    fn date(value: &str) -> Date {
+        value.parse().unwrap()
+    }
+
+    fn time(value: &str) -> Time {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn parse_datetime_reads_dates_and_floating_times() {
+        assert_eq!(
+            parse_datetime("20261020", None).unwrap(),
+            (date("2026-10-20"), None)
+        );
+        assert_eq!(
+            parse_datetime("20261020T101500", None).unwrap(),
+            (date("2026-10-20"), Some(time("10:15")))
+        );
+        assert!(parse_datetime("2026", None).is_err());
+        assert!(parse_datetime("20261320", None).is_err());
+        assert!(parse_datetime("2026ü020", None).is_err());
+        assert!(parse_datetime("20261020T1ü00", None).is_err());
+    }
+
+    #[test]
+    fn parse_datetime_converts_to_local_time() {
+        let utc = to_local(date("2026-10-20"), time("09:00"));
+        assert_eq!(
+            parse_datetime("20261020T090000Z", None).unwrap(),
+            (utc.0, Some(utc.1))
+        );
+        // Tokyo is 9 hours ahead of UTC without daylight saving time
+        assert_eq!(
+            parse_datetime("20261020T180000", Some("Asia/Tokyo")).unwrap(),
+            (utc.0, Some(utc.1))
+        );
+        // times of unknown zones are taken as written
+        assert_eq!(
+            parse_datetime("20261020T180000", Some("W. Europe Standard Time")).unwrap(),
+            (date("2026-10-20"), Some(time("18:00")))
+        );
+    }
+
+    #[test]
+    fn parse_events_marks_weekly_events() {
+        let content = "BEGIN:VCALENDAR\r\n\
+            BEGIN:VEVENT\r\nSUMMARY:Vorlesung\r\nDTSTART:20261020T101500\r\n\
+            DTEND:20261020T114500\r\nRRULE:FREQ=WEEKLY;COUNT=14\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nSUMMARY:Gastvortrag\r\nDTSTART:20261021T140000\r\n\
+            END:VEVENT\r\nEND:VCALENDAR\r\n";
+        let events = parse_events(content).unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].weekly);
+        assert_eq!(events[0].end, Some(time("11:45")));
+        assert!(!events[1].weekly);
+        assert_eq!(events[1].summary, "Gastvortrag");
+    }
+
+    #[test]
+    fn unescape_decodes_in_one_pass() {
+        assert_eq!(
+            unescape("Hörsaal 1\\, Raum 2\\; EG"),
+            "Hörsaal 1, Raum 2; EG"
+        );
+        assert_eq!(unescape("a\\nb\\Nc"), "a\nb\nc");
+        assert_eq!(unescape("C:\\\\new"), "C:\\new");
+        assert_eq!(unescape("end\\"), "end\\");
+    }
+
+    #[test]
+    fn fold_limits_lines_to_75_octets() {
+        let short = "SUMMARY:Lineare Algebra";
+        assert_eq!(fold(short), short);
+
+        let line = format!("SUMMARY:{}", "Übung ".repeat(20));
+        let folded = fold(&line);
+        assert!(folded.split("\r\n").all(|it| it.len() <= 75));
+        assert!(folded.split("\r\n").skip(1).all(|it| it.starts_with(' ')));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
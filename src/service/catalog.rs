@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use crate::{
+    cli::CatalogCommands,
+    domain::{Catalog, Course},
+    service::format::IntoFormatType,
+    StoreProvider,
+};
+
+use super::ServiceResult;
+
+pub(super) struct CatalogService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> CatalogService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self, command: CatalogCommands) -> ServiceResult {
+        match command {
+            CatalogCommands::Import { file } => self.import(file),
+        }
+    }
+
+    /// Replaces the stored catalog with the given file.
+    fn import(&self, file: PathBuf) -> ServiceResult {
+        let catalog = Catalog::read(&file)?;
+        let entry_point = self.store.entry_point();
+        catalog.save(&entry_point)?;
+
+        let courses = self.store.courses().collect::<Vec<Course>>();
+        let modules = catalog.modules();
+        let required = modules.iter().filter(|it| it.is_required()).count();
+        let matched = modules
+            .iter()
+            .filter(|module| courses.iter().any(|it| module.matches(it)))
+            .count();
+        let msg = format!(
+            "Imported {} modules ({} required) to: {}",
+            modules.len(),
+            required,
+            Catalog::file(&entry_point).display()
+        );
+        let info = format!(
+            "{} modules match existing courses. See what is left with 'mm plan'",
+            matched
+        );
+        Ok(msg.success().chain("".line()).chain(info.info()))
+    }
+}
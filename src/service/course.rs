@@ -1,11 +1,21 @@
-use crate::domain::Course;
+use crate::domain::{
+    missing_prerequisites, normalize_name, Catalog, Course, CourseFilter, CourseSort, CourseStatus,
+    Date, Progress, Repository, RepositoryUpdate,
+};
 use crate::service::format::FormatAlignment;
-use crate::table;
-use crate::{cli::CourseCommands, StoreProvider};
+use crate::{
+    cli::{CourseCommands, CourseSortDO},
+    MmError, StoreProvider,
+};
 use anyhow::{anyhow, bail};
+use colored::Colorize;
 
-use super::format::{DialogEntry, DialogOutput, FormatService, IntoFormatType};
-use super::ServiceResult;
+use super::format::{
+    DialogEntry, DialogOutput, FormatService, FormatType, IntoFormatType, TableBuilder,
+};
+use super::{
+    backup::snapshot_info, i18n::tr, reference::resolve_course, template::render, ServiceResult,
+};
 
 pub(super) struct CourseService<'s, Store>
 where
@@ -23,111 +33,699 @@ where
     }
 
     pub fn run(&mut self, command: Option<CourseCommands>) -> ServiceResult {
-        let command = command.unwrap_or(CourseCommands::List);
+        let command = command.unwrap_or(CourseCommands::List {
+            status: None,
+            all: false,
+            semester: None,
+            sort: CourseSortDO::Name,
+            graded: false,
+            ungraded: false,
+            degree: None,
+            uebk: false,
+            details: false,
+        });
         match command {
-            CourseCommands::List => self.list(),
-            CourseCommands::Add { name } => self.add(name),
-            CourseCommands::Remove { name } => self.remove(name),
+            CourseCommands::List {
+                status,
+                all,
+                semester,
+                sort,
+                graded,
+                ungraded,
+                degree,
+                uebk,
+                details,
+            } => {
+                let filter = CourseFilter {
+                    status: status.map(CourseStatus::from_do),
+                    graded: (graded || ungraded).then_some(graded),
+                    degree,
+                    uebk,
+                };
+                self.list(&filter, CourseSort::from_do(sort), details, all, semester)
+            }
+            CourseCommands::Add {
+                names,
+                template,
+                git,
+                git_dir,
+            } => {
+                let repository = git.map(|url| Repository::new(url, git_dir));
+                self.add_all(names, template, repository)
+            }
+            CourseCommands::Update { course, all } => self.update(course, all),
+            CourseCommands::Remove { names, force } => self.remove(names, force),
+            CourseCommands::Copy {
+                course,
+                semester,
+                name,
+            } => self.copy(&course, &semester, name),
+            CourseCommands::Move { course, to, force } => self.move_to(&course, &to, force),
+            CourseCommands::Grade {
+                grade,
+                component,
+                course,
+                force,
+            } => match component {
+                Some(component) => self.grade_component(course, &component, grade, force),
+                None => self.grade(course, grade, force),
+            },
+            CourseCommands::Status {
+                status,
+                course,
+                force,
+            } => self.status(course, CourseStatus::from_do(status), force),
+            CourseCommands::Lock { course } => self.lock(course, true),
+            CourseCommands::Unlock { course } => self.lock(course, false),
+            CourseCommands::Show { course } => self.show(course),
+            CourseCommands::Deps { course } => self.deps(course),
         }
     }
 
-    fn list(&self) -> ServiceResult {
-        let semester = match self.store.current_semester() {
-            Some(semester) => semester,
-            None => {
-                let error = "No active semester found".error();
-                let info =
-                    "An active semester is required in order to list the corresponding courses"
-                        .info();
-
-                return Ok(error.chain(info));
+    /// Courses of the active semester, of the given `semester` or of all semesters with their
+    /// status, grouped by semester. `details` adds the grade and ECTS of each course. Failed and dropped courses stay listed unless filtered out.
+    fn list(
+        &self,
+        filter: &CourseFilter,
+        sort: CourseSort,
+        details: bool,
+        all: bool,
+        semester: Option<String>,
+    ) -> ServiceResult {
+        let semesters = if all {
+            let mut semesters = self.store.semesters().collect::<Vec<_>>();
+            semesters.sort();
+            semesters
+        } else if let Some(name) = semester {
+            match self.store.get_semester(&name) {
+                Some(semester) => vec![semester],
+                None => bail!(MmError::SemesterNotFound(name)),
+            }
+        } else {
+            match self.store.current_semester() {
+                Some(semester) => vec![semester],
+                None => bail!(MmError::NoActiveSemester),
             }
         };
 
-        let mut courses = semester
-            .courses()
-            .map(|course| course.name())
-            .collect::<Vec<_>>();
-        courses.sort();
+        let active_course = self.store.current_course().map(|it| it.path().clone());
+        let mut entries = Vec::new();
+        for semester in semesters {
+            let semester_name = semester.name();
+            let mut courses = filter
+                .apply(self.store.semester_courses(semester))
+                .collect::<Vec<_>>();
+            courses.sort_by(|a, b| sort.compare(a, b));
+            entries.extend(
+                courses
+                    .into_iter()
+                    .map(|course| (semester_name.clone(), course)),
+            );
+        }
 
-        if courses.is_empty() {
-            let msg = "No courses found".info();
+        if entries.is_empty() {
+            let msg = match filter.status {
+                Some(status) => format!("No {} courses found", status).info(),
+                None => "No courses found".info(),
+            };
             return Ok(msg);
         }
 
-        let active_idx = self.store.current_semester().map(|active_sem| {
-            (&courses)
-                .iter()
-                .map(|course| {
-                    if course == &active_sem.name() {
-                        return "*".into();
-                    }
-                    return " ".into();
-                })
-                .collect()
-        });
+        let mut active = Vec::new();
+        let mut semesters = Vec::new();
+        let mut courses = Vec::new();
+        let mut states = Vec::new();
+        let mut grades = Vec::new();
+        let mut ects = Vec::new();
+        for (semester, course) in entries {
+            let is_active = active_course.as_ref() == Some(course.path());
+            active.push(if is_active { "*" } else { " " }.to_string());
+            semesters.push(semester);
+            courses.push(course.name());
+            states.push(status_cell(course.lifecycle()));
+            grades.push(
+                course
+                    .grade()
+                    .map_or("-".into(), |it| FormatType::grade(it, 1)),
+            );
+            ects.push(course.ects().map_or("-".into(), |it| it.to_string()));
+        }
 
-        let table = match active_idx {
-            Some(active) => {
-                table!("Active", "Courses"; active, courses; FormatAlignment::Right, FormatAlignment::Right)
+        let mut table = TableBuilder::new().column("Active", active, FormatAlignment::Right);
+        if all {
+            table = table.column("Semester", semesters, FormatAlignment::Left);
+        }
+        table = table
+            .column("Courses", courses, FormatAlignment::Right)
+            .column("Status", states, FormatAlignment::Left);
+        if details {
+            table = table
+                .column("Grade", grades, FormatAlignment::Right)
+                .column("ECTS", ects, FormatAlignment::Right);
+        }
+        Ok(table.build())
+    }
+
+    /// Adds each course like [Self::add] after checking that none of them exists yet.
+    fn add_all(
+        &mut self,
+        names: Vec<String>,
+        template: Option<String>,
+        repository: Option<Repository>,
+    ) -> ServiceResult {
+        if let [name] = names.as_slice() {
+            return self.add(name.clone(), template, repository);
+        }
+        if repository.is_some() {
+            bail!("A git repository can only be cloned into a single course");
+        }
+        if let Some(semester) = self.store.current_semester() {
+            for (idx, name) in names.iter().enumerate() {
+                let path = semester.path().path().join(name);
+                if path.exists() || names[..idx].contains(name) {
+                    bail!(MmError::CourseAlreadyExists(path));
+                }
             }
-            None => table!("Courses"; courses; FormatAlignment::Right),
-        };
-        Ok(table)
+        }
+
+        let mut res = Vec::new();
+        for name in names {
+            let msg = self.add(name, template.clone(), None)?;
+            if msg.is_error() {
+                return Ok(msg);
+            }
+            res.push(msg);
+        }
+        Ok(res
+            .into_iter()
+            .reduce(|msg, line| msg.chain("".line()).chain(line))
+            .unwrap_or_else(|| "No courses given".info()))
     }
 
-    fn add(&mut self, name: String) -> ServiceResult {
+    /// Creates the course folder and optionally clones a material repository and instantiates a
+    /// folder template. Templates support the placeholders `{{course}}`, `{{semester}}` and
+    /// `{{date}}` in names and text files.
+    fn add(
+        &mut self,
+        name: String,
+        template: Option<String>,
+        repository: Option<Repository>,
+    ) -> ServiceResult {
         let semester = match self.store.current_semester() {
             Some(semester) => semester,
-            None => {
-                let error = "No active semester found".error();
-                let info = "An active semester is required in order to add a new course".info();
-                return Ok(error.chain(info));
+            None => bail!(MmError::NoActiveSemester),
+        };
+
+        let template_dir = match template {
+            Some(template) => {
+                let dir = self.store.template_dir().join(&template);
+                if !dir.is_dir() {
+                    bail!("No course template found at: {}", dir.display());
+                }
+                Some(dir)
             }
+            None => None,
         };
 
         let course_path = semester.path().create_course_path(&name)?;
-        // used to create course data file
-        let _ = Course::from_path(course_path)?;
-        let msg = format!("Course '{}' has been added", name).success();
+        // cloned first as git requires an empty folder
+        if let Some(repository) = &repository {
+            if let Err(err) = repository.clone_into(&course_path) {
+                course_path.remove()?;
+                return Err(err);
+            }
+        }
+        let mut files = 0;
+        if let Some(dir) = template_dir {
+            let date = Date::today().to_string();
+            let variables = [
+                ("course", name.as_str()),
+                ("semester", semester.path().name()),
+                ("date", date.as_str()),
+            ];
+            files = course_path.apply_template(dir, |text| render(text, &variables))?;
+        }
+        course_path.create_data_file()?;
+        let mut course = Course::from_path(course_path)?;
+        let msg = match files {
+            0 => format!("Course '{}' has been added", name),
+            _ => format!(
+                "Course '{}' has been added with {} files from the template",
+                name, files
+            ),
+        };
+        let mut msg = msg.success();
+        if let Some(repository) = repository {
+            let dir = repository.dir(course.path());
+            course.set_repository(repository)?;
+            let info = format!("Material repository has been cloned to: {}", dir.display());
+            msg = msg.chain("".line()).chain(info.info());
+        }
+        if let Some(info) = self.missing_prerequisites(&course) {
+            msg = msg.chain("".line()).chain(info);
+        }
         Ok(msg)
     }
 
-    fn remove(&mut self, name: String) -> ServiceResult {
+    /// Prerequisites of the course and of its module in the catalog which are not passed yet.
+    fn missing_prerequisites(&self, course: &Course) -> Option<FormatType> {
+        let catalog = Catalog::load(&self.store.entry_point()).unwrap_or_default();
+        let mut prerequisites = course.prerequisites().clone();
+        for module in catalog.modules().iter().filter(|it| it.matches(course)) {
+            prerequisites.extend(module.prerequisites().iter().cloned());
+        }
+        prerequisites.dedup_by_key(|it| normalize_name(it));
+
+        let courses = self.store.courses().collect::<Vec<_>>();
+        let missing = missing_prerequisites(&prerequisites, &courses);
+        if missing.is_empty() {
+            return None;
+        }
+        let msg = format!(
+            "Prerequisites of '{}' which are not passed yet: {}",
+            course.name(),
+            missing.join(", ")
+        );
+        Some(msg.info())
+    }
+
+    /// Pulls the material repository of the given course, of all courses with `all` or of the
+    /// courses of the active semester. Failing repositories do not stop the others.
+    fn update(&self, course: Option<String>, all: bool) -> ServiceResult {
+        let courses: Vec<Course> = match (course, all, self.store.current_semester()) {
+            (Some(reference), _, _) => vec![resolve_course(self.store, Some(&reference))?],
+            (None, false, Some(semester)) => self.store.semester_courses(semester).collect(),
+            (None, _, _) => self.store.courses().collect(),
+        };
+        let mut courses = courses
+            .into_iter()
+            .filter(|it| it.repository().is_some())
+            .collect::<Vec<_>>();
+        if courses.is_empty() {
+            return Ok("No course with a material repository found".info());
+        }
+        courses.sort_by_key(|it| it.name());
+
+        let mut failed = 0;
+        let mut names = Vec::new();
+        let mut states = Vec::new();
+        let progress = Progress::bar("Updating repositories", courses.len() as u64);
+        for course in &courses {
+            let Some(repository) = course.repository() else {
+                continue;
+            };
+            progress.step(course.name());
+            let state = match repository.update(course.path()) {
+                Ok(RepositoryUpdate::Cloned) => "cloned".green().to_string(),
+                Ok(RepositoryUpdate::Updated) => "updated".green().to_string(),
+                Ok(RepositoryUpdate::UpToDate) => "up to date".to_string(),
+                Err(err) => {
+                    failed += 1;
+                    format!("{:#}", err).red().to_string()
+                }
+            };
+            names.push(course.name());
+            states.push(state);
+        }
+        drop(progress);
+
+        let body = TableBuilder::new()
+            .column("Course", names, FormatAlignment::Left)
+            .column("Repository", states, FormatAlignment::Left)
+            .build();
+        let summary = match failed {
+            0 => format!("{} repositories are up to date", courses.len()).success(),
+            _ => format!("{} of {} repositories failed", failed, courses.len()).error(),
+        };
+        Ok(body.chain("".line()).chain(summary))
+    }
+
+    /// Removes the courses of the active semester after a single confirmation listing all of them.
+    /// `force` skips the confirmation and removes locked courses. A snapshot of each course is
+    /// saved first.
+    fn remove(&mut self, names: Vec<String>, force: bool) -> ServiceResult {
         let semester = match self.store.current_semester() {
             Some(semester) => semester,
-            None => {
-                let error = "No active semester found".error();
-                let info = "An active semester is required in order to remove a course".info();
-                return Ok(error.chain(info));
-            }
+            None => bail!(MmError::NoActiveSemester),
         };
 
-        let dialog = vec![
-            DialogEntry::YesNoInput(format!("Are you sure that you want to permanently remove course '{}' with all its content? This action can not be reverted",name))
-        ];
-        let response = FormatService::dialog(dialog);
-        if let Some(res) = response {
+        let mut courses: Vec<Course> = Vec::new();
+        for name in &names {
+            let course = semester
+                .course(name)
+                .ok_or_else(|| MmError::CourseNotFound(name.clone()))?;
+            if course.is_locked() && !force {
+                bail!(
+                    "Course '{}' is locked. Use --force to remove it anyway",
+                    course.name()
+                );
+            }
+            if !courses.iter().any(|it| it.path() == course.path()) {
+                courses.push(course);
+            }
+        }
+
+        let dialog = match courses.as_slice() {
+            [course] => vec![
+                DialogEntry::YesNoInput(format!("Are you sure that you want to permanently remove course '{}' with all its content? This action can not be reverted", course.path().name()))
+            ],
+            _ => {
+                let mut dialog = vec![DialogEntry::Message(
+                    "The following courses will be permanently removed with all their content:"
+                        .into(),
+                )];
+                dialog.extend(courses.iter().map(|course| {
+                    DialogEntry::Message(format!(
+                        "  {} ({})",
+                        course.name(),
+                        course.path().display()
+                    ))
+                }));
+                dialog.push(DialogEntry::YesNoInput(
+                    "Are you sure? This action can not be reverted".into(),
+                ));
+                dialog
+            }
+        };
+        if !force {
+            let Some(res) = FormatService::dialog(dialog) else {
+                return Ok("Operation has been canceled".info());
+            };
             let res = res
                 .first()
                 .ok_or_else(|| anyhow!("Dialog has not returned not the specified output"))?;
             let DialogOutput::YesNo(cond) = res else {
                 bail!("Invalid input");
             };
+            if !*cond {
+                return Ok("Operation has been canceled".info());
+            }
+        }
 
-            if *cond {
-                let course = semester
-                    .course(&name)
-                    .ok_or_else(|| anyhow!("Course '{}' could not be found", name))?;
+        let mut res = Vec::new();
+        for course in courses {
+            let snapshot = self
+                .store
+                .backup_settings()
+                .snapshot(&self.store.entry_point(), course.path())?;
+            course.path().clone().remove()?;
+            let msg = format!("Course '{}' has been removed", course.path().name()).success();
+            res.push(msg.chain("".line()).chain(snapshot_info(&snapshot)));
+        }
+        Ok(res
+            .into_iter()
+            .reduce(|msg, line| msg.chain("".line()).chain("".line()).chain(line))
+            .unwrap_or_else(|| "No courses given".info()))
+    }
 
-                course.path().clone().remove()?;
-                let msg = format!("Semester '{}' has been removed", name).success();
-                return Ok(msg);
-            } else {
-                return Ok("Operation has been canceled".info());
+    /// Copies the folders and settings of a course into another semester, e.g. for the second
+    /// part of a lecture series.
+    fn copy(&mut self, reference: &str, semester: &str, name: Option<String>) -> ServiceResult {
+        let course = resolve_course(self.store, Some(reference))?;
+        let target = self
+            .store
+            .get_semester(semester)
+            .ok_or_else(|| MmError::SemesterNotFound(semester.into()))?;
+        let source = course
+            .path()
+            .as_path()
+            .parent()
+            .and_then(|it| it.file_name())
+            .map(|it| it.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let name = name.unwrap_or_else(|| course.path().name().to_string());
+        let path = target.path().create_course_path(&name)?;
+        let copy = course.copy_to(path, format!("{}/{}", source, course.path().name()))?;
+        Ok(format!(
+            "Course '{}' has been copied to: {}/{}",
+            course.name(),
+            target.name(),
+            copy.path().name()
+        )
+        .success())
+    }
+
+    /// Moves a course into another semester after saving a snapshot of it. The current course
+    /// stays current, which switches to the target semester.
+    fn move_to(&mut self, reference: &str, to: &str, force: bool) -> ServiceResult {
+        let course = resolve_course(self.store, Some(reference))?;
+        if course.is_locked() && !force {
+            bail!(
+                "Course '{}' is locked. Use --force to move it anyway",
+                course.name()
+            );
+        }
+        let mut target = self
+            .store
+            .get_semester(to)
+            .ok_or_else(|| MmError::SemesterNotFound(to.into()))?;
+        let was_current = self
+            .store
+            .current_course()
+            .is_some_and(|it| it.path() == course.path());
+
+        let snapshot = self
+            .store
+            .backup_settings()
+            .snapshot(&self.store.entry_point(), course.path())?;
+        let moved = self.store.move_course(&course, &mut target)?;
+        let mut msg = format!(
+            "Moved course '{}' to semester {}",
+            moved.name(),
+            target.name()
+        )
+        .success();
+        if was_current {
+            let info = format!("Switched to course: {}/{}", target.name(), moved.name()).info();
+            msg = msg.chain("".line()).chain(info);
+        }
+        Ok(msg.chain("".line()).chain(snapshot_info(&snapshot)))
+    }
+
+    fn grade(&mut self, course: Option<String>, grade: f32, force: bool) -> ServiceResult {
+        let mut course = resolve_course(self.store, course.as_deref())?;
+        if course.is_locked() && !force {
+            bail!(
+                "Course '{}' is locked. Use --force to change its grade anyway",
+                course.name()
+            );
+        }
+        course.set_grade(grade)?;
+        let msg = format!("Grade of '{}' has been set to {}", course.name(), grade).success();
+        Ok(msg)
+    }
+
+    fn status(
+        &mut self,
+        course: Option<String>,
+        status: CourseStatus,
+        force: bool,
+    ) -> ServiceResult {
+        let mut course = resolve_course(self.store, course.as_deref())?;
+        if course.is_locked() && !force {
+            bail!(
+                "Course '{}' is locked. Use --force to change its status anyway",
+                course.name()
+            );
+        }
+        course.set_status(status)?;
+        let msg = format!("Course '{}' has been marked as {}", course.name(), status).success();
+        Ok(msg)
+    }
+
+    fn grade_component(
+        &mut self,
+        course: Option<String>,
+        component: &str,
+        grade: f32,
+        force: bool,
+    ) -> ServiceResult {
+        let mut course = resolve_course(self.store, course.as_deref())?;
+        if course.is_locked() && !force {
+            bail!(
+                "Course '{}' is locked. Use --force to change its grade anyway",
+                course.name()
+            );
+        }
+        let computed = course.set_component_grade(component, grade)?;
+        let msg = format!(
+            "Component '{}' of '{}' has been set to {}",
+            component,
+            course.name(),
+            grade
+        )
+        .success();
+        let info = match computed {
+            Some(final_grade) => format!("The final grade is {:.1}", final_grade).info(),
+            None => {
+                let missing = course
+                    .components()
+                    .iter()
+                    .filter(|it| !it.is_bonus() && it.grade().is_none())
+                    .map(|it| it.name())
+                    .collect::<Vec<_>>();
+                match missing.is_empty() {
+                    true => "Set weights of the components to compute the final grade".info(),
+                    false => format!("Still missing: {}", missing.join(", ")).info(),
+                }
             }
-        } else {
-            return Ok("Operation has been canceled".info());
+        };
+        Ok(msg.chain("".line()).chain(info))
+    }
+
+    fn lock(&mut self, course: Option<String>, locked: bool) -> ServiceResult {
+        let mut course = resolve_course(self.store, course.as_deref())?;
+        course.set_locked(locked)?;
+        let state = if locked { "locked" } else { "unlocked" };
+        Ok(format!("Course '{}' has been {}", course.name(), state).success())
+    }
+
+    /// Settings and results of the course. Unset fields are left out.
+    fn show(&self, course: Option<String>) -> ServiceResult {
+        let course = resolve_course(self.store, course.as_deref())?;
+        let list = |values: &Vec<String>| (!values.is_empty()).then(|| values.join(", "));
+        let fields = [
+            ("Folder", Some(course.path().name().to_string())),
+            ("Status", Some(status_cell(course.lifecycle()))),
+            ("Grade", course.grade().map(|it| FormatType::grade(it, 1))),
+            ("ECTS", course.ects().map(|it| it.to_string())),
+            ("Degrees", list(course.degrees())),
+            ("Category", course.category().map(str::to_string)),
+            ("Code", course.code().map(str::to_string)),
+            ("Module", course.module_id().map(str::to_string)),
+            ("Instructor", course.instructor().map(str::to_string)),
+            ("URL", course.url().map(str::to_string)),
+            ("Tags", list(course.tags())),
+            ("Prerequisites", list(course.prerequisites())),
+            ("Related", list(course.related())),
+        ];
+
+        let (names, values): (Vec<_>, Vec<_>) = fields
+            .into_iter()
+            .filter_map(|(name, value)| Some((tr(name), value?)))
+            .unzip();
+        let table = TableBuilder::new()
+            .column("Field", names, FormatAlignment::Left)
+            .column("Value", values, FormatAlignment::Left)
+            .build();
+        Ok(course.name().line().chain("".line()).chain(table))
+    }
+
+    /// Tree of the prerequisites of the course, their prerequisites and so on, with the status
+    /// of each, followed by the courses which require the course. Prerequisites of catalog
+    /// modules are included.
+    fn deps(&self, course: Option<String>) -> ServiceResult {
+        let course = resolve_course(self.store, course.as_deref())?;
+        let graph = Graph {
+            courses: self.store.courses().collect(),
+            catalog: Catalog::load(&self.store.entry_point()).unwrap_or_default(),
+        };
+
+        let mut lines = vec![format!(
+            "{} {}",
+            course.name(),
+            status_cell(course.lifecycle())
+        )];
+        let mut ancestors = vec![normalize_name(&course.name())];
+        graph.tree(
+            &graph.prerequisites(Some(&course), &course.name()),
+            "",
+            &mut ancestors,
+            &mut lines,
+        );
+        if lines.len() == 1 {
+            lines.push(tr("No prerequisites"));
         }
+        let mut msg = lines.join("\n").line();
+
+        let required_by = graph
+            .courses
+            .iter()
+            .filter(|it| it.prerequisites().iter().any(|p| course.fulfills(p)))
+            .map(|it| it.name())
+            .collect::<Vec<_>>();
+        if !required_by.is_empty() {
+            let msg_required = format!("Required by: {}", required_by.join(", "));
+            msg = msg.chain("".line()).chain(msg_required.line());
+        }
+        Ok(msg)
+    }
+}
+
+/// Courses and catalog modules to look up prerequisites in, see `mm course deps`.
+struct Graph {
+    courses: Vec<Course>,
+    catalog: Catalog,
+}
+
+impl Graph {
+    /// The passed course meant by a prerequisite, otherwise the latest attempt.
+    fn course(&self, prerequisite: &str) -> Option<&Course> {
+        let mut matching = self.courses.iter().filter(|it| it.fulfills(prerequisite));
+        matching
+            .clone()
+            .find(|it| it.is_passed())
+            .or_else(|| matching.next_back())
+    }
+
+    /// Prerequisites of a course and of the catalog module it or `name` stands for.
+    fn prerequisites(&self, course: Option<&Course>, name: &str) -> Vec<String> {
+        let mut prerequisites = course
+            .map(|it| it.prerequisites().clone())
+            .unwrap_or_default();
+        let modules = self.catalog.modules().iter().filter(|module| match course {
+            Some(course) => module.matches(course),
+            None => module.is_named(name),
+        });
+        for module in modules {
+            prerequisites.extend(module.prerequisites().iter().cloned());
+        }
+        prerequisites.dedup_by_key(|it| normalize_name(it));
+        prerequisites
+    }
+
+    fn tree(
+        &self,
+        prerequisites: &[String],
+        indent: &str,
+        ancestors: &mut Vec<String>,
+        lines: &mut Vec<String>,
+    ) {
+        for (idx, prerequisite) in prerequisites.iter().enumerate() {
+            let last = idx + 1 == prerequisites.len();
+            let (branch, next) = match last {
+                true => ("└── ", "    "),
+                false => ("├── ", "│   "),
+            };
+            let course = self.course(prerequisite);
+            let state = match course {
+                Some(course) => status_cell(course.lifecycle()),
+                None => tr("not taken").red().to_string(),
+            };
+            let name = course.map(|it| it.name()).unwrap_or(prerequisite.clone());
+            let key = normalize_name(&name);
+            if ancestors.contains(&key) {
+                let cycle = tr("cycle").red();
+                lines.push(format!("{}{}{} ({})", indent, branch, name, cycle));
+                continue;
+            }
+            lines.push(format!("{}{}{} {}", indent, branch, name, state));
+
+            ancestors.push(key);
+            let children = self.prerequisites(course, prerequisite);
+            self.tree(&children, &format!("{}{}", indent, next), ancestors, lines);
+            ancestors.pop();
+        }
+    }
+}
+
+fn status_cell(status: CourseStatus) -> String {
+    let text = status.to_string();
+    match status {
+        CourseStatus::Passed => text.green().to_string(),
+        CourseStatus::Failed => text.red().to_string(),
+        CourseStatus::Dropped => text.dimmed().to_string(),
+        CourseStatus::Planned | CourseStatus::Enrolled => text,
     }
 }
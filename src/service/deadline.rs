@@ -0,0 +1,177 @@
+use anyhow::bail;
+use colored::Colorize;
+
+use crate::{
+    cli::DeadlineCommands,
+    domain::{Course, Date, Deadline, DeadlineKind},
+    service::format::{FormatAlignment, FormatType, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, ServiceResult};
+
+/// Deadlines within this many days are highlighted as due soon.
+pub(super) const SOON_DAYS: i64 = 3;
+
+pub(super) struct DeadlineService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s mut Store,
+}
+
+impl<'s, Store> DeadlineService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s mut Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(
+        &mut self,
+        course: Option<String>,
+        command: Option<DeadlineCommands>,
+    ) -> ServiceResult {
+        let command = command.unwrap_or(DeadlineCommands::List { all: false });
+        match command {
+            DeadlineCommands::List { all } => self.list(course, all),
+            DeadlineCommands::Add { title, date, kind } => {
+                self.add(course, title, date, DeadlineKind::from_do(kind))
+            }
+            DeadlineCommands::Remind { days } => self.remind(course, days),
+        }
+    }
+
+    /// Lists the deadlines of a single course or, without a course reference, of all courses in
+    /// the active semester. Without an active semester all courses are considered.
+    fn list(&self, course: Option<String>, all: bool) -> ServiceResult {
+        let courses: Vec<Course> = match (course, self.store.current_semester()) {
+            (Some(reference), _) => vec![resolve_course(self.store, Some(&reference))?],
+            (None, Some(semester)) => self.store.semester_courses(semester).collect(),
+            (None, None) => self.store.courses().collect(),
+        };
+
+        let today = Date::today();
+        let mut deadlines = courses
+            .iter()
+            .flat_map(|course| {
+                course
+                    .deadlines()
+                    .iter()
+                    .map(move |deadline| (course, deadline))
+            })
+            .filter(|(_, deadline)| all || deadline.days_left(&today) >= 0)
+            .collect::<Vec<_>>();
+        deadlines.sort_by_key(|(_, deadline)| deadline.date());
+
+        if deadlines.is_empty() {
+            return Ok("No upcoming deadlines found".info());
+        }
+        Ok(deadline_table(&deadlines, &today))
+    }
+
+    /// Sends one notification per deadline due within the configured days.
+    fn remind(&self, course: Option<String>, days: Option<i64>) -> ServiceResult {
+        let Some(settings) = self.store.notification_settings() else {
+            bail!("No notification channel configured. Add a [notifications] table to the config");
+        };
+        let days = days.unwrap_or(settings.days());
+        let courses: Vec<Course> = match (course, self.store.current_semester()) {
+            (Some(reference), _) => vec![resolve_course(self.store, Some(&reference))?],
+            (None, Some(semester)) => self.store.semester_courses(semester).collect(),
+            (None, None) => self.store.courses().collect(),
+        };
+
+        let today = Date::today();
+        let mut deadlines = courses
+            .iter()
+            .flat_map(|course| {
+                course
+                    .deadlines()
+                    .iter()
+                    .map(move |deadline| (course, deadline))
+            })
+            .filter(|(_, deadline)| (0..=days).contains(&deadline.days_left(&today)))
+            .collect::<Vec<_>>();
+        deadlines.sort_by_key(|(_, deadline)| deadline.date());
+        if deadlines.is_empty() {
+            return Ok(format!("No deadlines due within {} days", days).info());
+        }
+
+        let notifier = settings.notifier();
+        for (course, deadline) in &deadlines {
+            let due = match deadline.days_left(&today) {
+                0 => "today".to_string(),
+                1 => "tomorrow".to_string(),
+                d => format!("in {} days", d),
+            };
+            let title = format!("{}: {}", course.name(), deadline.title());
+            let message = format!("{} due {} ({})", deadline.kind(), due, deadline.date());
+            notifier.send(&title, &message)?;
+        }
+        Ok(format!("Sent reminders for {} deadlines", deadlines.len()).success())
+    }
+
+    fn add(
+        &mut self,
+        course: Option<String>,
+        title: String,
+        date: Date,
+        kind: DeadlineKind,
+    ) -> ServiceResult {
+        let mut course = resolve_course(self.store, course.as_deref())?;
+        course.add_deadline(Deadline::new(title.clone(), date, kind))?;
+        let msg = format!(
+            "{} deadline '{}' on {} has been added to '{}'",
+            kind,
+            title,
+            date,
+            course.name()
+        );
+        Ok(msg.success())
+    }
+}
+
+/// Table of deadlines with a colored due indicator in the last column.
+pub(super) fn deadline_table(deadlines: &[(&Course, &Deadline)], today: &Date) -> FormatType {
+    let dates = deadlines
+        .iter()
+        .map(|(_, deadline)| deadline.date().to_string())
+        .collect::<Vec<_>>();
+    let courses = deadlines
+        .iter()
+        .map(|(course, _)| course.name())
+        .collect::<Vec<_>>();
+    let kinds = deadlines
+        .iter()
+        .map(|(_, deadline)| deadline.kind().to_string())
+        .collect::<Vec<_>>();
+    let titles = deadlines
+        .iter()
+        .map(|(_, deadline)| deadline.title().to_string())
+        .collect::<Vec<_>>();
+    let due = deadlines
+        .iter()
+        .map(|(_, deadline)| due_indicator(deadline.days_left(today)))
+        .collect::<Vec<_>>();
+
+    // the colored column must stay last as escape codes break the column width calculation
+    TableBuilder::new()
+        .column("Date", dates, FormatAlignment::Left)
+        .column("Course", courses, FormatAlignment::Left)
+        .column("Kind", kinds, FormatAlignment::Left)
+        .column("Title", titles, FormatAlignment::Left)
+        .column("Due", due, FormatAlignment::Left)
+        .build()
+}
+
+fn due_indicator(days: i64) -> String {
+    match days {
+        d if d < 0 => format!("overdue by {} days", -d).red().to_string(),
+        0 => "today".red().to_string(),
+        1 => "tomorrow".yellow().to_string(),
+        d if d <= SOON_DAYS => format!("in {} days", d).yellow().to_string(),
+        d => format!("in {} days", d),
+    }
+}
@@ -0,0 +1,190 @@
+use anyhow::{anyhow, bail};
+use colored::Colorize;
+
+use crate::{
+    cli::DegreeCommands,
+    domain::{Course, Degree},
+    service::format::{FormatAlignment, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::ServiceResult;
+
+pub(super) struct DegreeService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> DegreeService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self, command: Option<DegreeCommands>) -> ServiceResult {
+        match command.unwrap_or(DegreeCommands::List) {
+            DegreeCommands::List => self.list(),
+            DegreeCommands::Show { name } => self.show(name),
+        }
+    }
+
+    /// Progress of all configured degrees. Degrees which are only named by courses are listed
+    /// as not configured.
+    fn list(&self) -> ServiceResult {
+        let degrees = self.store.degrees();
+        let courses = self.store.courses().collect::<Vec<_>>();
+
+        let mut names = Vec::new();
+        let mut ects = Vec::new();
+        let mut progress = Vec::new();
+        for degree in &degrees {
+            let counted = degree.progress(&courses).counted(degree);
+            names.push(degree.name().to_string());
+            ects.push(format!("{} / {}", counted, degree.ects()));
+            progress.push(progress_bar(counted, degree.ects()));
+        }
+
+        let mut unknown = courses
+            .iter()
+            .flat_map(|it| it.degrees())
+            .filter(|name| !degrees.iter().any(|it| it.name() == *name))
+            .cloned()
+            .collect::<Vec<_>>();
+        unknown.sort();
+        unknown.dedup();
+        for name in unknown {
+            let earned = courses
+                .iter()
+                .filter(|it| it.degrees().contains(&name))
+                .filter_map(|it| it.earned_ects())
+                .map(|it| it as u32)
+                .sum::<u32>();
+            names.push(name);
+            ects.push(earned.to_string());
+            progress.push("not configured".yellow().to_string());
+        }
+
+        if names.is_empty() {
+            let info = "No degrees found. Add [[degrees]] tables to the config file".info();
+            return Ok(info);
+        }
+        Ok(TableBuilder::new()
+            .column("Degree", names, FormatAlignment::Left)
+            .column("ECTS", ects, FormatAlignment::Right)
+            .column("Progress", progress, FormatAlignment::Left)
+            .build())
+    }
+
+    /// Earned against required ECTS per category of a degree. Without name the only configured
+    /// degree is shown.
+    fn show(&self, name: Option<String>) -> ServiceResult {
+        let degree = self.degree(name.as_deref())?;
+        let courses = self.store.courses().collect::<Vec<Course>>();
+        let progress = degree.progress(&courses);
+
+        let mut categories = Vec::new();
+        let mut earned = Vec::new();
+        let mut required = Vec::new();
+        let mut states = Vec::new();
+        for (name, ects, target) in &progress.categories {
+            categories.push(name.clone());
+            earned.push(ects.to_string());
+            required.push(target.to_string());
+            states.push(match ects.cmp(target) {
+                std::cmp::Ordering::Less => format!("{} missing", target - ects).yellow(),
+                std::cmp::Ordering::Equal => "complete".green(),
+                std::cmp::Ordering::Greater => format!("{} over", ects - target).red(),
+            });
+        }
+        if progress.uncategorized > 0 {
+            categories.push("Uncategorized".into());
+            earned.push(progress.uncategorized.to_string());
+            required.push("-".into());
+            states.push("set 'category' in course.toml".yellow());
+        }
+        if progress.uebk > 0 || degree.uebk_cap().is_some() {
+            categories.push("übK".into());
+            earned.push(progress.uebk.to_string());
+            match degree.uebk_cap() {
+                Some(cap) if progress.uebk > cap => {
+                    required.push(format!("max {}", cap));
+                    states.push(format!("{} over cap", progress.uebk - cap).red());
+                }
+                Some(cap) => {
+                    required.push(format!("max {}", cap));
+                    states.push("ok".green());
+                }
+                None => {
+                    required.push("-".into());
+                    states.push("no cap".normal());
+                }
+            }
+        }
+        let states = states
+            .into_iter()
+            .map(|it| it.to_string())
+            .collect::<Vec<_>>();
+
+        let counted = progress.counted(&degree);
+        let summary = format!(
+            "{} of {} ECTS count towards {}  {}",
+            counted,
+            degree.ects(),
+            degree.name(),
+            progress_bar(counted, degree.ects())
+        );
+        if categories.is_empty() {
+            return Ok(summary.line());
+        }
+        let body = TableBuilder::new()
+            .column("Category", categories, FormatAlignment::Left)
+            .column("Earned", earned, FormatAlignment::Right)
+            .column("Required", required, FormatAlignment::Right)
+            .column("State", states, FormatAlignment::Left)
+            .build();
+        Ok(summary.line().chain("".line()).chain(body))
+    }
+
+    fn degree(&self, name: Option<&str>) -> anyhow::Result<Degree> {
+        let degrees = self.store.degrees();
+        let Some(name) = name else {
+            return match degrees.as_slice() {
+                [degree] => Ok(degree.clone()),
+                [] => bail!("No degrees configured. Add [[degrees]] tables to the config file"),
+                _ => bail!("Several degrees are configured, please provide a name"),
+            };
+        };
+        if let Some(degree) = degrees.iter().find(|it| it.name() == name) {
+            return Ok(degree.clone());
+        }
+        let matches = degrees
+            .iter()
+            .filter(|it| it.matches(name))
+            .collect::<Vec<_>>();
+        match matches.as_slice() {
+            [degree] => Ok((*degree).clone()),
+            [] => Err(anyhow!("No degree configured matching: {}", name)),
+            _ => bail!("Several degrees match '{}', please be more specific", name),
+        }
+    }
+}
+
+/// A bar of 20 characters with the percentage of `value` in `total`.
+pub(super) fn progress_bar(value: u32, total: u32) -> String {
+    const WIDTH: u32 = 20;
+    let fraction = match total {
+        0 => 1.0,
+        total => (value as f32 / total as f32).min(1.0),
+    };
+    let filled = (fraction * WIDTH as f32).round() as usize;
+    format!(
+        "{}{} {:>3.0}%",
+        "█".repeat(filled).green(),
+        "░".repeat(WIDTH as usize - filled),
+        fraction * 100.0
+    )
+}
@@ -0,0 +1,179 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+use crate::{
+    cli::DemoCommands,
+    domain::{effects, Date, DATA_VERSION},
+    service::format::IntoFormatType,
+};
+
+use super::ServiceResult;
+
+/// Builds a realistic sample store to explore mm without touching real data. Does not require a
+/// configured entry point.
+pub(super) struct DemoService;
+
+struct DemoCourse {
+    folder: &'static str,
+    name: &'static str,
+    ects: u8,
+    grade: Option<f32>,
+    uebk: bool,
+    degrees: &'static [&'static str],
+}
+
+const INFO: &[&str] = &["B.Sc. Informatik"];
+const INFO_MATH: &[&str] = &["B.Sc. Informatik", "B.Sc. Mathematik"];
+
+#[rustfmt::skip]
+const SEMESTERS: [(&str, [DemoCourse; 5]); 3] = [
+    ("b01", [
+        DemoCourse { folder: "Analysis1", name: "Analysis I", ects: 9, grade: Some(2.3), uebk: false, degrees: INFO_MATH },
+        DemoCourse { folder: "LinAlg1", name: "Lineare Algebra I", ects: 9, grade: Some(1.7), uebk: false, degrees: INFO_MATH },
+        DemoCourse { folder: "Info1", name: "Informatik I", ects: 9, grade: Some(1.3), uebk: false, degrees: INFO },
+        DemoCourse { folder: "TechInfo", name: "Technische Informatik", ects: 6, grade: Some(2.0), uebk: false, degrees: INFO },
+        DemoCourse { folder: "WissArbeiten", name: "Wissenschaftliches Arbeiten", ects: 3, grade: Some(1.0), uebk: true, degrees: INFO },
+    ]),
+    ("b02", [
+        DemoCourse { folder: "Analysis2", name: "Analysis II", ects: 9, grade: Some(2.7), uebk: false, degrees: INFO_MATH },
+        DemoCourse { folder: "Info2", name: "Informatik II", ects: 9, grade: Some(1.7), uebk: false, degrees: INFO },
+        DemoCourse { folder: "Algorithms", name: "Algorithmen und Datenstrukturen", ects: 9, grade: Some(1.3), uebk: false, degrees: INFO },
+        DemoCourse { folder: "Stochastik", name: "Stochastik", ects: 6, grade: Some(2.3), uebk: false, degrees: INFO_MATH },
+        DemoCourse { folder: "Japanisch1", name: "Japanisch I", ects: 6, grade: Some(1.7), uebk: true, degrees: INFO },
+    ]),
+    ("b03", [
+        DemoCourse { folder: "Datenbanken", name: "Datenbanksysteme", ects: 6, grade: Some(2.0), uebk: false, degrees: INFO },
+        DemoCourse { folder: "Betriebssysteme", name: "Betriebssysteme", ects: 9, grade: None, uebk: false, degrees: INFO },
+        DemoCourse { folder: "TheoInf", name: "Theoretische Informatik", ects: 9, grade: None, uebk: false, degrees: INFO_MATH },
+        DemoCourse { folder: "ML", name: "Machine Learning", ects: 6, grade: None, uebk: false, degrees: INFO },
+        DemoCourse { folder: "Softwareprojekt", name: "Softwareprojekt", ects: 9, grade: None, uebk: false, degrees: INFO },
+    ]),
+];
+
+impl DemoService {
+    pub fn run(command: DemoCommands) -> ServiceResult {
+        match command {
+            DemoCommands::Create { path } => Self::create(path),
+        }
+    }
+
+    fn create(path: PathBuf) -> ServiceResult {
+        if path.exists()
+            && path
+                .read_dir()
+                .with_context(|| anyhow!("Failed to read directory: {}", path.display()))?
+                .next()
+                .is_some()
+        {
+            bail!(
+                "The directory '{}' is not empty. Please provide a new or empty directory",
+                path.display()
+            );
+        }
+        effects::create_dir_all(&path)
+            .with_context(|| anyhow!("Failed to create directory: {}", path.display()))?;
+
+        let today = Date::today();
+        for (semester, courses) in SEMESTERS.iter() {
+            let semester_path = path.join(semester);
+            effects::create_dir(&semester_path).with_context(|| {
+                anyhow!("Failed to create semester at: {}", semester_path.display())
+            })?;
+            for course in courses {
+                let content = Self::course_toml(course, &today);
+                write(
+                    &semester_path.join(course.folder).join("course.toml"),
+                    &content,
+                )?;
+            }
+        }
+        write(
+            &path.join("b03").join(".mm"),
+            &format!("version = {}\nactive_course = \"TheoInf\"\n", DATA_VERSION),
+        )?;
+        write(
+            &path.join(".mm"),
+            &format!("version = {}\nactive_semester = \"b03\"\n", DATA_VERSION),
+        )?;
+
+        let msg = format!("A demo store has been created at: {}", path.display()).success();
+        let info = format!(
+            "Set entry_point = \"{}\" in your config file to explore it",
+            path.display()
+        )
+        .info();
+        Ok(msg.chain("".line()).chain(info))
+    }
+
+    fn course_toml(course: &DemoCourse, today: &Date) -> String {
+        let degrees = course
+            .degrees
+            .iter()
+            .map(|it| format!("\"{}\"", it))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut content = format!(
+            "version = {}\nname = \"{}\"\nects = {}\ndegrees = [{}]\n",
+            DATA_VERSION, course.name, course.ects, degrees
+        );
+        let related = match course.folder {
+            "Analysis2" => Some("Analysis I"),
+            "Info2" => Some("Informatik I"),
+            _ => None,
+        };
+        if course.folder == "TheoInf" {
+            content.push_str("kind = \"latex\"\n");
+        }
+        if let Some(related) = related {
+            content.push_str(&format!("related = [\"{}\"]\n", related));
+        }
+        if let Some(grade) = course.grade {
+            content.push_str(&format!("grade = {:.1}\nlocked = true\n", grade));
+        }
+        if course.uebk {
+            content.push_str("\"übK\" = true\n");
+        }
+
+        let date = |days: i64| today.add_days(days);
+        let extra = match course.folder {
+            "Betriebssysteme" => format!(
+                "\n[[exams]]\ndate = \"{}\"\nlocation = \"Hörsaal 1\"\nattempt = 1\n\n[[slots]]\nweekday = \"tuesday\"\nstart = \"10:15\"\nend = \"11:45\"\nroom = \"Hörsaal 1\"\n",
+                date(24)
+            ),
+            "TheoInf" => format!(
+                "\n[[tasks]]\ntitle = \"Übungsblatt 4\"\ndue = \"{}\"\n\n[[tasks]]\ntitle = \"Pumping-Lemma wiederholen\"\n\n[[tasks]]\ntitle = \"Übungsblatt 3\"\ndue = \"{}\"\ndone = true\n\n[[exams]]\ndate = \"{}\"\nlocation = \"Audimax\"\nattempt = 1\n\n[[slots]]\nweekday = \"monday\"\nstart = \"08:15\"\nend = \"09:45\"\nroom = \"Audimax\"\n\n[[slots]]\nweekday = \"thursday\"\nstart = \"14:15\"\nend = \"15:45\"\nroom = \"Seminarraum 3\"\nkind = \"tutorial\"\n",
+                date(2),
+                date(-5),
+                date(38)
+            ),
+            "ML" => format!(
+                "\n[[deadlines]]\ntitle = \"Assignment 2\"\ndate = \"{}\"\nkind = \"exercise\"\n\n[[deadlines]]\ntitle = \"Assignment 1\"\ndate = \"{}\"\nkind = \"exercise\"\n\n[[slots]]\nweekday = \"wednesday\"\nstart = \"12:15\"\nend = \"13:45\"\nroom = \"Hörsaal 2\"\n",
+                date(6),
+                date(-2)
+            ),
+            "Softwareprojekt" => format!(
+                "\n[[tasks]]\ntitle = \"Sprint Review vorbereiten\"\ndue = \"{}\"\n\n[[deadlines]]\ntitle = \"Abschlusspräsentation\"\ndate = \"{}\"\nkind = \"project\"\n",
+                date(1),
+                date(12)
+            ),
+            "Analysis2" => format!(
+                "\n[[exams]]\ndate = \"{}\"\nattempt = 1\ngrade = 5.0\n\n[[exams]]\ndate = \"{}\"\nattempt = 2\ngrade = 2.7\n",
+                date(-250),
+                date(-190)
+            ),
+            _ => String::new(),
+        };
+        content.push_str(&extra);
+        content
+    }
+}
+
+fn write(path: &Path, content: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        effects::create_dir_all(parent)
+            .with_context(|| anyhow!("Failed to create directory: {}", parent.display()))?;
+    }
+    effects::write(path, content)
+        .with_context(|| anyhow!("Failed to write file: {}", path.display()))
+}
@@ -0,0 +1,64 @@
+use std::path::Path;
+
+use crate::{service::format::IntoFormatType, StoreProvider};
+
+use super::ServiceResult;
+
+/// Prints the active context as shell variables for `eval "$(mm env)"`. Variables without value
+/// are unset, so evaluating the output again after switching never leaves stale values behind.
+pub(super) struct EnvService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> EnvService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self) -> ServiceResult {
+        let entry_point = self.store.entry_point();
+        let semester = self.store.current_semester();
+        let course = semester.as_ref().and_then(|it| it.active_course());
+
+        let variables = [
+            ("MM_ENTRY_POINT", Some(path(&entry_point))),
+            (
+                "MM_SEMESTER",
+                semester.as_ref().map(|it| it.path().name().to_string()),
+            ),
+            (
+                "MM_SEMESTER_PATH",
+                semester.as_ref().map(|it| path(it.path().path())),
+            ),
+            (
+                "MM_COURSE",
+                course.as_ref().map(|it| it.path().name().to_string()),
+            ),
+            ("MM_COURSE_NAME", course.as_ref().map(|it| it.name())),
+            ("MM_COURSE_PATH", course.as_ref().map(|it| path(it.path()))),
+        ];
+        let lines = variables
+            .into_iter()
+            .map(|(name, value)| match value {
+                Some(value) => format!("export {}={}", name, quote(&value)),
+                None => format!("unset {}", name),
+            })
+            .collect::<Vec<_>>();
+        Ok(lines.join("\n").raw())
+    }
+}
+
+fn path(path: &Path) -> String {
+    path.display().to_string()
+}
+
+/// Single quotes a value for POSIX shells.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
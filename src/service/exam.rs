@@ -0,0 +1,203 @@
+use anyhow::bail;
+use colored::Colorize;
+
+use crate::{
+    cli::ExamCommands,
+    domain::{Course, Date, Exam},
+    service::format::{FormatAlignment, FormatType, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, ServiceResult};
+
+pub(super) struct ExamService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s mut Store,
+}
+
+impl<'s, Store> ExamService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s mut Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&mut self, command: Option<ExamCommands>) -> ServiceResult {
+        let command = command.unwrap_or(ExamCommands::List { all: false });
+        match command {
+            ExamCommands::List { all } => self.list(all),
+            ExamCommands::Add {
+                course,
+                date,
+                location,
+                attempt,
+            } => self.add(course, date, location, attempt),
+            ExamCommands::Grade {
+                course,
+                grade,
+                attempt,
+                force,
+            } => self.grade(course, grade, attempt, force),
+            ExamCommands::Attempts { course } => self.attempts(course),
+        }
+    }
+
+    fn list(&self, all: bool) -> ServiceResult {
+        let today = Date::today();
+        let courses = self.store.courses().collect::<Vec<_>>();
+        let mut exams = courses
+            .iter()
+            .flat_map(|course| course.exams().iter().map(move |exam| (course, exam)))
+            .filter(|(_, exam)| all || exam.date() >= today)
+            .collect::<Vec<_>>();
+        exams.sort_by_key(|(_, exam)| exam.date());
+
+        if exams.is_empty() {
+            return Ok("No upcoming exams found".info());
+        }
+
+        let dates = exams
+            .iter()
+            .map(|(_, exam)| exam.date().to_string())
+            .collect::<Vec<_>>();
+        let names = exams
+            .iter()
+            .map(|(course, _)| course.name())
+            .collect::<Vec<_>>();
+        let attempts = exams
+            .iter()
+            .map(|(_, exam)| exam.attempt().to_string())
+            .collect::<Vec<_>>();
+        let locations = exams
+            .iter()
+            .map(|(_, exam)| exam.location().unwrap_or_default().to_string())
+            .collect::<Vec<_>>();
+        let grades = exams
+            .iter()
+            .map(|(_, exam)| {
+                exam.grade()
+                    .map(|it| FormatType::grade(it, 1))
+                    .unwrap_or_default()
+            })
+            .collect::<Vec<_>>();
+
+        let table = TableBuilder::new()
+            .column("Date", dates, FormatAlignment::Left)
+            .column("Course", names, FormatAlignment::Left)
+            .column("Attempt", attempts, FormatAlignment::Right)
+            .column("Location", locations, FormatAlignment::Left)
+            .column("Grade", grades, FormatAlignment::Right)
+            .build();
+        Ok(table)
+    }
+
+    fn add(
+        &mut self,
+        course: String,
+        date: Date,
+        location: Option<String>,
+        attempt: Option<u8>,
+    ) -> ServiceResult {
+        let mut course = resolve_course(self.store, Some(&course))?;
+        let attempt = attempt.unwrap_or_else(|| course.next_attempt());
+        course.add_exam(Exam::new(date, location, attempt))?;
+        let msg = format!(
+            "Exam (attempt {}) for '{}' on {} has been added",
+            attempt,
+            course.name(),
+            date
+        );
+        Ok(msg.success())
+    }
+
+    fn grade(
+        &mut self,
+        course: String,
+        grade: f32,
+        attempt: Option<u8>,
+        force: bool,
+    ) -> ServiceResult {
+        let mut course = resolve_course(self.store, Some(&course))?;
+        if course.is_locked() && !force {
+            bail!(
+                "Course '{}' is locked. Use --force to record the exam result anyway",
+                course.name()
+            );
+        }
+        let name = course.name();
+        let policy = self.store.attempt_policy();
+        let exam = course.set_exam_grade(attempt, grade, policy)?.clone();
+        let msg = if exam.passed() {
+            let final_grade = course.grade().unwrap_or(grade);
+            format!(
+                "Exam (attempt {}) of '{}' passed with {:.1}. The final grade is {:.1}",
+                exam.attempt(),
+                name,
+                grade,
+                final_grade
+            )
+        } else {
+            format!(
+                "Exam (attempt {}) of '{}' has been graded with {:.1}",
+                exam.attempt(),
+                name,
+                grade
+            )
+        };
+        Ok(msg.success())
+    }
+
+    /// Attempts of a course in order with the one counting towards the final grade marked.
+    fn attempts(&self, course: Option<String>) -> ServiceResult {
+        let course = resolve_course(self.store, course.as_deref())?;
+        let exams = course.exams();
+        if exams.is_empty() {
+            let msg = format!("No exam attempts registered for '{}'", course.name()).info();
+            return Ok(msg);
+        }
+        let counting = self.store.attempt_policy().counting(exams);
+
+        let mut attempts = Vec::new();
+        let mut dates = Vec::new();
+        let mut grades = Vec::new();
+        let mut results = Vec::new();
+        for exam in exams {
+            attempts.push(exam.attempt().to_string());
+            dates.push(exam.date().to_string());
+            grades.push(
+                exam.grade()
+                    .map(|it| FormatType::grade(it, 1))
+                    .unwrap_or_default(),
+            );
+            let result = match exam.grade() {
+                None => "pending".normal(),
+                Some(_) if counting == Some(exam) => "counts".green().bold(),
+                Some(_) if exam.passed() => "passed".green(),
+                Some(_) => "failed".red(),
+            };
+            results.push(result.to_string());
+        }
+        let table = TableBuilder::new()
+            .column("Attempt", attempts, FormatAlignment::Right)
+            .column("Date", dates, FormatAlignment::Left)
+            .column("Grade", grades, FormatAlignment::Right)
+            .column("Result", results, FormatAlignment::Left)
+            .build();
+        Ok(format!("Exam attempts of '{}'", course.name())
+            .line()
+            .block(table))
+    }
+}
+
+/// The next upcoming exam across all courses.
+pub(super) fn next_exam(courses: &[Course], today: &Date) -> Option<(Course, Exam)> {
+    courses
+        .iter()
+        .flat_map(|course| course.exams().iter().map(move |exam| (course, exam)))
+        .filter(|(_, exam)| exam.date() >= *today && exam.grade().is_none())
+        .min_by_key(|(_, exam)| exam.date())
+        .map(|(course, exam)| (course.clone(), exam.clone()))
+}
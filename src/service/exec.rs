@@ -0,0 +1,34 @@
+use std::process::Command;
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::domain::{Config, Store};
+
+use super::reference::resolve_course;
+
+/// Runs a command with the course folder as working directory.
+pub(super) struct ExecService;
+
+impl ExecService {
+    /// Returns the exit code of the command. Commands killed by a signal exit with 128 + signal
+    /// like in shells.
+    pub fn run(course: Option<String>, command: Vec<String>) -> Result<i32> {
+        let store = Store::new(Config::new()?)?;
+        let course = resolve_course(&store, course.as_deref())?;
+        let (program, args) = command
+            .split_first()
+            .ok_or_else(|| anyhow!("No command given"))?;
+
+        let status = Command::new(program)
+            .args(args)
+            .current_dir(course.path().to_path_buf())
+            .status()
+            .with_context(|| anyhow!("Failed to run '{}'", program))?;
+
+        #[cfg(unix)]
+        if let Some(signal) = std::os::unix::process::ExitStatusExt::signal(&status) {
+            return Ok(128 + signal);
+        }
+        Ok(status.code().unwrap_or(1))
+    }
+}
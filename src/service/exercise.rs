@@ -0,0 +1,271 @@
+use anyhow::{anyhow, bail};
+use colored::Colorize;
+
+use crate::{
+    cli::ExerciseCommands,
+    domain::{percentage, Course, Date, Deadline, DeadlineKind, Exercise, SheetPoints},
+    service::format::{
+        DialogEntry, DialogOutput, FormatAlignment, FormatService, IntoFormatType, TableBuilder,
+    },
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, template::render, ServiceResult};
+
+pub(super) struct ExerciseService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s mut Store,
+}
+
+impl<'s, Store> ExerciseService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s mut Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(
+        &mut self,
+        course: Option<String>,
+        command: Option<ExerciseCommands>,
+    ) -> ServiceResult {
+        let command = command.unwrap_or(ExerciseCommands::List);
+        let course = resolve_course(self.store, course.as_deref())?;
+        match command {
+            ExerciseCommands::List => self.list(course),
+            ExerciseCommands::Add { name, due } => self.add(course, name, due),
+            ExerciseCommands::Fetch { name } => self.fetch(course, name),
+            ExerciseCommands::Remove { name } => self.remove(course, name),
+            ExerciseCommands::Move { from, to } => match to {
+                Some(to) => self.rename(course, Some(from), to),
+                None => self.rename(course, None, from),
+            },
+            ExerciseCommands::Points { sheet, points } => self.points(course, sheet, points),
+        }
+    }
+
+    /// Sheet folders and recorded points with the running percentage.
+    fn list(&self, course: Course) -> ServiceResult {
+        let exercises = Exercise::list(course.path())?;
+        let mut sheets = exercises
+            .iter()
+            .map(|it| it.name().to_string())
+            .chain(course.points().iter().map(|it| it.sheet().to_string()))
+            .collect::<Vec<_>>();
+        sheets.sort();
+        sheets.dedup();
+        if sheets.is_empty() {
+            let msg = format!("No exercises found for course '{}'", course.name()).info();
+            return Ok(msg);
+        }
+
+        let mut points = Vec::new();
+        let mut percentages = Vec::new();
+        for sheet in &sheets {
+            match course.points().iter().find(|it| it.sheet() == sheet) {
+                Some(it) => {
+                    points.push(format!("{} / {}", it.points(), it.max()));
+                    percentages.push(format!("{:.0}%", it.points() / it.max() * 100.0));
+                }
+                None => {
+                    points.push("-".into());
+                    percentages.push("".into());
+                }
+            }
+        }
+        let body = TableBuilder::new()
+            .column("Sheet", sheets, FormatAlignment::Left)
+            .column("Points", points, FormatAlignment::Right)
+            .column("%", percentages, FormatAlignment::Right)
+            .build();
+        let mut msg = course.name().line().block(body);
+        if let Some(summary) = admission_summary(&course) {
+            msg = msg.chain("".line()).chain(summary.line());
+        }
+        Ok(msg)
+    }
+
+    /// Creates the sheet folder from the `exercise_template` of the course. Templates support the
+    /// placeholders `{{course}}`, `{{semester}}`, `{{sheet}}`, `{{nr}}`, `{{due}}` and `{{date}}`.
+    fn add(
+        &mut self,
+        mut course: Course,
+        name: Option<String>,
+        due: Option<Date>,
+    ) -> ServiceResult {
+        let template = match course.exercise_template() {
+            Some(template) => {
+                let dir = self.store.template_dir().join(template);
+                if !dir.is_dir() {
+                    bail!("No exercise template found at: {}", dir.display());
+                }
+                Some(dir)
+            }
+            None => None,
+        };
+        let name = match name {
+            Some(name) => name,
+            None => Exercise::next_name(course.path())?,
+        };
+        let exercise = Exercise::create(course.path(), &name)?;
+
+        let mut files = 0;
+        if let Some(dir) = template {
+            let semester = course
+                .path()
+                .parent()
+                .and_then(|it| it.file_name())
+                .map(|it| it.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let nr = exercise
+                .number()
+                .map(|it| it.to_string())
+                .unwrap_or_default();
+            let due = due.map(|it| it.to_string()).unwrap_or_default();
+            let date = Date::today().to_string();
+            let course_name = course.name().to_string();
+            let variables = [
+                ("course", course_name.as_str()),
+                ("semester", semester.as_str()),
+                ("sheet", exercise.name()),
+                ("nr", nr.as_str()),
+                ("due", due.as_str()),
+                ("date", date.as_str()),
+            ];
+            files = exercise.apply_template(&dir, |text| render(text, &variables))?;
+        }
+        if let Some(due) = due {
+            let title = format!("Exercise {}", exercise.name());
+            course.add_deadline(Deadline::new(title, due, DeadlineKind::Exercise))?;
+        }
+
+        let msg = match files {
+            0 => format!(
+                "Exercise '{}' has been created at: {}",
+                exercise.name(),
+                exercise.path().display()
+            ),
+            _ => format!(
+                "Exercise '{}' has been created with {} files from the template at: {}",
+                exercise.name(),
+                files,
+                exercise.path().display()
+            ),
+        }
+        .success();
+        let Some(pattern) = course.exercise_url() else {
+            return Ok(msg);
+        };
+        // The sheet folder is kept when the download fails, e.g. if it is not published yet.
+        match exercise.fetch(pattern) {
+            Ok(path) => Ok(msg
+                .chain("".line())
+                .chain(format!("Downloaded sheet to: {}", path.display()).success())),
+            Err(err) => Ok(msg.chain("".line()).chain(format!("{:#}", err).error())),
+        }
+    }
+
+    fn fetch(&mut self, course: Course, name: Option<String>) -> ServiceResult {
+        let Some(pattern) = course.exercise_url() else {
+            bail!("Course '{}' has no exercise_url configured", course.name());
+        };
+        let exercise = match name {
+            Some(name) => Exercise::find(course.path(), &name)?,
+            None => Exercise::list(course.path())?
+                .pop()
+                .ok_or_else(|| anyhow!("No exercises found for course '{}'", course.name()))?,
+        };
+        let path = exercise.fetch(pattern)?;
+        Ok(format!("Downloaded sheet to: {}", path.display()).success())
+    }
+
+    fn remove(&mut self, course: Course, name: String) -> ServiceResult {
+        let exercise = Exercise::find(course.path(), &name)?;
+        let dialog = vec![DialogEntry::YesNoInput(format!(
+            "Are you sure that you want to permanently remove exercise '{}' with all its content?",
+            exercise.name()
+        ))];
+        let Some(response) = FormatService::dialog(dialog) else {
+            return Ok("Operation has been canceled".info());
+        };
+        let res = response
+            .first()
+            .ok_or_else(|| anyhow!("Dialog has not returned not the specified output"))?;
+        let DialogOutput::YesNo(cond) = res else {
+            bail!("Invalid input");
+        };
+        if !cond {
+            return Ok("Operation has been canceled".info());
+        }
+        let name = exercise.name().to_string();
+        exercise.remove()?;
+        Ok(format!("Exercise '{}' has been removed", name).success())
+    }
+
+    fn rename(&mut self, course: Course, from: Option<String>, to: String) -> ServiceResult {
+        let exercise = match from {
+            Some(from) => Exercise::find(course.path(), &from)?,
+            None => Exercise::list(course.path())?
+                .pop()
+                .ok_or_else(|| anyhow!("No exercises found for course '{}'", course.name()))?,
+        };
+        let from = exercise.name().to_string();
+        exercise.rename(&to)?;
+        Ok(format!("Exercise '{}' has been renamed to '{}'", from, to).success())
+    }
+
+    fn points(&mut self, mut course: Course, sheet: String, points: String) -> ServiceResult {
+        let sheet = match Exercise::find(course.path(), &sheet) {
+            Ok(exercise) => exercise.name().to_string(),
+            Err(_) => sheet,
+        };
+        let points = SheetPoints::parse(&sheet, &points)?;
+        course.record_points(points.clone())?;
+        let msg = format!(
+            "Recorded {} / {} points for '{}' of '{}'",
+            points.points(),
+            points.max(),
+            sheet,
+            course.name()
+        )
+        .success();
+        match admission_summary(&course) {
+            Some(summary) => Ok(msg.chain("".line()).chain(summary.line())),
+            None => Ok(msg),
+        }
+    }
+}
+
+/// Running percentage of the exercise points compared to the admission threshold.
+fn admission_summary(course: &Course) -> Option<String> {
+    let reached = percentage(course.points())?;
+    let total = course.points().iter().map(|it| it.points()).sum::<f32>();
+    let max = course.points().iter().map(|it| it.max()).sum::<f32>();
+    let summary = format!("Total: {} / {} points ({:.1}%)", total, max, reached);
+    Some(match course.admission() {
+        Some(required) if reached < required => {
+            format!("{}, {}% required for admission", summary, required)
+                .red()
+                .to_string()
+        }
+        Some(required) => format!("{}, {}% required for admission", summary, required)
+            .green()
+            .to_string(),
+        None => summary,
+    })
+}
+
+/// Courses whose exercise points are below the admission threshold.
+pub(super) fn admission_at_risk(courses: &[Course]) -> Vec<(&Course, f32, f32)> {
+    courses
+        .iter()
+        .filter_map(|course| {
+            let required = course.admission()?;
+            let reached = percentage(course.points())?;
+            (reached < required).then_some((course, reached, required))
+        })
+        .collect()
+}
@@ -0,0 +1,207 @@
+use std::{collections::BTreeMap, path::PathBuf};
+
+use anyhow::{anyhow, Context};
+
+use crate::{
+    cli::{ExportCommands, GraphFormatDO},
+    domain::{effects, Course},
+    service::format::IntoFormatType,
+    StoreProvider,
+};
+
+use super::ServiceResult;
+
+pub(super) struct ExportService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+/// Nodes and edges of the study structure, independent of the output format.
+#[derive(Default)]
+struct Graph {
+    /// id, label, kind
+    nodes: Vec<(String, String, NodeKind)>,
+    /// from, to, kind
+    edges: Vec<(String, String, EdgeKind)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+    Degree,
+    Semester,
+    Course,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EdgeKind {
+    Contains,
+    Related,
+    Retake,
+}
+
+impl<'s, Store> ExportService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self, command: ExportCommands) -> ServiceResult {
+        match command {
+            ExportCommands::Graph { format, out } => self.graph(format, out),
+        }
+    }
+
+    /// Exports degrees → semesters → courses. Courses with the same name in several semesters
+    /// are connected by retake edges, references in `related` by related edges.
+    fn graph(&self, format: GraphFormatDO, out: Option<PathBuf>) -> ServiceResult {
+        let mut semesters = self.store.semesters().collect::<Vec<_>>();
+        semesters.sort();
+
+        let mut graph = Graph::default();
+        let mut courses: Vec<(String, String, Course)> = Vec::new();
+        let mut degrees: BTreeMap<String, String> = BTreeMap::new();
+
+        for semester in semesters {
+            let semester_name = semester.path().name().to_string();
+            let semester_id = format!("sem_{}", node_id(&semester_name));
+            graph.nodes.push((
+                semester_id.clone(),
+                semester_name.clone(),
+                NodeKind::Semester,
+            ));
+
+            let mut semester_courses = self
+                .store
+                .semester_courses(semester.clone())
+                .collect::<Vec<_>>();
+            semester_courses.sort_by_key(|it| it.name());
+            for course in semester_courses {
+                let course_id = format!(
+                    "course_{}_{}",
+                    node_id(&semester_name),
+                    node_id(course.path().name())
+                );
+                graph
+                    .nodes
+                    .push((course_id.clone(), course.name(), NodeKind::Course));
+                graph
+                    .edges
+                    .push((semester_id.clone(), course_id.clone(), EdgeKind::Contains));
+
+                for degree in course.degrees() {
+                    let next_id = format!("deg_{}", degrees.len());
+                    let degree_id = degrees.entry(degree.clone()).or_insert(next_id).clone();
+                    let edge = (degree_id, semester_id.clone(), EdgeKind::Contains);
+                    if !graph.edges.contains(&edge) {
+                        graph.edges.push(edge);
+                    }
+                }
+                courses.push((course_id, semester_name.clone(), course));
+            }
+        }
+        for (degree, id) in &degrees {
+            graph
+                .nodes
+                .push((id.clone(), degree.clone(), NodeKind::Degree));
+        }
+
+        // courses are ordered by semester, so every later course with the same name is a retake
+        for (idx, (id, _, course)) in courses.iter().enumerate() {
+            if let Some((next, _, _)) = courses[idx + 1..]
+                .iter()
+                .find(|(_, _, it)| it.name() == course.name())
+            {
+                graph
+                    .edges
+                    .push((id.clone(), next.clone(), EdgeKind::Retake));
+            }
+            for reference in course.related() {
+                let target = courses.iter().find(|(_, semester, it)| {
+                    it.name() == *reference
+                        || it.path().name() == reference
+                        || format!("{}/{}", semester, it.path().name()) == *reference
+                });
+                if let Some((target, _, _)) = target {
+                    graph
+                        .edges
+                        .push((id.clone(), target.clone(), EdgeKind::Related));
+                }
+            }
+        }
+
+        let content = match format {
+            GraphFormatDO::Dot => graph.dot(),
+            GraphFormatDO::Mermaid => graph.mermaid(),
+        };
+        match out {
+            Some(path) => {
+                effects::write(&path, content)
+                    .with_context(|| anyhow!("Failed to write graph to: {}", path.display()))?;
+                Ok(format!("Graph has been exported to: {}", path.display()).success())
+            }
+            None => Ok(content.raw()),
+        }
+    }
+}
+
+impl Graph {
+    fn dot(&self) -> String {
+        let mut out = String::from("digraph studies {\n    rankdir=LR;\n");
+        for (id, label, kind) in &self.nodes {
+            let shape = match kind {
+                NodeKind::Degree => "doubleoctagon",
+                NodeKind::Semester => "folder",
+                NodeKind::Course => "box",
+            };
+            out.push_str(&format!(
+                "    {} [label=\"{}\", shape={}];\n",
+                id,
+                label.replace('\\', "\\\\").replace('"', "\\\""),
+                shape
+            ));
+        }
+        for (from, to, kind) in &self.edges {
+            let style = match kind {
+                EdgeKind::Contains => "",
+                EdgeKind::Related => " [style=dashed, label=\"related\"]",
+                EdgeKind::Retake => " [color=red, label=\"retake\"]",
+            };
+            out.push_str(&format!("    {} -> {}{};\n", from, to, style));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    fn mermaid(&self) -> String {
+        let mut out = String::from("graph LR\n");
+        for (id, label, kind) in &self.nodes {
+            let label = label.replace('"', "#quot;");
+            let node = match kind {
+                NodeKind::Degree => format!("{}{{{{\"{}\"}}}}", id, label),
+                NodeKind::Semester => format!("{}[/\"{}\"/]", id, label),
+                NodeKind::Course => format!("{}[\"{}\"]", id, label),
+            };
+            out.push_str(&format!("    {}\n", node));
+        }
+        for (from, to, kind) in &self.edges {
+            let arrow = match kind {
+                EdgeKind::Contains => "-->",
+                EdgeKind::Related => "-. related .->",
+                EdgeKind::Retake => "== retake ==>",
+            };
+            out.push_str(&format!("    {} {} {}\n", from, arrow, to));
+        }
+        out
+    }
+}
+
+/// Identifier safe for both Graphviz and Mermaid.
+fn node_id(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
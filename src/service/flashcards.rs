@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use anyhow::{bail, Context};
+
+use crate::{
+    cli::FlashcardsCommands,
+    domain::{effects, to_anki_tsv, Course, Flashcard},
+    service::format::IntoFormatType,
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, ServiceResult};
+
+pub(super) struct FlashcardsService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> FlashcardsService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self, course: Option<String>, command: FlashcardsCommands) -> ServiceResult {
+        let course = resolve_course(self.store, course.as_deref())?;
+        match command {
+            FlashcardsCommands::Export { output } => self.export(course, output),
+        }
+    }
+
+    fn export(&self, course: Course, output: Option<PathBuf>) -> ServiceResult {
+        let cards = Flashcard::collect(course.path())?;
+        if cards.is_empty() {
+            bail!(
+                "No flashcards found for course '{}'. Write them as 'Q:' and 'A:' lines in notes",
+                course.name()
+            );
+        }
+        let path = output.unwrap_or_else(|| course.path().join("flashcards.tsv"));
+        effects::write(&path, to_anki_tsv(&cards, course.path().name()))
+            .with_context(|| format!("Failed to write: {}", path.display()))?;
+        let msg = format!(
+            "Exported {} flashcards to: {}. Import the file in Anki with File > Import",
+            cards.len(),
+            path.display()
+        );
+        Ok(msg.success())
+    }
+}
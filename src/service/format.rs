@@ -1,92 +1,93 @@
-use std::{fmt::Display, rc::Rc};
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{IsTerminal, Write},
+    process::{Command, Stdio},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
 
 use colored::Colorize;
+use serde::Serialize;
+use unicode_width::UnicodeWidthChar;
 
-pub(super) struct FormatService;
+use crate::domain::Theme;
 
-#[macro_export]
-macro_rules! table {
-    ($($header:expr),+ ; $($columns:expr),+ ; $($alignment:expr),+) => {
-        {
-            use $crate::service::format::FormatType;
-            use std::cmp::max;
-
-            // ensure same length for input
-            let header = [$($header),+];
-            let alignment = [$($alignment),+];
-            let mut columns = [$($columns),+];
-            assert!(header.len() == alignment.len() && header.len() == columns.len(), "Header, columns and alignment must have the same length");
-
-            // Get len of all columns
-            let max_len = columns.iter().map(|col| col.len()).max().unwrap_or(0);
-
-            // Resize all columns to the same length
-            columns.iter_mut().for_each(|col| col.resize(max_len, "".into()));
-
-            //  Calculate max widths for each column
-            let max_len_columns = columns.iter().enumerate().map(|(idx, col)|
-                {
-                    let len = col.iter().map(|it| it.len()).max().unwrap_or(0);
-                    max(header[idx].len(), len)
-                }
-            ).collect::<Vec<_>>();
+use super::{i18n::tr, ui::select};
 
-            let header_padding = (0,0);
-            let padding = (0,0);
+pub(super) struct FormatService;
 
-            // Format Header to align with max columns width
-            let mut header_formatted = Vec::new();
-            for i in 0..header.len() {
-                 let header = FormatType::align(&header[i], *&alignment[i], max_len_columns[i], header_padding);
-                 header_formatted.push(header);
-            }
+/// Set by `mm --quiet`, see [FormatService::set_quiet].
+static QUIET: AtomicBool = AtomicBool::new(false);
 
-            let header = header_formatted.join(" | ");
-            let mut acc = FormatType::RawLine(header);
-
-            for i in 0..max_len {
-                let mut row = Vec::new();
-                for j in 0..columns.len() {
-                    let column = FormatType::align(&columns[j][i], alignment[j], max_len_columns[j], padding);
-                    row.push(column);
-                }
-                let row = row.join("   ");
-                acc = acc.chain(FormatType::RawLine(row));
-            }
-            acc
-        }
-    };
-}
+/// Set from `[theme]` of the config, see [FormatService::set_theme]. The default theme before.
+static THEME: Mutex<Option<Theme>> = Mutex::new(None);
 
 impl FormatService {
     pub fn run<T: FormatTypeable>(msg: T) {
-        println!("{}", msg.format());
+        let msg = match QUIET.load(Ordering::Relaxed) {
+            true => match msg.format().without_messages() {
+                Some(msg) => msg,
+                None => return,
+            },
+            false => msg.format(),
+        };
+        let msg = msg.translate();
+        // ignore closed pipes, e.g. when piping into `head`
+        let _ = writeln!(std::io::stdout(), "{}", msg);
+    }
+
+    /// Leaves out success and info messages in [Self::run], see `mm --quiet`.
+    pub fn set_quiet(quiet: bool) {
+        QUIET.store(quiet, Ordering::Relaxed);
+    }
+
+    /// Colors of messages and grades, see `[theme]` in the config file.
+    pub fn set_theme(theme: Theme) {
+        *THEME.lock().unwrap_or_else(|err| err.into_inner()) = Some(theme);
+    }
+
+    fn theme() -> Theme {
+        THEME
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+            .unwrap_or_default()
     }
 
     /// returns either a vec of [DialogOutput] which contain the user input or None if the dialog was canceled
+    /// or stdin has been closed
     pub fn dialog(dialog: Vec<DialogEntry>) -> Option<Vec<DialogOutput>> {
         let mut output = Vec::new();
         for entry in dialog {
             match entry {
                 DialogEntry::Message(msg) => {
-                    println!("{}", msg);
+                    println!("{}", tr(&msg));
                     continue;
                 }
                 DialogEntry::YesNoInput(msg) => {
                     let out = loop {
-                        println!("{} [y/n] (q to cancel)", msg);
+                        println!("{} {}", tr(&msg), tr("[y/n] (q to cancel)"));
                         let mut input = String::new();
-                        if std::io::stdin().read_line(&mut input).is_err() {
-                            println!("Failed to read input");
-                            continue;
+                        match std::io::stdin().read_line(&mut input) {
+                            // stdin has been closed, e.g. when run from a script
+                            Ok(0) => return None,
+                            Ok(_) => {}
+                            Err(_) => {
+                                println!("{}", tr("Failed to read input"));
+                                continue;
+                            }
                         }
 
                         match input.trim().to_lowercase().as_str() {
-                            "y" | "yes" => break DialogOutput::YesNo(true),
+                            "y" | "yes" | "j" | "ja" => break DialogOutput::YesNo(true),
                             "n" | "no" => break DialogOutput::YesNo(false),
                             "q" => return None,
                             _ => {
-                                println!("Invalid input, please enter 'y' or 'n'");
+                                println!("{}", tr("Invalid input, please enter 'y' or 'n'"));
                                 continue;
                             }
                         }
@@ -95,11 +96,16 @@ impl FormatService {
                 }
                 DialogEntry::NumberInput(msg) => {
                     let out = loop {
-                        println!("{} (q to cancel)", msg);
+                        println!("{} {}", tr(&msg), tr("(q to cancel)"));
                         let mut input = String::new();
-                        if std::io::stdin().read_line(&mut input).is_err() {
-                            println!("Failed to read input");
-                            continue;
+                        match std::io::stdin().read_line(&mut input) {
+                            // stdin has been closed, e.g. when run from a script
+                            Ok(0) => return None,
+                            Ok(_) => {}
+                            Err(_) => {
+                                println!("{}", tr("Failed to read input"));
+                                continue;
+                            }
                         }
 
                         let input = input.trim();
@@ -110,43 +116,180 @@ impl FormatService {
                         match input.parse::<usize>() {
                             Ok(number) => break DialogOutput::Number(number),
                             Err(_) => {
-                                println!("Invalid number, please try again");
+                                println!("{}", tr("Invalid number, please try again"));
+                                continue;
+                            }
+                        }
+                    };
+                    output.push(out);
+                }
+                DialogEntry::TextInput(input) => {
+                    let out = loop {
+                        match &input.default {
+                            Some(default) => {
+                                println!(
+                                    "{} [{}] {}",
+                                    tr(&input.prompt),
+                                    default,
+                                    tr("(q to cancel)")
+                                )
+                            }
+                            None => println!("{} {}", tr(&input.prompt), tr("(q to cancel)")),
+                        }
+                        let mut text = String::new();
+                        match std::io::stdin().read_line(&mut text) {
+                            // stdin has been closed, e.g. when run from a script
+                            Ok(0) => return None,
+                            Ok(_) => {}
+                            Err(_) => {
+                                println!("{}", tr("Failed to read input"));
+                                continue;
+                            }
+                        }
+
+                        let text = match (text.trim(), &input.default) {
+                            ("q", _) => return None,
+                            ("", Some(default)) => default.clone(),
+                            (text, _) => text.to_string(),
+                        };
+                        match input.validate.as_ref().map_or(Ok(()), |it| it(&text)) {
+                            Ok(()) => break DialogOutput::Text(text),
+                            Err(err) => {
+                                println!("{}", tr(&format!("{}, please try again", err)));
                                 continue;
                             }
                         }
                     };
                     output.push(out);
                 }
+                DialogEntry::Select { prompt, options } => {
+                    if options.is_empty() {
+                        return None;
+                    }
+                    let interactive =
+                        std::io::stdin().is_terminal() && std::io::stdout().is_terminal();
+                    let selected = match interactive {
+                        // falls back to numbers if the terminal can not be put into raw mode
+                        true => match select(&prompt, &options) {
+                            Ok(selected) => selected,
+                            Err(_) => Self::select_by_number(&prompt, &options),
+                        },
+                        false => Self::select_by_number(&prompt, &options),
+                    };
+                    output.push(DialogOutput::Selected(selected?));
+                }
             }
         }
         Some(output)
     }
+
+    /// Numbered list of the options, answered with the number of an option.
+    fn select_by_number(prompt: &str, options: &[String]) -> Option<usize> {
+        println!("{}", tr(prompt));
+        for (idx, option) in options.iter().enumerate() {
+            println!("  {}) {}", idx + 1, tr(option));
+        }
+        loop {
+            println!("{} {}", tr("Choose an option"), tr("(q to cancel)"));
+            let mut input = String::new();
+            match std::io::stdin().read_line(&mut input) {
+                // stdin has been closed, e.g. when run from a script
+                Ok(0) => return None,
+                Ok(_) => {}
+                Err(_) => {
+                    println!("{}", tr("Failed to read input"));
+                    continue;
+                }
+            }
+
+            let input = input.trim();
+            if input.eq_ignore_ascii_case("q") {
+                return None;
+            }
+
+            match input.parse::<usize>() {
+                Ok(number @ 1..) if number <= options.len() => return Some(number - 1),
+                _ => {
+                    let msg = format!(
+                        "Invalid option, please enter a number from 1 to {}",
+                        options.len()
+                    );
+                    println!("{}", tr(&msg));
+                    continue;
+                }
+            }
+        }
+    }
 }
 
-pub(crate) enum DialogEntry {
+pub enum DialogEntry {
     Message(String),
     YesNoInput(String),
     NumberInput(String),
+    TextInput(TextInput),
+    /// One of the options, answered with [DialogOutput::Selected].
+    Select {
+        prompt: String,
+        options: Vec<String>,
+    },
 }
 
-pub(crate) enum DialogOutput {
+/// Prompt for free text, answered with [DialogOutput::Text]. Empty input takes the default value,
+/// input rejected by the validation is asked for again.
+pub struct TextInput {
+    prompt: String,
+    default: Option<String>,
+    validate: Option<Validation>,
+}
+
+/// Check of a [TextInput], returning the reason why the input is invalid.
+type Validation = Box<dyn Fn(&str) -> Result<(), String>>;
+
+impl TextInput {
+    pub fn new(prompt: impl Into<String>) -> Self {
+        TextInput {
+            prompt: prompt.into(),
+            default: None,
+            validate: None,
+        }
+    }
+
+    pub fn default_value(mut self, default: impl Into<String>) -> Self {
+        self.default = Some(default.into());
+        self
+    }
+
+    /// Checks the input, the error is shown before asking again.
+    pub fn validate(mut self, validate: impl Fn(&str) -> Result<(), String> + 'static) -> Self {
+        self.validate = Some(Box::new(validate));
+        self
+    }
+}
+
+pub enum DialogOutput {
     Text(String),
     YesNo(bool),
     Number(usize),
+    /// Index of the selected option.
+    Selected(usize),
 }
 
 #[derive(Debug, Clone)]
-pub(crate) enum FormatType {
+pub enum FormatType {
     Bold(String),
+    /// Undecorated text without a trailing newline.
+    Raw(String),
     RawLine(String),
     Block(Rc<FormatType>, Rc<FormatType>),
     Chain(Vec<FormatType>),
     Success(String),
     Error(String),
     Info(String),
+    Table(Table),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
 pub enum FormatAlignment {
     Left,
     Right,
@@ -165,21 +308,155 @@ impl FormatType {
         self
     }
 
+    /// Returns true if this or any nested element is an [FormatType::Error].
+    /// The output without success and info messages, `None` if nothing is left.
+    fn without_messages(self) -> Option<FormatType> {
+        match self {
+            FormatType::Success(_) | FormatType::Info(_) => None,
+            FormatType::Chain(chain) => {
+                let chain = chain
+                    .into_iter()
+                    .filter_map(FormatType::without_messages)
+                    .collect::<Vec<_>>();
+                // blank lines only separated the messages
+                let is_blank =
+                    |it: &FormatType| matches!(it, FormatType::RawLine(line) if line.is_empty());
+                match chain.iter().all(is_blank) {
+                    true => None,
+                    false => Some(FormatType::Chain(chain)),
+                }
+            }
+            other => Some(other),
+        }
+    }
+
+    /// The messages in the selected language, see [tr]. Raw text is left as is as it is meant
+    /// for scripts.
+    fn translate(self) -> FormatType {
+        match self {
+            FormatType::Bold(msg) => FormatType::Bold(tr(&msg)),
+            FormatType::RawLine(msg) => FormatType::RawLine(tr(&msg)),
+            FormatType::Success(msg) => FormatType::Success(tr(&msg)),
+            FormatType::Error(msg) => FormatType::Error(tr(&msg)),
+            FormatType::Info(msg) => FormatType::Info(tr(&msg)),
+            FormatType::Block(header, content) => {
+                let header = header.as_ref().clone().translate();
+                let content = content.as_ref().clone().translate();
+                header.block(content)
+            }
+            FormatType::Chain(chain) => {
+                FormatType::Chain(chain.into_iter().map(FormatType::translate).collect())
+            }
+            FormatType::Table(mut table) => {
+                table.headers = table.headers.iter().map(|it| tr(it)).collect();
+                FormatType::Table(table)
+            }
+            raw @ FormatType::Raw(_) => raw,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        match self {
+            FormatType::Error(_) => true,
+            FormatType::Block(header, content) => header.is_error() || content.is_error(),
+            FormatType::Chain(chain) => chain.iter().any(|it| it.is_error()),
+            _ => false,
+        }
+    }
+
     pub fn block(self, body: FormatType) -> FormatType {
         FormatType::Block(Rc::new(self), Rc::new(body))
     }
 
+    /// The grade with `decimals` decimals, colored by the grade rules of the theme.
+    pub fn grade(grade: f32, decimals: usize) -> String {
+        let text = format!("{:.*}", decimals, grade);
+        match FormatService::theme().grade(grade) {
+            Some(color) => text.color(color).to_string(),
+            None => text,
+        }
+    }
+
+    /// ECTS enrolled in compared to the budget, e.g. `36 / 30 (6 over)`, colored as error if
+    /// overloaded, as info if underloaded and as success otherwise.
+    pub fn budget(enrolled: u32, budget: u32) -> String {
+        let theme = FormatService::theme();
+        let text = format!("{} / {}", enrolled, budget);
+        if enrolled > budget {
+            let diff = tr(&format!("{} over", enrolled - budget));
+            format!("{} ({})", text, diff).color(theme.error())
+        } else if enrolled < budget {
+            let diff = tr(&format!("{} under", budget - enrolled));
+            format!("{} ({})", text, diff).color(theme.info())
+        } else {
+            text.color(theme.success())
+        }
+        .to_string()
+    }
+
+    /// Columns the text takes up in a terminal. Wide characters like CJK and emoji take two,
+    /// combining accents and color codes none.
+    pub fn width(str: &str) -> usize {
+        let mut width = 0;
+        let mut chars = str.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                // skip the escape sequence up to its final letter, e.g. `\x1b[32m`
+                chars.by_ref().find(|it| it.is_ascii_alphabetic());
+                continue;
+            }
+            width += c.width().unwrap_or(0);
+        }
+        width
+    }
+
+    /// Cuts the text to `max_len` columns and marks the cut with an ellipsis. Escape sequences
+    /// are kept, so colors are still reset at the end.
+    pub fn truncate(str: &str, max_len: usize) -> String {
+        if Self::width(str) <= max_len {
+            return str.to_string();
+        }
+        let mut result = String::new();
+        let mut width = 0;
+        let mut cut = false;
+        let mut chars = str.chars();
+        while let Some(c) = chars.next() {
+            if c == '\x1b' {
+                result.push(c);
+                for c in chars.by_ref() {
+                    result.push(c);
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+                continue;
+            }
+            let char_width = c.width().unwrap_or(0);
+            if cut || width + char_width + 1 > max_len {
+                if !cut && max_len > 0 {
+                    result.push('…');
+                }
+                cut = true;
+                continue;
+            }
+            result.push(c);
+            width += char_width;
+        }
+        result
+    }
+
     pub fn align(
         str: &str,
         alignment: FormatAlignment,
         max_len: usize,
         padding: (usize, usize),
     ) -> String {
+        let width = Self::width(str);
         let (left, right) = match alignment {
-            FormatAlignment::Left => (0, max_len - str.len()),
-            FormatAlignment::Right => (max_len - str.len(), 0),
+            FormatAlignment::Left => (0, max_len - width),
+            FormatAlignment::Right => (max_len - width, 0),
             FormatAlignment::Center => {
-                let padding = max_len - str.len();
+                let padding = max_len - width;
                 let left = padding.div_ceil(2);
                 let right = padding.div_floor(2);
                 (left, right)
@@ -195,10 +472,20 @@ impl std::fmt::Display for FormatType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             FormatType::Bold(msg) => write!(f, "{}", msg.bold()),
-            FormatType::RawLine(msg) => write!(f, "{}\n", msg),
-            FormatType::Success(msg) => write!(f, "{} {}", "[SUCCESS]".green(), msg),
-            FormatType::Error(msg) => write!(f, "{} {}", "[ERROR]".red(), msg),
-            FormatType::Info(msg) => write!(f, "{} {}", "[INFO]".yellow(), msg),
+            FormatType::Raw(msg) => write!(f, "{}", msg),
+            FormatType::RawLine(msg) => writeln!(f, "{}", msg),
+            FormatType::Success(msg) => {
+                let tag = tr("[SUCCESS]").color(FormatService::theme().success());
+                write!(f, "{} {}", tag, msg)
+            }
+            FormatType::Error(msg) => {
+                let tag = tr("[ERROR]").color(FormatService::theme().error());
+                write!(f, "{} {}", tag, msg)
+            }
+            FormatType::Info(msg) => {
+                let tag = tr("[INFO]").color(FormatService::theme().info());
+                write!(f, "{} {}", tag, msg)
+            }
             FormatType::Block(header, content) => {
                 write!(f, "{}", FormatType::Bold(header.as_ref().to_string()))?;
                 write!(f, "{}", Offset(2, content.as_ref().clone()))
@@ -209,6 +496,12 @@ impl std::fmt::Display for FormatType {
                 }
                 Ok(())
             }
+            FormatType::Table(table) => {
+                for line in table.lines(terminal_width()) {
+                    writeln!(f, "{}", line)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -225,12 +518,172 @@ impl Display for Offset {
                 }
                 Ok(())
             }
+            FormatType::Table(table) => {
+                let max_width = terminal_width().map(|it| it.saturating_sub(self.0));
+                for line in table.lines(max_width) {
+                    writeln!(f, "{}{}", offset, line)?;
+                }
+                Ok(())
+            }
             _ => write!(f, "{}{}", offset, self.1),
         }
     }
 }
 
-pub(crate) trait FormatTypeable {
+/// Narrowest a column is shrunk to when the table does not fit into the terminal.
+const MIN_COLUMN_WIDTH: usize = 4;
+
+/// Rows of cells under a header, see [TableBuilder]. Serializes to its headers, rows and
+/// alignments, e.g. for `--json` output.
+#[derive(Debug, Clone, Serialize)]
+pub struct Table {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub alignments: Vec<FormatAlignment>,
+    #[serde(skip)]
+    pub border: bool,
+}
+
+impl Table {
+    /// Formatted lines of the table. Columns are shrunk, widest first, and their cells cut off
+    /// with an ellipsis until the table fits into `max_width`.
+    fn lines(&self, max_width: Option<usize>) -> Vec<String> {
+        let mut widths = self
+            .headers
+            .iter()
+            .enumerate()
+            .map(|(idx, header)| {
+                self.rows
+                    .iter()
+                    .map(|row| FormatType::width(&row[idx]))
+                    .chain([FormatType::width(header)])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+
+        if let Some(max_width) = max_width {
+            let separators = 3 * widths.len().saturating_sub(1) + if self.border { 4 } else { 0 };
+            while widths.iter().sum::<usize>() + separators > max_width {
+                match widths.iter_mut().max() {
+                    Some(widest) if *widest > MIN_COLUMN_WIDTH => *widest -= 1,
+                    _ => break,
+                }
+            }
+        }
+
+        let cells = |row: &[String]| {
+            row.iter()
+                .zip(&widths)
+                .zip(&self.alignments)
+                .map(|((cell, width), alignment)| {
+                    let cell = FormatType::truncate(cell, *width);
+                    FormatType::align(&cell, *alignment, *width, (0, 0))
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut lines = Vec::new();
+        match self.border {
+            true => {
+                let rule = |left: &str, middle: &str, right: &str| {
+                    let segments = widths
+                        .iter()
+                        .map(|it| "─".repeat(it + 2))
+                        .collect::<Vec<_>>();
+                    format!("{}{}{}", left, segments.join(middle), right)
+                };
+                lines.push(rule("┌", "┬", "┐"));
+                lines.push(format!("│ {} │", cells(&self.headers).join(" │ ")));
+                lines.push(rule("├", "┼", "┤"));
+                for row in &self.rows {
+                    lines.push(format!("│ {} │", cells(row).join(" │ ")));
+                }
+                lines.push(rule("└", "┴", "┘"));
+            }
+            false => {
+                lines.push(cells(&self.headers).join(" | "));
+                for row in &self.rows {
+                    lines.push(cells(row).join("   "));
+                }
+            }
+        }
+        lines
+    }
+}
+
+/// Builds a [FormatType::Table] column by column. Shorter columns are filled up with empty cells.
+#[derive(Debug, Default)]
+pub struct TableBuilder {
+    headers: Vec<String>,
+    columns: Vec<Vec<String>>,
+    alignments: Vec<FormatAlignment>,
+    border: bool,
+}
+
+impl TableBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn column(
+        mut self,
+        header: impl Into<String>,
+        cells: Vec<String>,
+        alignment: FormatAlignment,
+    ) -> Self {
+        self.headers.push(header.into());
+        self.columns.push(cells);
+        self.alignments.push(alignment);
+        self
+    }
+
+    /// Draws lines around the table and between header and rows.
+    pub fn border(mut self, border: bool) -> Self {
+        self.border = border;
+        self
+    }
+
+    pub fn build(self) -> FormatType {
+        let len = self.columns.iter().map(Vec::len).max().unwrap_or(0);
+        let rows = (0..len)
+            .map(|idx| {
+                self.columns
+                    .iter()
+                    .map(|column| column.get(idx).cloned().unwrap_or_default())
+                    .collect()
+            })
+            .collect();
+        FormatType::Table(Table {
+            headers: self.headers,
+            rows,
+            alignments: self.alignments,
+            border: self.border,
+        })
+    }
+}
+
+/// Columns of the terminal stdout is printed to, `None` if it is piped. `$COLUMNS` takes
+/// precedence, as most shells do not export it, it can be used to set a width for scripts.
+fn terminal_width() -> Option<usize> {
+    if let Some(columns) = std::env::var("COLUMNS").ok().and_then(|it| it.parse().ok()) {
+        return Some(columns);
+    }
+    if !std::io::stdout().is_terminal() {
+        return None;
+    }
+    let tty = File::open("/dev/tty").ok()?;
+    let output = Command::new("stty")
+        .arg("size")
+        .stdin(Stdio::from(tty))
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    let size = String::from_utf8_lossy(&output.stdout);
+    size.split_whitespace().nth(1)?.parse().ok()
+}
+
+pub trait FormatTypeable {
     fn format(self) -> FormatType;
 }
 
@@ -263,6 +716,7 @@ pub trait IntoFormatType {
     fn success(self) -> FormatType;
     fn error(self) -> FormatType;
     fn line(self) -> FormatType;
+    fn raw(self) -> FormatType;
 }
 
 impl IntoFormatType for String {
@@ -281,6 +735,10 @@ impl IntoFormatType for String {
     fn line(self) -> FormatType {
         FormatType::RawLine(self)
     }
+
+    fn raw(self) -> FormatType {
+        FormatType::Raw(self)
+    }
 }
 
 impl IntoFormatType for &str {
@@ -299,4 +757,96 @@ impl IntoFormatType for &str {
     fn line(self) -> FormatType {
         FormatType::RawLine(self.to_string())
     }
+
+    fn raw(self) -> FormatType {
+        FormatType::Raw(self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FormatAlignment, FormatType, Table, TableBuilder};
+
+    fn table(courses: &[&str], ects: &[&str]) -> Table {
+        let table = TableBuilder::new()
+            .column(
+                "Course",
+                courses.iter().map(|it| it.to_string()).collect(),
+                FormatAlignment::Left,
+            )
+            .column(
+                "ECTS",
+                ects.iter().map(|it| it.to_string()).collect(),
+                FormatAlignment::Right,
+            )
+            .build();
+        match table {
+            FormatType::Table(table) => table,
+            _ => unreachable!("the builder creates a table"),
+        }
+    }
+
+    #[test]
+    fn width_counts_columns() {
+        assert_eq!(FormatType::width("Prüfungsordnung"), 15);
+        // `ü` as `u` followed by a combining diaeresis, as read from macOS
+        assert_eq!(FormatType::width("Einfu\u{308}hrung"), 10);
+        assert_eq!(FormatType::width("日本語"), 6);
+        assert_eq!(FormatType::width("\x1b[32mÜbung\x1b[0m"), 5);
+        assert_eq!(FormatType::width(""), 0);
+    }
+
+    #[test]
+    fn truncate_marks_the_cut() {
+        assert_eq!(FormatType::truncate("Analysis", 8), "Analysis");
+        assert_eq!(FormatType::truncate("Einführung", 6), "Einfü…");
+        assert_eq!(
+            FormatType::truncate("Einfu\u{308}hrung", 6),
+            "Einfu\u{308}…"
+        );
+        assert_eq!(FormatType::truncate("日本語", 4), "日…");
+        assert_eq!(FormatType::truncate("Übung", 0), "");
+    }
+
+    #[test]
+    fn truncate_keeps_escape_sequences() {
+        let truncated = FormatType::truncate("\x1b[1mGrundlagen der Mathematik\x1b[0m", 8);
+        assert_eq!(truncated, "\x1b[1mGrundla…\x1b[0m");
+        assert_eq!(FormatType::width(&truncated), 8);
+    }
+
+    #[test]
+    fn lines_align_columns() {
+        let table = table(
+            &["Einführung in die Informatik", "Lineare Algebra"],
+            &["9", "6"],
+        );
+        assert_eq!(
+            table.lines(None),
+            vec![
+                format!("{:<28} | {:>4}", "Course", "ECTS"),
+                format!("{:<28}   {:>4}", "Einführung in die Informatik", "9"),
+                format!("{:<28}   {:>4}", "Lineare Algebra", "6"),
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_shrink_the_widest_column() {
+        let table = table(
+            &["Einführung in die Informatik", "Lineare Algebra"],
+            &["9", "6"],
+        );
+        let lines = table.lines(Some(20));
+        assert_eq!(lines[1], format!("{}   {:>4}", "Einführung i…", "9"));
+        assert_eq!(lines[2], format!("{}   {:>4}", "Lineare Alge…", "6"));
+        assert!(lines.iter().all(|it| FormatType::width(it) <= 20));
+    }
+
+    #[test]
+    fn lines_keep_the_minimum_column_width() {
+        let table = table(&["Übungsblätter"], &["10"]);
+        let lines = table.lines(Some(5));
+        assert_eq!(lines[1], "Übu…     10");
+    }
 }
@@ -0,0 +1,65 @@
+use crate::{cli::GetCommands, service::format::IntoFormatType, MmError, StoreProvider};
+use anyhow::{anyhow, bail};
+
+use super::{status::StatusService, ServiceResult};
+
+/// Prints exactly one undecorated value per command. The output of this service is meant to be
+/// consumed by scripts and must therefore stay stable independent of the pretty output.
+pub(super) struct GetService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> GetService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> GetService<'s, Store> {
+        GetService { store }
+    }
+
+    pub fn run(&self, command: GetCommands) -> ServiceResult {
+        match command {
+            GetCommands::CurrentCourse => self.current_course(),
+            GetCommands::CurrentSemester => self.current_semester(),
+            GetCommands::EntryPoint => self.entry_point(),
+            GetCommands::Average { degree } => self.average(degree),
+        }
+    }
+
+    fn current_course(&self) -> ServiceResult {
+        let Some(course) = self.store.current_course() else {
+            bail!(MmError::NoActiveCourse);
+        };
+        Ok(course.path().name().raw())
+    }
+
+    fn current_semester(&self) -> ServiceResult {
+        let Some(semester) = self.store.current_semester() else {
+            bail!(MmError::NoActiveSemester);
+        };
+        Ok(semester.path().name().raw())
+    }
+
+    fn entry_point(&self) -> ServiceResult {
+        let entry_point = self.store.entry_point();
+        Ok(entry_point.display().to_string().raw())
+    }
+
+    fn average(&self, degree: Option<String>) -> ServiceResult {
+        let status = StatusService::new(self.store);
+        let average = match degree {
+            Some(degree) => {
+                let degree = status.degree_name(degree)?;
+                status
+                    .weighted_average_by_degree()
+                    .remove(&degree)
+                    .ok_or_else(|| anyhow!("No graded courses found for degree: {}", degree))?
+            }
+            None => status.weighted_average(),
+        };
+        Ok(format!("{:.2}", average).raw())
+    }
+}
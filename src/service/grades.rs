@@ -0,0 +1,244 @@
+use std::path::PathBuf;
+
+use colored::Colorize;
+
+use crate::{
+    cli::GradesCommands,
+    domain::{normalize_name, Course, GradeRecord},
+    service::format::{FormatAlignment, FormatType, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::{
+    format::{DialogEntry, DialogOutput, FormatService},
+    ServiceResult,
+};
+
+/// Imports grades and ECTS from the exports of campus systems like HISinOne or CampusOnline.
+pub(super) struct GradesService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s mut Store,
+}
+
+/// A change of a course found in the export.
+struct GradeChange {
+    course: Course,
+    record: GradeRecord,
+}
+
+impl<'s, Store> GradesService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s mut Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&mut self, command: GradesCommands) -> ServiceResult {
+        match command {
+            GradesCommands::Import { file, yes, force } => self.import(file, yes, force),
+        }
+    }
+
+    /// Matches the rows of the export to courses by module id, then by name. Rows without a
+    /// matching course and unchanged courses are skipped, locked courses keep their grade unless
+    /// `force` is given. The changes are shown for review before they are written.
+    fn import(&mut self, file: PathBuf, yes: bool, force: bool) -> ServiceResult {
+        let records = GradeRecord::read(&file)?;
+        let courses = self.store.courses().collect::<Vec<_>>();
+
+        let mut changes: Vec<GradeChange> = Vec::new();
+        let mut unmatched = Vec::new();
+        let mut ambiguous = Vec::new();
+        for record in records.into_iter().filter(|it| it.grade().is_some()) {
+            let candidates = matching_courses(&courses, &record);
+            let course = match candidates.as_slice() {
+                [course] => *course,
+                [] => {
+                    unmatched.push(record.title().to_string());
+                    continue;
+                }
+                _ => {
+                    ambiguous.push(record.title().to_string());
+                    continue;
+                }
+            };
+            // repeated attempts are listed as separate rows, the best grade counts
+            match changes
+                .iter_mut()
+                .find(|it| it.course.path().to_path_buf() == course.path().to_path_buf())
+            {
+                Some(change) if record.grade() < change.record.grade() => change.record = record,
+                Some(_) => {}
+                None => changes.push(GradeChange {
+                    course: course.clone(),
+                    record,
+                }),
+            }
+        }
+
+        let mut locked = 0;
+        changes.retain(|it| {
+            let grade_changed = it.record.grade() != it.course.grade();
+            let ects_changed = it.record.ects().is_some() && it.record.ects() != it.course.ects();
+            if it.course.is_locked() && grade_changed && !force {
+                locked += 1;
+                return false;
+            }
+            grade_changed || ects_changed
+        });
+        changes.sort_by_key(|it| it.course.name());
+
+        let mut notes = Vec::new();
+        if !unmatched.is_empty() {
+            notes.push(
+                format!(
+                    "No course found for: {}. Set 'module_id' in course.toml to match them",
+                    unmatched.join(", ")
+                )
+                .info(),
+            );
+        }
+        if !ambiguous.is_empty() {
+            notes.push(
+                format!(
+                    "Several courses match: {}. Set 'module_id' in course.toml to match them",
+                    ambiguous.join(", ")
+                )
+                .info(),
+            );
+        }
+        if locked > 0 {
+            notes.push(
+                format!(
+                    "{} locked courses have a different grade. Use --force to change them anyway",
+                    locked
+                )
+                .info(),
+            );
+        }
+        let with_notes = |msg: FormatType| {
+            notes
+                .iter()
+                .cloned()
+                .fold(msg, |acc, it| acc.chain("".line()).chain(it))
+        };
+
+        if changes.is_empty() {
+            return Ok(with_notes("All grades are up to date".info()));
+        }
+
+        let review = review_table(&changes);
+        if !yes {
+            let dialog = vec![
+                DialogEntry::Message(review.to_string()),
+                DialogEntry::YesNoInput(format!("Apply {} changes?", changes.len())),
+            ];
+            match FormatService::dialog(dialog).as_deref() {
+                Some([DialogOutput::YesNo(true)]) => {}
+                _ => return Ok("Operation has been canceled".info()),
+            }
+        }
+
+        for change in &mut changes {
+            change
+                .course
+                .record_result(change.record.grade(), change.record.ects())?;
+        }
+        let msg = format!("Grades of {} courses have been imported", changes.len()).success();
+        match yes {
+            true => Ok(with_notes(review.chain("".line()).chain(msg))),
+            false => Ok(with_notes(msg)),
+        }
+    }
+}
+
+/// Courses with the module id of the record or, without such a course, with a similar name.
+/// Among several courses of the same name those without grade are preferred, e.g. a retake.
+fn matching_courses<'c>(courses: &'c [Course], record: &GradeRecord) -> Vec<&'c Course> {
+    if let Some(code) = record.code() {
+        let by_code = courses
+            .iter()
+            .filter(|it| {
+                it.module_id()
+                    .is_some_and(|id| id.eq_ignore_ascii_case(code))
+            })
+            .collect::<Vec<_>>();
+        if !by_code.is_empty() {
+            return by_code;
+        }
+    }
+
+    let title = normalize_name(record.title());
+    let names = |course: &Course| {
+        [
+            normalize_name(&course.name()),
+            normalize_name(course.path().name()),
+        ]
+    };
+    let mut by_name = courses
+        .iter()
+        .filter(|it| names(it).contains(&title))
+        .collect::<Vec<_>>();
+    if by_name.is_empty() {
+        by_name = courses
+            .iter()
+            .filter(|it| {
+                names(it)
+                    .iter()
+                    .any(|name| name.len() > 3 && (title.contains(name) || name.contains(&title)))
+            })
+            .collect();
+    }
+    if by_name.len() > 1 && by_name.iter().any(|it| it.grade().is_none()) {
+        by_name.retain(|it| it.grade().is_none());
+    }
+    by_name
+}
+
+fn review_table(changes: &[GradeChange]) -> FormatType {
+    let transition = |old: Option<String>, new: Option<String>| match (old, new) {
+        (old, None) => old.unwrap_or_else(|| "-".into()),
+        (Some(old), Some(new)) if old == new => old,
+        (old, Some(new)) => format!("{} → {}", old.unwrap_or_else(|| "-".into()), new)
+            .green()
+            .to_string(),
+    };
+    let courses = changes
+        .iter()
+        .map(|it| it.course.name())
+        .collect::<Vec<_>>();
+    let rows = changes
+        .iter()
+        .map(|it| match it.record.code() {
+            Some(code) => format!("{} {}", code, it.record.title()),
+            None => it.record.title().to_string(),
+        })
+        .collect::<Vec<_>>();
+    let ects = changes
+        .iter()
+        .map(|it| {
+            transition(
+                it.course.ects().map(|it| it.to_string()),
+                it.record.ects().map(|it| it.to_string()),
+            )
+        })
+        .collect::<Vec<_>>();
+    let grades = changes
+        .iter()
+        .map(|it| {
+            transition(
+                it.course.grade().map(|it| FormatType::grade(it, 1)),
+                it.record.grade().map(|it| FormatType::grade(it, 1)),
+            )
+        })
+        .collect::<Vec<_>>();
+    TableBuilder::new()
+        .column("Course", courses, FormatAlignment::Left)
+        .column("Export", rows, FormatAlignment::Left)
+        .column("ECTS", ects, FormatAlignment::Right)
+        .column("Grade", grades, FormatAlignment::Right)
+        .build()
+}
@@ -0,0 +1,384 @@
+use crate::{
+    cli::{
+        CalendarCommands, CatalogCommands, Commands, CourseCommands, DeadlineCommands,
+        ExamCommands, ExerciseCommands, GradesCommands, LitCommands, MaterialsCommands,
+        MoodleCommands, NoteCommands, PdfCommands, PlanCommands, ScheduleCommands,
+        SemesterCommands, SyncCommands, TodoCommands, TrackCommands,
+    },
+    domain::{to_local, AuditLog, CourseStatus, Date, History},
+    service::format::{FormatAlignment, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::ServiceResult;
+
+/// Shows the audit log of the store or the commits recorded with the `git` config option.
+pub(super) struct HistoryService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> HistoryService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    /// The latest `limit` entries of the audit log matching the filters, newest first.
+    pub fn run(
+        &self,
+        limit: usize,
+        since: Option<Date>,
+        until: Option<Date>,
+        search: Option<String>,
+    ) -> ServiceResult {
+        let search = search.map(|it| it.to_lowercase());
+        let entries = AuditLog::new(&self.store.entry_point())
+            .entries()?
+            .into_iter()
+            .rev()
+            .filter(|it| since.is_none_or(|since| *it.date() >= since))
+            .filter(|it| until.is_none_or(|until| *it.date() <= until))
+            .filter(|it| {
+                search.as_ref().is_none_or(|search| {
+                    it.change().to_lowercase().contains(search)
+                        || it.command().to_lowercase().contains(search)
+                })
+            })
+            .take(limit)
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return Ok("No matching changes have been recorded".info());
+        }
+
+        let times = entries
+            .iter()
+            .map(|it| {
+                let (date, time) = to_local(*it.date(), *it.time());
+                format!("{} {}", date, time)
+            })
+            .collect::<Vec<_>>();
+        let changes = entries
+            .iter()
+            .map(|it| it.change().to_string())
+            .collect::<Vec<_>>();
+        let commands = entries
+            .iter()
+            .map(|it| it.command().to_string())
+            .collect::<Vec<_>>();
+        Ok(TableBuilder::new()
+            .column("Time", times, FormatAlignment::Left)
+            .column("Change", changes, FormatAlignment::Left)
+            .column("Command", commands, FormatAlignment::Left)
+            .build())
+    }
+
+    pub fn commits(&self, limit: usize) -> ServiceResult {
+        let Some(mode) = self.store.git_mode() else {
+            let error = "Versioning is disabled".error();
+            let info = "Set 'git = \"metadata\"' or 'git = \"all\"' in the config file".info();
+            return Ok(error.chain("".line()).chain(info));
+        };
+        let entries = History::new(&self.store.entry_point(), mode).log(limit)?;
+        if entries.is_empty() {
+            return Ok("No changes have been recorded yet".info());
+        }
+
+        let hashes = entries
+            .iter()
+            .map(|it| it.hash().to_string())
+            .collect::<Vec<_>>();
+        let dates = entries
+            .iter()
+            .map(|it| it.date().to_string())
+            .collect::<Vec<_>>();
+        let messages = entries
+            .iter()
+            .map(|it| it.message().to_string())
+            .collect::<Vec<_>>();
+        Ok(TableBuilder::new()
+            .column("Commit", hashes, FormatAlignment::Left)
+            .column("Date", dates, FormatAlignment::Left)
+            .column("Change", messages, FormatAlignment::Left)
+            .build())
+    }
+
+    /// Commit message for `command`, e.g. "add course b03/Algorithms". Returns None for commands
+    /// which do not change the store.
+    pub fn describe(store: &Store, command: &Commands) -> Option<String> {
+        let message = match command {
+            Commands::Status { .. }
+            | Commands::Get { .. }
+            | Commands::Export { .. }
+            | Commands::Backup { .. }
+            | Commands::History { .. }
+            | Commands::Demo { .. }
+            | Commands::Debug { .. }
+            | Commands::Config { .. } => return None,
+            // every line is recorded on its own
+            Commands::Batch { .. } => return None,
+            Commands::Switch { reference, auto } => match (reference, auto) {
+                (Some(reference), _) => format!("switch to {}", reference),
+                (None, true) => "switch to the semester of today".into(),
+                (None, false) => "leave active semester".into(),
+            },
+            Commands::Semester { command } => match command.as_ref()? {
+                SemesterCommands::List { .. } => return None,
+                SemesterCommands::Add { number, .. } => format!("add semester {}", number),
+                SemesterCommands::Remove { name, .. } => format!("remove semester {}", name),
+                SemesterCommands::Close { name } => format!("close semester {}", name),
+            },
+            Commands::Course { command } => match command.as_ref()? {
+                CourseCommands::List { .. }
+                | CourseCommands::Show { .. }
+                | CourseCommands::Deps { .. } => return None,
+                CourseCommands::Add { names, .. } => {
+                    format!("add course {}", in_active_semester(store, names))
+                }
+                CourseCommands::Remove { names, .. } => {
+                    format!("remove course {}", in_active_semester(store, names))
+                }
+                CourseCommands::Copy {
+                    course, semester, ..
+                } => format!("copy course {} to {}", course, semester),
+                CourseCommands::Move { course, to, .. } => {
+                    format!("move course {} to {}", course, to)
+                }
+                CourseCommands::Grade {
+                    grade,
+                    component: Some(component),
+                    course,
+                    ..
+                } => format!(
+                    "set {} grade {} for {}",
+                    component,
+                    grade,
+                    course_ref(store, course)
+                ),
+                CourseCommands::Grade { grade, course, .. } => {
+                    format!("set grade {} for {}", grade, course_ref(store, course))
+                }
+                CourseCommands::Update { course, .. } => match course {
+                    Some(course) => format!("update material of {}", course),
+                    None => "update material".into(),
+                },
+                CourseCommands::Status { status, course, .. } => {
+                    let status = CourseStatus::from_do(*status);
+                    format!("mark {} as {}", course_ref(store, course), status)
+                }
+                CourseCommands::Lock { course } => format!("lock {}", course_ref(store, course)),
+                CourseCommands::Unlock { course } => {
+                    format!("unlock {}", course_ref(store, course))
+                }
+            },
+            Commands::Todo { course, command } => match command.as_ref()? {
+                TodoCommands::List { .. } => return None,
+                TodoCommands::Add { title, .. } => {
+                    format!("add todo '{}' to {}", title, course_ref(store, course))
+                }
+                TodoCommands::Done { id } => {
+                    format!("complete todo {} of {}", id, course_ref(store, course))
+                }
+                TodoCommands::Remove { id } => {
+                    format!("remove todo {} of {}", id, course_ref(store, course))
+                }
+            },
+            Commands::Attach { course, count, .. } => match count {
+                1 => format!("attach download to {}", course_ref(store, course)),
+                _ => format!(
+                    "attach {} downloads to {}",
+                    count,
+                    course_ref(store, course)
+                ),
+            },
+            Commands::Exercise { course, command } => match command.as_ref()? {
+                ExerciseCommands::List => return None,
+                ExerciseCommands::Add { name, .. } => match name {
+                    Some(name) => format!("add exercise {} to {}", name, course_ref(store, course)),
+                    None => format!("add exercise to {}", course_ref(store, course)),
+                },
+                ExerciseCommands::Fetch { name } => match name {
+                    Some(name) => {
+                        format!("fetch exercise {} of {}", name, course_ref(store, course))
+                    }
+                    None => format!("fetch exercise of {}", course_ref(store, course)),
+                },
+                ExerciseCommands::Remove { name } => {
+                    format!("remove exercise {} of {}", name, course_ref(store, course))
+                }
+                ExerciseCommands::Move { from, to } => format!(
+                    "rename exercise to {} in {}",
+                    to.as_ref().unwrap_or(from),
+                    course_ref(store, course)
+                ),
+                ExerciseCommands::Points { sheet, points } => format!(
+                    "record {} points for {} of {}",
+                    points,
+                    sheet,
+                    course_ref(store, course)
+                ),
+            },
+            Commands::Deadline { course, command } => match command.as_ref()? {
+                DeadlineCommands::List { .. } | DeadlineCommands::Remind { .. } => return None,
+                DeadlineCommands::Add { title, date, .. } => format!(
+                    "add deadline '{}' on {} to {}",
+                    title,
+                    date,
+                    course_ref(store, course)
+                ),
+            },
+            Commands::Exam { command } => match command.as_ref()? {
+                ExamCommands::List { .. } | ExamCommands::Attempts { .. } => return None,
+                ExamCommands::Add { course, date, .. } => {
+                    format!("add exam for {} on {}", course, date)
+                }
+                ExamCommands::Grade { course, grade, .. } => {
+                    format!("set exam grade {} for {}", grade, course)
+                }
+            },
+            Commands::Schedule { command } => match command.as_ref()? {
+                ScheduleCommands::Show => return None,
+                ScheduleCommands::Add {
+                    course,
+                    weekday,
+                    start,
+                    end,
+                    ..
+                } => format!(
+                    "add slot {} {}-{} to {}",
+                    weekday.abbreviation(),
+                    start,
+                    end,
+                    course_ref(store, course)
+                ),
+            },
+            Commands::Calendar { command } => match command {
+                CalendarCommands::Export { .. } => return None,
+                CalendarCommands::Import { course, .. } => {
+                    format!("import calendar into {}", course_ref(store, course))
+                }
+            },
+            Commands::Track { course, command } => match command.as_ref()? {
+                TrackCommands::Report { .. } => return None,
+                TrackCommands::Start => format!("start session for {}", course_ref(store, course)),
+                TrackCommands::Stop => "stop session".into(),
+                TrackCommands::Cancel => "cancel session".into(),
+                TrackCommands::Add { duration, .. } => {
+                    format!("track {} for {}", duration, course_ref(store, course))
+                }
+            },
+            Commands::Note { course, command } => match command.as_ref()? {
+                NoteCommands::List | NoteCommands::Open { .. } => return None,
+                NoteCommands::New { title, .. } => {
+                    format!("add note '{}' to {}", title, course_ref(store, course))
+                }
+                NoteCommands::Compile { note } => {
+                    format!("compile note {} of {}", note, course_ref(store, course))
+                }
+            },
+            Commands::Materials { command, .. } => match command {
+                MaterialsCommands::Verify { .. } => "verify materials".into(),
+                MaterialsCommands::Update { .. } => "update materials manifest".into(),
+            },
+            Commands::Lit { course, command } => match command.as_ref()? {
+                LitCommands::List | LitCommands::Export { .. } => return None,
+                LitCommands::Add { source, .. } => {
+                    format!("add reference {} to {}", source, course_ref(store, course))
+                }
+                LitCommands::Import { .. } => {
+                    format!("import references into {}", course_ref(store, course))
+                }
+                LitCommands::Remove { key } => {
+                    format!("remove reference {} of {}", key, course_ref(store, course))
+                }
+            },
+            Commands::Pdf { course, command } => match command {
+                PdfCommands::Normalize { .. } => {
+                    format!("normalize slides of {}", course_ref(store, course))
+                }
+            },
+            Commands::Grades { command } => match command {
+                GradesCommands::Import { .. } => "import grades".into(),
+            },
+            Commands::Moodle { command } => match command {
+                MoodleCommands::Courses => return None,
+                MoodleCommands::Link { course, .. } => match course {
+                    Some(course) => format!("link {} to moodle", course),
+                    None => "link courses to moodle".into(),
+                },
+                MoodleCommands::Import { .. } => "import moodle deadlines".into(),
+            },
+            Commands::Serve { .. }
+            | Commands::Flashcards { .. }
+            | Commands::Watch { .. }
+            | Commands::Ui
+            | Commands::Prompt { .. }
+            | Commands::Exec { .. }
+            | Commands::Degree { .. }
+            | Commands::Env
+            | Commands::Plan { command: None, .. }
+            | Commands::Stats { .. }
+            | Commands::Search { .. }
+            | Commands::Whatif { .. } => return None,
+            Commands::Plan {
+                command: Some(command),
+                ..
+            } => match command {
+                PlanCommands::Show => return None,
+                PlanCommands::Add { semester, name, .. } => {
+                    format!("plan {} for {}", name, semester)
+                }
+                PlanCommands::Remove { semester, name } => match name {
+                    Some(name) => format!("remove {} from the plan of {}", name, semester),
+                    None => format!("remove the plan of {}", semester),
+                },
+                PlanCommands::Apply { semester } => format!("apply the plan of {}", semester),
+            },
+            Commands::Catalog { command } => match command {
+                CatalogCommands::Import { .. } => "import module catalog".into(),
+            },
+            Commands::Migrate => "migrate data files".into(),
+            Commands::Sync { command } => match command {
+                SyncCommands::Pull { .. } => "sync pull".into(),
+                SyncCommands::Push { .. } | SyncCommands::Status => return None,
+            },
+        };
+        Some(message)
+    }
+}
+
+/// `semester/course` for a course name in the active semester.
+fn in_active_semester<Store: StoreProvider>(store: &Store, names: &[String]) -> String {
+    let semester = store.current_semester();
+    names
+        .iter()
+        .map(|name| match &semester {
+            Some(semester) => format!("{}/{}", semester.path().name(), name),
+            None => name.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The given reference or `semester/course` of the active course.
+fn course_ref<Store: StoreProvider>(store: &Store, reference: &Option<String>) -> String {
+    if let Some(reference) = reference {
+        return reference.clone();
+    }
+    let Some(course) = store.current_course() else {
+        return "active course".into();
+    };
+    let semester = course
+        .path()
+        .parent()
+        .and_then(|it| it.file_name())
+        .map(|it| it.to_string_lossy().to_string());
+    match semester {
+        Some(semester) => format!("{}/{}", semester, course.path().name()),
+        None => course.path().name().to_string(),
+    }
+}
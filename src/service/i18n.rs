@@ -0,0 +1,108 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex, OnceLock},
+};
+
+use regex::{Captures, Regex};
+
+use crate::domain::Language;
+
+/// German messages keyed by the English ones.
+const GERMAN: &str = include_str!("../../locales/de.toml");
+
+/// Set from the environment and `language` of the config, see [set_language].
+static LANGUAGE: Mutex<Language> = Mutex::new(Language::English);
+
+pub fn set_language(language: Language) {
+    *LANGUAGE.lock().unwrap_or_else(|err| err.into_inner()) = language;
+}
+
+pub fn language() -> Language {
+    *LANGUAGE.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// The message in the selected language. Messages without translation are returned unchanged.
+pub fn tr(msg: &str) -> String {
+    match language() {
+        Language::English => msg.to_string(),
+        Language::German => {
+            static CATALOG: OnceLock<Catalog> = OnceLock::new();
+            CATALOG
+                .get_or_init(|| Catalog::parse(GERMAN))
+                .translate(msg)
+        }
+    }
+}
+
+/// Translations of messages. `{}` in a message stands for a value like a course name, which is
+/// inserted in the same order into the translation, or by position with `{0}`, `{1}`, ...
+struct Catalog {
+    exact: HashMap<String, String>,
+    templates: Vec<(Regex, String)>,
+}
+
+impl Catalog {
+    fn parse(content: &str) -> Catalog {
+        let entries = toml_edit::de::from_str::<HashMap<String, String>>(content)
+            .expect("valid message catalog");
+        let (templates, exact): (Vec<_>, Vec<_>) =
+            entries.into_iter().partition(|(msg, _)| msg.contains("{}"));
+
+        // messages with fewer values are more specific, e.g. "Course '{}' has been added" over
+        // "Course '{}' has been {}"
+        let mut templates = templates;
+        templates.sort_by_key(|(msg, _)| (msg.matches("{}").count(), usize::MAX - msg.len()));
+        let templates = templates
+            .into_iter()
+            .map(|(msg, translation)| {
+                let pattern = msg
+                    .split("{}")
+                    .map(regex::escape)
+                    .collect::<Vec<_>>()
+                    .join("(.*?)");
+                let regex = Regex::new(&format!("(?s)^{}$", pattern)).expect("valid regex");
+                (regex, translation)
+            })
+            .collect();
+        Catalog {
+            exact: exact.into_iter().collect(),
+            templates,
+        }
+    }
+
+    /// Only the template is translated, captured values like course names are inserted as they
+    /// are. Messages composed of other messages translate those before, see
+    /// [super::reference::did_you_mean].
+    fn translate(&self, msg: &str) -> String {
+        if let Some(translation) = self.exact.get(msg) {
+            return translation.clone();
+        }
+        let Some((captures, translation)) = self
+            .templates
+            .iter()
+            .find_map(|(regex, translation)| Some((regex.captures(msg)?, translation)))
+        else {
+            return msg.to_string();
+        };
+        let values = captures
+            .iter()
+            .skip(1)
+            .map(|it| it.map_or("", |it| it.as_str()).to_string())
+            .collect::<Vec<_>>();
+
+        let mut next = 0;
+        let placeholder = Regex::new(r"\{(\d*)\}").expect("valid regex");
+        placeholder
+            .replace_all(translation, |captures: &Captures| {
+                let idx = match captures[1].parse::<usize>() {
+                    Ok(idx) => idx,
+                    Err(_) => {
+                        next += 1;
+                        next - 1
+                    }
+                };
+                values.get(idx).cloned().unwrap_or_default()
+            })
+            .to_string()
+    }
+}
@@ -0,0 +1,289 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{
+    domain::{Course, CourseStatus, Date, Deadline, DeadlineKind, Semester},
+    StoreProvider,
+};
+
+use super::status::StatusService;
+
+/// JSON representation of a semester.
+#[derive(Debug, Serialize)]
+pub(super) struct SemesterJson {
+    /// Folder name, used to reference the semester.
+    reference: String,
+    name: String,
+    active: bool,
+    courses: usize,
+    /// ECTS of graded courses.
+    ects: u32,
+    /// ECTS weighted average of graded courses.
+    average: Option<f64>,
+}
+
+/// JSON representation of a course.
+#[derive(Debug, Serialize)]
+pub(super) struct CourseJson {
+    /// `semester/course`, usable wherever a course reference is expected.
+    reference: String,
+    semester: String,
+    name: String,
+    grade: Option<f64>,
+    ects: Option<u8>,
+    degrees: Vec<String>,
+    locked: bool,
+    status: Option<CourseStatus>,
+    module_id: Option<String>,
+    open_tasks: usize,
+}
+
+/// JSON representation of a deadline with its course.
+#[derive(Debug, Serialize)]
+pub(super) struct DeadlineJson {
+    course: String,
+    course_name: String,
+    title: String,
+    date: Date,
+    kind: DeadlineKind,
+    days_left: i64,
+}
+
+/// JSON representation of the active context and the averages shown by `mm status`.
+#[derive(Debug, Serialize)]
+pub(super) struct StatusJson {
+    semester: Option<String>,
+    course: Option<String>,
+    average: f64,
+    weighted_average: f64,
+    by_degree: BTreeMap<String, f64>,
+}
+
+/// JSON document of `mm stats --json`, meant for dashboards and spreadsheets. Fields are only
+/// added, never renamed or removed.
+#[derive(Debug, Serialize)]
+pub(super) struct StatsJson {
+    date: Date,
+    overall: AggregateJson,
+    semesters: Vec<SemesterStatsJson>,
+    degrees: Vec<DegreeStatsJson>,
+    courses: Vec<CourseJson>,
+}
+
+/// Figures of a set of courses. Averages only consider graded courses.
+#[derive(Debug, Serialize)]
+pub(super) struct AggregateJson {
+    courses: usize,
+    graded: usize,
+    /// ECTS of passed courses.
+    earned_ects: u32,
+    /// ECTS of courses which are neither graded, passed, failed nor dropped.
+    enrolled_ects: u32,
+    average: Option<f64>,
+    weighted_average: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub(super) struct SemesterStatsJson {
+    reference: String,
+    study_cycle: String,
+    number: u16,
+    #[serde(flatten)]
+    aggregate: AggregateJson,
+}
+
+/// Degrees of the config and degrees listed by courses.
+#[derive(Debug, Serialize)]
+pub(super) struct DegreeStatsJson {
+    name: String,
+    /// Whether the degree is configured with `[[degrees]]`, which the following fields need.
+    configured: bool,
+    required_ects: Option<u32>,
+    /// Earned ECTS which count towards the degree, see `mm degree`.
+    counted_ects: Option<u32>,
+    /// Weighted average after applying the rules of the degree, without übK.
+    final_average: Option<f64>,
+    #[serde(flatten)]
+    aggregate: AggregateJson,
+}
+
+impl StatsJson {
+    pub fn new<Store: StoreProvider>(store: &Store) -> StatsJson {
+        let mut semesters = store.semesters().collect::<Vec<_>>();
+        semesters.sort();
+        let by_semester = semesters
+            .iter()
+            .map(|semester| (semester, semester.courses().collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        let all = by_semester
+            .iter()
+            .flat_map(|(_, courses)| courses.iter().cloned())
+            .collect::<Vec<_>>();
+
+        let configured = store.degrees();
+        let mut names = configured
+            .iter()
+            .map(|it| it.name().to_string())
+            .collect::<Vec<_>>();
+        for name in all.iter().flat_map(|it| it.degrees()) {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        let degrees = names
+            .into_iter()
+            .map(|name| {
+                let courses = all
+                    .iter()
+                    .filter(|it| it.degrees().contains(&name))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                let degree = configured.iter().find(|it| it.name() == name);
+                DegreeStatsJson {
+                    configured: degree.is_some(),
+                    required_ects: degree.map(|it| it.ects()),
+                    counted_ects: degree.map(|it| it.progress(&all).counted(it)),
+                    final_average: degree.and_then(|it| it.average(&all)).map(round),
+                    aggregate: AggregateJson::new(&courses),
+                    name,
+                }
+            })
+            .collect();
+
+        StatsJson {
+            date: Date::today(),
+            overall: AggregateJson::new(&all),
+            semesters: by_semester
+                .iter()
+                .map(|(semester, courses)| SemesterStatsJson {
+                    reference: semester.path().name().to_string(),
+                    study_cycle: semester.study_cycle().to_string().to_lowercase(),
+                    number: semester.number(),
+                    aggregate: AggregateJson::new(courses),
+                })
+                .collect(),
+            degrees,
+            courses: by_semester
+                .iter()
+                .flat_map(|(semester, courses)| {
+                    courses
+                        .iter()
+                        .map(|course| CourseJson::new(semester, course))
+                })
+                .collect(),
+        }
+    }
+}
+
+impl AggregateJson {
+    pub fn new(courses: &[Course]) -> AggregateJson {
+        let grades = courses
+            .iter()
+            .filter_map(|it| it.grade())
+            .collect::<Vec<_>>();
+        let (sum, weight) = courses
+            .iter()
+            .filter_map(|it| it.grade().zip(it.ects()))
+            .fold((0f32, 0u32), |(sum, weight), (grade, ects)| {
+                (sum + grade * ects as f32, weight + ects as u32)
+            });
+        AggregateJson {
+            courses: courses.len(),
+            graded: grades.len(),
+            earned_ects: courses
+                .iter()
+                .filter_map(|it| it.earned_ects())
+                .map(u32::from)
+                .sum(),
+            enrolled_ects: courses
+                .iter()
+                .filter(|it| it.is_enrolled())
+                .filter_map(|it| it.ects())
+                .map(u32::from)
+                .sum(),
+            average: (!grades.is_empty())
+                .then(|| round(grades.iter().sum::<f32>() / grades.len() as f32)),
+            weighted_average: (weight > 0).then(|| round(sum / weight as f32)),
+        }
+    }
+}
+
+impl SemesterJson {
+    pub fn new(semester: &Semester, active: Option<&Semester>) -> SemesterJson {
+        let courses = semester.courses().collect::<Vec<_>>();
+        let graded = courses
+            .iter()
+            .filter_map(|it| it.grade().zip(it.ects()))
+            .collect::<Vec<_>>();
+        let (sum, weight) = graded
+            .iter()
+            .fold((0f32, 0u32), |(sum, weight), (grade, ects)| {
+                (sum + grade * *ects as f32, weight + *ects as u32)
+            });
+        SemesterJson {
+            reference: semester.path().name().to_string(),
+            name: semester.name(),
+            active: active.is_some_and(|it| it.path() == semester.path()),
+            courses: courses.len(),
+            ects: graded.iter().map(|(_, ects)| *ects as u32).sum(),
+            average: (weight > 0).then(|| round(sum / weight as f32)),
+        }
+    }
+}
+
+impl CourseJson {
+    pub fn new(semester: &Semester, course: &Course) -> CourseJson {
+        CourseJson {
+            reference: format!("{}/{}", semester.path().name(), course.path().name()),
+            semester: semester.path().name().to_string(),
+            name: course.name(),
+            grade: course.grade().map(round),
+            ects: course.ects(),
+            degrees: course.degrees().clone(),
+            locked: course.is_locked(),
+            status: course.status(),
+            module_id: course.module_id().map(str::to_string),
+            open_tasks: course.open_tasks().count(),
+        }
+    }
+}
+
+impl DeadlineJson {
+    pub fn new(semester: &Semester, course: &Course, deadline: &Deadline, today: &Date) -> Self {
+        DeadlineJson {
+            course: format!("{}/{}", semester.path().name(), course.path().name()),
+            course_name: course.name(),
+            title: deadline.title().to_string(),
+            date: deadline.date(),
+            kind: deadline.kind(),
+            days_left: deadline.days_left(today),
+        }
+    }
+}
+
+impl StatusJson {
+    pub fn new<Store: StoreProvider>(store: &Store) -> StatusJson {
+        let status = StatusService::new(store);
+        let semester = store.current_semester();
+        StatusJson {
+            course: semester
+                .as_ref()
+                .and_then(|it| it.active_course())
+                .map(|it| it.path().name().to_string()),
+            semester: semester.map(|it| it.path().name().to_string()),
+            average: round(status.average()),
+            weighted_average: round(status.weighted_average()),
+            by_degree: status
+                .weighted_average_by_degree()
+                .into_iter()
+                .map(|(degree, average)| (degree, round(average)))
+                .collect(),
+        }
+    }
+}
+
+/// Grades are stored as `f32`, which shows as e.g. `1.2999999523` once widened for JSON.
+fn round(value: f32) -> f64 {
+    (value as f64 * 1000.0).round() / 1000.0
+}
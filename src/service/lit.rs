@@ -0,0 +1,174 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context};
+
+use crate::{
+    cli::LitCommands,
+    domain::{effects, Course, Literature, Reference},
+    service::format::{FormatAlignment, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, ServiceResult};
+
+pub(super) struct LitService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> LitService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self, course: Option<String>, command: Option<LitCommands>) -> ServiceResult {
+        let command = command.unwrap_or(LitCommands::List);
+        let course = resolve_course(self.store, course.as_deref())?;
+        match command {
+            LitCommands::List => self.list(course),
+            LitCommands::Add { source, pdf } => self.add(course, source, pdf),
+            LitCommands::Import { file } => self.import(course, file),
+            LitCommands::Remove { key } => self.remove(course, key),
+            LitCommands::Export { output, .. } => self.export(course, output),
+        }
+    }
+
+    fn list(&self, course: Course) -> ServiceResult {
+        let literature = Literature::load(course.path())?;
+        let references = literature.references();
+        if references.is_empty() {
+            let msg = format!("No literature found for course '{}'", course.name()).info();
+            return Ok(msg);
+        }
+        let keys: Vec<String> = references.iter().map(|it| it.key().to_string()).collect();
+        let titles: Vec<String> = references.iter().map(|it| it.title().to_string()).collect();
+        let authors: Vec<String> = references
+            .iter()
+            .map(|it| match it.authors().as_slice() {
+                [] => "".into(),
+                [author] => author.clone(),
+                [author, ..] => format!("{} et al.", author),
+            })
+            .collect();
+        let years: Vec<String> = references
+            .iter()
+            .map(|it| it.year().map(|it| it.to_string()).unwrap_or_default())
+            .collect();
+        let files: Vec<String> = references
+            .iter()
+            .map(|it| if it.file().is_some() { "pdf" } else { "" }.into())
+            .collect();
+        let body = TableBuilder::new()
+            .column("Key", keys, FormatAlignment::Left)
+            .column("Title", titles, FormatAlignment::Left)
+            .column("Authors", authors, FormatAlignment::Left)
+            .column("Year", years, FormatAlignment::Right)
+            .column("File", files, FormatAlignment::Left)
+            .build();
+        Ok(course.name().line().block(body))
+    }
+
+    fn add(&self, course: Course, source: String, pdf: bool) -> ServiceResult {
+        let mut literature = Literature::load(course.path())?;
+        let (reference, pdf_url) = Reference::fetch(&source)?;
+        let reference = literature.add(reference)?.clone();
+        let mut msg = format!(
+            "Added '{}' as '{}' to '{}'",
+            reference.title(),
+            reference.key(),
+            course.name()
+        )
+        .success();
+
+        if pdf {
+            // the reference is kept without PDF if the download fails
+            match pdf_url.map(|url| reference.download(course.path(), &url)) {
+                Some(Ok(file)) => {
+                    let line = format!("Downloaded PDF to: {}", file.display()).success();
+                    msg = msg.chain("".line()).chain(line);
+                    literature.set_file(reference.key(), file);
+                }
+                Some(Err(err)) => msg = msg.chain("".line()).chain(format!("{:#}", err).error()),
+                None => {
+                    let line = "No PDF link found, download it manually".info();
+                    msg = msg.chain("".line()).chain(line);
+                }
+            }
+        }
+        literature.save(course.path())?;
+        Ok(msg)
+    }
+
+    /// Adds all references not known by key or DOI yet.
+    fn import(&self, course: Course, file: PathBuf) -> ServiceResult {
+        let content = std::fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read: {}", file.display()))?;
+        let base = file.parent().unwrap_or(Path::new("."));
+        let references = Reference::parse_bibtex(&content, base)?;
+        if references.is_empty() {
+            bail!("No references found in: {}", file.display());
+        }
+
+        let mut literature = Literature::load(course.path())?;
+        let (mut added, mut skipped, mut linked) = (0, 0, 0);
+        for reference in references {
+            if literature.contains(&reference) {
+                skipped += 1;
+                continue;
+            }
+            if reference.file().is_some() {
+                linked += 1;
+            }
+            literature.add(reference)?;
+            added += 1;
+        }
+        literature.save(course.path())?;
+
+        let msg = format!(
+            "Imported {} references into '{}', {} with linked PDF",
+            added,
+            course.name(),
+            linked
+        )
+        .success();
+        match skipped {
+            0 => Ok(msg),
+            _ => {
+                let info = format!("Skipped {} references which already exist", skipped).info();
+                Ok(msg.chain("".line()).chain(info))
+            }
+        }
+    }
+
+    fn remove(&self, course: Course, key: String) -> ServiceResult {
+        let mut literature = Literature::load(course.path())?;
+        let reference = literature.remove(&key)?;
+        literature.save(course.path())?;
+        let msg = format!("Reference '{}' has been removed", reference.key()).success();
+        match reference.file() {
+            Some(file) => {
+                let info = format!("Its PDF is kept at: {}", file.display()).info();
+                Ok(msg.chain("".line()).chain(info))
+            }
+            None => Ok(msg),
+        }
+    }
+
+    fn export(&self, course: Course, output: Option<PathBuf>) -> ServiceResult {
+        let literature = Literature::load(course.path())?;
+        let path = output.unwrap_or_else(|| course.path().join("literature.bib"));
+        effects::write(&path, literature.to_bibtex())
+            .with_context(|| format!("Failed to write: {}", path.display()))?;
+        let msg = format!(
+            "Exported {} references to: {}",
+            literature.references().len(),
+            path.display()
+        );
+        Ok(msg.success())
+    }
+}
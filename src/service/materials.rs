@@ -0,0 +1,123 @@
+use colored::Colorize;
+
+use crate::{
+    cli::MaterialsCommands,
+    domain::{Course, Discrepancy, Materials, MATERIALS_DIR},
+    service::format::{FormatAlignment, FormatType, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, ServiceResult};
+
+/// Keeps a checksum manifest of the `materials` folder of each course to detect files broken by
+/// cloud sync.
+pub(super) struct MaterialsService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> MaterialsService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self, course: Option<String>, command: MaterialsCommands) -> ServiceResult {
+        match command {
+            MaterialsCommands::Verify { all } => self.verify(course, all),
+            MaterialsCommands::Update { all } => self.update(course, all),
+        }
+    }
+
+    /// The given course, all courses with `all` or the courses of the active semester. Courses
+    /// without a materials folder are skipped.
+    fn courses(&self, course: Option<String>, all: bool) -> anyhow::Result<Vec<Course>> {
+        let courses: Vec<Course> = match (course, all, self.store.current_semester()) {
+            (Some(reference), _, _) => vec![resolve_course(self.store, Some(&reference))?],
+            (None, false, Some(semester)) => self.store.semester_courses(semester).collect(),
+            (None, _, _) => self.store.courses().collect(),
+        };
+        Ok(courses
+            .into_iter()
+            .filter(|course| Materials::of(course.path()).exists())
+            .collect())
+    }
+
+    fn verify(&self, course: Option<String>, all: bool) -> ServiceResult {
+        let courses = self.courses(course, all)?;
+        if courses.is_empty() {
+            let msg = format!("No course with a '{}' folder found", MATERIALS_DIR);
+            return Ok(msg.info());
+        }
+
+        let mut res: Option<FormatType> = None;
+        let mut problems = 0;
+        for course in &courses {
+            let discrepancies = Materials::of(course.path()).verify()?;
+            if discrepancies.is_empty() {
+                continue;
+            }
+            problems += discrepancies.iter().filter(|it| it.is_problem()).count();
+
+            let files = discrepancies
+                .iter()
+                .map(|it| it.file().to_string())
+                .collect::<Vec<_>>();
+            let states = discrepancies.iter().map(colored_state).collect::<Vec<_>>();
+            let body = TableBuilder::new()
+                .column("File", files, FormatAlignment::Left)
+                .column("Status", states, FormatAlignment::Left)
+                .build();
+            let block = course.name().line().block(body);
+            res = Some(match res {
+                Some(res) => res.chain("".line()).chain(block),
+                None => block,
+            });
+        }
+
+        let summary = match problems {
+            0 => format!("All materials are intact ({} courses checked)", courses.len()).success(),
+            _ => format!(
+                "Found {} problems. Restore the files or accept the current state with 'mm materials update'",
+                problems
+            )
+            .error(),
+        };
+        Ok(match res {
+            Some(res) => res.chain("".line()).chain(summary),
+            None => summary,
+        })
+    }
+
+    fn update(&self, course: Option<String>, all: bool) -> ServiceResult {
+        let courses = self.courses(course, all)?;
+        if courses.is_empty() {
+            let msg = format!("No course with a '{}' folder found", MATERIALS_DIR);
+            return Ok(msg.info());
+        }
+
+        let mut files = 0;
+        for course in &courses {
+            files += Materials::of(course.path()).update()?;
+        }
+        let msg = format!(
+            "Recorded {} files of {} courses in the manifest",
+            files,
+            courses.len()
+        );
+        Ok(msg.success())
+    }
+}
+
+fn colored_state(discrepancy: &Discrepancy) -> String {
+    let text = discrepancy.to_string();
+    match discrepancy {
+        Discrepancy::New(_) => text.green().to_string(),
+        Discrepancy::Changed(_) | Discrepancy::Conflict(_) => text.yellow().to_string(),
+        Discrepancy::Missing(_) | Discrepancy::Corrupted(_) => text.red().to_string(),
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{
+    domain::{migrate, DATA_VERSION},
+    service::format::{FormatAlignment, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::ServiceResult;
+
+/// Upgrades the data files of stores created by older versions of mm.
+pub(super) struct MigrateService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> MigrateService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self) -> ServiceResult {
+        let entry_point = self.store.entry_point();
+        let migrations = migrate(&entry_point, &self.store.semester_names())?;
+        if migrations.is_empty() {
+            let msg = format!(
+                "All data files are up to date (data version {})",
+                DATA_VERSION
+            );
+            return Ok(msg.success());
+        }
+
+        let files: Vec<String> = migrations
+            .iter()
+            .map(|it| {
+                it.path
+                    .strip_prefix(&*entry_point)
+                    .unwrap_or(&it.path)
+                    .display()
+                    .to_string()
+            })
+            .collect();
+        let changes: Vec<String> = migrations.iter().map(|it| it.change.clone()).collect();
+        let msg = format!("Migrated {} data files", migrations.len()).success();
+        Ok(TableBuilder::new()
+            .column("File", files, FormatAlignment::Left)
+            .column("Change", changes, FormatAlignment::Left)
+            .build()
+            .chain("".line())
+            .chain(msg))
+    }
+}
@@ -1,15 +1,57 @@
+mod attach;
+mod backup;
+mod batch;
+mod calendar;
+mod catalog;
 mod course;
-mod format;
+mod deadline;
+mod debug;
+mod degree;
+mod demo;
+mod env;
+mod exam;
+mod exec;
+mod exercise;
+mod export;
+mod flashcards;
+pub mod format;
+mod get;
+mod grades;
+mod history;
+pub mod i18n;
+mod json;
+mod lit;
+mod materials;
+mod migrate;
+mod moodle;
+mod note;
+mod pdf;
+mod plan;
+mod prompt;
+mod reference;
+mod schedule;
+mod search;
 mod semester;
+mod serve;
+#[allow(clippy::module_inception)]
 mod service;
+mod stats;
 mod status;
 mod switch;
+mod sync;
+mod template;
+mod todo;
+mod track;
+mod ui;
+mod watch;
+mod whatif;
 
+pub use debug::install_panic_hook;
+pub use format::FormatType;
+use format::FormatTypeable;
+pub use service::{run_standalone, Service};
 
-use format::{FormatType, FormatTypeable};
-pub(crate) use service::Service;
-
-pub(crate) type ServiceResult = Result<FormatType, anyhow::Error>;
+pub type ServiceResult = Result<FormatType, anyhow::Error>;
 
 impl FormatTypeable for ServiceResult {
     fn format(self) -> FormatType {
@@ -0,0 +1,214 @@
+use anyhow::{anyhow, bail};
+use colored::Colorize;
+
+use crate::{
+    cli::MoodleCommands,
+    domain::{Course, Deadline, DeadlineKind, MoodleCourse, MoodleSettings},
+    service::format::{FormatAlignment, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, ServiceResult};
+
+/// Links courses to Moodle courses and imports their assignments as deadlines.
+pub(super) struct MoodleService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s mut Store,
+}
+
+impl<'s, Store> MoodleService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s mut Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&mut self, command: MoodleCommands) -> ServiceResult {
+        let moodle = self.store.moodle_settings().ok_or_else(|| {
+            anyhow!("Moodle is not configured. Add a [moodle] table with 'url' and 'token' to the config file")
+        })?;
+        match command {
+            MoodleCommands::Courses => self.courses(moodle),
+            MoodleCommands::Link { id, course } => self.link(moodle, id, course),
+            MoodleCommands::Import { all } => self.import(moodle, all),
+        }
+    }
+
+    fn courses(&self, moodle: MoodleSettings) -> ServiceResult {
+        let courses = moodle.courses()?;
+        if courses.is_empty() {
+            return Ok(format!("No enrolled courses found on {}", moodle.url()).info());
+        }
+        let local = self.store.courses().collect::<Vec<_>>();
+
+        let ids = courses
+            .iter()
+            .map(|it| it.id().to_string())
+            .collect::<Vec<_>>();
+        let short_names = courses
+            .iter()
+            .map(|it| it.shortname().to_string())
+            .collect::<Vec<_>>();
+        let names = courses
+            .iter()
+            .map(|it| it.fullname().to_string())
+            .collect::<Vec<_>>();
+        let linked = courses
+            .iter()
+            .map(|moodle| {
+                local
+                    .iter()
+                    .find(|it| it.moodle_id() == Some(moodle.id()))
+                    .map(|it| it.name().green().to_string())
+                    .unwrap_or_else(|| "-".into())
+            })
+            .collect::<Vec<_>>();
+        Ok(TableBuilder::new()
+            .column("Id", ids, FormatAlignment::Right)
+            .column("Short name", short_names, FormatAlignment::Left)
+            .column("Moodle course", names, FormatAlignment::Left)
+            .column("Linked course", linked, FormatAlignment::Left)
+            .build())
+    }
+
+    /// Links the given or active course to `id`. Without id the Moodle course is matched by name,
+    /// for all unlinked courses of the active semester if no course is given either.
+    fn link(
+        &mut self,
+        moodle: MoodleSettings,
+        id: Option<u64>,
+        course: Option<String>,
+    ) -> ServiceResult {
+        let enrolled = moodle.courses()?;
+
+        if let Some(id) = id {
+            let target = enrolled
+                .iter()
+                .find(|it| it.id() == id)
+                .ok_or_else(|| anyhow!("You are not enrolled in a Moodle course with id {}", id))?;
+            let mut course = resolve_course(self.store, course.as_deref())?;
+            course.set_moodle_id(id)?;
+            let msg = format!("Linked '{}' to '{}'", course.name(), target.fullname());
+            return Ok(msg.success());
+        }
+
+        if course.is_some() {
+            let mut course = resolve_course(self.store, course.as_deref())?;
+            let target = unique_match(&enrolled, &course)?;
+            course.set_moodle_id(target.id())?;
+            let msg = format!("Linked '{}' to '{}'", course.name(), target.fullname());
+            return Ok(msg.success());
+        }
+
+        let semester = self
+            .store
+            .current_semester()
+            .ok_or_else(|| anyhow!("An active semester is required to link courses by name"))?;
+        let mut names = Vec::new();
+        let mut targets = Vec::new();
+        for mut course in self.store.semester_courses(semester) {
+            if course.moodle_id().is_some() {
+                continue;
+            }
+            let state = match unique_match(&enrolled, &course) {
+                Ok(target) => {
+                    course.set_moodle_id(target.id())?;
+                    target.fullname().green().to_string()
+                }
+                Err(err) => err.to_string().yellow().to_string(),
+            };
+            names.push(course.name());
+            targets.push(state);
+        }
+        if names.is_empty() {
+            return Ok("All courses of the active semester are already linked".info());
+        }
+        Ok(TableBuilder::new()
+            .column("Course", names, FormatAlignment::Left)
+            .column("Moodle course", targets, FormatAlignment::Left)
+            .build())
+    }
+
+    /// Imports the due dates of all assignments of linked courses as exercise deadlines. Locked
+    /// courses are finished and skipped.
+    fn import(&mut self, moodle: MoodleSettings, all: bool) -> ServiceResult {
+        let courses: Vec<Course> = match (all, self.store.current_semester()) {
+            (false, Some(semester)) => self.store.semester_courses(semester).collect(),
+            _ => self.store.courses().collect(),
+        };
+        let mut courses = courses
+            .into_iter()
+            .filter(|it| it.moodle_id().is_some() && !it.is_locked())
+            .collect::<Vec<_>>();
+        if courses.is_empty() {
+            let error = "No linked courses found".error();
+            let info = "Link courses to Moodle with 'mm moodle link'".info();
+            return Ok(error.chain("".line()).chain(info));
+        }
+        courses.sort_by_key(|it| it.name());
+
+        let ids = courses
+            .iter()
+            .filter_map(|it| it.moodle_id())
+            .collect::<Vec<_>>();
+        let assignments = moodle.assignments(&ids)?;
+
+        let mut names = Vec::new();
+        let mut counts = Vec::new();
+        let (mut added, mut moved) = (0, 0);
+        for course in &mut courses {
+            let deadlines = assignments
+                .iter()
+                .filter(|it| Some(it.course()) == course.moodle_id())
+                .filter_map(|it| {
+                    let due = it.due()?;
+                    Some(Deadline::new(
+                        it.name().to_string(),
+                        due,
+                        DeadlineKind::Exercise,
+                    ))
+                })
+                .collect::<Vec<_>>();
+            let (new, changed) = course.import_deadlines(deadlines)?;
+            added += new;
+            moved += changed;
+            names.push(course.name());
+            counts.push(match (new, changed) {
+                (0, 0) => "up to date".to_string(),
+                (new, 0) => format!("{} new", new).green().to_string(),
+                (new, changed) => format!("{} new, {} moved", new, changed)
+                    .yellow()
+                    .to_string(),
+            });
+        }
+
+        let body = TableBuilder::new()
+            .column("Course", names, FormatAlignment::Left)
+            .column("Deadlines", counts, FormatAlignment::Left)
+            .build();
+        let msg = format!(
+            "Imported {} new deadlines, {} have been moved",
+            added, moved
+        );
+        Ok(body.chain("".line()).chain(msg.success()))
+    }
+}
+
+fn unique_match<'m>(
+    enrolled: &'m [MoodleCourse],
+    course: &Course,
+) -> anyhow::Result<&'m MoodleCourse> {
+    let name = course.name();
+    let matches = enrolled
+        .iter()
+        .filter(|it| it.matches(&name) || it.matches(course.path().name()))
+        .collect::<Vec<_>>();
+    match matches.as_slice() {
+        [target] => Ok(target),
+        [] => bail!("no Moodle course matches '{}'", name),
+        _ => bail!("'{}' matches several Moodle courses, link it by id", name),
+    }
+}
@@ -0,0 +1,220 @@
+use std::process::Command;
+
+use anyhow::{anyhow, bail, Context};
+
+use crate::{
+    cli::{NoteCommands, NoteFormatDO},
+    domain::{compile_latex, effects, Course, CourseKind, Date, Note, NoteFormat, MAIN_DOCUMENT},
+    service::format::{FormatAlignment, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, template::render, ServiceResult};
+
+/// Creates, lists and opens the dated notes of a course.
+pub(super) struct NoteService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> NoteService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self, course: Option<String>, command: Option<NoteCommands>) -> ServiceResult {
+        let course = resolve_course(self.store, course.as_deref())?;
+        let command = command.unwrap_or(NoteCommands::List);
+        match command {
+            NoteCommands::New { title, format } => self.new_note(course, title, format),
+            NoteCommands::List => self.list(course),
+            NoteCommands::Open { note } => self.open(course, note),
+            NoteCommands::Compile { note } => self.compile(course, note),
+        }
+    }
+
+    /// Uses `note.<ext>` from the template directory or a built-in template. Supported
+    /// placeholders are `{{title}}`, `{{course}}`, `{{semester}}` and `{{date}}`. Notes of LaTeX
+    /// courses default to `.tex` and the course gets a `main.tex` if it has none yet.
+    fn new_note(
+        &self,
+        course: Course,
+        title: String,
+        format: Option<NoteFormatDO>,
+    ) -> ServiceResult {
+        let format = match (format, course.kind()) {
+            (Some(format), _) => NoteFormat::from_do(format),
+            (None, CourseKind::Latex) => NoteFormat::Latex,
+            (None, CourseKind::Markdown) => NoteFormat::Markdown,
+        };
+        let date = Date::today();
+        let mut res = None;
+
+        if course.kind() == CourseKind::Latex {
+            let main = course.path().join(MAIN_DOCUMENT);
+            if !main.exists() {
+                let template = self.template(MAIN_DOCUMENT, NoteFormat::main_template())?;
+                let content = self.render(&template, &course, &title, &date, NoteFormat::Latex);
+                effects::write(&main, content).with_context(|| {
+                    anyhow!("Failed to write main document to: {}", main.display())
+                })?;
+                res = Some(format!("Created main document: {}", main.display()).success());
+            }
+        }
+
+        let name = format!("note.{}", format.extension());
+        let template = self.template(&name, format.default_template())?;
+        let content = self.render(&template, &course, &title, &date, format);
+        let note = Note::create(course.path(), date, &title, format, &content)?;
+        let msg = format!("Created note: {}", note.path().display()).success();
+        Ok(match res {
+            Some(res) => res.chain("".line()).chain(msg),
+            None => msg,
+        })
+    }
+
+    /// Reads `name` from the template directory or falls back to `default`.
+    fn template(&self, name: &str, default: &str) -> anyhow::Result<String> {
+        let path = self.store.template_dir().join(name);
+        if !path.is_file() {
+            return Ok(default.to_string());
+        }
+        std::fs::read_to_string(&path)
+            .with_context(|| anyhow!("Failed to read template: {}", path.display()))
+    }
+
+    fn render(
+        &self,
+        template: &str,
+        course: &Course,
+        title: &str,
+        date: &Date,
+        format: NoteFormat,
+    ) -> String {
+        let semester = course
+            .path()
+            .parent()
+            .and_then(|it| it.file_name())
+            .map(|it| it.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let escape = |value: &str| match format {
+            NoteFormat::Latex => escape_latex(value),
+            NoteFormat::Markdown => value.to_string(),
+        };
+        render(
+            template,
+            &[
+                ("title", &escape(title)),
+                ("course", &escape(&course.name())),
+                ("semester", &escape(&semester)),
+                ("date", &date.to_string()),
+                (
+                    "ects",
+                    &course.ects().map(|it| it.to_string()).unwrap_or_default(),
+                ),
+                ("degrees", &escape(&course.degrees().join(", "))),
+            ],
+        )
+    }
+
+    fn list(&self, course: Course) -> ServiceResult {
+        let notes = Note::list(course.path())?;
+        if notes.is_empty() {
+            return Ok(format!("No notes found for course '{}'", course.name()).info());
+        }
+
+        let dates = notes
+            .iter()
+            .map(|it| it.date().map(|it| it.to_string()).unwrap_or_default())
+            .collect::<Vec<_>>();
+        let titles = notes
+            .iter()
+            .map(|it| it.title().to_string())
+            .collect::<Vec<_>>();
+        let files = notes
+            .iter()
+            .map(|it| it.file_name().to_string())
+            .collect::<Vec<_>>();
+
+        let header = course.name().line();
+        let body = TableBuilder::new()
+            .column("Date", dates, FormatAlignment::Left)
+            .column("Title", titles, FormatAlignment::Left)
+            .column("File", files, FormatAlignment::Left)
+            .build();
+        Ok(header.block(body))
+    }
+
+    /// Opens the latest note or the first note whose file name contains `note` with `$VISUAL`,
+    /// `$EDITOR` or the default application of the system.
+    fn open(&self, course: Course, note: String) -> ServiceResult {
+        let notes = Note::list(course.path())?;
+        let note = find_note(&notes, &note, &course)?;
+
+        let editor = std::env::var("VISUAL")
+            .or_else(|_| std::env::var("EDITOR"))
+            .unwrap_or_else(|_| {
+                if cfg!(target_os = "macos") {
+                    "open".into()
+                } else {
+                    "xdg-open".into()
+                }
+            });
+        let status = Command::new(&editor)
+            .arg(note.path())
+            .status()
+            .with_context(|| anyhow!("Failed to run '{}'", editor))?;
+        if !status.success() {
+            bail!("'{}' exited with {}", editor, status);
+        }
+        Ok(format!("Opened note: {}", note.file_name()).success())
+    }
+
+    /// Compiles the latest note, a note matching `note` or with `main` the main document.
+    fn compile(&self, course: Course, note: String) -> ServiceResult {
+        let path = match note.as_str() {
+            "main" => {
+                let main = course.path().join(MAIN_DOCUMENT);
+                if !main.is_file() {
+                    bail!("Course '{}' has no {}", course.name(), MAIN_DOCUMENT);
+                }
+                main
+            }
+            _ => {
+                let notes = Note::list(course.path())?
+                    .into_iter()
+                    .filter(|it| it.path().extension().is_some_and(|it| it == "tex"))
+                    .collect::<Vec<_>>();
+                find_note(&notes, &note, &course)?.path().to_path_buf()
+            }
+        };
+        let pdf = compile_latex(&path)?;
+        Ok(format!("Compiled: {}", pdf.display()).success())
+    }
+}
+
+fn find_note<'n>(notes: &'n [Note], name: &str, course: &Course) -> anyhow::Result<&'n Note> {
+    match name {
+        "latest" => notes.last(),
+        name => notes.iter().rev().find(|it| it.file_name().contains(name)),
+    }
+    .ok_or_else(|| anyhow!("No note '{}' found for course '{}'", name, course.name()))
+}
+
+fn escape_latex(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => format!("\\{}", c),
+            '~' => "\\textasciitilde{}".into(),
+            '^' => "\\textasciicircum{}".into(),
+            '\\' => "\\textbackslash{}".into(),
+            c => c.to_string(),
+        })
+        .collect()
+}
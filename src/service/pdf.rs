@@ -0,0 +1,80 @@
+use anyhow::{anyhow, bail};
+
+use crate::{
+    cli::PdfCommands,
+    domain::{apply_renames, plan_normalize, Course},
+    service::format::{
+        DialogEntry, DialogOutput, FormatAlignment, FormatService, IntoFormatType, TableBuilder,
+    },
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, ServiceResult};
+
+pub(super) struct PdfService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> PdfService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self, course: Option<String>, command: PdfCommands) -> ServiceResult {
+        let course = resolve_course(self.store, course.as_deref())?;
+        match command {
+            PdfCommands::Normalize { dir } => self.normalize(course, dir),
+        }
+    }
+
+    /// Shows the planned renames and applies them after confirmation.
+    fn normalize(&self, course: Course, dir: String) -> ServiceResult {
+        let dir = course.path().join(dir);
+        if !dir.is_dir() {
+            bail!("No slides folder found at: {}", dir.display());
+        }
+        let renames = plan_normalize(&dir, course.path().name(), &self.store.slide_pattern())?;
+        if renames.is_empty() {
+            return Ok("All slides are already normalized".info());
+        }
+
+        let file_name = |path: &std::path::Path| {
+            path.file_name()
+                .map(|it| it.to_string_lossy().to_string())
+                .unwrap_or_default()
+        };
+        let from: Vec<String> = renames.iter().map(|it| file_name(&it.from)).collect();
+        let to: Vec<String> = renames.iter().map(|it| file_name(&it.to)).collect();
+        FormatService::run(
+            TableBuilder::new()
+                .column("File", from, FormatAlignment::Left)
+                .column("New name", to, FormatAlignment::Left)
+                .build(),
+        );
+
+        let dialog = vec![DialogEntry::YesNoInput(format!(
+            "Rename {} slides?",
+            renames.len()
+        ))];
+        let Some(response) = FormatService::dialog(dialog) else {
+            return Ok("Operation has been canceled".info());
+        };
+        let res = response
+            .first()
+            .ok_or_else(|| anyhow!("Dialog has not returned not the specified output"))?;
+        let DialogOutput::YesNo(cond) = res else {
+            bail!("Invalid input");
+        };
+        if !cond {
+            return Ok("Operation has been canceled".info());
+        }
+        apply_renames(&renames)?;
+        Ok(format!("Renamed {} slides in: {}", renames.len(), dir.display()).success())
+    }
+}
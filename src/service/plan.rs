@@ -0,0 +1,384 @@
+use anyhow::{anyhow, bail};
+use colored::Colorize;
+
+use crate::{
+    cli::PlanCommands,
+    domain::{missing_prerequisites, Catalog, Course, CourseStatus, Module, Plan, PlannedCourse},
+    service::format::{FormatAlignment, FormatType, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::ServiceResult;
+
+/// Compares the module catalog with the courses taken so far and plans courses for future
+/// semesters.
+pub(super) struct PlanService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+/// Progress of a catalog module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ModuleState {
+    Passed,
+    Enrolled,
+    Failed,
+    Missing,
+}
+
+impl<'s, Store> PlanService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self, budget: u32, command: Option<PlanCommands>) -> ServiceResult {
+        let Some(command) = command else {
+            return self.suggest(budget);
+        };
+        match command {
+            PlanCommands::Add {
+                semester,
+                name,
+                ects,
+                degree,
+                module_id,
+            } => self.add(semester, name, ects, degree, module_id),
+            PlanCommands::Remove { semester, name } => self.remove(semester, name),
+            PlanCommands::Show => self.show(),
+            PlanCommands::Apply { semester } => self.apply(semester),
+        }
+    }
+
+    /// Lists required modules which are not passed yet and suggests modules for the next
+    /// semester within `budget` ECTS. Required modules come first, then by recommended semester.
+    fn suggest(&self, budget: u32) -> ServiceResult {
+        let catalog = Catalog::load(&self.store.entry_point())?;
+        if catalog.modules().is_empty() {
+            let error = "No module catalog found".error();
+            let info = "Import one with 'mm catalog import FILE'".info();
+            return Ok(error.chain("".line()).chain(info));
+        }
+        let courses = self.store.courses().collect::<Vec<_>>();
+        let modules = catalog
+            .modules()
+            .iter()
+            .map(|it| (it, state(it, &courses)))
+            .collect::<Vec<_>>();
+
+        let mut open = modules
+            .iter()
+            .filter(|(it, state)| it.is_required() && *state != ModuleState::Passed)
+            .collect::<Vec<_>>();
+        open.sort_by_key(|(it, _)| (it.semester().unwrap_or(u16::MAX), it.name()));
+        let open_block = match open.is_empty() {
+            true => "All required modules are passed".success(),
+            false => {
+                let codes: Vec<String> = open.iter().map(|(it, _)| code(it)).collect();
+                let names: Vec<String> = open.iter().map(|(it, _)| it.name().to_string()).collect();
+                let ects: Vec<String> = open.iter().map(|(it, _)| it.ects().to_string()).collect();
+                let semesters: Vec<String> = open.iter().map(|(it, _)| semester(it)).collect();
+                let states: Vec<String> = open
+                    .iter()
+                    .map(|(_, state)| match state {
+                        ModuleState::Enrolled => "enrolled".green().to_string(),
+                        ModuleState::Failed => "failed".red().to_string(),
+                        _ => "missing".yellow().to_string(),
+                    })
+                    .collect();
+                "Open required modules".line().block(
+                    TableBuilder::new()
+                        .column("Code", codes, FormatAlignment::Left)
+                        .column("Module", names, FormatAlignment::Left)
+                        .column("ECTS", ects, FormatAlignment::Right)
+                        .column("Sem.", semesters, FormatAlignment::Right)
+                        .column("State", states, FormatAlignment::Left)
+                        .build(),
+                )
+            }
+        };
+
+        let next = self.next_semester();
+        let mut candidates = modules
+            .iter()
+            .filter(|(_, state)| matches!(state, ModuleState::Missing | ModuleState::Failed))
+            .map(|(it, _)| *it)
+            .filter(|it| it.semester().is_none_or(|semester| semester <= next))
+            .collect::<Vec<_>>();
+        candidates.sort_by_key(|it| {
+            (
+                !it.is_required(),
+                it.semester().unwrap_or(u16::MAX),
+                it.name(),
+            )
+        });
+        let mut planned = 0;
+        let mut suggested = Vec::new();
+        for module in candidates {
+            if planned + module.ects() as u32 <= budget {
+                planned += module.ects() as u32;
+                suggested.push(module);
+            }
+        }
+
+        let header = format!("Suggestion for semester {} ({} ECTS)", next, budget).line();
+        let suggestion = match suggested.is_empty() {
+            true => "No open modules fit into the next semester".info(),
+            false => {
+                let codes: Vec<String> = suggested.iter().map(|it| code(it)).collect();
+                let names: Vec<String> = suggested.iter().map(|it| it.name().to_string()).collect();
+                let ects: Vec<String> = suggested.iter().map(|it| it.ects().to_string()).collect();
+                let categories: Vec<String> = suggested
+                    .iter()
+                    .map(|it| it.category().unwrap_or("-").to_string())
+                    .collect();
+                TableBuilder::new()
+                    .column("Code", codes, FormatAlignment::Left)
+                    .column("Module", names, FormatAlignment::Left)
+                    .column("ECTS", ects, FormatAlignment::Right)
+                    .column("Category", categories, FormatAlignment::Left)
+                    .build()
+                    .chain("".line())
+                    .chain(format!("{} of {} ECTS planned", planned, budget).line())
+            }
+        };
+        let mut msg = open_block.chain("".line()).chain(header.block(suggestion));
+        if let Some(missing) = self.missing_prerequisites(&catalog, &courses, &suggested) {
+            let header = "Prerequisites not passed yet".line();
+            msg = msg.chain("".line()).chain(header.block(missing));
+        }
+        Ok(msg)
+    }
+
+    /// Prerequisites of the enrolled courses and the suggested modules which are not passed yet.
+    /// Returns `None` if all are passed.
+    fn missing_prerequisites(
+        &self,
+        catalog: &Catalog,
+        courses: &[Course],
+        suggested: &[&Module],
+    ) -> Option<FormatType> {
+        let enrolled = courses.iter().filter(|it| it.is_enrolled()).map(|course| {
+            let mut prerequisites = course.prerequisites().clone();
+            for module in catalog.modules().iter().filter(|it| it.matches(course)) {
+                prerequisites.extend(module.prerequisites().iter().cloned());
+            }
+            (course.name(), prerequisites)
+        });
+        let suggested = suggested
+            .iter()
+            .map(|it| (it.name().to_string(), it.prerequisites().clone()));
+
+        let mut names = Vec::new();
+        let mut missing = Vec::new();
+        for (name, prerequisites) in enrolled.chain(suggested) {
+            let open = missing_prerequisites(&prerequisites, courses);
+            if !open.is_empty() && !names.contains(&name) {
+                names.push(name);
+                missing.push(open.join(", ").red().to_string());
+            }
+        }
+        if names.is_empty() {
+            return None;
+        }
+        Some(
+            TableBuilder::new()
+                .column("Module", names, FormatAlignment::Left)
+                .column("Missing", missing, FormatAlignment::Left)
+                .build(),
+        )
+    }
+
+    /// Plans a course for a semester which does not exist yet. ECTS and module id default to
+    /// the module of the same name in the catalog.
+    fn add(
+        &self,
+        semester: String,
+        name: String,
+        ects: Option<u8>,
+        degrees: Vec<String>,
+        module_id: Option<String>,
+    ) -> ServiceResult {
+        if !self.store.semester_names().is_name(&semester) {
+            bail!("'{}' is not a valid semester name", semester);
+        }
+        if self.store.get_semester(&semester).is_some() {
+            bail!(
+                "Semester '{}' already exists, add the course with 'mm course add'",
+                semester
+            );
+        }
+        let entry_point = self.store.entry_point();
+        let catalog = Catalog::load(&entry_point)?;
+        let module = catalog.modules().iter().find(|it| it.is_named(&name));
+        let ects = ects.or(module.map(|it| it.ects()));
+        let module_id = module_id.or(module.and_then(|it| it.code().map(str::to_string)));
+
+        let mut plan = Plan::load(&entry_point)?;
+        plan.add(
+            &semester,
+            PlannedCourse::new(name.clone(), ects, degrees, module_id),
+        )?;
+        plan.save(&entry_point)?;
+        let total = plan.semester(&semester).map(|it| it.ects()).unwrap_or(0);
+        Ok(format!(
+            "'{}' has been planned for {}, which has {} ECTS planned",
+            name, semester, total
+        )
+        .success())
+    }
+
+    fn remove(&self, semester: String, name: Option<String>) -> ServiceResult {
+        let entry_point = self.store.entry_point();
+        let mut plan = Plan::load(&entry_point)?;
+        plan.remove(&semester, name.as_deref())?;
+        plan.save(&entry_point)?;
+        let msg = match name {
+            Some(name) => format!("'{}' has been removed from the plan of {}", name, semester),
+            None => format!("The plan of {} has been removed", semester),
+        };
+        Ok(msg.success())
+    }
+
+    /// The planned semesters in order with their courses and the sum of their ECTS, compared to
+    /// the configured `ects_budget`.
+    fn show(&self) -> ServiceResult {
+        let plan = Plan::load(&self.store.entry_point())?;
+        let names = self.store.semester_names();
+        let mut semesters = plan.semesters().iter().collect::<Vec<_>>();
+        semesters.sort_by_key(|it| {
+            names
+                .deserialize(it.name())
+                .map(|(number, cycle)| (cycle, number))
+                .ok()
+        });
+        if semesters.is_empty() {
+            return Ok("No semesters planned, plan one with 'mm plan add SEMESTER COURSE'".info());
+        }
+
+        let blocks = semesters.iter().map(|semester| {
+            let courses = semester.courses();
+            let table = TableBuilder::new()
+                .column(
+                    "Course",
+                    courses.iter().map(|it| it.name().to_string()).collect(),
+                    FormatAlignment::Left,
+                )
+                .column(
+                    "ECTS",
+                    courses
+                        .iter()
+                        .map(|it| it.ects().map_or("-".into(), |it| it.to_string()))
+                        .collect(),
+                    FormatAlignment::Right,
+                )
+                .column(
+                    "Degrees",
+                    courses.iter().map(|it| it.degrees().join(", ")).collect(),
+                    FormatAlignment::Left,
+                )
+                .build();
+            let header = match self.store.ects_budget() {
+                Some(budget) => {
+                    let ects = FormatType::budget(semester.ects(), budget);
+                    format!("{}: {}", semester.name(), ects)
+                }
+                None => format!("{}: {} ECTS", semester.name(), semester.ects()),
+            };
+            header.line().block(table)
+        });
+        let msg = blocks
+            .reduce(|msg, block| msg.chain("".line()).chain(block))
+            .unwrap_or_else(|| "".line());
+
+        let total = semesters.iter().map(|it| it.ects()).sum::<u32>();
+        let summary = format!("{} ECTS planned in {} semesters", total, semesters.len());
+        Ok(msg.chain("".line()).chain(summary.line()))
+    }
+
+    /// Creates the folders of the planned semester, unless it exists already, and of its
+    /// courses with their ECTS, degrees and module id. The semester is removed from the plan.
+    fn apply(&self, name: String) -> ServiceResult {
+        let entry_point = self.store.entry_point();
+        let mut plan = Plan::load(&entry_point)?;
+        let planned = plan
+            .semester(&name)
+            .cloned()
+            .ok_or_else(|| anyhow!("No semester '{}' is planned", name))?;
+
+        let semester = match self.store.get_semester(&name) {
+            Some(semester) => semester,
+            None => {
+                let path = entry_point.create_semester_path(&name)?;
+                self.store
+                    .get_semester(path.name())
+                    .ok_or_else(|| anyhow!("Failed to retrieve newly created semester"))?
+            }
+        };
+        let mut created = 0;
+        for planned in planned.courses() {
+            // courses added in the meantime are kept as they are
+            if semester.course(planned.name()).is_some() {
+                continue;
+            }
+            let path = semester.path().create_course_path(planned.name())?;
+            path.create_data_file()?;
+            Course::from_path(path)?.set_planned(planned)?;
+            created += 1;
+        }
+        plan.remove(&name, None)?;
+        plan.save(&entry_point)?;
+
+        let msg = format!(
+            "Semester '{}' has been created with {} courses",
+            name, created
+        );
+        let info = format!("Switch to it with 'mm switch {}'", name);
+        Ok(msg.success().chain("".line()).chain(info.info()))
+    }
+
+    /// Number of the semester after the active or the latest one.
+    fn next_semester(&self) -> u16 {
+        let current = self
+            .store
+            .current_semester()
+            .or_else(|| self.store.semesters().max());
+        current.map(|it| it.number() + 1).unwrap_or(1)
+    }
+}
+
+fn state(module: &Module, courses: &[Course]) -> ModuleState {
+    let attempts = courses
+        .iter()
+        .filter(|it| module.matches(it))
+        .collect::<Vec<_>>();
+    if attempts.iter().any(|it| it.is_passed()) {
+        return ModuleState::Passed;
+    }
+    let failed = |it: &&Course| {
+        matches!(
+            it.status(),
+            Some(CourseStatus::Failed | CourseStatus::Dropped)
+        ) || it.grade().is_some()
+    };
+    match attempts.as_slice() {
+        [] => ModuleState::Missing,
+        attempts if attempts.iter().all(failed) => ModuleState::Failed,
+        _ => ModuleState::Enrolled,
+    }
+}
+
+fn code(module: &Module) -> String {
+    module.code().unwrap_or("-").to_string()
+}
+
+fn semester(module: &Module) -> String {
+    module
+        .semester()
+        .map(|it| it.to_string())
+        .unwrap_or_else(|| "-".into())
+}
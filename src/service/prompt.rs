@@ -0,0 +1,33 @@
+use crate::{
+    domain::{Config, Store},
+    service::format::{FormatType, IntoFormatType},
+    ConfigProvider, StoreProvider,
+};
+
+use super::template::render;
+
+/// Prints the active context for shell prompts. It runs on every prompt, so it only reads the
+/// store data files of the active semester and course and prints nothing instead of errors.
+pub(super) struct PromptService;
+
+impl PromptService {
+    /// Returns `None` if there is no active semester or the store can not be loaded.
+    pub fn run(format: Option<String>) -> Option<FormatType> {
+        // unlike Config::new, a missing config file is not created on every prompt
+        let config = Config::from_path(Config::file_path().ok()?).ok()?;
+        let format = format.unwrap_or_else(|| config.prompt_format());
+        let store = Store::new(config).ok()?;
+
+        let semester = store.current_semester()?;
+        let Some(course) = semester.active_course() else {
+            return Some(semester.path().name().raw());
+        };
+        let name = course.name();
+        let variables = [
+            ("semester", semester.path().name()),
+            ("course", course.path().name()),
+            ("course_name", name.as_str()),
+        ];
+        Some(render(&format, &variables).raw())
+    }
+}
@@ -0,0 +1,245 @@
+use std::io::IsTerminal;
+
+use anyhow::{bail, Result};
+
+use crate::{
+    domain::{fold_name, Course, Semester},
+    MmError, StoreProvider,
+};
+
+use super::{
+    format::{DialogEntry, DialogOutput, FormatService},
+    i18n::tr,
+};
+
+/// Resolves a course reference of the form `semester/course` or `course`. A plain course is
+/// looked up in the active semester first and afterwards by name in all semesters. Without a
+/// reference the active course is returned.
+pub(super) fn resolve_course<Store>(store: &Store, reference: Option<&str>) -> Result<Course>
+where
+    Store: StoreProvider,
+{
+    let Some(reference) = reference else {
+        return store
+            .current_course()
+            .ok_or_else(|| MmError::NoActiveCourse.into());
+    };
+
+    let split = reference.split('/').collect::<Vec<&str>>();
+    match split.as_slice() {
+        [semester, course] => store
+            .get_semester(semester)
+            .ok_or_else(|| MmError::SemesterNotFound(semester.to_string()))?
+            .course(course)
+            .ok_or_else(|| MmError::CourseNotFound(reference.into()).into()),
+        [course] => {
+            if let Some(course) = store
+                .current_semester()
+                .and_then(|semester| semester.course(course))
+            {
+                return Ok(course);
+            }
+            let course = fold_name(course);
+            let mut courses = store
+                .courses()
+                .filter(|it| {
+                    fold_name(&it.name()) == course || fold_name(it.path().name()) == course
+                })
+                .collect::<Vec<_>>();
+            match courses.len() {
+                0 => Err(MmError::CourseNotFound(reference.into()).into()),
+                1 => Ok(courses.remove(0)),
+                _ => choose_course(reference, courses),
+            }
+        }
+        _ => Err(MmError::InvalidReference(reference.into()).into()),
+    }
+}
+
+/// Resolves a semester or a range of semesters like `b01..b04`, whose ends may be left out, e.g.
+/// `b03..` for all semesters from `b03` on. Returns the first and last semester.
+pub(super) fn resolve_semester_range<Store>(
+    store: &Store,
+    range: &str,
+) -> Result<(Option<Semester>, Option<Semester>)>
+where
+    Store: StoreProvider,
+{
+    let resolve = |name: &str| match name.trim() {
+        "" => Ok(None),
+        name => store
+            .get_semester(name)
+            .map(Some)
+            .ok_or_else(|| MmError::SemesterNotFound(name.to_string())),
+    };
+    match range.split_once("..") {
+        Some((from, to)) => Ok((resolve(from)?, resolve(to)?)),
+        None => {
+            let semester = resolve(range)?;
+            Ok((semester.clone(), semester))
+        }
+    }
+}
+
+/// Asks which of the courses of several semesters is meant. Without a terminal, e.g. in scripts,
+/// the first one is taken.
+fn choose_course(reference: &str, mut courses: Vec<Course>) -> Result<Course> {
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return Ok(courses.remove(0));
+    }
+    let options = courses
+        .iter()
+        .map(|course| {
+            let semester = course
+                .path()
+                .as_path()
+                .parent()
+                .and_then(|it| it.file_name())
+                .map(|it| it.to_string_lossy().to_string())
+                .unwrap_or_default();
+            format!("{}/{} ({})", semester, course.path().name(), course.name())
+        })
+        .collect();
+    let dialog = vec![DialogEntry::Select {
+        prompt: format!("'{}' matches courses of several semesters:", reference),
+        options,
+    }];
+    match FormatService::dialog(dialog).as_deref() {
+        Some([DialogOutput::Selected(idx)]) => Ok(courses.remove(*idx)),
+        _ => bail!("Operation has been canceled"),
+    }
+}
+
+/// Adds the names closest to a semester or course which could not be found to the error, e.g.
+/// "Did you mean 'Algorithms'?". Other errors are returned unchanged.
+pub(super) fn did_you_mean<Store>(store: &Store, err: anyhow::Error) -> anyhow::Error
+where
+    Store: StoreProvider,
+{
+    let (reference, candidates) = match err.downcast_ref::<MmError>() {
+        Some(MmError::SemesterNotFound(name)) => {
+            let names = store.semesters().map(|it| it.name()).collect::<Vec<_>>();
+            (name.clone(), names)
+        }
+        Some(MmError::CourseNotFound(reference)) => {
+            let qualified = |course: &Course| {
+                let semester = course
+                    .path()
+                    .as_path()
+                    .parent()
+                    .and_then(|it| it.file_name())
+                    .map(|it| it.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                format!("{}/{}", semester, course.path().name())
+            };
+            let plain = |course: Course| vec![course.path().name().to_string(), course.name()];
+            // commands look up plain names in the active semester, so only its courses are
+            // suggested for them
+            let names = match (reference.contains('/'), store.current_semester()) {
+                (true, _) => store.courses().map(|it| qualified(&it)).collect(),
+                (false, Some(semester)) => semester.courses().flat_map(plain).collect(),
+                (false, None) => store.courses().flat_map(plain).collect::<Vec<_>>(),
+            };
+            (reference.clone(), names)
+        }
+        _ => return err,
+    };
+
+    let closest = closest(&reference, candidates);
+    if closest.is_empty() {
+        return err;
+    }
+    let closest = closest
+        .iter()
+        .map(|it| format!("'{}'", it))
+        .collect::<Vec<_>>()
+        .join(" or ");
+    // the error is translated here as the values of a message are inserted untranslated
+    let msg = format!("{}. Did you mean {}?", tr(&err.to_string()), closest);
+    err.context(msg)
+}
+
+/// Candidates with the smallest edit distance to the reference, at most a third of its length.
+/// Candidates equal to the reference are left out as they would not help.
+fn closest(reference: &str, mut candidates: Vec<String>) -> Vec<String> {
+    let reference = fold_name(reference);
+    let max = (reference.chars().count() / 3).max(1);
+    candidates.sort();
+    candidates.dedup();
+    let distances = candidates
+        .into_iter()
+        .map(|it| (levenshtein(&reference, &fold_name(&it)), it))
+        .filter(|(distance, _)| (1..=max).contains(distance))
+        .collect::<Vec<_>>();
+    let Some(min) = distances.iter().map(|(distance, _)| *distance).min() else {
+        return Vec::new();
+    };
+    distances
+        .into_iter()
+        .filter(|(distance, _)| *distance == min)
+        .map(|(_, it)| it)
+        .take(3)
+        .collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = match ca == *cb {
+                true => previous,
+                false => 1 + previous.min(row[j]).min(row[j + 1]),
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{closest, levenshtein};
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|it| it.to_string()).collect()
+    }
+
+    #[test]
+    fn levenshtein_counts_edits() {
+        assert_eq!(levenshtein("", ""), 0);
+        assert_eq!(levenshtein("analysis", "analysis"), 0);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("datenbankn", "datenbanken"), 1);
+        // umlauts are single characters, not their UTF-8 bytes
+        assert_eq!(levenshtein("übung", "ubung"), 1);
+    }
+
+    #[test]
+    fn closest_suggests_the_nearest_names() {
+        let candidates = names(&["Datenbanken", "Datenstrukturen", "Analysis I"]);
+        assert_eq!(closest("Datenbankn", candidates), names(&["Datenbanken"]));
+        // equally close names are all suggested in order
+        let candidates = names(&["Info2", "Info1", "Algebra"]);
+        assert_eq!(closest("Info", candidates), names(&["Info1", "Info2"]));
+    }
+
+    #[test]
+    fn closest_ignores_case_and_decomposed_umlauts() {
+        let candidates = names(&["Einführung in die Informatik"]);
+        assert_eq!(
+            closest("einfu\u{308}hrung in die informatk", candidates),
+            names(&["Einführung in die Informatik"])
+        );
+    }
+
+    #[test]
+    fn closest_leaves_out_the_reference_and_distant_names() {
+        assert!(closest("Info1", names(&["Info1", "info1"])).is_empty());
+        assert!(closest("LA", names(&["Analysis"])).is_empty());
+    }
+}
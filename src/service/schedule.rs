@@ -0,0 +1,134 @@
+use anyhow::{anyhow, bail};
+
+use crate::{
+    cli::ScheduleCommands,
+    domain::{Date, Slot, SlotKind, Time, Weekday},
+    service::format::{FormatAlignment, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, ServiceResult};
+
+pub(super) struct ScheduleService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s mut Store,
+}
+
+impl<'s, Store> ScheduleService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s mut Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&mut self, command: Option<ScheduleCommands>) -> ServiceResult {
+        let command = command.unwrap_or(ScheduleCommands::Show);
+        match command {
+            ScheduleCommands::Show => self.show(),
+            ScheduleCommands::Add {
+                course,
+                weekday,
+                start,
+                end,
+                room,
+                kind,
+            } => self.add(course, weekday, start, end, room, SlotKind::from_do(kind)),
+        }
+    }
+
+    /// Renders the slots of all courses in the active semester for the current week.
+    fn show(&self) -> ServiceResult {
+        let semester = self
+            .store
+            .current_semester()
+            .ok_or_else(|| anyhow!("An active semester is required to show the schedule"))?;
+
+        let courses = self.store.semester_courses(semester).collect::<Vec<_>>();
+        let mut slots = courses
+            .iter()
+            .flat_map(|course| course.slots().iter().map(move |slot| (course, slot)))
+            .collect::<Vec<_>>();
+        slots.sort_by_key(|(_, slot)| (slot.weekday(), slot.start()));
+
+        if slots.is_empty() {
+            return Ok("No lecture slots found in the active semester".info());
+        }
+
+        let today = Date::today();
+        let monday = today.start_of_week();
+
+        let marker = slots
+            .iter()
+            .map(|(_, slot)| {
+                if slot.weekday() == today.weekday() {
+                    "*"
+                } else {
+                    " "
+                }
+                .to_string()
+            })
+            .collect::<Vec<_>>();
+        let days = slots
+            .iter()
+            .map(|(_, slot)| {
+                let date = monday.add_days(slot.weekday() as i64);
+                format!("{} {}", slot.weekday().abbreviation(), date)
+            })
+            .collect::<Vec<_>>();
+        let times = slots
+            .iter()
+            .map(|(_, slot)| format!("{}-{}", slot.start(), slot.end()))
+            .collect::<Vec<_>>();
+        let names = slots
+            .iter()
+            .map(|(course, _)| course.name())
+            .collect::<Vec<_>>();
+        let kinds = slots
+            .iter()
+            .map(|(_, slot)| slot.kind().to_string())
+            .collect::<Vec<_>>();
+        let rooms = slots
+            .iter()
+            .map(|(_, slot)| slot.room().unwrap_or_default().to_string())
+            .collect::<Vec<_>>();
+
+        let header = format!("Week of {}", monday).line();
+        let body = TableBuilder::new()
+            .column("Today", marker, FormatAlignment::Center)
+            .column("Day", days, FormatAlignment::Left)
+            .column("Time", times, FormatAlignment::Left)
+            .column("Course", names, FormatAlignment::Left)
+            .column("Kind", kinds, FormatAlignment::Left)
+            .column("Room", rooms, FormatAlignment::Left)
+            .build();
+        Ok(header.block(body))
+    }
+
+    fn add(
+        &mut self,
+        course: Option<String>,
+        weekday: Weekday,
+        start: Time,
+        end: Time,
+        room: Option<String>,
+        kind: SlotKind,
+    ) -> ServiceResult {
+        if end <= start {
+            bail!("The end of a slot must be after its start");
+        }
+        let mut course = resolve_course(self.store, course.as_deref())?;
+        course.add_slot(Slot::new(weekday, start, end, room, kind))?;
+        let msg = format!(
+            "{} slot on {:?} {}-{} has been added to '{}'",
+            kind,
+            weekday,
+            start,
+            end,
+            course.name()
+        );
+        Ok(msg.success())
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::{bail, Result};
+
+use crate::{
+    cli::SearchScopeDO,
+    domain::{search_content, Course},
+    service::format::{FormatAlignment, IntoFormatType, TableBuilder},
+    MmError, StoreProvider,
+};
+
+use super::{i18n::tr, reference::resolve_course, ServiceResult};
+
+/// Finds courses by their metadata, see [crate::domain::Course::search], or lines of their notes,
+/// see [search_content].
+pub(super) struct SearchService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> SearchService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(
+        &self,
+        query: String,
+        content: bool,
+        pdf: bool,
+        scope: SearchScopeDO,
+    ) -> ServiceResult {
+        let query = query.trim();
+        if query.is_empty() {
+            bail!("The search query must not be empty");
+        }
+        let courses = self.courses(scope)?;
+        match content {
+            true => self.content(courses, query, pdf),
+            false => self.metadata(courses, query),
+        }
+    }
+
+    fn metadata(&self, courses: Vec<(String, Course)>, query: &str) -> ServiceResult {
+        let mut semesters = Vec::new();
+        let mut names = Vec::new();
+        let mut fields = Vec::new();
+        let mut values = Vec::new();
+        for (semester, course) in courses {
+            if let Some((field, value)) = course.search(query) {
+                semesters.push(semester);
+                names.push(course.path().name().to_string());
+                fields.push(tr(field));
+                values.push(value);
+            }
+        }
+        if names.is_empty() {
+            return Ok(format!("No courses match '{}'", query).info());
+        }
+        let table = TableBuilder::new()
+            .column("Semester", semesters, FormatAlignment::Left)
+            .column("Course", names, FormatAlignment::Left)
+            .column("Field", fields, FormatAlignment::Left)
+            .column("Match", values, FormatAlignment::Left)
+            .build();
+        Ok(table)
+    }
+
+    /// Matching lines of the notes as `semester/course` reference, file relative to the course
+    /// folder, line or PDF page and the trimmed line.
+    fn content(&self, courses: Vec<(String, Course)>, query: &str, pdf: bool) -> ServiceResult {
+        let mut references = Vec::new();
+        let mut files = Vec::new();
+        let mut positions = Vec::new();
+        let mut lines = Vec::new();
+        for (semester, course) in courses {
+            for hit in search_content(course.path(), query, pdf)? {
+                references.push(format!("{}/{}", semester, course.path().name()));
+                files.push(hit.file.display().to_string());
+                positions.push(hit.position.to_string());
+                lines.push(hit.text);
+            }
+        }
+        if lines.is_empty() {
+            return Ok(format!("No notes contain '{}'", query).info());
+        }
+        let summary = format!("{} matching lines", lines.len());
+        let table = TableBuilder::new()
+            .column("Course", references, FormatAlignment::Left)
+            .column("File", files, FormatAlignment::Left)
+            .column("Line", positions, FormatAlignment::Right)
+            .column("Text", lines, FormatAlignment::Left)
+            .build();
+        Ok(table.chain("".line()).chain(summary.line()))
+    }
+
+    /// Courses of the scope with the name of their semester, ordered by semester and course.
+    fn courses(&self, scope: SearchScopeDO) -> Result<Vec<(String, Course)>> {
+        let semesters = match scope {
+            SearchScopeDO::Course => {
+                let course = resolve_course(self.store, None)?;
+                let Some(semester) = self.store.current_semester() else {
+                    bail!(MmError::NoActiveSemester);
+                };
+                return Ok(vec![(semester.path().name().to_string(), course)]);
+            }
+            SearchScopeDO::Semester => match self.store.current_semester() {
+                Some(semester) => vec![semester],
+                None => bail!(MmError::NoActiveSemester),
+            },
+            SearchScopeDO::All => self.store.semesters().collect(),
+        };
+
+        let mut courses = Vec::new();
+        for semester in semesters {
+            let name = semester.path().name().to_string();
+            for course in self.store.semester_courses(semester) {
+                courses.push((name.clone(), course));
+            }
+        }
+        courses.sort_by(|a, b| (&a.0, a.1.path().name()).cmp(&(&b.0, b.1.path().name())));
+        Ok(courses)
+    }
+}
@@ -1,16 +1,18 @@
 use crate::{
     cli::SemesterCommands,
-    domain::StudyCycle,
+    domain::{Course, CourseStatus, Date, Semester, StudyCycle},
     service::{
-        format::{DialogEntry, FormatAlignment, FormatService, IntoFormatType},
+        format::{
+            DialogEntry, FormatAlignment, FormatService, FormatType, IntoFormatType, TableBuilder,
+        },
         ServiceResult,
     },
-    table, StoreProvider,
+    MmError, StoreProvider,
 };
 
-use anyhow::{anyhow, bail, Context};
+use anyhow::{anyhow, bail};
 
-use super::format::DialogOutput;
+use super::{backup::snapshot_info, format::DialogOutput};
 
 pub(super) struct SemesterService<'s, Store>
 where
@@ -28,94 +30,367 @@ where
     }
 
     pub fn run(&mut self, command: Option<SemesterCommands>) -> ServiceResult {
-        let command = command.unwrap_or(SemesterCommands::List);
+        let command = command.unwrap_or(SemesterCommands::List { ordinal: false });
         match command {
-            SemesterCommands::List => self.list(),
+            SemesterCommands::List { ordinal } => self.list(ordinal),
             SemesterCommands::Add {
                 number,
                 study_cycle,
-            } => self.add(number, study_cycle.map(|it| StudyCycle::from_do(it))),
-            SemesterCommands::Remove { name } => self.remove(name),
+                start,
+                end,
+                ects_budget,
+            } => self.add(
+                number,
+                study_cycle.map(StudyCycle::from_do),
+                start,
+                end,
+                ects_budget,
+            ),
+            SemesterCommands::Remove { name, force } => self.remove(name, force),
+            SemesterCommands::Close { name } => self.close(name),
         }
     }
 
-    fn list(&self) -> ServiceResult {
-        // Collect and sort semester names
-        let mut semester_names: Vec<String> = self
-            .store
-            .semesters()
+    fn list(&self, ordinal: bool) -> ServiceResult {
+        // Collect semester names ordered by study cycle and number
+        let mut semesters = self.store.semesters().collect::<Vec<_>>();
+        semesters.sort();
+        let semester_names = semesters
+            .iter()
             .map(|semester| semester.name())
-            .collect();
-        semester_names.sort();
+            .collect::<Vec<_>>();
 
         if semester_names.is_empty() {
             bail!("No semesters found!")
         }
 
-        let res = if let Some(active_semester) = self.store.current_semester() {
-            let active = semester_names
-                .iter()
-                .map(|course_name| {
-                    if course_name == &active_semester.name() {
-                        "*".to_string()
-                    } else {
-                        " ".to_string()
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            table!("active", "courses" ; active, semester_names ; FormatAlignment::Center, FormatAlignment::Left)
-        } else {
-            table!("courses"; semester_names; FormatAlignment::Left)
-        };
-        Ok(res)
+        let active_name = self.store.current_semester().map(|it| it.name());
+        let active = semester_names
+            .iter()
+            .map(|name| match Some(name) == active_name.as_ref() {
+                true => "*".to_string(),
+                false => " ".to_string(),
+            })
+            .collect::<Vec<_>>();
+
+        let summaries = semesters
+            .iter()
+            .map(|semester| Summary::new(semester.courses()))
+            .collect::<Vec<_>>();
+        let courses = summaries.iter().map(|it| it.courses.to_string()).collect();
+        let ects = summaries.iter().map(|it| it.ects.to_string()).collect();
+        let completed = summaries
+            .iter()
+            .map(|it| it.completed.to_string())
+            .collect();
+        let average = summaries
+            .iter()
+            .map(|it| match it.average() {
+                Some(average) => FormatType::grade(average, 2),
+                None => "-".into(),
+            })
+            .collect();
+
+        let mut table = TableBuilder::new()
+            .column("active", active, FormatAlignment::Center)
+            .column("semester", semester_names, FormatAlignment::Left);
+        if ordinal {
+            let ordinals = semesters.iter().map(Semester::ordinal).collect::<Vec<_>>();
+            table = table.column("position", ordinals, FormatAlignment::Left);
+        }
+        let mut table = table
+            .column("courses", courses, FormatAlignment::Right)
+            .column("ECTS", ects, FormatAlignment::Right)
+            .column("completed", completed, FormatAlignment::Right)
+            .column("average", average, FormatAlignment::Right);
+
+        // Finished semesters have no enrolled courses left to compare with the budget
+        let budgets = semesters
+            .iter()
+            .map(|semester| {
+                let budget = semester.ects_budget().or(self.store.ects_budget())?;
+                let enrolled = semester.enrolled_ects();
+                let active = Some(semester.name()) == active_name;
+                (active || enrolled > 0).then(|| FormatType::budget(enrolled, budget))
+            })
+            .collect::<Vec<_>>();
+        if budgets.iter().any(Option::is_some) {
+            let budgets = budgets
+                .into_iter()
+                .map(|it| it.unwrap_or_else(|| "-".into()))
+                .collect();
+            table = table.column("budget", budgets, FormatAlignment::Right);
+        }
+        Ok(table.build())
     }
 
-    fn add(&mut self, number: u16, study_cycle: Option<StudyCycle>) -> ServiceResult {
+    fn add(
+        &mut self,
+        number: u16,
+        study_cycle: Option<StudyCycle>,
+        start: Option<Date>,
+        end: Option<Date>,
+        ects_budget: Option<u32>,
+    ) -> ServiceResult {
         let study_cycle =
             study_cycle.or_else(|| self.store.current_semester().map(|it| it.study_cycle()));
         let Some(cycle) = study_cycle else {
             bail!("A study cycle must be provided as currently no semester is active.");
         };
 
-        let path = self
-            .store
-            .entry_point()
-            .create_semester_path(number, cycle)?;
+        let name = self.store.semester_names().serialize(number, cycle)?;
+        let path = self.store.entry_point().create_semester_path(&name)?;
 
         // make sure everything is set up correctly
-        let sememester = self
+        let mut sememester = self
             .store
             .get_semester(path.name())
             .ok_or_else(|| anyhow!("Failed to retrieve newly created semester"))?;
+        if start.is_some() || end.is_some() {
+            sememester.set_dates(start, end)?;
+        }
+        if ects_budget.is_some() {
+            sememester.set_ects_budget(ects_budget)?;
+        }
         Ok(format!("{} was created.", sememester.name()).success())
     }
 
-    fn remove(&mut self, name: String) -> ServiceResult {
-        let dialog = vec![
-            DialogEntry::YesNoInput(format!("Are you sure that you want to permanently remove semester '{}' with all its courses? This action can not be reverted",name))
-        ];
-        let response = FormatService::dialog(dialog);
-        if let Some(res) = response {
+    /// Removes the semester after confirmation, which `force` skips. A snapshot is saved first.
+    fn remove(&mut self, name: String, force: bool) -> ServiceResult {
+        let semester = self
+            .store
+            .get_semester(&name)
+            .ok_or_else(|| MmError::SemesterNotFound(name.clone()))?;
+
+        if !force {
+            let dialog = vec![
+                DialogEntry::YesNoInput(format!("Are you sure that you want to permanently remove semester '{}' with all its courses? This action can not be reverted",name))
+            ];
+            let Some(res) = FormatService::dialog(dialog) else {
+                return Ok("Operation has been canceled".info());
+            };
             let res = res
                 .first()
                 .ok_or_else(|| anyhow!("Dialog has not returned not the specified output"))?;
             let DialogOutput::YesNo(cond) = res else {
                 bail!("Invalid Input")
             };
+            if !*cond {
+                return Ok("Operation has been canceled".info());
+            }
+        }
+
+        let snapshot = self
+            .store
+            .backup_settings()
+            .snapshot(&self.store.entry_point(), semester.path().path())?;
+        semester.path().clone().remove()?;
+        Ok(format!("Semester '{}' has been removed", name)
+            .success()
+            .chain("".line())
+            .chain(snapshot_info(&snapshot)))
+    }
+
+    /// Guided end-of-term checklist: verifies grades and ECTS, resolves ungraded courses, locks
+    /// the courses, writes the semester report and optionally archives the semester.
+    fn close(&mut self, name: String) -> ServiceResult {
+        let semester = self
+            .store
+            .get_semester(&name)
+            .ok_or_else(|| MmError::SemesterNotFound(name.clone()))?;
+        let mut courses = self
+            .store
+            .semester_courses(semester.clone())
+            .collect::<Vec<_>>();
+        courses.sort_by_key(|course| course.name());
+        let canceled = Ok("Operation has been canceled".info());
+
+        // 1. verify that grades and ECTS are present
+        let missing = courses
+            .iter()
+            .filter_map(|course| {
+                let mut missing = Vec::new();
+                if course.grade().is_none() {
+                    missing.push("grade");
+                }
+                if course.ects().is_none() {
+                    missing.push("ECTS");
+                }
+                (!missing.is_empty())
+                    .then(|| format!("  - {}: missing {}", course.name(), missing.join(", ")))
+            })
+            .collect::<Vec<_>>();
+        let check = if missing.is_empty() {
+            "All courses have a grade and ECTS".to_string()
+        } else {
+            format!("Incomplete courses:\n{}", missing.join("\n"))
+        };
+        FormatService::dialog(vec![DialogEntry::Message(check)]);
+
+        // 2. mark remaining courses as failed or dropped
+        for course in courses.iter_mut().filter(|course| {
+            course.grade().is_none()
+                && !matches!(
+                    course.status(),
+                    Some(CourseStatus::Failed | CourseStatus::Dropped)
+                )
+        }) {
+            let dialog = vec![DialogEntry::Select {
+                prompt: format!("'{}' has no grade:", course.name()),
+                options: vec![
+                    "mark as failed".into(),
+                    "mark as dropped".into(),
+                    "keep as is".into(),
+                ],
+            }];
+            let Some(res) = FormatService::dialog(dialog) else {
+                return canceled;
+            };
+            match res.first() {
+                Some(DialogOutput::Selected(0)) => course.set_status(CourseStatus::Failed)?,
+                Some(DialogOutput::Selected(1)) => course.set_status(CourseStatus::Dropped)?,
+                _ => {}
+            }
+        }
 
-            if *cond {
-                let semester = self
-                    .store
-                    .get_semester(&name)
-                    .with_context(|| anyhow!("Semester could not be found"))?;
-                semester.path().clone().remove()?;
-                Ok(format!("Semester '{}' has been removed", name).success())
-            } else {
-                Ok("Operation has been canceled".info())
+        let mut summary = Vec::new();
+
+        // 3. lock courses
+        let dialog = vec![DialogEntry::YesNoInput(format!(
+            "Lock all {} courses of '{}'?",
+            courses.len(),
+            semester.name()
+        ))];
+        let Some(res) = FormatService::dialog(dialog) else {
+            return canceled;
+        };
+        if let Some(DialogOutput::YesNo(true)) = res.first() {
+            for course in courses.iter_mut().filter(|course| !course.is_locked()) {
+                course.set_locked(true)?;
             }
+            summary.push(format!("{} courses have been locked", courses.len()).success());
+        }
+
+        // 4. semester report
+        let report = semester.path().report();
+        let mut write = true;
+        if report.exists() {
+            let dialog = vec![DialogEntry::YesNoInput(format!(
+                "A report already exists at '{}'. Overwrite it?",
+                report.display()
+            ))];
+            let Some(res) = FormatService::dialog(dialog) else {
+                return canceled;
+            };
+            write = matches!(res.first(), Some(DialogOutput::YesNo(true)));
+        }
+        if write {
+            let report = semester
+                .path()
+                .write_report(&Self::report(&semester, &courses))?;
+            summary.push(format!("Report has been written to: {}", report.display()).success());
+        }
+
+        // 5. archive
+        let dialog = vec![DialogEntry::YesNoInput(format!(
+            "Archive '{}' as a compressed copy?",
+            semester.name()
+        ))];
+        let Some(res) = FormatService::dialog(dialog) else {
+            return canceled;
+        };
+        if let Some(DialogOutput::YesNo(true)) = res.first() {
+            let archive = semester
+                .path()
+                .archive(self.store.entry_point().join(".archive"))?;
+            summary.push(format!("Semester has been archived to: {}", archive.display()).success());
+        }
+
+        let msg = summary.into_iter().fold(
+            format!("Semester '{}' has been closed", semester.name()).success(),
+            |acc, it| acc.chain("".line()).chain(it),
+        );
+        Ok(msg)
+    }
+
+    fn report(semester: &Semester, courses: &[Course]) -> String {
+        let mut report = format!("# Semester {}\n\n", semester.name());
+        report.push_str("| Course | ECTS | Grade | Status |\n|---|---|---|---|\n");
+        for course in courses {
+            report.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                course.name(),
+                course.ects().map(|it| it.to_string()).unwrap_or_default(),
+                course
+                    .grade()
+                    .map(|it| format!("{:.1}", it))
+                    .unwrap_or_default(),
+                course.status().map(|it| it.to_string()).unwrap_or_default(),
+            ));
+        }
+
+        // like the degree progress, only passed courses earn ECTS and count towards the average
+        let ects = courses
+            .iter()
+            .filter_map(|course| course.earned_ects())
+            .map(|ects| ects as u32)
+            .sum::<u32>();
+        let graded = courses
+            .iter()
+            .filter_map(|course| course.grade().zip(course.earned_ects()))
+            .collect::<Vec<_>>();
+        let graded_ects = graded.iter().map(|(_, ects)| *ects as u32).sum::<u32>();
+        let weighted = graded
+            .iter()
+            .map(|(grade, ects)| grade * (*ects as f32))
+            .sum::<f32>();
+        let average = if graded_ects > 0 {
+            weighted / graded_ects as f32
         } else {
-            Ok("Operation has been canceled".info())
+            0.0
+        };
+        report.push_str(&format!(
+            "\nCourses: {}\nEarned ECTS: {}\nWeighted average: {:.2}\n",
+            courses.len(),
+            ects,
+            average
+        ));
+        report
+    }
+}
+
+/// Course count, ECTS and average of a semester for `mm semester list`.
+struct Summary {
+    courses: usize,
+    ects: u32,
+    completed: u32,
+    grade_sum: f32,
+    graded_ects: u32,
+}
+
+impl Summary {
+    fn new(courses: impl Iterator<Item = Course>) -> Summary {
+        let mut summary = Summary {
+            courses: 0,
+            ects: 0,
+            completed: 0,
+            grade_sum: 0.0,
+            graded_ects: 0,
+        };
+        for course in courses {
+            summary.courses += 1;
+            summary.ects += course.ects().unwrap_or(0) as u32;
+            summary.completed += course.earned_ects().unwrap_or(0) as u32;
+            if let Some((grade, ects)) = course.grade().zip(course.ects()) {
+                summary.grade_sum += grade * ects as f32;
+                summary.graded_ects += ects as u32;
+            }
         }
+        summary
+    }
+
+    /// Average of the graded courses weighted by their ECTS.
+    fn average(&self) -> Option<f32> {
+        (self.graded_ects > 0).then(|| self.grade_sum / self.graded_ects as f32)
     }
 }
@@ -0,0 +1,508 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    io::{BufRead, BufReader, Read, Write},
+    net::{IpAddr, TcpListener, TcpStream},
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::json;
+
+use crate::{
+    domain::{AuditLog, Date, History, Semester},
+    service::format::{FormatService, IntoFormatType},
+    MmError, StoreProvider,
+};
+
+use super::{
+    json::{CourseJson, DeadlineJson, SemesterJson, StatusJson},
+    reference::resolve_course,
+    switch::SwitchService,
+    ServiceResult,
+};
+
+/// Single page showing `mm status` in the browser, served at `/`.
+const DASHBOARD: &str = include_str!("../../dashboard.html");
+
+/// Requests with a larger body are rejected.
+const MAX_BODY: usize = 64 * 1024;
+
+/// Time a client may take to send its request or receive the response. As requests are handled
+/// one after another, an idle connection would otherwise block the server.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Serves the store as a small JSON API over HTTP. Requests are handled one after another, which
+/// is plenty for a dashboard widget or shortcuts on the local network.
+///
+/// Requests of other web pages are rejected by their `Origin` and, on loopback, by their `Host`
+/// to prevent DNS rebinding. Changes require a JSON body, which browsers do not send cross-origin
+/// without asking first. On other addresses every API request needs the token printed at start.
+///
+/// Errors are answered as `{"error": message, "kind": kind}`, where `kind` is the stable name of
+/// an [MmError], see [MmError::kind], or `null` for other failures.
+pub(super) struct ServeService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s mut Store,
+    token: Option<String>,
+}
+
+struct Request {
+    method: String,
+    path: Vec<String>,
+    query: Vec<(String, String)>,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+/// An error response with its status code and, for an [MmError], its kind.
+struct HttpError {
+    code: u16,
+    message: String,
+    kind: Option<&'static str>,
+}
+
+impl HttpError {
+    fn new(code: u16, message: String) -> HttpError {
+        HttpError {
+            code,
+            message,
+            kind: None,
+        }
+    }
+}
+
+impl From<anyhow::Error> for HttpError {
+    /// Missing semesters and courses are reported as 404, clashing paths as 409.
+    fn from(err: anyhow::Error) -> Self {
+        let code = match err.downcast_ref::<MmError>() {
+            Some(
+                MmError::SemesterNotFound(_)
+                | MmError::CourseNotFound(_)
+                | MmError::NoActiveSemester
+                | MmError::NoActiveCourse,
+            ) => 404,
+            Some(MmError::SemesterAlreadyExists(_) | MmError::CourseAlreadyExists(_)) => 409,
+            _ => 400,
+        };
+        HttpError {
+            code,
+            message: format!("{:#}", err),
+            kind: MmError::kind_of(&err),
+        }
+    }
+}
+
+impl From<MmError> for HttpError {
+    fn from(err: MmError) -> Self {
+        anyhow::Error::from(err).into()
+    }
+}
+
+type Response = Result<serde_json::Value, HttpError>;
+
+#[derive(Deserialize)]
+struct SwitchBody {
+    reference: String,
+}
+
+#[derive(Deserialize)]
+struct GradeBody {
+    grade: f32,
+    #[serde(default)]
+    force: bool,
+}
+
+impl<'s, Store> ServeService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s mut Store) -> Self {
+        Self { store, token: None }
+    }
+
+    pub fn run(&mut self, bind: &str, port: u16) -> ServiceResult {
+        let listener = TcpListener::bind((bind, port))
+            .with_context(|| anyhow!("Failed to listen on {}:{}", bind, port))?;
+        let loopback = is_loopback(bind);
+        match loopback {
+            true => {
+                let msg = format!("Serving the store on http://{}:{}/", bind, port);
+                FormatService::run(msg.info().chain("".line()));
+            }
+            false => {
+                let token = new_token();
+                let msg = format!(
+                    "Serving the store on http://{}:{}/?token={}\nOther clients send the header 'Authorization: Bearer {}'",
+                    bind, port, token, token
+                );
+                FormatService::run(msg.info().chain("".line()));
+                self.token = Some(token);
+            }
+        }
+
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+            if stream.set_read_timeout(Some(TIMEOUT)).is_err()
+                || stream.set_write_timeout(Some(TIMEOUT)).is_err()
+            {
+                continue;
+            }
+            let request = read_request(&mut stream).and_then(|it| check_origin(it, loopback, port));
+            if let Ok(Request { method, path, .. }) = &request {
+                if method == "GET" && path.is_empty() {
+                    let _ = write_response(&mut stream, 200, "text/html; charset=utf-8", DASHBOARD);
+                    continue;
+                }
+            }
+            let (code, body) = match request.and_then(|it| self.handle(it)) {
+                Ok(body) => (200, body),
+                Err(HttpError {
+                    code,
+                    message,
+                    kind,
+                }) => (code, json!({ "error": message, "kind": kind })),
+            };
+            // a client which hung up does not stop the server
+            let _ = write_response(&mut stream, code, "application/json", &body.to_string());
+        }
+        Ok("Server has been stopped".info())
+    }
+
+    fn handle(&mut self, request: Request) -> Response {
+        if let Some(token) = &self.token {
+            let bearer = request
+                .header("authorization")
+                .and_then(|it| it.strip_prefix("Bearer "));
+            if bearer != Some(token.as_str()) {
+                return Err(HttpError::new(401, "Missing or wrong token".into()));
+            }
+        }
+        if request.method != "GET"
+            && !request
+                .header("content-type")
+                .is_some_and(|it| it.starts_with("application/json"))
+        {
+            return Err(HttpError::new(
+                415,
+                "Changes require the Content-Type application/json".into(),
+            ));
+        }
+
+        let path = request.path.iter().map(String::as_str).collect::<Vec<_>>();
+        // the server runs for a long time, so the store is only locked while handling a change
+        let _lock = match request.method.as_str() {
+            "GET" => None,
+            _ => Some(self.store.entry_point().lock()?),
+        };
+        match (request.method.as_str(), path.as_slice()) {
+            ("GET", ["status"]) => to_json(StatusJson::new(self.store)),
+            ("GET", ["semesters"]) => self.semesters(),
+            ("GET", ["courses"]) => {
+                let semester = request
+                    .query
+                    .iter()
+                    .find(|(key, _)| key == "semester")
+                    .map(|(_, value)| value.as_str());
+                self.courses(semester)
+            }
+            ("GET", ["deadlines"]) => {
+                let days = match request.query.iter().find(|(key, _)| key == "days") {
+                    Some((_, days)) => days.parse().map_err(|_| {
+                        HttpError::new(400, format!("Invalid number of days: {}", days))
+                    })?,
+                    None => 14,
+                };
+                self.deadlines(days)
+            }
+            ("GET", ["semesters", semester, "courses"]) => self.courses(Some(semester)),
+            ("GET", ["courses", semester, course]) => self.course(semester, course),
+            ("POST", ["switch"]) => {
+                let body: SwitchBody = parse_body(&request.body)?;
+                SwitchService::new(self.store).run(Some(body.reference.clone()), false)?;
+                self.commit(&format!("switch to {}", body.reference));
+                to_json(StatusJson::new(self.store))
+            }
+            ("PUT", ["courses", semester, course, "grade"]) => {
+                let body: GradeBody = parse_body(&request.body)?;
+                self.grade(semester, course, body)
+            }
+            (_, ["status" | "semesters" | "courses" | "deadlines" | "switch", ..]) => {
+                Err(HttpError::new(
+                    405,
+                    format!("Method {} is not allowed here", request.method),
+                ))
+            }
+            _ => Err(HttpError::new(404, "Not found".into())),
+        }
+    }
+
+    fn semesters(&self) -> Response {
+        let active = self.store.current_semester();
+        let mut semesters = self.store.semesters().collect::<Vec<_>>();
+        semesters.sort();
+        let semesters = semesters
+            .iter()
+            .map(|it| SemesterJson::new(it, active.as_ref()))
+            .collect::<Vec<_>>();
+        to_json(semesters)
+    }
+
+    fn courses(&self, semester: Option<&str>) -> Response {
+        let mut semesters = match semester {
+            Some(name) => vec![self.semester(name)?],
+            None => self.store.semesters().collect(),
+        };
+        semesters.sort();
+        let mut courses = Vec::new();
+        for semester in &semesters {
+            let mut semester_courses = semester.courses().collect::<Vec<_>>();
+            semester_courses.sort_by_key(|it| it.name());
+            courses.extend(
+                semester_courses
+                    .iter()
+                    .map(|it| CourseJson::new(semester, it)),
+            );
+        }
+        to_json(courses)
+    }
+
+    /// Deadlines of the active semester which passed within the last week or are due within
+    /// `days`, like the deadlines of `mm status`.
+    fn deadlines(&self, days: i64) -> Response {
+        let today = Date::today();
+        let Some(semester) = self.store.current_semester() else {
+            return to_json(Vec::<DeadlineJson>::new());
+        };
+        let courses = semester.courses().collect::<Vec<_>>();
+        let mut deadlines = courses
+            .iter()
+            .flat_map(|course| course.deadlines().iter().map(move |it| (course, it)))
+            .filter(|(_, it)| (-7..=days).contains(&it.days_left(&today)))
+            .collect::<Vec<_>>();
+        deadlines.sort_by_key(|(_, it)| it.date());
+        let deadlines = deadlines
+            .into_iter()
+            .map(|(course, it)| DeadlineJson::new(&semester, course, it, &today))
+            .collect::<Vec<_>>();
+        to_json(deadlines)
+    }
+
+    fn course(&self, semester: &str, course: &str) -> Response {
+        let semester = self.semester(semester)?;
+        let course = semester
+            .course(course)
+            .ok_or_else(|| MmError::CourseNotFound(course.into()))?;
+        to_json(CourseJson::new(&semester, &course))
+    }
+
+    /// Sets the final grade like `mm course grade`. Locked courses require `force`.
+    fn grade(&mut self, semester: &str, course: &str, body: GradeBody) -> Response {
+        let reference = format!("{}/{}", semester, course);
+        let mut course = resolve_course(self.store, Some(&reference))?;
+        if course.is_locked() && !body.force {
+            return Err(HttpError::new(
+                409,
+                format!(
+                    "Course '{}' is locked. Set force to change its grade anyway",
+                    course.name()
+                ),
+            ));
+        }
+        course.set_grade(body.grade)?;
+        self.commit(&format!("set grade {} for {}", body.grade, reference));
+        self.course(semester, course.path().name())
+    }
+
+    fn semester(&self, name: &str) -> Result<Semester, HttpError> {
+        self.store
+            .get_semester(name)
+            .ok_or_else(|| MmError::SemesterNotFound(name.into()).into())
+    }
+
+    /// Records a change with the 'git' config option, like commands run from the command line.
+    fn commit(&self, message: &str) {
+        if let Err(err) = AuditLog::new(&self.store.entry_point()).record(message) {
+            FormatService::run(err);
+        }
+        let Some(mode) = self.store.git_mode() else {
+            return;
+        };
+        if let Err(err) = History::new(&self.store.entry_point(), mode).commit(message) {
+            FormatService::run(err);
+        }
+    }
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn is_loopback(bind: &str) -> bool {
+    bind == "localhost" || bind.parse::<IpAddr>().is_ok_and(|it| it.is_loopback())
+}
+
+/// Rejects requests of other web pages. Their `Origin` has to be the server itself. On loopback
+/// the `Host` has to be a loopback name as well, otherwise a page could resolve its own domain
+/// to 127.0.0.1 and read the responses.
+fn check_origin(request: Request, loopback: bool, port: u16) -> Result<Request, HttpError> {
+    let forbidden = |msg: &str| Err(HttpError::new(403, msg.to_string()));
+    let host = request.header("host").unwrap_or_default();
+    if loopback {
+        let name = host.strip_suffix(&format!(":{}", port)).unwrap_or(host);
+        if !matches!(name, "localhost" | "127.0.0.1" | "[::1]") {
+            return forbidden("Unknown host");
+        }
+    }
+    if let Some(origin) = request.header("origin") {
+        if origin != format!("http://{}", host) {
+            return forbidden("Requests of other origins are not allowed");
+        }
+    }
+    Ok(request)
+}
+
+/// Random token of 32 hex digits for clients of the server.
+fn new_token() -> String {
+    (0..2)
+        .map(|_| format!("{:016x}", RandomState::new().build_hasher().finish()))
+        .collect()
+}
+
+fn to_json<T: Serialize>(value: T) -> Response {
+    serde_json::to_value(value).map_err(|err| HttpError::new(500, err.to_string()))
+}
+
+fn parse_body<T: DeserializeOwned>(body: &[u8]) -> Result<T, HttpError> {
+    serde_json::from_slice(body)
+        .map_err(|err| HttpError::new(400, format!("Invalid body: {}", err)))
+}
+
+fn read_request(stream: &mut TcpStream) -> Result<Request, HttpError> {
+    let bad_request = |msg: &str| HttpError::new(400, msg.to_string());
+    let mut reader = BufReader::new(stream);
+
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|_| bad_request("Failed to read the request"))?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(bad_request("Malformed request line"));
+    };
+    let method = method.to_string();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let path = path
+        .split('/')
+        .filter(|it| !it.is_empty())
+        .map(percent_decode)
+        .collect();
+    let query = query
+        .split('&')
+        .filter_map(|it| it.split_once('='))
+        .map(|(key, value)| (percent_decode(key), percent_decode(value)))
+        .collect();
+
+    let mut length = 0;
+    let mut headers = Vec::new();
+    loop {
+        let mut header = String::new();
+        reader
+            .read_line(&mut header)
+            .map_err(|_| bad_request("Failed to read the request headers"))?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                length = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| bad_request("Invalid Content-Length"))?;
+            }
+            headers.push((name.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    if length > MAX_BODY {
+        return Err(HttpError::new(413, "Request body is too large".into()));
+    }
+    let mut body = vec![0; length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|_| bad_request("Failed to read the request body"))?;
+
+    Ok(Request {
+        method,
+        path,
+        query,
+        headers,
+        body,
+    })
+}
+
+fn write_response(
+    stream: &mut TcpStream,
+    code: u16,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    let reason = match code {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        415 => "Unsupported Media Type",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code,
+        reason,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+/// Decodes `%XX` escapes and `+` in paths and query strings.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        match bytes[idx] {
+            b'%' if idx + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[idx + 1..idx + 3]).ok();
+                match hex.and_then(|it| u8::from_str_radix(it, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        idx += 3;
+                        continue;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        idx += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
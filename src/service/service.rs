@@ -1,13 +1,93 @@
+use std::{path::PathBuf, time::Instant};
+
+use anyhow::bail;
+
 use crate::{
     cli::{Cli, Commands},
-    StoreProvider,
+    domain::{
+        effects::{is_dry_run, set_dry_run, take_planned},
+        init_tracing, is_logging, is_read_only, set_log_file, set_progress, set_read_only,
+        stats::{set_verbose, Timings},
+        AuditLog, Config, Date, History, Language,
+    },
+    MmError, StoreProvider,
 };
 
 use super::{
-    course::CourseService, format::FormatService, semester::SemesterService, status::StatusService,
+    attach::AttachService, backup::BackupService, batch::BatchService, calendar::CalendarService,
+    catalog::CatalogService, course::CourseService, deadline::DeadlineService, debug::DebugService,
+    degree::DegreeService, demo::DemoService, env::EnvService, exam::ExamService,
+    exec::ExecService, exercise::ExerciseService, export::ExportService,
+    flashcards::FlashcardsService, format::FormatService, get::GetService, grades::GradesService,
+    history::HistoryService, lit::LitService, materials::MaterialsService, migrate::MigrateService,
+    moodle::MoodleService, note::NoteService, pdf::PdfService, plan::PlanService,
+    prompt::PromptService, schedule::ScheduleService, search::SearchService,
+    semester::SemesterService, stats::StatsService, status::StatusService, whatif::WhatifService,
 };
-use super::{switch::SwitchService, ServiceResult};
+use super::{
+    format::{FormatType, FormatTypeable, IntoFormatType},
+    i18n::set_language,
+    reference::did_you_mean,
+    serve::ServeService,
+    switch::{semester_of, SwitchService},
+    sync::SyncService,
+    todo::TodoService,
+    track::TrackService,
+    ui::UiService,
+    watch::WatchService,
+    ServiceResult,
+};
+
+/// Runs commands which do not require a store, e.g. because they create one. Returns the
+/// arguments if the command has to be run by [Service] instead.
+pub fn run_standalone(args: Cli) -> Option<Cli> {
+    // like `git -C`, relative paths of the other arguments are relative to the directory
+    if let Some(dir) = &args.directory {
+        if let Err(err) = std::env::set_current_dir(dir) {
+            let msg = format!("Failed to change to directory '{}': {}", dir.display(), err);
+            FormatService::run(msg.error());
+            std::process::exit(1);
+        }
+    }
+    Config::set_file(args.config.clone());
+    if let Some(path) = &args.log_file {
+        set_log_file(path.clone());
+    }
+    set_read_only(args.read_only);
+    set_dry_run(args.dry_run);
+    set_verbose(args.verbose);
+    init_tracing();
+    FormatService::set_quiet(args.quiet);
+    set_progress(!args.quiet);
+    set_language(Language::from_env());
+    match args.command {
+        Commands::Demo { command } => {
+            FormatService::run(with_planned_effects(DemoService::run(command).format()));
+            None
+        }
+        Commands::Debug { command } => {
+            FormatService::run(with_planned_effects(DebugService::run(command).format()));
+            None
+        }
+        Commands::Exec { course, command } => {
+            // the exit code of the command is forwarded
+            let code = ExecService::run(course, command).unwrap_or_else(|err| {
+                FormatService::run(err);
+                1
+            });
+            std::process::exit(code);
+        }
+        Commands::Prompt { format } => {
+            if let Some(segment) = PromptService::run(format) {
+                FormatService::run(segment);
+            }
+            None
+        }
+        _ => Some(args),
+    }
+}
 
+/// Runs the commands of the CLI against a store.
 pub struct Service<Store>
 where
     Store: StoreProvider,
@@ -23,15 +103,274 @@ where
         Service { store }
     }
 
-    pub fn run(&mut self, args: Cli) {
-        let res: ServiceResult = match args.command {
+    /// Runs the command and prints its result. Returns the exit code, 1 if the command failed and
+    /// for `mm status --porcelain` 0 with active course, 2 with active semester only and 3 without
+    /// active semester.
+    pub fn run(&mut self, args: Cli) -> i32 {
+        set_read_only(args.read_only);
+        set_dry_run(args.dry_run);
+        set_verbose(args.verbose);
+        init_tracing();
+        FormatService::set_quiet(args.quiet);
+        set_progress(!args.quiet);
+        FormatService::set_theme(self.store.theme());
+        if let Some(language) = self.store.language() {
+            set_language(language);
+        }
+        if self.store.log() && !is_logging() {
+            set_log_file(None);
+        }
+        let command = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+        tracing::info!("command: mm {}", command);
+        tracing::info!(
+            "context: semester={} course={}",
+            self.store
+                .current_semester()
+                .map_or("-".into(), |it| it.name()),
+            self.store
+                .current_course()
+                .map_or("-".into(), |it| it.path().name().to_string())
+        );
+        let start = Instant::now();
+        if self.is_auto_switch_due(&args.command) {
+            FormatService::run(self.dispatch(Commands::Switch {
+                reference: None,
+                auto: true,
+            }));
+        }
+        let code = match args.command {
+            Commands::Status {
+                porcelain: true, ..
+            } => StatusService::new(&self.store).exit_code(),
+            _ => 0,
+        };
+        let res = self.dispatch(args.command);
+        let code = match res.is_error() {
+            true => 1,
+            false => code,
+        };
+        FormatService::run(res);
+        tracing::info!("exit code: {}", code);
+
+        if args.verbose {
+            if let Err(err) = Timings::collect(command, start.elapsed()).save() {
+                FormatService::run(err);
+            }
+        }
+        code
+    }
+
+    /// With the 'auto_switch' config option the semester lasting over today is activated
+    /// before a command, unless the active semester lasts over today or the command changes
+    /// the active semester itself.
+    fn is_auto_switch_due(&self, command: &Commands) -> bool {
+        if !self.store.auto_switch()
+            || is_read_only()
+            || is_dry_run()
+            || matches!(command, Commands::Switch { .. })
+        {
+            return false;
+        }
+        let today = Date::today();
+        let current = self.store.current_semester();
+        if current.as_ref().is_some_and(|it| it.contains(&today)) {
+            return false;
+        }
+        semester_of(&self.store, &today)
+            .is_some_and(|it| current.is_none_or(|current| current.name() != it.name()))
+    }
+
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+
+    /// Executes a command and records the change in the audit log and with the 'git' config option
+    /// unless it failed. With `--dry-run` the planned changes are listed instead.
+    /// The result is returned instead of printed.
+    pub fn dispatch(&mut self, command: Commands) -> FormatType {
+        if is_dry_run() {
+            let res = self.execute(command);
+            return with_planned_effects(
+                res.map_err(|err| did_you_mean(&self.store, err)).format(),
+            );
+        }
+
+        // commands which change the store wait for other mm processes to finish their changes
+        let message = HistoryService::describe(&self.store, &command);
+        if message.is_some() && is_read_only() {
+            return anyhow::Error::from(MmError::ReadOnly).format();
+        }
+        let lock = match message {
+            Some(_) => match self.store.entry_point().lock() {
+                Ok(lock) => Some(lock),
+                Err(err) => return err.format(),
+            },
+            None => None,
+        };
+        // the process holding the lock before may have changed the active semester
+        if lock.is_some() {
+            if let Err(err) = self.store.reload() {
+                return err.format();
+            }
+        }
+        let res = self
+            .execute(command)
+            .map_err(|err| did_you_mean(&self.store, err))
+            .format();
+
+        let (Some(message), false) = (message, res.is_error()) else {
+            return res;
+        };
+        if let Err(err) = AuditLog::new(&self.store.entry_point()).record(&message) {
+            return res.chain("".line()).chain(err.format());
+        }
+        if let Some(mode) = self.store.git_mode() {
+            let history = History::new(&self.store.entry_point(), mode);
+            if let Err(err) = history.commit(&message) {
+                return res.chain("".line()).chain(err.format());
+            }
+        }
+        res
+    }
+
+    fn execute(&mut self, command: Commands) -> ServiceResult {
+        match command {
             Commands::Semester { command } => SemesterService::new(&mut self.store).run(command),
             Commands::Course { command } => CourseService::new(&mut self.store).run(command),
-            Commands::Switch { reference } => SwitchService::new(&mut self.store).run(reference),
-            Commands::Status {} => StatusService::new(&self.store).run(),
-            _ => todo!(),
-        };
+            Commands::Switch { reference, auto } => {
+                SwitchService::new(&mut self.store).run(reference, auto)
+            }
+            Commands::Ui => UiService::new(self).run(),
+            Commands::Status {
+                scale,
+                porcelain,
+                degree,
+                cycle,
+                semester,
+            } => StatusService::new(&self.store).run(scale, porcelain, degree, cycle, semester),
+            Commands::Search {
+                query,
+                content,
+                pdf,
+                scope,
+            } => SearchService::new(&self.store).run(query, content, pdf, scope),
+            Commands::Degree { command } => DegreeService::new(&self.store).run(command),
+            Commands::Catalog { command } => CatalogService::new(&self.store).run(command),
+            Commands::Plan { ects, command } => PlanService::new(&self.store).run(ects, command),
+            Commands::Stats { by_semester, json } => {
+                StatsService::new(&self.store).run(by_semester, json)
+            }
+            Commands::Whatif { grades, target } => {
+                WhatifService::new(&self.store).run(grades, target)
+            }
+            Commands::Env => EnvService::new(&self.store).run(),
+            Commands::Get { command } => GetService::new(&self.store).run(command),
+            Commands::Attach {
+                course,
+                count,
+                lectures,
+                exercise,
+                rename,
+            } => AttachService::new(&self.store).run(course, count, lectures, exercise, rename),
+            Commands::Watch { interval } => WatchService::new(&self.store).run(interval),
+            Commands::Todo { course, command } => {
+                TodoService::new(&mut self.store).run(course, command)
+            }
+            Commands::Deadline { course, command } => {
+                DeadlineService::new(&mut self.store).run(course, command)
+            }
+            Commands::Track { course, command } => {
+                TrackService::new(&mut self.store).run(course, command)
+            }
+            Commands::Note { course, command } => {
+                NoteService::new(&self.store).run(course, command)
+            }
+            Commands::Materials { course, command } => {
+                MaterialsService::new(&self.store).run(course, command)
+            }
+            Commands::Flashcards { course, command } => {
+                FlashcardsService::new(&self.store).run(course, command)
+            }
+            Commands::Lit { course, command } => LitService::new(&self.store).run(course, command),
+            Commands::Pdf { course, command } => PdfService::new(&self.store).run(course, command),
+            Commands::Export { command } => ExportService::new(&self.store).run(command),
+            Commands::Grades { command } => GradesService::new(&mut self.store).run(command),
+            Commands::Moodle { command } => MoodleService::new(&mut self.store).run(command),
+            Commands::Serve { bind, port } => ServeService::new(&mut self.store).run(&bind, port),
+            Commands::History {
+                limit,
+                since,
+                until,
+                search,
+                git,
+            } => {
+                let history = HistoryService::new(&self.store);
+                match git {
+                    true => history.commits(limit),
+                    false => history.run(limit, since, until, search),
+                }
+            }
+            Commands::Sync { command } => SyncService::new(&self.store).run(command),
+            Commands::Backup { full, command } => {
+                BackupService::new(&self.store).run(full, command)
+            }
+            Commands::Exam { command } => ExamService::new(&mut self.store).run(command),
+            Commands::Schedule { command } => ScheduleService::new(&mut self.store).run(command),
+            Commands::Calendar { command } => CalendarService::new(&mut self.store).run(command),
+            Commands::Exercise { course, command } => {
+                ExerciseService::new(&mut self.store).run(course, command)
+            }
+            Commands::Batch { file } => self.batch(file),
+            Commands::Demo { command } => DemoService::run(command),
+            Commands::Debug { command } => DebugService::run(command),
+            Commands::Migrate => MigrateService::new(&self.store).run(),
+            Commands::Config { .. } => {
+                bail!("Changing the configuration is not implemented yet, edit the config file")
+            }
+            // run by `run_standalone` and rejected by `BatchService::parse`
+            Commands::Prompt { .. } | Commands::Exec { .. } => {
+                bail!("This command can not be run within another command")
+            }
+        }
+    }
 
-        FormatService::run(res);
+    /// Validates all lines of the batch file first and then executes them in order. Execution
+    /// stops at the first failing command.
+    fn batch(&mut self, file: Option<PathBuf>) -> ServiceResult {
+        let lines = BatchService::parse(file)?;
+        let total = lines.len();
+
+        for (idx, line) in lines.into_iter().enumerate() {
+            let res = self.dispatch(line.command);
+            let failed = res.is_error();
+            FormatService::run(res);
+
+            if failed {
+                let msg = format!(
+                    "Line {} failed: '{}' ({} of {} commands succeeded)",
+                    line.number, line.source, idx, total
+                );
+                return Ok(msg.error());
+            }
+        }
+
+        Ok(format!("All {} commands have been executed", total).success())
+    }
+}
+
+/// Appends the changes recorded by `mm --dry-run` to the result of a command, if there are any.
+fn with_planned_effects(res: FormatType) -> FormatType {
+    if !is_dry_run() {
+        return res;
+    }
+    let effects = take_planned();
+    if effects.is_empty() {
+        return res;
     }
+    effects.iter().fold(
+        res.chain("".line())
+            .chain("Dry run, nothing has been changed. The command would:".info())
+            .chain("".line()),
+        |acc, effect| acc.chain(format!("  {}", effect).line()),
+    )
 }
@@ -0,0 +1,132 @@
+use crate::{
+    domain::Course,
+    service::format::{FormatAlignment, FormatType, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::{json::StatsJson, ServiceResult};
+
+pub(super) struct StatsService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+/// Figures of a set of courses. Averages only consider graded courses.
+struct Summary {
+    courses: usize,
+    ects: u32,
+    average: Option<f32>,
+    weighted: Option<f32>,
+}
+
+impl<'s, Store> StatsService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self, by_semester: bool, json: bool) -> ServiceResult {
+        match (by_semester, json) {
+            (_, true) => self.json(),
+            (true, _) => self.by_semester(),
+            (false, _) => self.overall(),
+        }
+    }
+
+    /// Courses, semesters and degrees for dashboards and spreadsheets, see [StatsJson].
+    fn json(&self) -> ServiceResult {
+        let json = serde_json::to_string_pretty(&StatsJson::new(self.store))?;
+        Ok(json.raw())
+    }
+
+    fn overall(&self) -> ServiceResult {
+        let summary = Summary::of(&self.store.courses().collect::<Vec<_>>());
+        let labels = vec![
+            "Courses".into(),
+            "ECTS".into(),
+            "Average".into(),
+            "Weighted".into(),
+        ];
+        let values = vec![
+            summary.courses.to_string(),
+            summary.ects.to_string(),
+            format_average(summary.average),
+            format_average(summary.weighted),
+        ];
+        Ok(TableBuilder::new()
+            .column("", labels, FormatAlignment::Left)
+            .column("Overall", values, FormatAlignment::Right)
+            .build())
+    }
+
+    /// One row per semester in chronological order.
+    fn by_semester(&self) -> ServiceResult {
+        let mut semesters = self.store.semesters().collect::<Vec<_>>();
+        semesters.sort();
+        if semesters.is_empty() {
+            return Ok("No semesters found".info());
+        }
+
+        let mut names = Vec::new();
+        let mut courses = Vec::new();
+        let mut ects = Vec::new();
+        let mut averages = Vec::new();
+        let mut weighted = Vec::new();
+        for semester in semesters {
+            let summary = Summary::of(&semester.courses().collect::<Vec<_>>());
+            names.push(semester.name());
+            courses.push(summary.courses.to_string());
+            ects.push(summary.ects.to_string());
+            averages.push(format_average(summary.average));
+            weighted.push(format_average(summary.weighted));
+        }
+        Ok(TableBuilder::new()
+            .column("Semester", names, FormatAlignment::Left)
+            .column("Courses", courses, FormatAlignment::Right)
+            .column("ECTS", ects, FormatAlignment::Right)
+            .column("Average", averages, FormatAlignment::Right)
+            .column("Weighted", weighted, FormatAlignment::Right)
+            .build())
+    }
+}
+
+impl Summary {
+    fn of(courses: &[Course]) -> Summary {
+        let grades = courses
+            .iter()
+            .filter_map(|it| it.grade())
+            .collect::<Vec<_>>();
+        let average =
+            (!grades.is_empty()).then(|| grades.iter().sum::<f32>() / grades.len() as f32);
+
+        let (sum, credits) = courses
+            .iter()
+            .filter_map(|it| it.grade().zip(it.ects()))
+            .fold((0f32, 0u32), |(sum, count), (grade, ects)| {
+                (sum + grade * ects as f32, count + ects as u32)
+            });
+        let weighted = (credits > 0).then(|| sum / credits as f32);
+
+        let ects = courses
+            .iter()
+            .filter_map(|it| it.earned_ects())
+            .map(|it| it as u32)
+            .sum();
+        Summary {
+            courses: courses.len(),
+            ects,
+            average,
+            weighted,
+        }
+    }
+}
+
+fn format_average(average: Option<f32>) -> String {
+    average
+        .map(|it| FormatType::grade(it, 2))
+        .unwrap_or_else(|| "-".into())
+}
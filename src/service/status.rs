@@ -1,16 +1,33 @@
+use anyhow::{bail, Result};
+use colored::Colorize;
+
 use crate::{
-    service::format::{FormatAlignment, IntoFormatType},
-    table, StoreProvider,
+    cli::{ScaleDO, StudyCycleDO},
+    domain::{Course, CourseFilter, Date, Semester, SemesterFilter, StudyCycle},
+    service::format::{FormatAlignment, FormatType, IntoFormatType, TableBuilder},
+    StoreProvider,
 };
 use std::collections::HashMap;
 
-use super::ServiceResult;
+use super::{
+    deadline::deadline_table, degree::progress_bar, exam::next_exam, exercise::admission_at_risk,
+    i18n::tr, reference::resolve_semester_range, ServiceResult,
+};
+
+type GradeEcts = (Option<f32>, Option<u8>);
+
+/// Number of best and worst courses in the performance of `mm status`.
+const EXTREMES: usize = 3;
 
 pub(super) struct StatusService<'s, Store>
 where
     Store: StoreProvider,
 {
     store: &'s Store,
+    /// Courses the averages and ECTS are computed from, see `mm status --degree`.
+    courses: CourseFilter,
+    /// Semesters the averages and ECTS are computed from, see `mm status --cycle --semester`.
+    semesters: SemesterFilter,
 }
 
 impl<'s, Store> StatusService<'s, Store>
@@ -18,26 +35,205 @@ where
     Store: StoreProvider,
 {
     pub fn new(store: &'s Store) -> StatusService<'s, Store> {
-        StatusService { store }
+        StatusService {
+            store,
+            courses: CourseFilter::default(),
+            semesters: SemesterFilter::default(),
+        }
+    }
+
+    pub fn run(
+        mut self,
+        scale: Option<ScaleDO>,
+        porcelain: bool,
+        degree: Option<String>,
+        cycle: Option<StudyCycleDO>,
+        semester: Option<String>,
+    ) -> ServiceResult {
+        if porcelain {
+            return Ok(self.porcelain());
+        }
+        self.courses.degree = degree.map(|it| self.degree_name(it)).transpose()?;
+        self.semesters.study_cycle = cycle.map(StudyCycle::from_do);
+        if let Some(range) = semester {
+            (self.semesters.from, self.semesters.to) = resolve_semester_range(self.store, &range)?;
+        }
+        self.status(scale)
+    }
+
+    /// Full name of a degree referenced by its full name or a part of it, e.g. `Informatik` for
+    /// `B.Sc. Informatik`, see [crate::domain::Degree::matches]. Configured degrees and the
+    /// degrees listed by courses are searched, a part matching several degrees is an error.
+    pub(super) fn degree_name(&self, reference: String) -> Result<String> {
+        let lowercase = reference.to_lowercase();
+        let mut names = self
+            .store
+            .degrees()
+            .iter()
+            .map(|it| it.name().to_string())
+            .chain(self.store.courses().flat_map(|it| it.degrees().clone()))
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        if names.contains(&reference) {
+            return Ok(reference);
+        }
+        names.retain(|it| it.to_lowercase().contains(&lowercase));
+        match names.len() {
+            0 => Ok(reference),
+            1 => Ok(names.remove(0)),
+            _ => bail!(
+                "Degree '{}' is ambiguous, it matches: {}",
+                reference,
+                names.join(", ")
+            ),
+        }
+    }
+
+    /// Semesters matching the filters in order.
+    fn semesters(&self) -> Vec<Semester> {
+        let mut semesters = self
+            .store
+            .semesters()
+            .filter(|it| self.semesters.matches(it))
+            .collect::<Vec<_>>();
+        semesters.sort();
+        semesters
+    }
+
+    /// Courses of the semesters matching the filters.
+    fn courses(&self) -> Vec<Course> {
+        self.semesters()
+            .iter()
+            .flat_map(|semester| self.courses.apply(semester.courses()).collect::<Vec<_>>())
+            .collect()
     }
 
-    pub fn run(&self) -> ServiceResult {
-        self.status()
+    /// Exit code of `mm status --porcelain`: 0 with active course, 2 with active semester only
+    /// and 3 without active semester.
+    pub fn exit_code(&self) -> i32 {
+        match self.store.current_semester() {
+            Some(semester) => match semester.active_course() {
+                Some(_) => 0,
+                None => 2,
+            },
+            None => 3,
+        }
     }
 
-    fn status(&self) -> ServiceResult {
+    /// The active semester and course as `key=value` lines, which stay the same across versions.
+    /// Keys without value are printed empty.
+    fn porcelain(&self) -> FormatType {
+        let semester = self.store.current_semester();
+        let course = semester.as_ref().and_then(|it| it.active_course());
+        let lines = [
+            ("semester", semester.as_ref().map(|it| it.name())),
+            (
+                "semester_path",
+                semester
+                    .as_ref()
+                    .map(|it| it.path().path().display().to_string()),
+            ),
+            (
+                "course",
+                course.as_ref().map(|it| it.path().name().to_string()),
+            ),
+            ("course_name", course.as_ref().map(|it| it.name())),
+            (
+                "course_path",
+                course.as_ref().map(|it| it.path().display().to_string()),
+            ),
+        ];
+        lines
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, value.unwrap_or_default()))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .raw()
+    }
+
+    fn status(&self, scale: Option<ScaleDO>) -> ServiceResult {
+        let today = Date::today();
         let acc = match self.store.current_semester() {
             Some(semester) => match semester.active_course() {
                 Some(course) => format!("Active on course: {}/{}", semester.name(), course.name(),),
                 None => format!("Active on: {}/", semester.name()),
             },
-            None => format!("No active semester or course"),
+            None => "No active semester or course".to_string(),
         };
 
+        let mut acc = acc.line();
+        if let Some((course, exam)) = next_exam(&self.store.courses().collect::<Vec<_>>(), &today) {
+            let days = today.days_until(&exam.date());
+            let countdown = match days {
+                0 => "today".to_string(),
+                1 => "tomorrow".to_string(),
+                d => format!("in {} days", d),
+            };
+            let line = format!(
+                "Next exam: {} on {} ({})",
+                course.name(),
+                exam.date(),
+                countdown
+            );
+            acc = acc.chain(line.line());
+        }
+
         let header = "Performance".line();
-        let average = format!("{:.2}", self.average());
-        let weighted_average = format!("{:.2}", self.weighted_average());
-        let body = table!("Average", "Grade"; vec!["Overall".into(), "Weighted".into()], vec![average, weighted_average]; FormatAlignment::Left, FormatAlignment::Left);
+        let graded = self.graded();
+        let average = self.format_grade(self.average(), scale);
+        let weighted_average = self.format_grade(self.weighted_average(), scale);
+        let median = self.format_grade(median(&graded), scale);
+        // a spread of grades, which has no meaning in another scale
+        let deviation = match graded.is_empty() {
+            true => "-".to_string(),
+            false => format!("{:.2}", standard_deviation(&graded)),
+        };
+        let grade = match scale {
+            None => "Grade",
+            Some(ScaleDO::Gpa) => "GPA",
+            Some(ScaleDO::Ects) => "ECTS",
+        };
+        let mut rows = vec![
+            "Overall".to_string(),
+            "Weighted".into(),
+            "Median".into(),
+            "Std. deviation".into(),
+        ];
+        let mut values = vec![average, weighted_average, median, deviation];
+        let averages = self.semester_averages();
+        if let [first, .., last] = averages[..] {
+            rows.push("Trend".into());
+            values.push(format!(
+                "{} {} → {}",
+                sparkline(&averages),
+                self.format_grade(first, scale),
+                self.format_grade(last, scale)
+            ));
+        }
+        let mut body = TableBuilder::new()
+            .column("Average", rows, FormatAlignment::Left)
+            .column(grade, values, FormatAlignment::Left)
+            .build();
+        // with fewer courses the best and worst would be the same
+        if graded.len() > EXTREMES {
+            let extremes = |courses: Vec<&(Course, f32)>| {
+                let names = courses.iter().map(|(course, _)| course.name()).collect();
+                let grades = courses
+                    .iter()
+                    .map(|(_, grade)| self.format_grade(*grade, scale))
+                    .collect();
+                TableBuilder::new()
+                    .column("Course", names, FormatAlignment::Left)
+                    .column(grade, grades, FormatAlignment::Left)
+                    .build()
+            };
+            let best = extremes(graded.iter().take(EXTREMES).collect());
+            let worst = extremes(graded.iter().rev().take(EXTREMES).collect());
+            body = body
+                .chain("Best Courses".line().block(best))
+                .chain("Worst Courses".line().block(worst));
+        }
 
         let block_header = "By Degree".line();
 
@@ -48,63 +244,383 @@ where
             let degree = weighted_averages.keys().cloned().collect::<Vec<_>>();
             let average = weighted_averages
                 .values()
-                .map(|f| format!("{:.2}", f))
+                .map(|f| self.format_grade(*f, scale))
                 .collect::<Vec<_>>();
-            table!("Degree", "Average"; degree, average; FormatAlignment::Left, FormatAlignment::Left)
+            TableBuilder::new()
+                .column("Degree", degree, FormatAlignment::Left)
+                .column("Average", average, FormatAlignment::Left)
+                .build()
         };
 
-        let msg = acc
-            .line()
-            .chain(header.block(body.chain(block_header.block(block_body))));
+        let mut msg = acc.chain(header.block(body.chain(block_header.block(block_body))));
+        if let Some(targets) = self.targets(scale) {
+            msg = msg.chain("Targets".line().block(targets));
+        }
+        msg = msg.chain("ECTS".line().block(self.ects()));
+
+        if let Some(admission) = self.admission() {
+            msg = msg.chain("Exam Admission".line().block(admission));
+        }
+
+        if let Some(tasks) = self.open_tasks() {
+            msg = msg.chain("Open Tasks".line().block(tasks));
+        }
+
+        if let Some(deadlines) = self.upcoming_deadlines() {
+            msg = msg.chain("Deadlines".line().block(deadlines));
+        }
 
         Ok(msg)
     }
 
+    /// Formats an average in the local scale or converted to `scale`.
+    fn format_grade(&self, grade: f32, scale: Option<ScaleDO>) -> String {
+        let conversion = self.store.grade_scale();
+        match scale {
+            // The averages are 0 without any graded course.
+            None if grade == 0.0 => format!("{:.2}", grade),
+            None => FormatType::grade(grade, 2),
+            Some(_) if grade == 0.0 => "-".into(),
+            Some(ScaleDO::Gpa) => format!("{:.2}", conversion.gpa(grade)),
+            Some(ScaleDO::Ects) => conversion.ects_letter(grade),
+        }
+    }
+
+    /// Gap of the average of each degree with `target_grade` to the target and the average needed
+    /// over the ECTS still missing. Returns `None` if no degree has a target.
+    fn targets(&self, scale: Option<ScaleDO>) -> Option<FormatType> {
+        let courses = self.store.courses().collect::<Vec<_>>();
+        let mut names = Vec::new();
+        let mut targets = Vec::new();
+        let mut averages = Vec::new();
+        let mut gaps = Vec::new();
+        let mut needed = Vec::new();
+        for degree in self.store.degrees() {
+            if self
+                .courses
+                .degree
+                .as_ref()
+                .is_some_and(|it| it != degree.name())
+            {
+                continue;
+            }
+            let Some(target) = degree.target(&courses) else {
+                continue;
+            };
+            names.push(degree.name().to_string());
+            targets.push(self.format_grade(target.grade, scale));
+            averages.push(match target.average {
+                Some(average) => self.format_grade(average, scale),
+                None => "-".into(),
+            });
+            // lower grades are better, a positive gap is still to be closed
+            gaps.push(match target.average {
+                // the difference of converted grades is not meaningful
+                Some(_) if scale.is_some() => "-".into(),
+                Some(average) if average > target.grade => {
+                    format!("{:+.2}", average - target.grade).red().to_string()
+                }
+                Some(average) => format!("{:+.2}", average - target.grade)
+                    .green()
+                    .to_string(),
+                None => "-".into(),
+            });
+            needed.push(match target.needed {
+                None => "-".into(),
+                Some(it) if it < 1.0 => tr("out of reach").red().to_string(),
+                Some(it) if it >= 4.0 => tr("any passing grade").green().to_string(),
+                Some(it) => format!(
+                    "{} {}",
+                    self.format_grade(it, scale),
+                    tr(&format!("on {} ECTS", target.remaining))
+                ),
+            });
+        }
+        if names.is_empty() {
+            return None;
+        }
+        Some(
+            TableBuilder::new()
+                .column("Degree", names, FormatAlignment::Left)
+                .column("Target", targets, FormatAlignment::Right)
+                .column("Average", averages, FormatAlignment::Right)
+                .column("Gap", gaps, FormatAlignment::Right)
+                .column("Needed", needed, FormatAlignment::Left)
+                .build(),
+        )
+    }
+
+    /// Earned ECTS and ECTS of courses in progress, the progress of configured degrees and the
+    /// ECTS by semester.
+    fn ects(&self) -> FormatType {
+        let courses = self.courses();
+        let (earned, in_progress) = ects_of(&courses);
+        let mut msg = format!("Earned: {}, in progress: {}", earned, in_progress).line();
+        let uebk = courses
+            .iter()
+            .filter(|it| it.uebk().unwrap_or(false))
+            .filter_map(|it| it.earned_ects())
+            .map(u32::from)
+            .sum::<u32>();
+        if uebk > 0 {
+            msg = msg.chain(format!("Earned übK: {}", uebk).line());
+        }
+        if let Some(semester) = self.store.current_semester() {
+            if let Some(budget) = semester.ects_budget().or(self.store.ects_budget()) {
+                let enrolled = FormatType::budget(semester.enrolled_ects(), budget);
+                msg = msg.chain(format!("Enrolled in {}: {}", semester.name(), enrolled).line());
+            }
+        }
+
+        let degrees = self
+            .store
+            .degrees()
+            .into_iter()
+            .filter(|it| {
+                self.courses
+                    .degree
+                    .as_ref()
+                    .is_none_or(|name| name == it.name())
+            })
+            .collect::<Vec<_>>();
+        if !degrees.is_empty() {
+            let mut names = Vec::new();
+            let mut ects = Vec::new();
+            let mut progress = Vec::new();
+            let mut uebk = Vec::new();
+            let mut warnings = Vec::new();
+            let mut any_uebk = false;
+            for degree in &degrees {
+                let degree_progress = degree.progress(&courses);
+                any_uebk |= degree_progress.uebk > 0 || degree.uebk_cap().is_some();
+                let counted = degree_progress.counted(degree);
+                names.push(degree.name().to_string());
+                ects.push(format!("{} / {}", counted, degree.ects()));
+                progress.push(progress_bar(counted, degree.ects()));
+                uebk.push(match degree.uebk_cap() {
+                    Some(cap) if degree_progress.uebk > cap => {
+                        warnings.push(format!(
+                            "{} übK ECTS exceed the cap of {} of {}, {} of them do not count",
+                            degree_progress.uebk,
+                            cap,
+                            degree.name(),
+                            degree_progress.uebk - cap
+                        ));
+                        format!("{} / max {}", degree_progress.uebk, cap)
+                            .red()
+                            .to_string()
+                    }
+                    Some(cap) => format!("{} / max {}", degree_progress.uebk, cap),
+                    None => degree_progress.uebk.to_string(),
+                });
+            }
+            let mut table = TableBuilder::new()
+                .column("Degree", names, FormatAlignment::Left)
+                .column("ECTS", ects, FormatAlignment::Right)
+                .column("Progress", progress, FormatAlignment::Left);
+            if any_uebk {
+                table = table.column("übK", uebk, FormatAlignment::Right);
+            }
+            let mut body = table.build();
+            for warning in warnings {
+                body = body.chain(warning.info()).chain("".line());
+            }
+            msg = msg.chain("By Degree".line().block(body));
+        }
+
+        let mut semesters = Vec::new();
+        let mut earned = Vec::new();
+        let mut in_progress = Vec::new();
+        for semester in self.semesters() {
+            let courses = self.courses.apply(semester.courses()).collect::<Vec<_>>();
+            let (done, open) = ects_of(&courses);
+            semesters.push(semester.name());
+            earned.push(done.to_string());
+            in_progress.push(open.to_string());
+        }
+        if !semesters.is_empty() {
+            let body = TableBuilder::new()
+                .column("Semester", semesters, FormatAlignment::Left)
+                .column("Earned", earned, FormatAlignment::Right)
+                .column("In Progress", in_progress, FormatAlignment::Right)
+                .build();
+            msg = msg.chain("By Semester".line().block(body));
+        }
+        msg
+    }
+
+    /// Deadlines of the active semester which passed within the last week or are due within the
+    /// next two weeks. Returns `None` if there are no such deadlines.
+    fn upcoming_deadlines(&self) -> Option<FormatType> {
+        let semester = self.store.current_semester()?;
+        let today = Date::today();
+
+        let courses = self.store.semester_courses(semester).collect::<Vec<_>>();
+        let mut deadlines = courses
+            .iter()
+            .flat_map(|course| {
+                course
+                    .deadlines()
+                    .iter()
+                    .map(move |deadline| (course, deadline))
+            })
+            .filter(|(_, deadline)| (-7..=14).contains(&deadline.days_left(&today)))
+            .collect::<Vec<_>>();
+        deadlines.sort_by_key(|(_, deadline)| deadline.date());
+
+        if deadlines.is_empty() {
+            return None;
+        }
+        Some(deadline_table(&deadlines, &today))
+    }
+
+    /// Courses of the active semester whose exercise points are below the admission threshold.
+    /// Returns `None` if no course is at risk.
+    fn admission(&self) -> Option<FormatType> {
+        let semester = self.store.current_semester()?;
+        let courses = self.store.semester_courses(semester).collect::<Vec<_>>();
+        let at_risk = admission_at_risk(&courses);
+        if at_risk.is_empty() {
+            return None;
+        }
+        let names = at_risk.iter().map(|(it, _, _)| it.name()).collect();
+        let reached = at_risk
+            .iter()
+            .map(|(_, reached, _)| format!("{:.1}%", reached).red().to_string())
+            .collect::<Vec<_>>();
+        let required = at_risk
+            .iter()
+            .map(|(_, _, required)| format!("{}%", required))
+            .collect();
+        Some(
+            TableBuilder::new()
+                .column("Course", names, FormatAlignment::Left)
+                .column("Required", required, FormatAlignment::Right)
+                .column("Reached", reached, FormatAlignment::Right)
+                .build(),
+        )
+    }
+
+    /// Open and overdue task counts of all courses in the active semester. Returns `None` if no
+    /// course has open tasks.
+    fn open_tasks(&self) -> Option<FormatType> {
+        let semester = self.store.current_semester()?;
+        let today = Date::today();
+
+        let mut courses = Vec::new();
+        let mut open = Vec::new();
+        let mut overdue = Vec::new();
+        for course in self.store.semester_courses(semester) {
+            let count = course.open_tasks().count();
+            if count == 0 {
+                continue;
+            }
+            let overdue_count = course
+                .open_tasks()
+                .filter(|task| task.is_overdue(&today))
+                .count();
+            courses.push(course.name());
+            open.push(count.to_string());
+            overdue.push(overdue_count.to_string());
+        }
+
+        if courses.is_empty() {
+            return None;
+        }
+        Some(
+            TableBuilder::new()
+                .column("Course", courses, FormatAlignment::Left)
+                .column("Open", open, FormatAlignment::Right)
+                .column("Overdue", overdue, FormatAlignment::Right)
+                .build(),
+        )
+    }
+
+    /// Graded courses, best grade first.
+    fn graded(&self) -> Vec<(Course, f32)> {
+        let mut graded = self
+            .courses()
+            .into_iter()
+            .filter_map(|course| {
+                let grade = course.grade()?;
+                Some((course, grade))
+            })
+            .collect::<Vec<_>>();
+        graded.sort_by(|a, b| a.1.total_cmp(&b.1));
+        graded
+    }
+
+    /// Weighted averages of the semesters in order, leaving out semesters without grades.
+    fn semester_averages(&self) -> Vec<f32> {
+        self.semesters()
+            .iter()
+            .filter_map(|semester| {
+                let (sum, ects) = self
+                    .courses
+                    .apply(semester.courses())
+                    .filter_map(|course| course.grade().zip(course.ects()))
+                    .fold((0f32, 0u32), |(sum, count), (grade, ects)| {
+                        (sum + grade * ects as f32, count + ects as u32)
+                    });
+                (ects > 0).then(|| sum / ects as f32)
+            })
+            .collect()
+    }
+
     // Unweighted average accross all degrees and course types (übK included) // Only coures with a defined grade are considered.
     pub fn average(&self) -> f32 {
         let (sum, count) = self
-            .store
-            .semesters()
-            .flat_map(|semester| semester.courses())
+            .courses()
+            .iter()
             .filter_map(|course| course.grade())
             .fold((0f32, 0), |(sum, count), grade| (sum + grade, count + 1));
-        let average = if count > 0 { sum / (count as f32) } else { 0.0 };
-        average
+
+        if count > 0 {
+            sum / (count as f32)
+        } else {
+            0.0
+        }
     }
 
     // Weighted average accross all degrees and course types (übK included)
     // Only coures with a defined grade and ects are considered.
     pub fn weighted_average(&self) -> f32 {
         let (sum, count) = self
-            .store
-            .semesters()
-            .flat_map(|semester| semester.courses())
+            .courses()
+            .iter()
             .filter_map(|course| course.grade().zip(course.ects()))
             .fold((0f32, 0), |(sum, count), (grade, ects)| {
                 (sum + grade * (ects as f32), count + ects)
             });
-        let average = if count > 0 { sum / (count as f32) } else { 0.0 };
-        average
+
+        if count > 0 {
+            sum / (count as f32)
+        } else {
+            0.0
+        }
     }
 
-    // Calculates the weighted average by degree. This does not include coures marked with üBK
+    // Calculates the weighted average by degree. This does not include coures marked with üBK.
+    // Configured degrees apply their rules, see [Degree::average].
     pub fn weighted_average_by_degree(&self) -> HashMap<String, f32> {
-        let mut degrees: HashMap<String, Vec<(Option<f32>, Option<u8>)>> = HashMap::new();
-        self.store
-            .semesters()
-            .flat_map(|semester| semester.courses())
-            .for_each(|course| {
-                for d in course.degrees() {
-                    if course.uebk().unwrap_or(false) {
-                        continue;
-                    }
-                    degrees
-                        .entry(d.to_string())
-                        .or_insert(vec![])
-                        .push((course.grade(), course.ects()));
+        let mut degrees: HashMap<String, Vec<GradeEcts>> = HashMap::new();
+        let all_courses = self.courses();
+        all_courses.iter().for_each(|course| {
+            for d in course.degrees() {
+                if course.uebk().unwrap_or(false) {
+                    continue;
                 }
-            });
+                if self.courses.degree.as_ref().is_some_and(|it| it != d) {
+                    continue;
+                }
+                degrees
+                    .entry(d.to_string())
+                    .or_insert(vec![])
+                    .push((course.grade(), course.ects()));
+            }
+        });
 
+        let configured = self.store.degrees();
         let weighted_averages: HashMap<String, f32> = degrees
             .into_iter()
             .map(|(degree, courses)| {
@@ -116,9 +632,72 @@ where
                         (sum + grade * (ects as f32), count + ects)
                     });
                 let average = if count > 0 { sum / (count as f32) } else { 0.0 };
+                let average = match configured.iter().find(|it| it.name() == degree) {
+                    Some(config) => config.average(&all_courses).unwrap_or(0.0),
+                    None => average,
+                };
                 (degree, average)
             })
             .collect();
         weighted_averages
     }
 }
+
+/// One bar per grade, the better the grade the higher the bar.
+fn sparkline(grades: &[f32]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let best = grades.iter().copied().fold(f32::INFINITY, f32::min);
+    let worst = grades.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    grades
+        .iter()
+        .map(|grade| match worst - best {
+            // all grades are the same
+            spread if spread <= f32::EPSILON => BARS[BARS.len() / 2],
+            spread => {
+                let level = (worst - grade) / spread * (BARS.len() - 1) as f32;
+                BARS[level.round() as usize]
+            }
+        })
+        .collect()
+}
+
+/// Median of grades sorted in order, 0 without grades like the averages.
+fn median(graded: &[(Course, f32)]) -> f32 {
+    let len = graded.len();
+    match len {
+        0 => 0.0,
+        _ if len.is_multiple_of(2) => (graded[len / 2 - 1].1 + graded[len / 2].1) / 2.0,
+        _ => graded[len / 2].1,
+    }
+}
+
+/// Standard deviation of the grades from their unweighted average.
+fn standard_deviation(graded: &[(Course, f32)]) -> f32 {
+    if graded.is_empty() {
+        return 0.0;
+    }
+    let count = graded.len() as f32;
+    let mean = graded.iter().map(|(_, grade)| grade).sum::<f32>() / count;
+    let variance = graded
+        .iter()
+        .map(|(_, grade)| (grade - mean).powi(2))
+        .sum::<f32>()
+        / count;
+    variance.sqrt()
+}
+
+/// Earned ECTS and ECTS of ungraded courses which are neither failed nor dropped.
+fn ects_of(courses: &[Course]) -> (u32, u32) {
+    let earned = courses
+        .iter()
+        .filter_map(|it| it.earned_ects())
+        .map(|it| it as u32)
+        .sum();
+    let in_progress = courses
+        .iter()
+        .filter(|it| it.is_enrolled())
+        .filter_map(|it| it.ects())
+        .map(|it| it as u32)
+        .sum();
+    (earned, in_progress)
+}
@@ -1,6 +1,10 @@
 use std::env;
 
-use crate::{service::format::IntoFormatType, StoreProvider};
+use crate::{
+    domain::{fold_name, Date, Semester},
+    service::format::IntoFormatType,
+    MmError, StoreProvider,
+};
 use anyhow::{anyhow, bail, Context};
 
 use super::ServiceResult;
@@ -20,17 +24,38 @@ where
         SwitchService { store }
     }
 
-    pub fn run(&mut self, reference: Option<String>) -> ServiceResult {
+    pub fn run(&mut self, reference: Option<String>, auto: bool) -> ServiceResult {
         match reference {
             Some(it) => self.reference_switch(it),
+            None if auto => self.auto_switch(),
             None => self.context_switch(),
         }
     }
 
+    /// Activates the semester which lasts over today.
+    fn auto_switch(&mut self) -> ServiceResult {
+        let today = Date::today();
+        let semester = semester_of(self.store, &today).ok_or_else(|| {
+            anyhow!(
+                "No semester lasts over today ({}). Set `start` and `end` in the `.mm` file of the semester",
+                today
+            )
+        })?;
+        if self
+            .store
+            .current_semester()
+            .is_some_and(|it| it.name() == semester.name())
+        {
+            return Ok(format!("Semester {} is already active", semester.name()).info());
+        }
+        self.store.set_current_semester(Some(&semester))?;
+        Ok(format!("Switched to semester: {}", semester.name()).success())
+    }
+
     fn reference_switch(&mut self, reference: String) -> ServiceResult {
         let split = reference.split('/').collect::<Vec<&str>>();
         match split.len() {
-            0 => bail!("Invalid reference"),
+            0 => bail!(MmError::InvalidReference(reference)),
             1 => {
                 // Check if reference is a semester
                 if let Some(semester) = self.store.get_semester(split[0]) {
@@ -56,12 +81,14 @@ where
 
                 // Check if reference is a course in any semester
                 let courses: Vec<_> = self.store.courses().collect();
-                if let Some(course) = courses.iter().find(|course| course.name() == split[0]) {
+                let folded = fold_name(split[0]);
+                if let Some(course) = courses.iter().find(|course| {
+                    fold_name(&course.name()) == folded || fold_name(course.path().name()) == folded
+                }) {
                     let semesters: Vec<_> = self.store.semesters().collect();
-                    if let Some(mut semester) = semesters
-                        .into_iter()
-                        .find(|semester| semester.course(&course.name()).is_some())
-                    {
+                    if let Some(mut semester) = semesters.into_iter().find(|semester| {
+                        Some(semester.path().path().as_path()) == course.path().as_path().parent()
+                    }) {
                         self.store.set_current_semester(Some(&semester))?;
                         self.store.set_current_course(&mut semester, Some(course))?;
                         let msg =
@@ -71,23 +98,16 @@ where
                     }
                     bail!("No semester found for course: {}", course.name());
                 }
-                bail!("No course found by reference: {}", reference)
+                bail!(MmError::CourseNotFound(reference.clone()))
             }
             2 => {
-                let mut semester = self.store.get_semester(split[0]).ok_or_else(|| {
-                    anyhow!(
-                        "No semester found matching the reference semester part '{}' of '{}'",
-                        split[0],
-                        reference
-                    )
-                })?;
-                let course = semester.course(split[1]).ok_or_else(|| {
-                    anyhow!(
-                        "No Course found matchin the reference course part '{}' of '{}'",
-                        split[1],
-                        reference
-                    )
-                })?;
+                let mut semester = self
+                    .store
+                    .get_semester(split[0])
+                    .ok_or_else(|| MmError::SemesterNotFound(split[0].into()))?;
+                let course = semester
+                    .course(split[1])
+                    .ok_or_else(|| MmError::CourseNotFound(reference.clone()))?;
                 self.store.set_current_semester(Some(&semester))?;
                 self.store
                     .set_current_course(&mut semester, Some(&course))?;
@@ -96,7 +116,7 @@ where
                     format!("Switched to course: {}/{}", semester.name(), course.name()).success();
                 Ok(msg)
             }
-            _ => bail!("Please provide a valid reference"),
+            _ => bail!(MmError::InvalidReference(reference)),
         }
     }
 
@@ -130,7 +150,7 @@ where
             })?;
             let semester = self
                 .store
-                .get_semester(&name.to_string_lossy().to_string())
+                .get_semester(name.to_string_lossy().as_ref())
                 .ok_or_else(|| anyhow!("Current directory is not a subdirectory of a semester"))?;
             self.store.set_current_semester(Some(&semester))?;
 
@@ -154,7 +174,7 @@ where
             })?;
             let mut semester = self
                 .store
-                .get_semester(&semester_name.to_string_lossy().to_string())
+                .get_semester(semester_name.to_string_lossy().as_ref())
                 .ok_or_else(|| anyhow!("Current directory is not a subdirectory of a semester"))?;
             self.store.set_current_semester(Some(&semester))?;
 
@@ -173,7 +193,7 @@ where
             })?;
 
             let course = semester
-                .course(&course_name.to_string_lossy().to_string())
+                .course(course_name.to_string_lossy().as_ref())
                 .ok_or_else(|| anyhow!("Current directory is not a subdirectory of a course"))?;
             self.store
                 .set_current_course(&mut semester, Some(&course))?;
@@ -185,3 +205,14 @@ where
         unreachable!()
     }
 }
+
+/// The semester lasting over `date`. Of overlapping semesters the one starting last wins.
+pub(super) fn semester_of<Store>(store: &Store, date: &Date) -> Option<Semester>
+where
+    Store: StoreProvider,
+{
+    store
+        .semesters()
+        .filter(|it| it.contains(date))
+        .max_by_key(|it| it.dates())
+}
@@ -0,0 +1,108 @@
+use anyhow::anyhow;
+use colored::Colorize;
+
+use crate::{
+    cli::SyncCommands,
+    domain::{Direction, SyncSettings},
+    service::format::{FormatAlignment, FormatType, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::ServiceResult;
+
+/// Copies the whole entry point to the configured remote and back.
+pub(super) struct SyncService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> SyncService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&self, command: SyncCommands) -> ServiceResult {
+        let settings = self.store.sync_settings().ok_or_else(|| {
+            anyhow!("No remote configured. Add a [sync] table with 'remote' to the config file")
+        })?;
+        match command {
+            SyncCommands::Push { force } => self.sync(settings, Direction::Push, force),
+            SyncCommands::Pull { force } => self.sync(settings, Direction::Pull, force),
+            SyncCommands::Status => self.status(settings),
+        }
+    }
+
+    fn sync(&self, settings: SyncSettings, direction: Direction, force: bool) -> ServiceResult {
+        let report = settings.sync(&self.store.entry_point(), direction, force)?;
+
+        if !report.conflicts.is_empty() {
+            let states = report
+                .conflicts
+                .iter()
+                .map(|_| "changed on both sides".red().to_string())
+                .collect::<Vec<_>>();
+            let body = TableBuilder::new()
+                .column("File", report.conflicts, FormatAlignment::Left)
+                .column("Status", states, FormatAlignment::Left)
+                .build();
+            let flag = match direction {
+                Direction::Push => "'mm sync push --force' to keep the local",
+                Direction::Pull => "'mm sync pull --force' to keep the remote",
+            };
+            let msg = format!(
+                "Nothing has been transferred. Resolve the conflicts or run {} version",
+                flag
+            );
+            return Ok("Conflicts"
+                .line()
+                .block(body)
+                .chain("".line())
+                .chain(msg.error()));
+        }
+
+        let target = match direction {
+            Direction::Push => settings.remote().to_string(),
+            Direction::Pull => "the local store".to_string(),
+        };
+        let mut res = match report.transferred.len() {
+            0 => format!("Already up to date with {}", settings.remote()).success(),
+            n => format!("Copied {} files to {}", n, target).success(),
+        };
+        if !report.skipped.is_empty() {
+            let msg = format!(
+                "{} files are newer on the remote. Run 'mm sync pull' to fetch them",
+                report.skipped.len()
+            );
+            res = res.chain("".line()).chain(msg.info());
+        }
+        Ok(res)
+    }
+
+    fn status(&self, settings: SyncSettings) -> ServiceResult {
+        let (incoming, outgoing) = settings.status(&self.store.entry_point())?;
+        if incoming.is_empty() && outgoing.is_empty() {
+            return Ok(format!("Up to date with {}", settings.remote()).success());
+        }
+
+        let mut files: Vec<String> = Vec::new();
+        let mut states: Vec<String> = Vec::new();
+        for file in incoming {
+            files.push(file);
+            states.push("newer on remote".yellow().to_string());
+        }
+        for file in outgoing {
+            files.push(file);
+            states.push("newer locally".green().to_string());
+        }
+        let body: FormatType = TableBuilder::new()
+            .column("File", files, FormatAlignment::Left)
+            .column("Status", states, FormatAlignment::Left)
+            .build();
+        Ok(settings.remote().line().block(body))
+    }
+}
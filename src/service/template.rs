@@ -0,0 +1,8 @@
+/// Replaces `{{name}}` placeholders in templates. Unknown placeholders are kept as they are.
+pub(super) fn render(template: &str, variables: &[(&str, &str)]) -> String {
+    variables
+        .iter()
+        .fold(template.to_string(), |acc, (name, value)| {
+            acc.replace(&format!("{{{{{}}}}}", name), value)
+        })
+}
@@ -0,0 +1,110 @@
+use std::num::NonZeroUsize;
+
+use crate::{
+    cli::TodoCommands,
+    domain::{Date, Task},
+    service::format::{FormatAlignment, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, ServiceResult};
+
+pub(super) struct TodoService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s mut Store,
+}
+
+impl<'s, Store> TodoService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s mut Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&mut self, course: Option<String>, command: Option<TodoCommands>) -> ServiceResult {
+        let command = command.unwrap_or(TodoCommands::List { all: false });
+        let course = course.as_deref();
+        match command {
+            TodoCommands::List { all } => self.list(course, all),
+            TodoCommands::Add { title, due } => self.add(course, title, due),
+            TodoCommands::Done { id } => self.done(course, id),
+            TodoCommands::Remove { id } => self.remove(course, id),
+        }
+    }
+
+    fn list(&self, course: Option<&str>, all: bool) -> ServiceResult {
+        let course = resolve_course(self.store, course)?;
+        let today = Date::today();
+
+        let tasks = course
+            .tasks()
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| all || !task.done())
+            .collect::<Vec<_>>();
+
+        if tasks.is_empty() {
+            return Ok(format!("No open tasks for course '{}'", course.name()).info());
+        }
+
+        let ids = tasks
+            .iter()
+            .map(|(idx, _)| (idx + 1).to_string())
+            .collect::<Vec<_>>();
+        let titles = tasks
+            .iter()
+            .map(|(_, task)| task.title().to_string())
+            .collect::<Vec<_>>();
+        let due = tasks
+            .iter()
+            .map(|(_, task)| {
+                let date = task.due().map(|it| it.to_string()).unwrap_or_default();
+                if task.is_overdue(&today) {
+                    format!("{} (overdue)", date)
+                } else {
+                    date
+                }
+            })
+            .collect::<Vec<_>>();
+        let done = tasks
+            .iter()
+            .map(|(_, task)| if task.done() { "x" } else { " " }.to_string())
+            .collect::<Vec<_>>();
+
+        let header = course.name().line();
+        let body = TableBuilder::new()
+            .column("Id", ids, FormatAlignment::Right)
+            .column("Task", titles, FormatAlignment::Left)
+            .column("Due", due, FormatAlignment::Left)
+            .column("Done", done, FormatAlignment::Center)
+            .build();
+        Ok(header.block(body))
+    }
+
+    fn add(&mut self, course: Option<&str>, title: String, due: Option<Date>) -> ServiceResult {
+        let mut course = resolve_course(self.store, course)?;
+        course.add_task(Task::new(title.clone(), due))?;
+        let msg = format!(
+            "Task '{}' (id {}) has been added to '{}'",
+            title,
+            course.tasks().len(),
+            course.name()
+        );
+        Ok(msg.success())
+    }
+
+    fn done(&mut self, course: Option<&str>, id: NonZeroUsize) -> ServiceResult {
+        let mut course = resolve_course(self.store, course)?;
+        let task = course.complete_task(id.get() - 1)?;
+        Ok(format!("Task '{}' has been marked as done", task.title()).success())
+    }
+
+    fn remove(&mut self, course: Option<&str>, id: NonZeroUsize) -> ServiceResult {
+        let mut course = resolve_course(self.store, course)?;
+        let task = course.remove_task(id.get() - 1)?;
+        Ok(format!("Task '{}' has been removed", task.title()).success())
+    }
+}
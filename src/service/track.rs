@@ -0,0 +1,244 @@
+use std::cmp::Reverse;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::{
+    cli::TrackCommands,
+    domain::{now, Course, Date, Session, Time},
+    service::format::{FormatAlignment, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, ServiceResult};
+
+/// Records study sessions in the `course.toml` of a course. At most one session is running at a
+/// time across all courses.
+pub(super) struct TrackService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s mut Store,
+}
+
+impl<'s, Store> TrackService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s mut Store) -> Self {
+        Self { store }
+    }
+
+    pub fn run(&mut self, course: Option<String>, command: Option<TrackCommands>) -> ServiceResult {
+        let course = course.as_deref();
+        let Some(command) = command else {
+            return self.show();
+        };
+        match command {
+            TrackCommands::Start => self.start(course),
+            TrackCommands::Stop => self.stop(),
+            TrackCommands::Cancel => self.cancel(),
+            TrackCommands::Add { duration, date } => self.add(course, &duration, date),
+            TrackCommands::Report { weeks } => self.report(course, weeks),
+        }
+    }
+
+    fn running(&self) -> Option<Course> {
+        self.store
+            .courses()
+            .find(|course| course.running_session().is_some())
+    }
+
+    fn show(&self) -> ServiceResult {
+        let Some(course) = self.running() else {
+            return Ok("No session running".info());
+        };
+        let (date, time) = now();
+        let session = course
+            .running_session()
+            .expect("course has a running session");
+        let msg = format!(
+            "Tracking '{}' since {} ({})",
+            course.name(),
+            session
+                .start_time()
+                .map(|it| it.to_string())
+                .unwrap_or_default(),
+            format_minutes(session.elapsed(&date, &time))
+        );
+        Ok(msg.info())
+    }
+
+    fn start(&mut self, course: Option<&str>) -> ServiceResult {
+        if let Some(running) = self.running() {
+            bail!(
+                "A session is already running for '{}'. Stop it first",
+                running.name()
+            );
+        }
+        let mut course = resolve_course(self.store, course)?;
+        let (date, time) = now();
+        course.add_session(Session::start(date, time))?;
+        let msg = format!("Started tracking '{}' at {}", course.name(), time);
+        Ok(msg.success())
+    }
+
+    fn stop(&mut self) -> ServiceResult {
+        let mut course = self
+            .running()
+            .ok_or_else(|| anyhow!("No session running"))?;
+        let (date, time) = now();
+        let session = course.stop_session(&date, &time)?;
+        let msg = format!(
+            "Tracked {} for '{}'",
+            format_minutes(session.minutes().unwrap_or_default()),
+            course.name()
+        );
+        Ok(msg.success())
+    }
+
+    fn cancel(&mut self) -> ServiceResult {
+        let mut course = self
+            .running()
+            .ok_or_else(|| anyhow!("No session running"))?;
+        course.cancel_session()?;
+        Ok(format!("Session for '{}' has been discarded", course.name()).success())
+    }
+
+    fn add(&mut self, course: Option<&str>, duration: &str, date: Option<Date>) -> ServiceResult {
+        let minutes = parse_duration(duration)?;
+        let mut course = resolve_course(self.store, course)?;
+        let date = date.unwrap_or_else(Date::today);
+        course.add_session(Session::manual(date, minutes))?;
+        let msg = format!(
+            "Added {} on {} for '{}'",
+            format_minutes(minutes),
+            date,
+            course.name()
+        );
+        Ok(msg.success())
+    }
+
+    /// Shows the time spent per course and per week. Running sessions are counted up to now.
+    fn report(&self, course: Option<&str>, weeks: usize) -> ServiceResult {
+        let courses: Vec<Course> = match course {
+            Some(_) => vec![resolve_course(self.store, course)?],
+            None => self
+                .store
+                .courses()
+                .filter(|it| !it.sessions().is_empty())
+                .collect(),
+        };
+        if courses.iter().all(|it| it.sessions().is_empty()) {
+            return Ok("No study sessions tracked yet".info());
+        }
+
+        let (today, time) = now();
+        let this_week = today.start_of_week();
+        let sessions = courses
+            .iter()
+            .flat_map(|course| {
+                course.sessions().iter().map(move |session| {
+                    let minutes = session
+                        .minutes()
+                        .unwrap_or_else(|| session.elapsed(&today, &time));
+                    (course, session.date(), minutes)
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut per_course = courses
+            .iter()
+            .map(|course| {
+                let total = sum(&sessions, |(it, _, _)| it.path() == course.path());
+                let week = sum(&sessions, |(it, date, _)| {
+                    it.path() == course.path() && *date >= this_week
+                });
+                (course.name(), week, total)
+            })
+            .collect::<Vec<_>>();
+        per_course.sort_by_key(|it| Reverse(it.2));
+
+        let names = per_course.iter().map(|it| it.0.clone()).collect::<Vec<_>>();
+        let week = per_course
+            .iter()
+            .map(|it| format_minutes(it.1))
+            .collect::<Vec<_>>();
+        let total = per_course
+            .iter()
+            .map(|it| format_minutes(it.2))
+            .collect::<Vec<_>>();
+        let course_table = TableBuilder::new()
+            .column("Course", names, FormatAlignment::Left)
+            .column("This week", week, FormatAlignment::Right)
+            .column("Total", total, FormatAlignment::Right)
+            .build();
+
+        let (starts, hours): (Vec<_>, Vec<_>) = (0..weeks)
+            .map(|idx| {
+                let start = this_week.add_days(-7 * idx as i64);
+                let end = start.add_days(7);
+                let minutes = sum(&sessions, |(_, date, _)| *date >= start && *date < end);
+                (start.to_string(), format_minutes(minutes))
+            })
+            .unzip();
+        let week_table = TableBuilder::new()
+            .column("Week of", starts, FormatAlignment::Left)
+            .column("Time", hours, FormatAlignment::Right)
+            .build();
+
+        let res = "Per course"
+            .line()
+            .block(course_table)
+            .chain("".line())
+            .chain("Per week".line().block(week_table));
+        Ok(res)
+    }
+}
+
+fn sum(sessions: &[(&Course, Date, u32)], filter: impl Fn(&(&Course, Date, u32)) -> bool) -> u32 {
+    sessions
+        .iter()
+        .filter(|it| filter(it))
+        .map(|(_, _, minutes)| minutes)
+        .sum()
+}
+
+fn format_minutes(minutes: u32) -> String {
+    format!("{}h {:02}m", minutes / 60, minutes % 60)
+}
+
+/// Parses durations like `1h30m`, `2h`, `45m`, `45` (minutes), `1:30` or `1.5h`.
+fn parse_duration(input: &str) -> Result<u32> {
+    let invalid = || {
+        anyhow!(
+            "Invalid duration '{}'. Expected e.g. 1h30m, 45m or 1:30",
+            input
+        )
+    };
+    let value = input.trim().to_lowercase();
+
+    if value.contains(':') {
+        let time = value.parse::<Time>().map_err(|_| invalid())?;
+        return Ok(time.hour() as u32 * 60 + time.minute() as u32);
+    }
+
+    let minutes = match value.split_once('h') {
+        Some((hours, rest)) => {
+            let hours = hours.parse::<f32>().map_err(|_| invalid())?;
+            let rest = rest.trim_end_matches('m');
+            let minutes = match rest {
+                "" => 0,
+                _ => rest.parse::<u32>().map_err(|_| invalid())?,
+            };
+            (hours * 60.0).round() as u32 + minutes
+        }
+        None => value
+            .trim_end_matches('m')
+            .parse::<u32>()
+            .map_err(|_| invalid())?,
+    };
+    if minutes == 0 {
+        return Err(invalid());
+    }
+    Ok(minutes)
+}
@@ -0,0 +1,529 @@
+use std::io::{stdout, IsTerminal};
+
+use anyhow::{bail, Context, Result};
+use ratatui::{
+    backend::CrosstermBackend,
+    crossterm::{
+        event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+        terminal,
+    },
+    layout::{Constraint, Layout, Position},
+    style::{Style, Stylize},
+    text::Line,
+    widgets::{
+        Block, HighlightSpacing, List, ListItem, ListState, Paragraph, Row, Table, TableState,
+    },
+    DefaultTerminal, Frame, Terminal, TerminalOptions, Viewport,
+};
+use regex::Regex;
+
+use crate::{
+    cli::{Commands, CourseCommands},
+    domain::{Course, Date, Semester},
+    service::format::IntoFormatType,
+    StoreProvider,
+};
+
+use super::{i18n::tr, service::Service, ServiceResult};
+
+const HELP: &str = "←→ pane  ↑↓ move  enter switch  g grade  L lock/unlock  r reload  q quit";
+
+/// Full-screen interface with panes for semesters, courses and the selected course. Changes are
+/// run as regular commands through [Service], so they behave and are recorded like on the
+/// command line.
+pub(super) struct UiService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    service: &'s mut Service<Store>,
+    semesters: Vec<Semester>,
+    courses: Vec<Course>,
+    pane: Pane,
+    semester_idx: usize,
+    course_idx: usize,
+    mode: Mode,
+    message: Option<(String, bool)>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Pane {
+    Semesters,
+    Courses,
+}
+
+enum Mode {
+    Normal,
+    /// Editing the grade of the selected course.
+    Grade(String),
+}
+
+enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Escape,
+    Backspace,
+    Char(char),
+}
+
+/// Raw mode of the terminal, disabled again on drop.
+struct RawMode;
+
+impl<'s, Store> UiService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(service: &'s mut Service<Store>) -> Self {
+        Self {
+            service,
+            semesters: Vec::new(),
+            courses: Vec::new(),
+            pane: Pane::Semesters,
+            semester_idx: 0,
+            course_idx: 0,
+            mode: Mode::Normal,
+            message: None,
+        }
+    }
+
+    pub fn run(&mut self) -> ServiceResult {
+        if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+            bail!("'mm ui' requires an interactive terminal");
+        }
+        self.reload();
+        // start on the active semester and course
+        if let Some(active) = self.service.store().current_semester() {
+            if let Some(idx) = self
+                .semesters
+                .iter()
+                .position(|it| it.path() == active.path())
+            {
+                self.semester_idx = idx;
+                self.reload_courses();
+                if let Some(course) = active.active_course() {
+                    self.course_idx = self
+                        .courses
+                        .iter()
+                        .position(|it| it.path() == course.path())
+                        .unwrap_or(0);
+                    self.pane = Pane::Courses;
+                }
+            }
+        }
+
+        let mut terminal = ratatui::try_init().context("Failed to set up the terminal")?;
+        let res = self.draw_loop(&mut terminal);
+        ratatui::restore();
+        res?;
+        Ok("".raw())
+    }
+
+    fn draw_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.render(frame))?;
+            if !self.handle(read_key()?) {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Returns false to quit.
+    fn handle(&mut self, key: Key) -> bool {
+        if let Mode::Grade(input) = &mut self.mode {
+            match key {
+                Key::Char(c) if c.is_ascii_digit() || c == '.' || c == ',' => input.push(c),
+                Key::Backspace => {
+                    input.pop();
+                }
+                Key::Enter => {
+                    let input = input.replace(',', ".");
+                    self.mode = Mode::Normal;
+                    match input.parse::<f32>() {
+                        Ok(grade) => self.grade(grade),
+                        Err(_) => self.message = Some((format!("Invalid grade: {}", input), true)),
+                    }
+                }
+                Key::Escape => self.mode = Mode::Normal,
+                _ => {}
+            }
+            return true;
+        }
+
+        self.message = None;
+        match key {
+            Key::Char('q') | Key::Escape => return false,
+            Key::Up | Key::Char('k') => self.select(-1),
+            Key::Down | Key::Char('j') => self.select(1),
+            Key::Left | Key::Char('h') => self.pane = Pane::Semesters,
+            Key::Right | Key::Char('l') | Key::Char('\t') if !self.courses.is_empty() => {
+                self.pane = Pane::Courses
+            }
+            Key::Enter => self.switch(),
+            Key::Char('g') if self.selected_course().is_some() => {
+                self.mode = Mode::Grade(String::new())
+            }
+            Key::Char('L') => self.toggle_lock(),
+            Key::Char('r') => {
+                self.reload();
+                self.message = Some(("Reloaded".into(), false));
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn select(&mut self, offset: isize) {
+        let (idx, len) = match self.pane {
+            Pane::Semesters => (&mut self.semester_idx, self.semesters.len()),
+            Pane::Courses => (&mut self.course_idx, self.courses.len()),
+        };
+        if len == 0 {
+            return;
+        }
+        *idx = idx.saturating_add_signed(offset).min(len - 1);
+        if self.pane == Pane::Semesters {
+            self.course_idx = 0;
+            self.reload_courses();
+        }
+    }
+
+    fn selected_semester(&self) -> Option<&Semester> {
+        self.semesters.get(self.semester_idx)
+    }
+
+    fn selected_course(&self) -> Option<&Course> {
+        match self.pane {
+            Pane::Courses => self.courses.get(self.course_idx),
+            Pane::Semesters => None,
+        }
+    }
+
+    /// Reference of the selected course or, in the semester pane, of the selected semester.
+    fn reference(&self) -> Option<String> {
+        let semester = self.selected_semester()?.path().name().to_string();
+        match self.selected_course() {
+            Some(course) => Some(format!("{}/{}", semester, course.path().name())),
+            None => Some(semester),
+        }
+    }
+
+    fn switch(&mut self) {
+        let Some(reference) = self.reference() else {
+            return;
+        };
+        self.execute(Commands::Switch {
+            reference: Some(reference),
+            auto: false,
+        });
+    }
+
+    fn grade(&mut self, grade: f32) {
+        let Some(reference) = self.reference() else {
+            return;
+        };
+        self.execute(Commands::Course {
+            command: Some(CourseCommands::Grade {
+                component: None,
+                grade,
+                course: Some(reference),
+                force: false,
+            }),
+        });
+    }
+
+    fn toggle_lock(&mut self) {
+        let Some(course) = self.selected_course() else {
+            return;
+        };
+        let locked = course.is_locked();
+        let course = self.reference();
+        let command = match locked {
+            true => CourseCommands::Unlock { course },
+            false => CourseCommands::Lock { course },
+        };
+        self.execute(Commands::Course {
+            command: Some(command),
+        });
+    }
+
+    /// Runs the command, shows its message in the status line and reloads the panes.
+    fn execute(&mut self, command: Commands) {
+        let res = self.service.dispatch(command);
+        let failed = res.is_error();
+        let ansi = Regex::new(r"\x1b\[[0-9;]*m").expect("valid regex");
+        let message = ansi.replace_all(&res.to_string(), "").replace('\n', " ");
+        self.message = Some((message.trim().to_string(), failed));
+        self.reload();
+    }
+
+    fn reload(&mut self) {
+        let mut semesters = self.service.store().semesters().collect::<Vec<_>>();
+        semesters.sort();
+        self.semesters = semesters;
+        self.semester_idx = self
+            .semester_idx
+            .min(self.semesters.len().saturating_sub(1));
+        self.reload_courses();
+    }
+
+    fn reload_courses(&mut self) {
+        let mut courses = match self.selected_semester() {
+            Some(semester) => semester.courses().collect::<Vec<_>>(),
+            None => Vec::new(),
+        };
+        courses.sort_by_key(|it| it.name());
+        self.courses = courses;
+        self.course_idx = self.course_idx.min(self.courses.len().saturating_sub(1));
+        if self.courses.is_empty() {
+            self.pane = Pane::Semesters;
+        }
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let store = self.service.store();
+        let active_semester = store.current_semester();
+        let active_course = active_semester.as_ref().and_then(|it| it.active_course());
+
+        let [main, status] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+        let [semester_area, course_area, detail_area] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Fill(2),
+            Constraint::Fill(2),
+        ])
+        .areas(main);
+
+        let semesters = self.semesters.iter().map(|it| {
+            let active = active_semester
+                .as_ref()
+                .is_some_and(|active| active.path() == it.path());
+            ListItem::new(format!("{} {}", marker(active), it.path().name()))
+        });
+        let mut state = ListState::default().with_selected(Some(self.semester_idx));
+        frame.render_stateful_widget(
+            List::new(semesters)
+                .block(pane("Semesters", self.pane == Pane::Semesters))
+                .highlight_style(Style::new().reversed()),
+            semester_area,
+            &mut state,
+        );
+
+        let courses = self.courses.iter().map(|it| {
+            let active = active_course
+                .as_ref()
+                .is_some_and(|active| active.path() == it.path());
+            let grade = it.grade().map(|g| format!("{:.1}", g)).unwrap_or_default();
+            Row::new([
+                Line::from(format!("{} {}", marker(active), it.name())),
+                Line::from(grade).right_aligned(),
+            ])
+        });
+        let mut state = TableState::default()
+            .with_selected((self.pane == Pane::Courses).then_some(self.course_idx));
+        frame.render_stateful_widget(
+            Table::new(courses, [Constraint::Fill(1), Constraint::Length(3)])
+                .block(pane("Courses", self.pane == Pane::Courses))
+                .row_highlight_style(Style::new().reversed()),
+            course_area,
+            &mut state,
+        );
+
+        let details = match self.courses.get(self.course_idx) {
+            Some(course) => details(course),
+            None => vec![Line::from("No courses")],
+        };
+        frame.render_widget(
+            Paragraph::new(details).block(pane("Details", false)),
+            detail_area,
+        );
+
+        let status_line = match (&self.mode, &self.message) {
+            (Mode::Grade(input), _) => {
+                Line::from(format!("Grade: {}█  (enter to save, esc to cancel)", input))
+            }
+            (Mode::Normal, Some((message, true))) => Line::from(message.as_str()).red(),
+            (Mode::Normal, Some((message, false))) => Line::from(message.as_str()).green(),
+            (Mode::Normal, None) => Line::from(HELP).dim(),
+        };
+        frame.render_widget(status_line, status);
+    }
+}
+
+/// Marks the active semester and course.
+fn marker(active: bool) -> &'static str {
+    match active {
+        true => "*",
+        false => " ",
+    }
+}
+
+/// Border and title of a pane, highlighted while the pane has the focus.
+fn pane(title: &str, focused: bool) -> Block<'_> {
+    let block = Block::bordered()
+        .title(title)
+        .border_style(Style::new().dim());
+    match focused {
+        true => block.title_style(Style::new().bold().reversed()),
+        false => block.title_style(Style::new().bold()),
+    }
+}
+
+/// Lines of the detail pane.
+fn details(course: &Course) -> Vec<Line<'static>> {
+    let today = Date::today();
+    let value = |it: Option<String>| it.unwrap_or_else(|| "-".into());
+    let mut lines = vec![
+        Line::from(course.name()).bold(),
+        Line::default(),
+        Line::from(format!(
+            "Grade    {}",
+            value(course.grade().map(|it| format!("{:.1}", it)))
+        )),
+        Line::from(format!(
+            "ECTS     {}",
+            value(course.ects().map(|it| it.to_string()))
+        )),
+        Line::from(format!(
+            "Degrees  {}",
+            value(Some(course.degrees().join(", ")).filter(|it| !it.is_empty()))
+        )),
+        Line::from(format!(
+            "Status   {}",
+            value(course.status().map(|it| it.to_string()))
+        )),
+        Line::from(format!(
+            "Locked   {}",
+            if course.is_locked() { "yes" } else { "no" }
+        )),
+    ];
+
+    let tasks = course.open_tasks().collect::<Vec<_>>();
+    if !tasks.is_empty() {
+        lines.push(Line::default());
+        lines.push(Line::from("Open tasks").bold());
+        lines.extend(
+            tasks
+                .iter()
+                .map(|it| Line::from(format!("• {}", it.title()))),
+        );
+    }
+
+    let mut deadlines = course
+        .deadlines()
+        .iter()
+        .filter(|it| it.days_left(&today) >= 0)
+        .collect::<Vec<_>>();
+    deadlines.sort_by_key(|it| it.date());
+    if !deadlines.is_empty() {
+        lines.push(Line::default());
+        lines.push(Line::from("Deadlines").bold());
+        lines.extend(
+            deadlines
+                .iter()
+                .map(|it| Line::from(format!("{}  {}", it.date(), it.title()))),
+        );
+    }
+    lines
+}
+
+/// Lets the user pick one of the options below the output with the arrow keys or its number.
+/// Returns the index of the option, `None` if canceled.
+pub(super) fn select(prompt: &str, options: &[String]) -> Result<Option<usize>> {
+    let raw_mode = RawMode::enable()?;
+    let mut terminal = Terminal::with_options(
+        CrosstermBackend::new(stdout()),
+        TerminalOptions {
+            viewport: Viewport::Inline(options.len() as u16 + 1),
+        },
+    )?;
+    let title = format!(
+        "{} {}",
+        tr(prompt),
+        tr("(↑↓ or number, enter to select, q to cancel)")
+    );
+    let items = options
+        .iter()
+        .enumerate()
+        .map(|(idx, option)| ListItem::new(format!("{}) {}", idx + 1, tr(option))))
+        .collect::<Vec<_>>();
+    let mut state = ListState::default().with_selected(Some(0));
+    let selected = loop {
+        terminal.draw(|frame| {
+            let [title_area, list_area] =
+                Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(frame.area());
+            frame.render_widget(Line::from(title.as_str()), title_area);
+            frame.render_stateful_widget(
+                List::new(items.clone())
+                    .highlight_symbol("> ")
+                    .highlight_spacing(HighlightSpacing::Always)
+                    .highlight_style(Style::new().reversed()),
+                list_area,
+                &mut state,
+            );
+        })?;
+
+        let current = state.selected().unwrap_or(0);
+        match read_key()? {
+            Key::Up => state.select(Some(current.saturating_sub(1))),
+            Key::Down => state.select(Some((current + 1).min(options.len().saturating_sub(1)))),
+            Key::Enter => break Some(current),
+            Key::Escape | Key::Char('q') => break None,
+            Key::Char(c) => {
+                if let Some(number) = c.to_digit(10).map(|it| it as usize) {
+                    if (1..=options.len()).contains(&number) {
+                        state.select(Some(number - 1));
+                    }
+                }
+            }
+            _ => {}
+        }
+    };
+    // continue the output below the options
+    let area = terminal.get_frame().area();
+    terminal.set_cursor_position(Position::new(0, area.bottom().saturating_sub(1)))?;
+    terminal.show_cursor()?;
+    drop(raw_mode);
+    println!();
+    Ok(selected)
+}
+
+/// Waits for the next key press. Ctrl-c counts as `q`.
+fn read_key() -> Result<Key> {
+    loop {
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        let key = match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => Key::Char('q'),
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::Left => Key::Left,
+            KeyCode::Right => Key::Right,
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Esc => Key::Escape,
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Tab => Key::Char('\t'),
+            KeyCode::Char(c) => Key::Char(c),
+            _ => continue,
+        };
+        return Ok(key);
+    }
+}
+
+impl RawMode {
+    fn enable() -> Result<RawMode> {
+        terminal::enable_raw_mode().context("Failed to put the terminal into raw mode")?;
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
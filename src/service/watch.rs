@@ -0,0 +1,108 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    domain::{file_into, is_complete, now, target_dir, Inbox},
+    service::format::{FormatService, FormatType, IntoFormatType},
+    StoreProvider,
+};
+
+use super::ServiceResult;
+
+pub(super) struct WatchService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> WatchService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    /// Polls the inbox until interrupted and files every new download matching an inbox rule
+    /// into the active course. Files already in the inbox on start are left alone, new files are
+    /// filed once their size stopped changing between two polls.
+    pub fn run(&self, interval: u64) -> ServiceResult {
+        let inbox = self.store.inbox();
+        let mut seen = inbox
+            .newest(usize::MAX)?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let mut pending: HashMap<PathBuf, u64> = HashMap::new();
+        let msg = format!(
+            "Watching {} for new downloads, stop with Ctrl+C",
+            inbox.path().display()
+        );
+        FormatService::run(msg.info());
+
+        loop {
+            thread::sleep(Duration::from_secs(interval.max(1)));
+            let files = match inbox.newest(usize::MAX) {
+                Ok(files) => files,
+                Err(err) => {
+                    FormatService::run(format!("{:#}", err).error());
+                    continue;
+                }
+            };
+            // forget removed files so a download with the same name is filed again
+            seen.retain(|it| files.contains(it));
+            pending.retain(|it, _| files.contains(it));
+
+            for file in files {
+                if seen.contains(&file) {
+                    continue;
+                }
+                let Ok(size) = std::fs::metadata(&file).map(|it| it.len()) else {
+                    continue;
+                };
+                if pending.insert(file.clone(), size) != Some(size) || !is_complete(&file) {
+                    continue;
+                }
+                pending.remove(&file);
+                seen.insert(file.clone());
+                FormatService::run(self.file(&inbox, &file));
+            }
+        }
+    }
+
+    fn file(&self, inbox: &Inbox, file: &Path) -> FormatType {
+        let (_, time) = now();
+        let Some(rule) = inbox.rule(file) else {
+            let msg = format!(
+                "[{}] No rule matches '{}', left in inbox",
+                time,
+                file.display()
+            );
+            return msg.info();
+        };
+        let Some(course) = self.store.current_course() else {
+            let msg = format!(
+                "[{}] No active course to file '{}' into",
+                time,
+                file.display()
+            );
+            return msg.error();
+        };
+        let res = target_dir(course.path(), rule.folder())
+            .and_then(|dir| file_into(file, &dir, rule.rename()));
+        match res {
+            Ok(target) => format!(
+                "[{}] Filed '{}' to: {}",
+                time,
+                file.display(),
+                target.display()
+            )
+            .success(),
+            Err(err) => format!("[{}] {:#}", time, err).error(),
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use anyhow::{anyhow, bail, Result};
+use colored::Colorize;
+
+use crate::{
+    domain::{Course, CourseStatus},
+    service::format::{FormatAlignment, FormatType, IntoFormatType, TableBuilder},
+    StoreProvider,
+};
+
+use super::{reference::resolve_course, ServiceResult};
+
+/// Simulates hypothetical grades without writing them to the courses.
+pub(super) struct WhatifService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    store: &'s Store,
+}
+
+impl<'s, Store> WhatifService<'s, Store>
+where
+    Store: StoreProvider,
+{
+    pub fn new(store: &'s Store) -> Self {
+        Self { store }
+    }
+
+    /// Weighted average with the given `course=grade` assignments. With a target, the average
+    /// needed over the ECTS of the remaining ungraded courses is shown as well.
+    pub fn run(&self, grades: Vec<String>, target: Option<f32>) -> ServiceResult {
+        let assumed = grades
+            .iter()
+            .map(|it| self.parse(it))
+            .collect::<Result<Vec<_>>>()?;
+        if let Some(target) = target {
+            check_grade(target)?;
+        }
+
+        let courses = self.store.courses().collect::<Vec<_>>();
+        let (sum, ects) = weighted(courses.iter().filter_map(|it| it.grade().zip(it.ects())));
+        let current = average(sum, ects);
+
+        let mut names = Vec::new();
+        let mut credits = Vec::new();
+        let mut values = Vec::new();
+        let (mut sum, mut ects) = (sum, ects);
+        for (course, grade) in &assumed {
+            let Some(credit) = course.ects() else {
+                bail!("No ECTS set for course: {}", course.name());
+            };
+            // An assumed grade replaces the actual one.
+            if let Some(actual) = course.grade() {
+                sum -= actual * credit as f32;
+                ects -= credit as u32;
+            }
+            sum += grade * credit as f32;
+            ects += credit as u32;
+            names.push(course.name());
+            credits.push(credit.to_string());
+            values.push(format!("{:.1}", grade).cyan().to_string());
+        }
+        let simulated = average(sum, ects);
+
+        let mut msg = None;
+        if !assumed.is_empty() {
+            let body = TableBuilder::new()
+                .column("Course", names, FormatAlignment::Left)
+                .column("ECTS", credits, FormatAlignment::Right)
+                .column("Grade", values, FormatAlignment::Right)
+                .build();
+            let change = match (current, simulated) {
+                (Some(current), Some(simulated)) => {
+                    format!(" ({:+.2})", simulated - current)
+                }
+                _ => String::new(),
+            };
+            let result = format!(
+                "Weighted average: {} -> {}{}",
+                format_average(current),
+                format_average(simulated),
+                change
+            );
+            let block = "Assumed grades".line().block(body);
+            msg = Some(block.chain("".line()).chain(result.line()));
+        }
+
+        let Some(target) = target else {
+            return Ok(msg.unwrap_or_else(|| {
+                "Provide grades like 'Algorithms=1.3' or a target with --target".info()
+            }));
+        };
+        let result = self.needed(&courses, &assumed, target, sum, ects);
+        Ok(match msg {
+            Some(msg) => msg.chain("".line()).chain(result),
+            None => result,
+        })
+    }
+
+    /// Average needed over the ECTS of ungraded courses which have no assumed grade to reach
+    /// `target`, given the weighted `sum` of grades over `ects` so far.
+    fn needed(
+        &self,
+        courses: &[Course],
+        assumed: &[(Course, f32)],
+        target: f32,
+        sum: f32,
+        ects: u32,
+    ) -> FormatType {
+        let remaining = courses
+            .iter()
+            .filter(|it| it.grade().is_none())
+            .filter(|it| !matches!(it.status(), Some(CourseStatus::Dropped)))
+            .filter(|it| !assumed.iter().any(|(course, _)| course.path() == it.path()))
+            .filter_map(|it| it.ects())
+            .map(|it| it as u32)
+            .sum::<u32>();
+        if remaining == 0 {
+            return "No ungraded courses with ECTS left to reach the target".error();
+        }
+
+        let needed = (target * (ects + remaining) as f32 - sum) / remaining as f32;
+        let line = format!(
+            "To reach {:.2} you need {:.2} on average over the remaining {} ECTS",
+            target, needed, remaining
+        );
+        match needed {
+            needed if needed < 1.0 => format!(
+                "{:.2} is out of reach, even 1.0 in all remaining courses gives {:.2}",
+                target,
+                (sum + remaining as f32) / (ects + remaining) as f32
+            )
+            .error(),
+            needed if needed >= 4.0 => format!(
+                "{:.2} is reached with any passing grade in the remaining {} ECTS",
+                target, remaining
+            )
+            .success(),
+            _ => line.info(),
+        }
+    }
+
+    /// Parses `course=grade` where course is a reference like for `mm switch`.
+    fn parse(&self, assignment: &str) -> Result<(Course, f32)> {
+        let (reference, grade) = assignment
+            .rsplit_once('=')
+            .ok_or_else(|| anyhow!("Expected COURSE=GRADE but got: {}", assignment))?;
+        let grade = grade
+            .trim()
+            .replace(',', ".")
+            .parse::<f32>()
+            .map_err(|_| anyhow!("Invalid grade: {}", grade))?;
+        check_grade(grade)?;
+        let course = resolve_course(self.store, Some(reference.trim()))?;
+        Ok((course, grade))
+    }
+}
+
+fn check_grade(grade: f32) -> Result<()> {
+    if !(1.0..=5.0).contains(&grade) {
+        bail!("Grades must be between 1.0 and 5.0 but got: {}", grade);
+    }
+    Ok(())
+}
+
+fn weighted(grades: impl Iterator<Item = (f32, u8)>) -> (f32, u32) {
+    grades.fold((0f32, 0), |(sum, count), (grade, ects)| {
+        (sum + grade * ects as f32, count + ects as u32)
+    })
+}
+
+fn average(sum: f32, ects: u32) -> Option<f32> {
+    (ects > 0).then(|| sum / ects as f32)
+}
+
+fn format_average(average: Option<f32>) -> String {
+    average
+        .map(|it| format!("{:.2}", it))
+        .unwrap_or_else(|| "-".into())
+}